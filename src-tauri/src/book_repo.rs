@@ -0,0 +1,589 @@
+// book.json — typed model, schema migrations and load/save
+// Every command reads and writes book.json through here. Fields this version doesn't
+// know are kept in `extra` and written back, so older and newer app versions can
+// share a project.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use crate::error::AppError;
+
+/// Schema version written by this app version.
+pub const BOOK_SCHEMA_VERSION: &str = "1.1.0";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Book {
+    pub version: String,
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub genre_id: String,
+    #[serde(default)]
+    pub sub_genre_id: String,
+    #[serde(default)]
+    pub sort_order: u32,
+    #[serde(default)]
+    pub created: String,
+    #[serde(default)]
+    pub modified: String,
+    #[serde(default)]
+    pub target_word_count: u64,
+    #[serde(default)]
+    pub current_word_count: u64,
+    #[serde(default)]
+    pub phase_progress: PhaseProgress,
+    /// Front matter pages present, keyed by subtype with `_` for `-`. Mostly
+    /// booleans, but back matter's `appendices` is a list.
+    #[serde(default)]
+    pub front_matter: Map<String, Value>,
+    #[serde(default)]
+    pub back_matter: Map<String, Value>,
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+    #[serde(default)]
+    pub settings: BookSettings,
+    /// Highest chapter number ever handed out, so a deleted chapter's id isn't reused
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub last_chapter_number: u32,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chapter {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub sort_order: u32,
+    #[serde(default)]
+    pub scenes: Vec<Scene>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub last_scene_number: u32,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scene {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub sort_order: u32,
+    #[serde(rename = "type", default = "default_scene_type")]
+    pub scene_type: String, // "action" or "reaction"
+    #[serde(default = "not_started")]
+    pub status: String,     // "not_started", "outlined", "drafted", "revised"
+    #[serde(default)]
+    pub word_count: u64,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookSettings {
+    #[serde(default)]
+    pub perspective: String,
+    #[serde(default)]
+    pub tense: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub writing_style_notes: String,
+    /// Words to write per day; 0 means no goal
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub daily_word_goal: u32,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Progress through the five Sapling phases. A phase's `status` is "not_started",
+/// "in_progress" or "complete".
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhaseProgress {
+    pub seed: SeedProgress,
+    pub root: RootProgress,
+    pub sprout: SproutProgress,
+    pub flourish: FlourishProgress,
+    pub bloom: BloomProgress,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeedProgress {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    pub elements: BTreeMap<String, bool>,
+    pub deliverables: BTreeMap<String, bool>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RootProgress {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    pub beats_drafted: u32,
+    pub beats_total: u32,
+    pub deliverables: BTreeMap<String, bool>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SproutProgress {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    pub characters: Map<String, Value>,
+    pub deliverables: BTreeMap<String, bool>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FlourishProgress {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    pub scenes_outlined: u32,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BloomProgress {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+    pub scenes_drafted: u32,
+    pub scenes_total: u32,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+fn not_started() -> String {
+    "not_started".to_string()
+}
+
+fn default_scene_type() -> String {
+    "action".to_string()
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+fn flags(keys: &[&str]) -> BTreeMap<String, bool> {
+    keys.iter().map(|k| (k.to_string(), false)).collect()
+}
+
+impl Default for SeedProgress {
+    fn default() -> Self {
+        SeedProgress {
+            status: not_started(),
+            completed_at: None,
+            elements: flags(&["premise", "theme", "protagonist", "central_conflict", "story_world", "emotional_promise"]),
+            deliverables: flags(&["logline", "story_foundation"]),
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Default for RootProgress {
+    fn default() -> Self {
+        RootProgress {
+            status: not_started(),
+            completed_at: None,
+            beats_drafted: 0,
+            beats_total: 21,
+            deliverables: flags(&["story_structure_outline"]),
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Default for SproutProgress {
+    fn default() -> Self {
+        SproutProgress {
+            status: not_started(),
+            completed_at: None,
+            characters: Map::new(),
+            deliverables: flags(&["relationship_dynamics"]),
+            extra: Map::new(),
+        }
+    }
+}
+
+impl Default for FlourishProgress {
+    fn default() -> Self {
+        FlourishProgress { status: not_started(), completed_at: None, scenes_outlined: 0, extra: Map::new() }
+    }
+}
+
+impl Default for BloomProgress {
+    fn default() -> Self {
+        BloomProgress { status: not_started(), completed_at: None, scenes_drafted: 0, scenes_total: 0, extra: Map::new() }
+    }
+}
+
+impl Book {
+    /// A new, empty book at the current schema version.
+    pub fn new(id: &str, title: &str, sort_order: u32) -> Self {
+        let now = Utc::now().to_rfc3339();
+        Book {
+            version: BOOK_SCHEMA_VERSION.to_string(),
+            id: id.to_string(),
+            title: title.to_string(),
+            author: String::new(),
+            genre_id: String::new(),
+            sub_genre_id: String::new(),
+            sort_order,
+            created: now.clone(),
+            modified: now,
+            target_word_count: 0,
+            current_word_count: 0,
+            phase_progress: PhaseProgress::default(),
+            front_matter: Map::new(),
+            back_matter: Map::new(),
+            chapters: Vec::new(),
+            settings: BookSettings::default(),
+            last_chapter_number: 0,
+            extra: Map::new(),
+        }
+    }
+
+    pub fn chapter_mut(&mut self, chapter_id: &str) -> Result<&mut Chapter, AppError> {
+        self.chapters
+            .iter_mut()
+            .find(|c| c.id == chapter_id)
+            .ok_or_else(|| AppError::ChapterNotFound(chapter_id.to_string()))
+    }
+
+    /// A chapter id no chapter has had: numbered past every chapter in book.json,
+    /// every folder in `chapters_dir` and every id handed out before.
+    pub fn allocate_chapter_id(&mut self, chapters_dir: &Path) -> String {
+        let listed = self.chapters.iter().map(|c| c.id.as_str());
+        self.last_chapter_number = next_free_number("ch-", listed, chapters_dir, self.last_chapter_number);
+        format!("ch-{:02}", self.last_chapter_number)
+    }
+
+    /// Position for a chapter added at the end.
+    pub fn next_chapter_sort_order(&self) -> u32 {
+        self.chapters.iter().map(|c| c.sort_order).max().unwrap_or(0) + 1
+    }
+
+    /// Give chapters contiguous sort orders 1..n, keeping their reading order.
+    pub fn close_chapter_gaps(&mut self) {
+        self.chapters.sort_by_key(|c| c.sort_order);
+        for (i, chapter) in self.chapters.iter_mut().enumerate() {
+            chapter.sort_order = i as u32 + 1;
+        }
+    }
+
+    /// Chapters in reading order.
+    pub fn sorted_chapters(&self) -> Vec<&Chapter> {
+        let mut chapters: Vec<&Chapter> = self.chapters.iter().collect();
+        chapters.sort_by_key(|c| c.sort_order);
+        chapters
+    }
+}
+
+impl Chapter {
+    pub fn new(id: &str, title: &str, sort_order: u32) -> Self {
+        Chapter {
+            id: id.to_string(),
+            title: title.to_string(),
+            sort_order,
+            scenes: Vec::new(),
+            last_scene_number: 0,
+            extra: Map::new(),
+        }
+    }
+
+    /// A scene id unused in this chapter, as `Book::allocate_chapter_id`.
+    pub fn allocate_scene_id(&mut self, chapter_dir: &Path) -> String {
+        let listed = self.scenes.iter().map(|s| s.id.as_str());
+        self.last_scene_number = next_free_number("scene-", listed, chapter_dir, self.last_scene_number);
+        format!("scene-{:02}", self.last_scene_number)
+    }
+
+    /// Give scenes contiguous sort orders 1..n, keeping their reading order.
+    pub fn close_scene_gaps(&mut self) {
+        self.scenes.sort_by_key(|s| s.sort_order);
+        for (i, scene) in self.scenes.iter_mut().enumerate() {
+            scene.sort_order = i as u32 + 1;
+        }
+    }
+
+    /// Scenes in reading order.
+    pub fn sorted_scenes(&self) -> Vec<&Scene> {
+        let mut scenes: Vec<&Scene> = self.scenes.iter().collect();
+        scenes.sort_by_key(|s| s.sort_order);
+        scenes
+    }
+}
+
+impl Scene {
+    pub fn new(id: &str, title: &str, sort_order: u32, scene_type: &str) -> Self {
+        Scene {
+            id: id.to_string(),
+            title: title.to_string(),
+            sort_order,
+            scene_type: scene_type.to_string(),
+            status: not_started(),
+            word_count: 0,
+            extra: Map::new(),
+        }
+    }
+}
+
+/// The number in an id like `ch-07` or `scene-12`.
+pub fn id_number(id: &str, prefix: &str) -> Option<u32> {
+    id.strip_prefix(prefix)?.parse().ok()
+}
+
+/// One past the highest `prefix` number among `listed` ids, folders in `dir` and `last`.
+fn next_free_number<'a>(prefix: &str, listed: impl Iterator<Item = &'a str>, dir: &Path, last: u32) -> u32 {
+    let on_disk: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.file_name().to_string_lossy().to_string()).collect())
+        .unwrap_or_default();
+    listed
+        .filter_map(|id| id_number(id, prefix))
+        .chain(on_disk.iter().filter_map(|name| id_number(name, prefix)))
+        .fold(last, u32::max)
+        + 1
+}
+
+// ─── Load / save ───
+
+pub fn book_json_path(project_dir: &Path, book_id: &str) -> PathBuf {
+    project_dir.join("books").join(book_id).join("book.json")
+}
+
+/// Load a book, migrating it to the current schema in memory. The file itself is
+/// only rewritten by the next save.
+pub fn load_book(project_dir: &Path, book_id: &str) -> Result<Book, AppError> {
+    let path = book_json_path(project_dir, book_id);
+    if !path.exists() {
+        return Err(AppError::BookNotFound(book_id.to_string()));
+    }
+    let mut data: Value = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    migrate(&mut data)?;
+    let mut book: Book = serde_json::from_value(data)?;
+    if book.id.is_empty() {
+        book.id = book_id.to_string();
+    }
+    Ok(book)
+}
+
+/// Write a book atomically to `books/<id>/book.json`.
+pub fn save_book(project_dir: &Path, book: &Book) -> Result<(), AppError> {
+    let path = book_json_path(project_dir, &book.id);
+    crate::file_io::atomic_write(&path, serde_json::to_string_pretty(book)?)
+}
+
+/// Every book under `books/` whose book.json loads; the others are skipped with a warning.
+pub fn load_books(project_dir: &Path) -> Vec<Book> {
+    let Ok(entries) = std::fs::read_dir(project_dir.join("books")) else {
+        return Vec::new();
+    };
+    let mut books = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let book_id = entry.file_name().to_string_lossy().to_string();
+        if !book_json_path(project_dir, &book_id).exists() {
+            continue;
+        }
+        match load_book(project_dir, &book_id) {
+            Ok(book) => books.push(book),
+            Err(e) => eprintln!("Warning: failed to read book {}: {}", book_id, e),
+        }
+    }
+    books
+}
+
+/// Where chapters and scenes sit in reading order, from book.json rather than
+/// their ids, which keep the number they were created with.
+#[derive(Debug, Default)]
+pub struct ReadingOrder {
+    /// (book id, chapter id) → chapter number, counting from 1
+    chapters: HashMap<(String, String), u32>,
+    /// (book id, chapter id, scene id) → position in the chapter, counting from 1
+    scenes: HashMap<(String, String, String), u32>,
+}
+
+impl ReadingOrder {
+    pub fn load(project_dir: &Path) -> Self {
+        let mut order = ReadingOrder::default();
+        for book in load_books(project_dir) {
+            for (i, chapter) in book.sorted_chapters().into_iter().enumerate() {
+                order.chapters.insert((book.id.clone(), chapter.id.clone()), i as u32 + 1);
+                for (j, scene) in chapter.sorted_scenes().into_iter().enumerate() {
+                    order.scenes.insert((book.id.clone(), chapter.id.clone(), scene.id.clone()), j as u32 + 1);
+                }
+            }
+        }
+        order
+    }
+
+    pub fn chapter_number(&self, book_id: &str, chapter_id: &str) -> Option<u32> {
+        self.chapters.get(&(book_id.to_string(), chapter_id.to_string())).copied()
+    }
+
+    pub fn scene_number(&self, book_id: &str, chapter_id: &str, scene_id: &str) -> Option<u32> {
+        self.scenes.get(&(book_id.to_string(), chapter_id.to_string(), scene_id.to_string())).copied()
+    }
+
+    /// Chapters numbered `from..=to` (either end open), as (book id, chapter id).
+    pub fn chapters_in_range(&self, from: Option<u32>, to: Option<u32>) -> Vec<(String, String)> {
+        let mut found: Vec<(String, String)> = self
+            .chapters
+            .iter()
+            .filter(|(_, &n)| from.is_none_or(|f| n >= f) && to.is_none_or(|t| n <= t))
+            .map(|(key, _)| key.clone())
+            .collect();
+        found.sort();
+        found
+    }
+}
+
+/// Load a book, apply `edit`, stamp `modified` and save it.
+pub fn update_book<T>(
+    project_dir: &Path,
+    book_id: &str,
+    edit: impl FnOnce(&mut Book) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let mut book = load_book(project_dir, book_id)?;
+    let result = edit(&mut book)?;
+    book.modified = Utc::now().to_rfc3339();
+    save_book(project_dir, &book)?;
+    Ok(result)
+}
+
+// ─── Migrations ───
+
+/// Each step upgrades book.json from one schema version to the next, on raw JSON
+/// so fields the typed model doesn't know survive.
+type Migration = (&'static str, &'static str, fn(&mut Map<String, Value>));
+const MIGRATIONS: &[Migration] = &[
+    ("1.0.0", "1.1.0", migrate_1_0_0),
+];
+
+/// Bring `data` up to `BOOK_SCHEMA_VERSION`. Returns whether anything changed.
+/// Books written by a newer app version are left as they are.
+pub fn migrate(data: &mut Value) -> Result<bool, AppError> {
+    let obj = data
+        .as_object_mut()
+        .ok_or_else(|| AppError::General("book.json is not a JSON object".into()))?;
+    // Books created before versioning have no version field
+    let mut version = obj.get("version").and_then(|v| v.as_str()).unwrap_or("1.0.0").to_string();
+    let mut changed = false;
+    while let Some(&(_, to, step)) = MIGRATIONS.iter().find(|(from, _, _)| *from == version) {
+        step(obj);
+        version = to.to_string();
+        changed = true;
+    }
+    if changed || !obj.contains_key("version") {
+        obj.insert("version".into(), Value::String(version));
+        changed = true;
+    }
+    Ok(changed)
+}
+
+/// 1.0.0 → 1.1.0: containers may be missing or null, and scenes written back from
+/// `create_scene`'s result carry `scene_type` instead of `type`.
+fn migrate_1_0_0(book: &mut Map<String, Value>) {
+    if !book.get("chapters").is_some_and(|v| v.is_array()) {
+        book.insert("chapters".into(), Value::Array(Vec::new()));
+    }
+    for key in ["front_matter", "back_matter", "settings", "phase_progress"] {
+        if !book.get(key).is_some_and(|v| v.is_object()) {
+            book.remove(key);
+        }
+    }
+    let Some(Value::Array(chapters)) = book.get_mut("chapters") else { return };
+    for chapter in chapters.iter_mut().filter_map(|c| c.as_object_mut()) {
+        if !chapter.get("scenes").is_some_and(|v| v.is_array()) {
+            chapter.insert("scenes".into(), Value::Array(Vec::new()));
+        }
+        let Some(Value::Array(scenes)) = chapter.get_mut("scenes") else { continue };
+        for scene in scenes.iter_mut().filter_map(|s| s.as_object_mut()) {
+            if let Some(scene_type) = scene.remove("scene_type") {
+                scene.entry("type").or_insert(scene_type);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_migrates_and_keeps_unknown_fields() {
+        let dir = std::env::temp_dir().join(format!("saipling-book-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("books").join("book-01")).unwrap();
+        std::fs::write(
+            book_json_path(&dir, "book-01"),
+            r#"{
+                "version": "1.0.0", "id": "book-01", "title": "Tides", "sort_order": 1,
+                "front_matter": null, "back_matter": { "epilogue": true, "appendices": [] }, "cover_art": "cover.png",
+                "phase_progress": { "seed": { "status": "complete", "mood_board": true }, "root": { "status": "in_progress" } },
+                "chapters": [{ "id": "ch-01", "title": "Dawn", "sort_order": 1, "pacing": "slow", "scenes": [
+                    { "id": "scene-01", "title": "Harbour", "sort_order": 1, "scene_type": "reaction", "status": "drafted", "word_count": 900 }
+                ] }],
+                "settings": { "perspective": "first", "tense": "present", "dialect": "british" }
+            }"#,
+        )
+        .unwrap();
+
+        let book = load_book(&dir, "book-01").unwrap();
+        assert_eq!(book.version, BOOK_SCHEMA_VERSION);
+        assert_eq!(book.chapters[0].scenes[0].scene_type, "reaction");
+        assert_eq!(book.phase_progress.seed.status, "complete");
+        assert_eq!(book.phase_progress.root.beats_total, 21);
+        assert_eq!(book.phase_progress.bloom.status, "not_started");
+        assert!(book.front_matter.is_empty());
+        assert_eq!(book.back_matter["epilogue"], true);
+
+        update_book(&dir, "book-01", |book| {
+            book.chapters[0].title = "First Light".into();
+            Ok(())
+        })
+        .unwrap();
+        let saved: Value = serde_json::from_str(&std::fs::read_to_string(book_json_path(&dir, "book-01")).unwrap()).unwrap();
+        assert_eq!(saved["version"], BOOK_SCHEMA_VERSION);
+        assert_eq!(saved["cover_art"], "cover.png");
+        assert_eq!(saved["chapters"][0]["title"], "First Light");
+        assert_eq!(saved["chapters"][0]["pacing"], "slow");
+        assert_eq!(saved["chapters"][0]["scenes"][0]["type"], "reaction");
+        assert!(saved["chapters"][0]["scenes"][0].get("scene_type").is_none());
+        assert_eq!(saved["phase_progress"]["seed"]["mood_board"], true);
+        assert_eq!(saved["settings"]["dialect"], "british");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_newer_schema_is_left_alone() {
+        let mut data = serde_json::json!({ "version": "9.0.0", "id": "book-01", "hologram": { "enabled": true } });
+        assert!(!migrate(&mut data).unwrap());
+        let book: Book = serde_json::from_value(data).unwrap();
+        assert_eq!(book.version, "9.0.0");
+        assert_eq!(serde_json::to_value(&book).unwrap()["hologram"]["enabled"], true);
+    }
+}
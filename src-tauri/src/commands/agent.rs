@@ -5,7 +5,7 @@ use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
 use once_cell::sync::Lazy;
 use crate::error::AppError;
 use crate::commands::config::{get_config, get_api_key_internal};
-use crate::context::skills::{load_skill, list_skills, SkillMeta};
+use crate::context::skills::{load_skill, list_skills, validate_skills as lint_skills, SkillIssue, SkillMeta};
 use crate::context::assembler::{assemble_context, enrich_with_search};
 use crate::context::tokens::{estimate_tokens, estimate_cost, format_cost};
 use crate::agent::claude::{stream_claude, ClaudeMessage};
@@ -134,16 +134,12 @@ fn substitute_prompt_vars(template: &str, project_dir: &PathBuf, scope: &Context
     let mut prompt = template.to_string();
 
     // Genre comes from book.json if a book is in scope
-    let genre_context = if let Some(book_id) = &scope.book {
-        let book_path = project_dir.join("books").join(book_id).join("book.json");
-        std::fs::read_to_string(&book_path)
-            .ok()
-            .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
-            .and_then(|meta| meta.get("genre_id").and_then(|g| g.as_str()).filter(|g| !g.is_empty()).map(|g| format!("The story's genre is: {}", g)))
-            .unwrap_or_default()
-    } else {
-        String::new()
-    };
+    let book = scope.book.as_ref().and_then(|book_id| crate::book_repo::load_book(project_dir, book_id).ok());
+    let genre_context = book
+        .as_ref()
+        .filter(|b| !b.genre_id.is_empty())
+        .map(|b| format!("The story's genre is: {}", b.genre_id))
+        .unwrap_or_default();
 
     let foundation_context = if let Some(book_id) = &scope.book {
         let path = project_dir.join("books").join(book_id).join("phase-1-seed").join("story-foundation.md");
@@ -154,20 +150,16 @@ fn substitute_prompt_vars(template: &str, project_dir: &PathBuf, scope: &Context
     }.unwrap_or_default();
 
     // POV and tense now come from book.json settings
-    let (pov, tense, style_notes) = if let Some(book_id) = &scope.book {
-        let book_path = project_dir.join("books").join(book_id).join("book.json");
-        std::fs::read_to_string(&book_path)
-            .ok()
-            .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
-            .map(|meta| {
-                let pov = meta.pointer("/settings/perspective").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).unwrap_or("third person limited").to_string();
-                let tense = meta.pointer("/settings/tense").and_then(|v| v.as_str()).filter(|s| !s.is_empty()).unwrap_or("past").to_string();
-                let notes = meta.pointer("/settings/writing_style_notes").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                (pov, tense, notes)
-            })
-            .unwrap_or_else(|| ("third person limited".into(), "past".into(), String::new()))
-    } else {
-        ("third person limited".into(), "past".into(), String::new())
+    let (pov, tense, style_notes) = match &book {
+        Some(b) => {
+            let or_default = |s: &str, default: &str| if s.is_empty() { default.to_string() } else { s.to_string() };
+            (
+                or_default(&b.settings.perspective, "third person limited"),
+                or_default(&b.settings.tense, "past"),
+                b.settings.writing_style_notes.clone(),
+            )
+        }
+        None => ("third person limited".into(), "past".into(), String::new()),
     };
 
     let style_block = format!("POV: {}\nTense: {}\n{}", pov, tense, style_notes);
@@ -193,27 +185,7 @@ pub async fn agent_plan(
     let skill_name = if intent.is_empty() { "brainstorm".to_string() } else { intent };
     let skills_path = skills_dir();
 
-    let mut skill_def = load_skill(&skill_name, &skills_path).unwrap_or_else(|_| {
-        crate::context::skills::SkillDefinition {
-            skill: crate::context::skills::SkillMeta {
-                name: "brainstorm".to_string(),
-                display_name: "Brainstorm".to_string(),
-                description: "Creative brainstorming".to_string(),
-                default_model: "claude-sonnet-4-6".to_string(),
-                temperature: 0.9,
-            },
-            context: crate::context::skills::SkillContext {
-                always_include: vec![],
-                when_book: None,
-                optional: None,
-                max_context_tokens: 20000,
-                vector_search: None,
-            },
-            system_prompt: crate::context::skills::SkillPrompt {
-                template: "You are a creative writing assistant. Help the author brainstorm ideas.".to_string(),
-            },
-        }
-    });
+    let mut skill_def = load_skill(&skill_name, &skills_path)?;
 
     // Apply max_context_tokens override if set
     skill_def.context.max_context_tokens = resolve_skill_max_tokens(&skill_name, skill_def.context.max_context_tokens);
@@ -474,6 +446,30 @@ pub fn estimate_context_tokens(
     })
 }
 
+/// Lint all skill definitions: parse errors, unknown models, invalid temperatures,
+/// unknown template variables, invalid vector search modes and dead context patterns.
+#[tauri::command]
+pub fn validate_skills() -> Result<Vec<SkillIssue>, AppError> {
+    let known_models = crate::commands::models::known_model_ids();
+    lint_skills(&skills_dir(), &known_models)
+}
+
+/// Run skill validation once at startup and log any problems.
+pub fn report_skill_issues() {
+    match validate_skills() {
+        Ok(issues) => {
+            for issue in issues {
+                let location = match issue.line {
+                    Some(line) => format!("{}:{}", issue.file, line),
+                    None => issue.file.clone(),
+                };
+                eprintln!("Skill {} in {}: {}", issue.severity, location, issue.message);
+            }
+        }
+        Err(e) => eprintln!("Warning: failed to validate skills: {}", e),
+    }
+}
+
 #[tauri::command]
 pub fn list_available_skills() -> Result<Vec<SkillMeta>, AppError> {
     let skills_path = skills_dir();
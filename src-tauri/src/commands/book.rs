@@ -1,36 +1,9 @@
-use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use chrono::Utc;
+use crate::book_repo::{self, Book};
 use crate::error::AppError;
 use super::project::{BookRef, create_book_dirs};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BookMetadata {
-    pub version: String,
-    pub id: String,
-    pub title: String,
-    #[serde(default)]
-    pub author: String,
-    #[serde(default)]
-    pub genre_id: String,
-    #[serde(default)]
-    pub sub_genre_id: String,
-    pub sort_order: u32,
-    pub created: String,
-    pub modified: String,
-    pub target_word_count: u64,
-    pub current_word_count: u64,
-    pub phase_progress: serde_json::Value,
-    #[serde(default)]
-    pub front_matter: serde_json::Value,
-    #[serde(default)]
-    pub back_matter: serde_json::Value,
-    #[serde(default)]
-    pub chapters: Vec<serde_json::Value>,
-    #[serde(default)]
-    pub settings: serde_json::Value,
-}
-
 #[tauri::command]
 pub fn create_book(
     project_dir: PathBuf,
@@ -41,7 +14,7 @@ pub fn create_book(
     tense: String,
     perspective: String,
     target_word_count: u64,
-) -> Result<BookMetadata, AppError> {
+) -> Result<Book, AppError> {
     // Read project.json to determine next book number
     let project_json_path = project_dir.join("project.json");
     let project_data: serde_json::Value =
@@ -74,34 +47,26 @@ pub fn create_book(
     }
     project_data["modified"] = serde_json::Value::String(Utc::now().to_rfc3339());
 
-    std::fs::write(&project_json_path, serde_json::to_string_pretty(&project_data)?)?;
+    crate::file_io::atomic_write(&project_json_path, serde_json::to_string_pretty(&project_data)?)?;
 
-    // Read and return the book metadata
-    let book_json = std::fs::read_to_string(book_dir.join("book.json"))?;
-    let meta: BookMetadata = serde_json::from_str(&book_json)?;
-    Ok(meta)
+    book_repo::load_book(&project_dir, &book_id)
 }
 
 #[tauri::command]
-pub fn get_book_metadata(project_dir: PathBuf, book_id: String) -> Result<BookMetadata, AppError> {
-    let book_json_path = project_dir.join("books").join(&book_id).join("book.json");
-    if !book_json_path.exists() {
-        return Err(AppError::BookNotFound(book_id));
-    }
-    let data = std::fs::read_to_string(&book_json_path)?;
-    let meta: BookMetadata = serde_json::from_str(&data)?;
-    Ok(meta)
+pub fn get_book_metadata(project_dir: PathBuf, book_id: String) -> Result<Book, AppError> {
+    book_repo::load_book(&project_dir, &book_id)
 }
 
 #[tauri::command]
 pub fn update_book_metadata(
     project_dir: PathBuf,
     book_id: String,
-    metadata: BookMetadata,
+    metadata: Book,
 ) -> Result<(), AppError> {
-    let book_json_path = project_dir.join("books").join(&book_id).join("book.json");
-    std::fs::write(&book_json_path, serde_json::to_string_pretty(&metadata)?)?;
-    Ok(())
+    if metadata.id != book_id {
+        return Err(AppError::General(format!("Metadata for {} passed for book {}", metadata.id, book_id)));
+    }
+    book_repo::save_book(&project_dir, &metadata)
 }
 
 #[tauri::command]
@@ -121,7 +86,7 @@ pub fn delete_book(project_dir: PathBuf, book_id: String) -> Result<(), AppError
     }
 
     project_data["modified"] = serde_json::Value::String(Utc::now().to_rfc3339());
-    std::fs::write(&project_json_path, serde_json::to_string_pretty(&project_data)?)?;
+    crate::file_io::atomic_write(&project_json_path, serde_json::to_string_pretty(&project_data)?)?;
 
     // Delete the book directory
     let book_dir = project_dir.join("books").join(&book_id);
@@ -152,6 +117,6 @@ pub fn reorder_books(project_dir: PathBuf, book_ids: Vec<String>) -> Result<(),
         *books = reordered;
     }
 
-    std::fs::write(&project_json_path, serde_json::to_string_pretty(&project_data)?)?;
+    crate::file_io::atomic_write(&project_json_path, serde_json::to_string_pretty(&project_data)?)?;
     Ok(())
 }
@@ -1,300 +1,875 @@
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use chrono::Utc;
+use crate::book_repo::{self, Book, Chapter, Scene};
 use crate::error::AppError;
+use crate::frontmatter::{set_frontmatter_fields, split_frontmatter};
+use crate::manuscript::{self, set_scene_frontmatter_ids, word_count, CHAPTERS_DIR, CHAPTER_FILE, DRAFT_FILE, OUTLINE_FILE, SNAPSHOTS_DIR};
+use crate::project_path::ProjectPath;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ChapterMetadata {
-    pub id: String,
-    pub title: String,
-    pub sort_order: u32,
-    pub scenes: Vec<SceneMetadata>,
-}
+#[tauri::command]
+pub fn create_chapter(
+    project_dir: PathBuf,
+    book_id: String,
+    title: String,
+) -> Result<Chapter, AppError> {
+    let book_dir = project_dir.join("books").join(&book_id);
+    book_repo::update_book(&project_dir, &book_id, |book| {
+        let ch_num = book.next_chapter_sort_order();
+        let ch_id = book.allocate_chapter_id(&book_dir.join(CHAPTERS_DIR));
+        write_chapter_file(&book_dir.join(CHAPTERS_DIR).join(&ch_id), &book_id, &ch_id, &title, ch_num)?;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SceneMetadata {
-    pub id: String,
-    pub title: String,
-    pub sort_order: u32,
-    pub scene_type: String, // "action" or "reaction"
-    pub status: String,     // "not_started", "outlined", "drafted", "revised"
-    pub word_count: u64,
+        let chapter = Chapter::new(&ch_id, &title, ch_num);
+        book.chapters.push(chapter.clone());
+        Ok(chapter)
+    })
 }
 
 #[tauri::command]
-pub fn create_chapter(
+pub fn create_scene(
     project_dir: PathBuf,
     book_id: String,
+    chapter_id: String,
     title: String,
-) -> Result<ChapterMetadata, AppError> {
+    scene_type: String,
+) -> Result<Scene, AppError> {
     let book_dir = project_dir.join("books").join(&book_id);
-    let book_json_path = book_dir.join("book.json");
-    if !book_json_path.exists() {
-        return Err(AppError::BookNotFound(book_id));
-    }
+    book_repo::update_book(&project_dir, &book_id, |book| {
+        let chapter = book.chapter_mut(&chapter_id)?;
+        let sc_num = chapter.scenes.iter().map(|s| s.sort_order).max().unwrap_or(0) + 1;
+        let sc_id = chapter.allocate_scene_id(&book_dir.join(CHAPTERS_DIR).join(&chapter_id));
+        let sc_dir = book_dir.join(CHAPTERS_DIR).join(&chapter_id).join(&sc_id);
+        std::fs::create_dir_all(&sc_dir)?;
+        std::fs::create_dir_all(sc_dir.join(SNAPSHOTS_DIR))?;
+        std::fs::create_dir_all(sc_dir.join("attachments"))?;
 
-    let mut book_data: serde_json::Value =
-        serde_json::from_str(&std::fs::read_to_string(&book_json_path)?)?;
+        let now = Utc::now();
+        // Create outline.md
+        std::fs::write(
+            sc_dir.join(OUTLINE_FILE),
+            format!(
+                "---\ntype: scene-outline\nbook: {}\nchapter: {}\nscene: {}\ntitle: {}\nscene_type: {}\npov_character: \"\"\nlocation: \"\"\nbeats: []\ncreated: {}\nmodified: {}\nstatus: not_started\n---\n\n# {} — {}\n\n## Scene Type: {}\n\n### Character Goal\n\n\n### Mounting Conflict\n\n\n### Outcome Crisis\n\n\n## Setting Details\n\n\n## Characters Present\n\n\n## Advances Plot By\n\n\n## Advances Character Arc By\n\n\n## Notes / Attachments\n\n",
+                book_id, chapter_id, sc_id, quoted(&title),
+                scene_type.to_uppercase(),
+                now.format("%Y-%m-%d"), now.format("%Y-%m-%d"),
+                format!("Scene {}.{}", chapter_id.replace("ch-", ""), sc_num),
+                title,
+                scene_type.to_uppercase()
+            ),
+        )?;
 
-    let chapters = book_data.get("chapters")
-        .and_then(|v| v.as_array())
-        .map(|a| a.len())
-        .unwrap_or(0);
+        // Create empty draft.md
+        std::fs::write(
+            sc_dir.join(DRAFT_FILE),
+            format!(
+                "---\ntype: scene-draft\nbook: {}\nchapter: {}\nscene: {}\ndraft_number: 1\nword_count: 0\ncreated: {}\nmodified: {}\nstatus: not_started\n---\n\n",
+                book_id, chapter_id, sc_id,
+                now.format("%Y-%m-%d"), now.format("%Y-%m-%d")
+            ),
+        )?;
 
-    let ch_num = chapters + 1;
-    let ch_id = format!("ch-{:02}", ch_num);
-    let ch_dir = book_dir.join("chapters").join(&ch_id);
-    std::fs::create_dir_all(&ch_dir)?;
+        let scene = Scene::new(&sc_id, &title, sc_num, &scene_type);
+        chapter.scenes.push(scene.clone());
+        Ok(scene)
+    })
+}
 
-    let now = Utc::now();
-    // Create _chapter.md
-    std::fs::write(
-        ch_dir.join("_chapter.md"),
-        format!(
-            "---\ntype: chapter\nbook: {}\nchapter: {}\ntitle: \"{}\"\nsort_order: {}\ncreated: {}\nmodified: {}\n---\n\n# {}\n\n",
-            book_id, ch_id, title, ch_num, now.format("%Y-%m-%d"), now.format("%Y-%m-%d"), title
-        ),
-    )?;
+#[tauri::command]
+pub fn reorder_chapters(
+    project_dir: PathBuf,
+    book_id: String,
+    chapter_ids: Vec<String>,
+) -> Result<(), AppError> {
+    book_repo::update_book(&project_dir, &book_id, |book| {
+        let mut reordered = Vec::new();
+        for (i, id) in chapter_ids.iter().enumerate() {
+            if let Some(ch) = book.chapters.iter().find(|c| c.id == *id) {
+                let mut ch = ch.clone();
+                ch.sort_order = i as u32 + 1;
+                reordered.push(ch);
+            }
+        }
+        book.chapters = reordered;
+        Ok(())
+    })
+}
 
-    let chapter_meta = ChapterMetadata {
-        id: ch_id.clone(),
-        title: title.clone(),
-        sort_order: ch_num as u32,
-        scenes: Vec::new(),
-    };
+#[tauri::command]
+pub fn reorder_scenes(
+    project_dir: PathBuf,
+    book_id: String,
+    chapter_id: String,
+    scene_ids: Vec<String>,
+) -> Result<(), AppError> {
+    book_repo::update_book(&project_dir, &book_id, |book| {
+        if let Ok(ch) = book.chapter_mut(&chapter_id) {
+            let mut reordered = Vec::new();
+            for (i, id) in scene_ids.iter().enumerate() {
+                if let Some(sc) = ch.scenes.iter().find(|s| s.id == *id) {
+                    let mut sc = sc.clone();
+                    sc.sort_order = i as u32 + 1;
+                    reordered.push(sc);
+                }
+            }
+            ch.scenes = reordered;
+        }
+        Ok(())
+    })
+}
 
-    // Update book.json
-    let ch_json = serde_json::json!({
-        "id": ch_id,
-        "title": title,
-        "sort_order": ch_num,
-        "scenes": []
-    });
-    if let Some(ch_arr) = book_data.get_mut("chapters").and_then(|v| v.as_array_mut()) {
-        ch_arr.push(ch_json);
+/// Move a scene to `position` in another chapter (or the same one). The scene keeps
+/// its id unless the target chapter already has one like it. Returns the moved scene.
+#[tauri::command]
+pub fn move_scene(
+    project_dir: PathBuf,
+    book_id: String,
+    scene_id: String,
+    from_chapter: String,
+    to_chapter: String,
+    position: usize,
+    to_book_id: Option<String>,
+) -> Result<Scene, AppError> {
+    if let Some(to_book_id) = to_book_id.filter(|b| *b != book_id) {
+        return move_scene_to_book(&project_dir, &book_id, &scene_id, &from_chapter, &to_book_id, &to_chapter, position);
+    }
+    let chapters_dir = manuscript::chapters_dir(&project_dir, &book_id);
+    let scene = book_repo::update_book(&project_dir, &book_id, |book| {
+        let src_ch = book.chapter_mut(&from_chapter)?;
+        let idx = src_ch.scenes.iter().position(|s| s.id == scene_id)
+            .ok_or_else(|| AppError::SceneNotFound(scene_id.clone()))?;
+        let mut scene = src_ch.scenes.remove(idx);
+        src_ch.close_scene_gaps();
+
+        let dst_ch = book.chapter_mut(&to_chapter)?;
+        if from_chapter != to_chapter {
+            let dst_dir = chapters_dir.join(&to_chapter);
+            if dst_ch.scenes.iter().any(|s| s.id == scene.id) || dst_dir.join(&scene.id).exists() {
+                scene.id = dst_ch.allocate_scene_id(&dst_dir);
+            }
+
+            // Move the physical directory
+            let from_dir = chapters_dir.join(&from_chapter).join(&scene_id);
+            let to_dir = dst_dir.join(&scene.id);
+            if from_dir.exists() {
+                std::fs::create_dir_all(&dst_dir)?;
+                // Use copy + delete since rename may fail across drives
+                copy_dir_recursive(&from_dir, &to_dir)?;
+                std::fs::remove_dir_all(&from_dir)?;
+                set_scene_frontmatter_ids(&to_dir, &to_chapter, &scene.id)?;
+            }
+        }
+
+        Ok(insert_scene_at(dst_ch, scene, position))
+    })?;
+    if from_chapter != to_chapter {
+        let to_dir = chapters_dir.join(&to_chapter).join(&scene.id);
+        super::writing::record_drafts_moved(&project_dir, &[(chapters_dir.join(&from_chapter).join(&scene_id), to_dir)]);
     }
-    book_data["modified"] = serde_json::Value::String(now.to_rfc3339());
-    std::fs::write(&book_json_path, serde_json::to_string_pretty(&book_data)?)?;
+    Ok(scene)
+}
 
-    Ok(chapter_meta)
+/// Insert a scene at `position` in a chapter's reading order and renumber the
+/// chapter's scenes. Returns the scene with its new sort order.
+fn insert_scene_at(chapter: &mut Chapter, mut scene: Scene, position: usize) -> Scene {
+    chapter.close_scene_gaps();
+    let pos = position.min(chapter.scenes.len());
+    scene.sort_order = pos as u32 + 1;
+    chapter.scenes.insert(pos, scene.clone());
+    for (i, sc) in chapter.scenes.iter_mut().enumerate() {
+        sc.sort_order = i as u32 + 1;
+    }
+    scene
 }
 
+/// Move a chapter and its scenes to the trash and close the gap in the reading order.
+/// The folder is trashed first, so a failed trash leaves book.json listing it.
 #[tauri::command]
-pub fn create_scene(
+pub fn delete_chapter(project_dir: PathBuf, book_id: String, chapter_id: String) -> Result<(), AppError> {
+    let ch_dir = manuscript::chapter_dir(&project_dir, &book_id, &chapter_id);
+    book_repo::load_book(&project_dir, &book_id)?.chapter_mut(&chapter_id)?;
+    if ch_dir.exists() {
+        let words = super::writing::draft_words_under(&ch_dir);
+        super::filesystem::delete_entry(ch_dir.clone())?;
+        super::writing::record_drafts_removed(&project_dir, &ch_dir, words);
+    }
+    book_repo::update_book(&project_dir, &book_id, |book| {
+        book.chapters.retain(|c| c.id != chapter_id);
+        book.close_chapter_gaps();
+        Ok(())
+    })
+}
+
+/// Move a scene to the trash and close the gap in its chapter's reading order,
+/// trashing the folder first as `delete_chapter` does.
+#[tauri::command]
+pub fn delete_scene(
     project_dir: PathBuf,
     book_id: String,
     chapter_id: String,
-    title: String,
-    scene_type: String,
-) -> Result<SceneMetadata, AppError> {
-    let book_dir = project_dir.join("books").join(&book_id);
-    let book_json_path = book_dir.join("book.json");
-    if !book_json_path.exists() {
-        return Err(AppError::BookNotFound(book_id.clone()));
+    scene_id: String,
+) -> Result<(), AppError> {
+    let sc_dir = manuscript::scene_dir(&project_dir, &book_id, &chapter_id, &scene_id);
+    if !book_repo::load_book(&project_dir, &book_id)?.chapter_mut(&chapter_id)?.scenes.iter().any(|s| s.id == scene_id) {
+        return Err(AppError::SceneNotFound(scene_id));
     }
+    if sc_dir.exists() {
+        let words = super::writing::draft_words_under(&sc_dir);
+        super::filesystem::delete_entry(sc_dir.clone())?;
+        super::writing::record_drafts_removed(&project_dir, &sc_dir, words);
+    }
+    book_repo::update_book(&project_dir, &book_id, |book| {
+        let chapter = book.chapter_mut(&chapter_id)?;
+        chapter.scenes.retain(|s| s.id != scene_id);
+        chapter.close_scene_gaps();
+        Ok(())
+    })
+}
 
-    let mut book_data: serde_json::Value =
-        serde_json::from_str(&std::fs::read_to_string(&book_json_path)?)?;
+/// Rename chapter and scene folders to match the reading order (`ch-01`, `ch-02`, …
+/// and `scene-01`, … within each chapter), updating book.json and the ids in each
+/// file's frontmatter. Context settings and search index entries follow the folders.
+/// If a rename or saving book.json fails, the folders are renamed back. Returns the
+/// renumbered book.
+#[tauri::command]
+pub fn renumber_book(project_dir: PathBuf, book_id: String) -> Result<Book, AppError> {
+    let chapters_dir = manuscript::chapters_dir(&project_dir, &book_id);
+    let mut renamed = Renames::default();
+    let result = book_repo::update_book(&project_dir, &book_id, |book| {
+        book.close_chapter_gaps();
+        for chapter in &mut book.chapters {
+            chapter.close_scene_gaps();
+        }
 
-    // Find the chapter
-    let chapters = book_data.get("chapters")
-        .and_then(|v| v.as_array())
-        .ok_or_else(|| AppError::ChapterNotFound(chapter_id.clone()))?;
+        // Check every target first so a stray folder can't leave the book half renamed
+        let chapter_renames: Vec<(String, String)> = book.chapters.iter()
+            .map(|c| (c.id.clone(), format!("ch-{:02}", c.sort_order)))
+            .collect();
+        check_renames(&chapters_dir, &chapter_renames)?;
+        let scene_renames: Vec<Vec<(String, String)>> = book.chapters.iter()
+            .map(|c| c.scenes.iter().map(|s| (s.id.clone(), format!("scene-{:02}", s.sort_order))).collect())
+            .collect();
+        for (chapter, renames) in book.chapters.iter().zip(&scene_renames) {
+            check_renames(&chapters_dir.join(&chapter.id), renames)?;
+        }
 
-    let ch_idx = chapters.iter().position(|ch| {
-        ch.get("id").and_then(|v| v.as_str()) == Some(&chapter_id)
-    }).ok_or_else(|| AppError::ChapterNotFound(chapter_id.clone()))?;
+        renamed.rename_all(&chapters_dir, &chapter_renames)?;
+        for ((chapter, (_, new_ch_id)), renames) in book.chapters.iter_mut().zip(&chapter_renames).zip(&scene_renames) {
+            chapter.id = new_ch_id.clone();
+            renamed.rename_all(&chapters_dir.join(&chapter.id), renames)?;
+            for (scene, (_, new_sc_id)) in chapter.scenes.iter_mut().zip(renames) {
+                scene.id = new_sc_id.clone();
+            }
+            chapter.last_scene_number = chapter.scenes.len() as u32;
+        }
+        book.last_chapter_number = book.chapters.len() as u32;
+        Ok(book.clone())
+    });
+    let book = match result {
+        Ok(book) => book,
+        Err(e) => {
+            renamed.undo();
+            return Err(e);
+        }
+    };
 
-    let existing_scenes = chapters[ch_idx].get("scenes")
-        .and_then(|v| v.as_array())
-        .map(|a| a.len())
-        .unwrap_or(0);
+    // book.json and the folders agree now; what follows only updates references to them
+    for chapter in book.sorted_chapters() {
+        let ch_dir = chapters_dir.join(&chapter.id);
+        let mut updated = set_frontmatter_fields(&ch_dir.join(CHAPTER_FILE), &[
+            ("chapter", chapter.id.clone()),
+            ("sort_order", chapter.sort_order.to_string()),
+        ]);
+        for scene in &chapter.scenes {
+            updated = updated.and_then(|_| set_scene_frontmatter_ids(&ch_dir.join(&scene.id), &chapter.id, &scene.id));
+        }
+        if let Err(e) = updated {
+            eprintln!("Warning: failed to update the frontmatter of {}: {}", chapter.id, e);
+        }
+    }
+    follow_renames(&project_dir, &renamed.done);
+    Ok(book)
+}
 
-    let sc_num = existing_scenes + 1;
-    let sc_id = format!("scene-{:02}", sc_num);
-    let sc_dir = book_dir.join("chapters").join(&chapter_id).join(&sc_id);
-    std::fs::create_dir_all(&sc_dir)?;
-    std::fs::create_dir_all(sc_dir.join(".drafts"))?;
-    std::fs::create_dir_all(sc_dir.join("attachments"))?;
+/// Point references to renamed folders, writing history included, at their new
+/// paths, once book.json lists them there. Failures only warn.
+fn follow_renames(project_dir: &Path, done: &[(PathBuf, PathBuf)]) {
+    for (from, to) in done {
+        if let Err(e) = follow_move(project_dir, from, to) {
+            eprintln!("Warning: failed to update references to {}: {}", from.to_string_lossy(), e);
+        }
+    }
+    super::writing::record_drafts_moved(project_dir, done);
+}
 
-    let now = Utc::now();
-    // Create outline.md
-    std::fs::write(
-        sc_dir.join("outline.md"),
-        format!(
-            "---\ntype: scene-outline\nbook: {}\nchapter: {}\nscene: {}\ntitle: \"{}\"\nscene_type: {}\npov_character: \"\"\nlocation: \"\"\nbeats: []\ncreated: {}\nmodified: {}\nstatus: not_started\n---\n\n# {} — {}\n\n## Scene Type: {}\n\n### Character Goal\n\n\n### Mounting Conflict\n\n\n### Outcome Crisis\n\n\n## Setting Details\n\n\n## Characters Present\n\n\n## Advances Plot By\n\n\n## Advances Character Arc By\n\n\n## Notes / Attachments\n\n",
-            book_id, chapter_id, sc_id, title,
-            scene_type.to_uppercase(),
-            now.format("%Y-%m-%d"), now.format("%Y-%m-%d"),
-            format!("Scene {}.{}", chapter_id.replace("ch-", ""), sc_num),
-            title,
-            scene_type.to_uppercase()
-        ),
-    )?;
+/// Fail if renaming `from → to` inside `dir` would overwrite a folder that isn't
+/// itself being renamed.
+fn check_renames(dir: &Path, renames: &[(String, String)]) -> Result<(), AppError> {
+    for (from, to) in renames {
+        if from != to && dir.join(to).exists() && !renames.iter().any(|(f, _)| f == to) {
+            return Err(AppError::General(format!(
+                "Cannot renumber {} to {}: {} already exists",
+                from, to, dir.join(to).to_string_lossy()
+            )));
+        }
+    }
+    Ok(())
+}
 
-    // Create empty draft.md
-    std::fs::write(
-        sc_dir.join("draft.md"),
-        format!(
-            "---\ntype: scene-draft\nbook: {}\nchapter: {}\nscene: {}\ndraft_number: 1\nword_count: 0\ncreated: {}\nmodified: {}\nstatus: not_started\n---\n\n",
-            book_id, chapter_id, sc_id,
-            now.format("%Y-%m-%d"), now.format("%Y-%m-%d")
-        ),
-    )?;
+/// Folder renames done so far, in order, so they can be undone.
+#[derive(Default)]
+struct Renames {
+    done: Vec<(PathBuf, PathBuf)>,
+}
 
-    let scene_meta = SceneMetadata {
-        id: sc_id.clone(),
-        title: title.clone(),
-        sort_order: sc_num as u32,
-        scene_type: scene_type.clone(),
-        status: "not_started".to_string(),
-        word_count: 0,
-    };
+impl Renames {
+    fn rename(&mut self, from: PathBuf, to: PathBuf) -> Result<(), AppError> {
+        std::fs::rename(&from, &to)?;
+        crate::watcher::note_own_write(&from);
+        crate::watcher::note_own_write(&to);
+        self.done.push((from, to));
+        Ok(())
+    }
 
-    // Update book.json
-    let sc_json = serde_json::json!({
-        "id": sc_id,
-        "title": title,
-        "sort_order": sc_num,
-        "type": scene_type,
-        "status": "not_started",
-        "word_count": 0
-    });
-    if let Some(ch_arr) = book_data.get_mut("chapters").and_then(|v| v.as_array_mut()) {
-        if let Some(scenes) = ch_arr[ch_idx].get_mut("scenes").and_then(|v| v.as_array_mut()) {
-            scenes.push(sc_json);
+    /// Rename folders in `dir`, via temporary names so swapped ids don't collide.
+    fn rename_all(&mut self, dir: &Path, renames: &[(String, String)]) -> Result<(), AppError> {
+        let moving: Vec<&(String, String)> = renames.iter()
+            .filter(|(from, to)| from != to && dir.join(from).exists())
+            .collect();
+        for (from, _) in &moving {
+            self.rename(dir.join(from), dir.join(format!(".renumber-{}", from)))?;
+        }
+        for (from, to) in &moving {
+            self.rename(dir.join(format!(".renumber-{}", from)), dir.join(to))?;
         }
+        Ok(())
     }
-    book_data["modified"] = serde_json::Value::String(now.to_rfc3339());
-    std::fs::write(&book_json_path, serde_json::to_string_pretty(&book_data)?)?;
 
-    Ok(scene_meta)
+    /// Rename everything back, latest first.
+    fn undo(self) {
+        for (from, to) in self.done.into_iter().rev() {
+            match std::fs::rename(&to, &from) {
+                Ok(()) => {
+                    crate::watcher::note_own_write(&to);
+                    crate::watcher::note_own_write(&from);
+                }
+                Err(e) => eprintln!("Warning: failed to rename {} back: {}", to.to_string_lossy(), e),
+            }
+        }
+    }
 }
 
+// ─── Moving between books ───
+
+/// Move a chapter, with its scenes, to `to_book_id` at `position` in that book's
+/// reading order. It keeps its id unless the other book already uses it.
 #[tauri::command]
-pub fn reorder_chapters(
+pub fn move_chapter_to_book(
     project_dir: PathBuf,
     book_id: String,
-    chapter_ids: Vec<String>,
-) -> Result<(), AppError> {
-    let book_json_path = project_dir.join("books").join(&book_id).join("book.json");
-    let mut book_data: serde_json::Value =
-        serde_json::from_str(&std::fs::read_to_string(&book_json_path)?)?;
+    chapter_id: String,
+    to_book_id: String,
+    position: usize,
+) -> Result<Chapter, AppError> {
+    if to_book_id == book_id {
+        return Err(AppError::General("The chapter is already in this book".into()));
+    }
+    let from_dir = manuscript::chapter_dir(&project_dir, &book_id, &chapter_id);
+    let to_chapters = manuscript::chapters_dir(&project_dir, &to_book_id);
+    let (source, target) = (book_repo::load_book(&project_dir, &book_id)?, book_repo::load_book(&project_dir, &to_book_id)?);
 
-    if let Some(chapters) = book_data.get_mut("chapters").and_then(|v| v.as_array_mut()) {
-        let mut reordered = Vec::new();
-        for (i, id) in chapter_ids.iter().enumerate() {
-            if let Some(ch) = chapters.iter().find(|c| {
-                c.get("id").and_then(|v| v.as_str()) == Some(id)
-            }) {
-                let mut ch = ch.clone();
-                ch["sort_order"] = serde_json::Value::Number((i as u32 + 1).into());
-                reordered.push(ch);
+    let (mut book, mut to_book) = (source.clone(), target.clone());
+    let idx = book.chapters.iter().position(|c| c.id == chapter_id)
+        .ok_or_else(|| AppError::ChapterNotFound(chapter_id.clone()))?;
+    let mut chapter = book.chapters.remove(idx);
+    book.close_chapter_gaps();
+    if to_book.chapters.iter().any(|c| c.id == chapter.id) || to_chapters.join(&chapter.id).exists() {
+        chapter.id = to_book.allocate_chapter_id(&to_chapters);
+    }
+    to_book.close_chapter_gaps();
+    let pos = position.min(to_book.chapters.len());
+    to_book.chapters.insert(pos, chapter.clone());
+    for (i, ch) in to_book.chapters.iter_mut().enumerate() {
+        ch.sort_order = i as u32 + 1;
+    }
+
+    let to_dir = to_chapters.join(&chapter.id);
+    save_move(&project_dir, &from_dir, &to_dir, [&source, &target], [book, to_book.clone()])?;
+    let ids = set_frontmatter_fields(&to_dir.join(CHAPTER_FILE), &[("chapter", chapter.id.clone())]);
+    let ids = chapter.scenes.iter().fold(ids, |ids, scene| {
+        ids.and_then(|_| set_scene_frontmatter_ids(&to_dir.join(&scene.id), &chapter.id, &scene.id))
+    });
+    if let Err(e) = ids {
+        eprintln!("Warning: failed to update the frontmatter of {}: {}", chapter.id, e);
+    }
+    Ok(to_book.chapters[pos].clone())
+}
+
+/// `move_scene` into a chapter of another book.
+fn move_scene_to_book(
+    project_dir: &Path,
+    book_id: &str,
+    scene_id: &str,
+    from_chapter: &str,
+    to_book_id: &str,
+    to_chapter: &str,
+    position: usize,
+) -> Result<Scene, AppError> {
+    let from_dir = manuscript::scene_dir(project_dir, book_id, from_chapter, scene_id);
+    let dst_dir = manuscript::chapter_dir(project_dir, to_book_id, to_chapter);
+    let (source, target) = (book_repo::load_book(project_dir, book_id)?, book_repo::load_book(project_dir, to_book_id)?);
+
+    let (mut book, mut to_book) = (source.clone(), target.clone());
+    let src_ch = book.chapter_mut(from_chapter)?;
+    let idx = src_ch.scenes.iter().position(|s| s.id == scene_id)
+        .ok_or_else(|| AppError::SceneNotFound(scene_id.to_string()))?;
+    let mut scene = src_ch.scenes.remove(idx);
+    src_ch.close_scene_gaps();
+    let dst_ch = to_book.chapter_mut(to_chapter)?;
+    if dst_ch.scenes.iter().any(|s| s.id == scene.id) || dst_dir.join(&scene.id).exists() {
+        scene.id = dst_ch.allocate_scene_id(&dst_dir);
+    }
+    let scene = insert_scene_at(dst_ch, scene, position);
+
+    let to_dir = dst_dir.join(&scene.id);
+    save_move(project_dir, &from_dir, &to_dir, [&source, &target], [book, to_book])?;
+    if let Err(e) = set_scene_frontmatter_ids(&to_dir, to_chapter, &scene.id) {
+        eprintln!("Warning: failed to update the frontmatter of {}: {}", scene.id, e);
+    }
+    Ok(scene)
+}
+
+/// Save both books of a move between books, the source first, then move the folder.
+/// If the target can't be saved or the folder can't be moved, both books are put
+/// back as they were, so the item is never left listed in neither or both.
+fn save_move(project_dir: &Path, from: &Path, to: &Path, before: [&Book; 2], after: [Book; 2]) -> Result<(), AppError> {
+    let now = Utc::now().to_rfc3339();
+    let [mut source, mut target] = after;
+    source.modified = now.clone();
+    target.modified = now;
+    book_repo::save_book(project_dir, &source)?;
+    let moved = book_repo::save_book(project_dir, &target)
+        .and_then(|_| move_to_book(project_dir, from, to, &target.id));
+    if let Err(e) = moved {
+        for book in before {
+            if let Err(restore) = book_repo::save_book(project_dir, book) {
+                eprintln!("Warning: failed to restore book {}: {}", book.id, restore);
             }
         }
-        *chapters = reordered;
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Move a chapter or scene folder, draft history included, into another book. Its
+/// `book:` frontmatter, context settings, search index entries and writing history
+/// follow it, so the moved files are not re-embedded. Only the move itself can fail; what follows it
+/// is reported as a warning.
+fn move_to_book(project_dir: &Path, from: &Path, to: &Path, to_book_id: &str) -> Result<(), AppError> {
+    if from.exists() {
+        super::filesystem::move_entry(from.to_path_buf(), to.to_path_buf())?;
+        if let Err(e) = set_book_frontmatter(to, to_book_id) {
+            eprintln!("Warning: failed to update the book of {}: {}", to.to_string_lossy(), e);
+        }
+    }
+    if let Err(e) = follow_move(project_dir, from, to) {
+        eprintln!("Warning: failed to update context settings for {}: {}", to.to_string_lossy(), e);
+    }
+    super::writing::record_drafts_moved(project_dir, &[(from.to_path_buf(), to.to_path_buf())]);
+    crate::watcher::queue_removed(project_dir, from);
+    crate::watcher::queue_created(project_dir, to);
+    Ok(())
+}
+
+/// Point context settings and search index entries under `from` at `to`, where the
+/// folder has moved, so its files keep their settings and aren't re-embedded.
+fn follow_move(project_dir: &Path, from: &Path, to: &Path) -> Result<(), AppError> {
+    if let (Some(from_rel), Some(to_rel)) =
+        (ProjectPath::from_absolute(project_dir, from), ProjectPath::from_absolute(project_dir, to))
+    {
+        crate::context::assembler::move_context_settings(project_dir, &from_rel, &to_rel)?;
+        if let Err(e) = crate::context::vector::indexer::move_indexed_dir(
+            &project_dir.to_path_buf(), from_rel.as_str(), to_rel.as_str(),
+        ) {
+            eprintln!("Warning: failed to move index entries for {}: {}", from_rel, e);
+        }
     }
+    Ok(())
+}
 
-    std::fs::write(&book_json_path, serde_json::to_string_pretty(&book_data)?)?;
+/// Point the `book:` frontmatter of every markdown file under `dir` at `book_id`.
+fn set_book_frontmatter(dir: &Path, book_id: &str) -> Result<(), AppError> {
+    for entry in std::fs::read_dir(dir)?.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        if path.is_dir() {
+            set_book_frontmatter(&path, book_id)?;
+        } else if path.extension().is_some_and(|ext| ext == "md") {
+            set_frontmatter_fields(&path, &[("book", book_id.to_string())])?;
+        }
+    }
     Ok(())
 }
 
+// ─── Split & merge ───
+
+/// Split a scene's draft at `at_offset`, counted in characters of the draft body and
+/// moved forward to the next line start so no line is cut. The rest of the draft
+/// becomes a new scene right after this one, with copies of the outline, attachments
+/// and draft history. The unsplit draft is kept as a snapshot. The new scene is written
+/// before the draft is cut, and if anything fails the draft is put back and the new
+/// folder removed. Returns the new scene.
 #[tauri::command]
-pub fn reorder_scenes(
+pub fn split_scene(
     project_dir: PathBuf,
     book_id: String,
     chapter_id: String,
-    scene_ids: Vec<String>,
-) -> Result<(), AppError> {
-    let book_json_path = project_dir.join("books").join(&book_id).join("book.json");
-    let mut book_data: serde_json::Value =
-        serde_json::from_str(&std::fs::read_to_string(&book_json_path)?)?;
-
-    if let Some(chapters) = book_data.get_mut("chapters").and_then(|v| v.as_array_mut()) {
-        if let Some(ch) = chapters.iter_mut().find(|c| {
-            c.get("id").and_then(|v| v.as_str()) == Some(&chapter_id)
-        }) {
-            if let Some(scenes) = ch.get_mut("scenes").and_then(|v| v.as_array_mut()) {
-                let mut reordered = Vec::new();
-                for (i, id) in scene_ids.iter().enumerate() {
-                    if let Some(sc) = scenes.iter().find(|s| {
-                        s.get("id").and_then(|v| v.as_str()) == Some(id)
-                    }) {
-                        let mut sc = sc.clone();
-                        sc["sort_order"] = serde_json::Value::Number((i as u32 + 1).into());
-                        reordered.push(sc);
-                    }
+    scene_id: String,
+    at_offset: usize,
+    new_title: Option<String>,
+) -> Result<Scene, AppError> {
+    let ch_dir = manuscript::chapter_dir(&project_dir, &book_id, &chapter_id);
+    let draft_path = ch_dir.join(&scene_id).join(DRAFT_FILE);
+    let mut created = None;
+    let mut unsplit = None;
+    let result = book_repo::update_book(&project_dir, &book_id, |book| {
+        let chapter = book.chapter_mut(&chapter_id)?;
+        chapter.close_scene_gaps();
+        let idx = chapter.scenes.iter().position(|s| s.id == scene_id)
+            .ok_or_else(|| AppError::SceneNotFound(scene_id.clone()))?;
+
+        let sc_dir = ch_dir.join(&scene_id);
+        let content = std::fs::read_to_string(&draft_path)?;
+        let (head, body) = split_frontmatter(&content);
+        let cut = line_boundary_at(body, at_offset);
+        let (first, second) = (body[..cut].trim_end(), body[cut..].trim());
+        if first.trim().is_empty() || second.is_empty() {
+            return Err(AppError::General("A split must leave text in both scenes".into()));
+        }
+
+        // Snapshots the unsplit draft, so the new scene's copied history has it too
+        super::draft::snapshot_draft(&draft_path)?;
+        let new_id = chapter.allocate_scene_id(&ch_dir);
+        let new_dir = ch_dir.join(&new_id);
+        created = Some(new_dir.clone());
+        copy_dir_recursive(&sc_dir, &new_dir)?;
+        crate::file_io::atomic_write(&new_dir.join(DRAFT_FILE), with_body(head, second))?;
+        set_scene_frontmatter_ids(&new_dir, &chapter_id, &new_id)?;
+        let original = &mut chapter.scenes[idx];
+        let title = new_title.unwrap_or_else(|| format!("{} (part 2)", original.title));
+        set_frontmatter_fields(&new_dir.join(OUTLINE_FILE), &[("title", quoted(&title))])?;
+        set_frontmatter_fields(&new_dir.join(DRAFT_FILE), &[("word_count", word_count(second).to_string())])?;
+
+        // Cut the draft last, once the rest of its text is safe in the new scene
+        unsplit = Some(content.clone());
+        crate::file_io::atomic_write(&draft_path, with_body(head, first))?;
+        set_frontmatter_fields(&draft_path, &[("word_count", word_count(first).to_string())])?;
+        original.word_count = word_count(first);
+        let mut scene = original.clone();
+        scene.id = new_id;
+        scene.title = title;
+        scene.word_count = word_count(second);
+        scene.extra.clear();
+
+        chapter.scenes.insert(idx + 1, scene);
+        chapter.close_scene_gaps();
+        Ok(chapter.scenes[idx + 1].clone())
+    });
+    let new_scene = match result {
+        Ok(scene) => scene,
+        Err(e) => {
+            if let Some(content) = unsplit {
+                if let Err(restore) = crate::file_io::atomic_write(&draft_path, content) {
+                    eprintln!("Warning: failed to restore the draft of {}: {}", scene_id, restore);
                 }
-                *scenes = reordered;
             }
+            if let Some(new_dir) = created.filter(|dir| dir.exists()) {
+                if let Err(remove) = std::fs::remove_dir_all(&new_dir) {
+                    eprintln!("Warning: failed to remove {}: {}", new_dir.to_string_lossy(), remove);
+                }
+            }
+            return Err(e);
         }
+    };
+    crate::watcher::queue_created(&project_dir, &ch_dir.join(&scene_id));
+    crate::watcher::queue_created(&project_dir, &ch_dir.join(&new_scene.id));
+    // Words only moved between scenes; both drafts get a new baseline
+    let new_draft = ch_dir.join(&new_scene.id).join(DRAFT_FILE);
+    super::writing::record_drafts_rearranged(&project_dir, &[draft_path.clone(), new_draft], None);
+    super::progress::recompute_after_save(&draft_path);
+    Ok(new_scene)
+}
+
+/// Merge `next_scene_id` into `scene_id` in the same chapter: its draft and outline
+/// are appended, and its attachments and draft history moved over. The merged-away
+/// scene's folder goes to the trash after book.json is saved. Returns the merged scene.
+#[tauri::command]
+pub fn merge_scenes(
+    project_dir: PathBuf,
+    book_id: String,
+    chapter_id: String,
+    scene_id: String,
+    next_scene_id: String,
+) -> Result<Scene, AppError> {
+    if scene_id == next_scene_id {
+        return Err(AppError::General("Cannot merge a scene with itself".into()));
     }
+    let ch_dir = manuscript::chapter_dir(&project_dir, &book_id, &chapter_id);
+    let next_dir = ch_dir.join(&next_scene_id);
+    let merged = book_repo::update_book(&project_dir, &book_id, |book| {
+        let chapter = book.chapter_mut(&chapter_id)?;
+        let idx = chapter.scenes.iter().position(|s| s.id == scene_id)
+            .ok_or_else(|| AppError::SceneNotFound(scene_id.clone()))?;
+        let next_idx = chapter.scenes.iter().position(|s| s.id == next_scene_id)
+            .ok_or_else(|| AppError::SceneNotFound(next_scene_id.clone()))?;
+        let sc_dir = ch_dir.join(&scene_id);
+        std::fs::create_dir_all(&sc_dir)?;
 
-    std::fs::write(&book_json_path, serde_json::to_string_pretty(&book_data)?)?;
-    Ok(())
+        // Draft: this scene's text, then the next one's. Snapshots the unmerged draft.
+        let draft = std::fs::read_to_string(sc_dir.join(DRAFT_FILE)).unwrap_or_default();
+        let next_draft = std::fs::read_to_string(next_dir.join(DRAFT_FILE)).unwrap_or_default();
+        let (head, body) = split_frontmatter(&draft);
+        let (_, next_body) = split_frontmatter(&next_draft);
+        let merged: String = [body.trim(), next_body.trim()]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        super::draft::snapshot_draft(&sc_dir.join(DRAFT_FILE))?;
+        crate::file_io::atomic_write(&sc_dir.join(DRAFT_FILE), with_body(head, &merged))?;
+        set_frontmatter_fields(&sc_dir.join(DRAFT_FILE), &[("word_count", word_count(&merged).to_string())])?;
+        append_body(&sc_dir.join(OUTLINE_FILE), &next_dir.join(OUTLINE_FILE))?;
+        move_children(&next_dir.join(SNAPSHOTS_DIR), &sc_dir.join(SNAPSHOTS_DIR), &next_scene_id)?;
+        move_children(&next_dir.join("attachments"), &sc_dir.join("attachments"), &next_scene_id)?;
+
+        chapter.scenes[idx].word_count = word_count(&merged);
+        chapter.scenes.remove(next_idx);
+        chapter.close_scene_gaps();
+        crate::watcher::queue_created(&project_dir, &sc_dir);
+        chapter.scenes.iter().find(|s| s.id == scene_id).cloned()
+            .ok_or_else(|| AppError::SceneNotFound(scene_id.clone()))
+    })?;
+    trash_merged(&project_dir, &next_dir);
+    let draft_path = ch_dir.join(&scene_id).join(DRAFT_FILE);
+    super::writing::record_drafts_rearranged(&project_dir, std::slice::from_ref(&draft_path), Some(&next_dir));
+    super::progress::recompute_after_save(&draft_path);
+    Ok(merged)
 }
 
+/// Split a chapter before `at_scene`: that scene and every later one move, keeping
+/// their ids, to a new chapter right after this one. If a move or saving book.json
+/// fails, the scenes are moved back and the new chapter folder removed. Returns the
+/// new chapter.
 #[tauri::command]
-pub fn move_scene(
+pub fn split_chapter(
     project_dir: PathBuf,
     book_id: String,
-    scene_id: String,
-    from_chapter: String,
-    to_chapter: String,
-    position: usize,
-) -> Result<(), AppError> {
-    let book_dir = project_dir.join("books").join(&book_id);
-    let book_json_path = book_dir.join("book.json");
-    let mut book_data: serde_json::Value =
-        serde_json::from_str(&std::fs::read_to_string(&book_json_path)?)?;
-
-    // Move the physical directory
-    let from_dir = book_dir.join("chapters").join(&from_chapter).join(&scene_id);
-    let to_dir = book_dir.join("chapters").join(&to_chapter).join(&scene_id);
-    if from_dir.exists() {
-        if let Some(parent) = to_dir.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
-        // Use copy + delete since rename may fail across drives
-        copy_dir_recursive(&from_dir, &to_dir)?;
-        std::fs::remove_dir_all(&from_dir)?;
-    }
-
-    // Update book.json: remove from source chapter, insert in target
-    if let Some(chapters) = book_data.get_mut("chapters").and_then(|v| v.as_array_mut()) {
-        let mut scene_data = None;
-
-        // Remove from source
-        if let Some(src_ch) = chapters.iter_mut().find(|c| {
-            c.get("id").and_then(|v| v.as_str()) == Some(&from_chapter)
-        }) {
-            if let Some(scenes) = src_ch.get_mut("scenes").and_then(|v| v.as_array_mut()) {
-                if let Some(idx) = scenes.iter().position(|s| {
-                    s.get("id").and_then(|v| v.as_str()) == Some(&scene_id)
-                }) {
-                    scene_data = Some(scenes.remove(idx));
-                }
+    chapter_id: String,
+    at_scene: String,
+    new_title: Option<String>,
+) -> Result<Chapter, AppError> {
+    let chapters_dir = manuscript::chapters_dir(&project_dir, &book_id);
+    let mut renamed = Renames::default();
+    let mut created = None;
+    let result = book_repo::update_book(&project_dir, &book_id, |book| {
+        book.close_chapter_gaps();
+        let ch_idx = book.chapters.iter().position(|c| c.id == chapter_id)
+            .ok_or_else(|| AppError::ChapterNotFound(chapter_id.clone()))?;
+        let chapter = &mut book.chapters[ch_idx];
+        chapter.close_scene_gaps();
+        let at = chapter.scenes.iter().position(|s| s.id == at_scene)
+            .ok_or_else(|| AppError::SceneNotFound(at_scene.clone()))?;
+        if at == 0 {
+            return Err(AppError::General("A split must leave scenes in both chapters".into()));
+        }
+        let moving = chapter.scenes.split_off(at);
+        let title = new_title.unwrap_or_else(|| format!("{} (part 2)", chapter.title));
+        let sort_order = chapter.sort_order + 1;
+
+        let new_id = book.allocate_chapter_id(&chapters_dir);
+        let new_dir = chapters_dir.join(&new_id);
+        created = Some(new_dir.clone());
+        write_chapter_file(&new_dir, &book_id, &new_id, &title, sort_order)?;
+        let mut new_chapter = Chapter::new(&new_id, &title, sort_order);
+        for scene in moving {
+            renamed.rename(chapters_dir.join(&chapter_id).join(&scene.id), new_dir.join(&scene.id))?;
+            new_chapter.scenes.push(scene);
+        }
+        new_chapter.close_scene_gaps();
+
+        for later in book.chapters.iter_mut().filter(|c| c.sort_order >= sort_order) {
+            later.sort_order += 1;
+        }
+        book.chapters.insert(ch_idx + 1, new_chapter.clone());
+        Ok(new_chapter)
+    });
+    let new_chapter = match result {
+        Ok(chapter) => chapter,
+        Err(e) => {
+            renamed.undo();
+            if let Some(new_dir) = created {
+                let _ = std::fs::remove_file(new_dir.join(CHAPTER_FILE));
+                let _ = std::fs::remove_dir(&new_dir);
+            }
+            return Err(e);
+        }
+    };
+
+    // book.json and the folders agree now; what follows only updates references to them
+    let new_dir = chapters_dir.join(&new_chapter.id);
+    for scene in &new_chapter.scenes {
+        if let Err(e) = set_scene_frontmatter_ids(&new_dir.join(&scene.id), &new_chapter.id, &scene.id) {
+            eprintln!("Warning: failed to update the frontmatter of {}: {}", scene.id, e);
+        }
+    }
+    follow_renames(&project_dir, &renamed.done);
+    for (from, _) in &renamed.done {
+        crate::watcher::queue_removed(&project_dir, from);
+    }
+    crate::watcher::queue_created(&project_dir, &new_dir);
+    Ok(new_chapter)
+}
+
+/// Merge `next_chapter_id` into `chapter_id`: its scenes are appended (renamed if
+/// their ids are taken) and its chapter notes appended to `_chapter.md`. The emptied
+/// chapter folder goes to the trash after book.json is saved. If a move or saving
+/// book.json fails, the scenes and notes are put back. Returns the merged chapter.
+#[tauri::command]
+pub fn merge_chapters(
+    project_dir: PathBuf,
+    book_id: String,
+    chapter_id: String,
+    next_chapter_id: String,
+) -> Result<Chapter, AppError> {
+    if chapter_id == next_chapter_id {
+        return Err(AppError::General("Cannot merge a chapter with itself".into()));
+    }
+    let chapters_dir = manuscript::chapters_dir(&project_dir, &book_id);
+    let (ch_dir, next_dir) = (chapters_dir.join(&chapter_id), chapters_dir.join(&next_chapter_id));
+    let notes = std::fs::read_to_string(ch_dir.join(CHAPTER_FILE)).ok();
+    let mut renamed = Renames::default();
+    let result = book_repo::update_book(&project_dir, &book_id, |book| {
+        book.chapter_mut(&chapter_id)?;
+        let next_idx = book.chapters.iter().position(|c| c.id == next_chapter_id)
+            .ok_or_else(|| AppError::ChapterNotFound(next_chapter_id.clone()))?;
+        let next = book.chapters.remove(next_idx);
+        let chapter = book.chapter_mut(&chapter_id)?;
+        chapter.close_scene_gaps();
+
+        for mut scene in next.sorted_scenes().into_iter().cloned() {
+            let from = next_dir.join(&scene.id);
+            if chapter.scenes.iter().any(|s| s.id == scene.id) || ch_dir.join(&scene.id).exists() {
+                scene.id = chapter.allocate_scene_id(&ch_dir);
             }
+            if from.exists() {
+                renamed.rename(from, ch_dir.join(&scene.id))?;
+            }
+            scene.sort_order = chapter.scenes.len() as u32 + 1;
+            chapter.scenes.push(scene);
         }
+        append_body(&ch_dir.join(CHAPTER_FILE), &next_dir.join(CHAPTER_FILE))?;
+        let merged = chapter.clone();
 
-        // Insert in target
-        if let Some(scene) = scene_data {
-            if let Some(dst_ch) = chapters.iter_mut().find(|c| {
-                c.get("id").and_then(|v| v.as_str()) == Some(&to_chapter)
-            }) {
-                if let Some(scenes) = dst_ch.get_mut("scenes").and_then(|v| v.as_array_mut()) {
-                    let pos = position.min(scenes.len());
-                    scenes.insert(pos, scene);
-                    // Re-number sort_order
-                    for (i, sc) in scenes.iter_mut().enumerate() {
-                        sc["sort_order"] = serde_json::Value::Number((i as u32 + 1).into());
-                    }
+        book.close_chapter_gaps();
+        Ok(merged)
+    });
+    let merged = match result {
+        Ok(merged) => merged,
+        Err(e) => {
+            renamed.undo();
+            if let Some(notes) = notes {
+                if let Err(restore) = crate::file_io::atomic_write(&ch_dir.join(CHAPTER_FILE), notes) {
+                    eprintln!("Warning: failed to restore the notes of {}: {}", chapter_id, restore);
                 }
             }
+            return Err(e);
+        }
+    };
+
+    // book.json and the folders agree now; what follows only updates references to them
+    for (_, to) in &renamed.done {
+        let scene_id = to.file_name().unwrap_or_default().to_string_lossy();
+        if let Err(e) = set_scene_frontmatter_ids(to, &chapter_id, &scene_id) {
+            eprintln!("Warning: failed to update the frontmatter of {}: {}", scene_id, e);
         }
     }
+    follow_renames(&project_dir, &renamed.done);
+    crate::watcher::queue_created(&project_dir, &ch_dir);
+    trash_merged(&project_dir, &next_dir);
+    Ok(merged)
+}
 
-    std::fs::write(&book_json_path, serde_json::to_string_pretty(&book_data)?)?;
+/// Move what is left of a merged-away chapter or scene folder to the trash. Runs
+/// once book.json no longer lists it; everything in it has been merged by then, so
+/// a folder the trash refuses is only left behind.
+fn trash_merged(project_dir: &Path, dir: &Path) {
+    if dir.exists() {
+        if let Err(e) = super::filesystem::delete_entry(dir.to_path_buf()) {
+            eprintln!("Warning: failed to move {} to the trash: {}", dir.to_string_lossy(), e);
+        }
+    }
+    crate::watcher::queue_removed(project_dir, dir);
+}
+
+/// Move the entries of `from` into `to`, adding `-<suffix>` to names already taken.
+fn move_children(from: &Path, to: &Path, suffix: &str) -> Result<(), AppError> {
+    let Ok(entries) = std::fs::read_dir(from) else { return Ok(()) };
+    std::fs::create_dir_all(to)?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let mut target = to.join(&name);
+        if target.exists() {
+            let (stem, ext) = match name.rsplit_once('.') {
+                Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), format!(".{}", ext)),
+                _ => (name.clone(), String::new()),
+            };
+            target = to.join(format!("{}-{}{}", stem, suffix, ext));
+        }
+        super::filesystem::move_entry(entry.path(), target)?;
+    }
+    Ok(())
+}
+
+/// Append the body of `from` (without its frontmatter) to the file at `into`.
+fn append_body(into: &Path, from: &Path) -> Result<(), AppError> {
+    let Ok(extra) = std::fs::read_to_string(from) else { return Ok(()) };
+    let (_, extra_body) = split_frontmatter(&extra);
+    if extra_body.trim().is_empty() {
+        return Ok(());
+    }
+    let existing = std::fs::read_to_string(into).unwrap_or_default();
+    crate::file_io::atomic_write(into, format!("{}\n\n{}\n", existing.trim_end(), extra_body.trim()))
+}
+
+fn with_body(head: &str, body: &str) -> String {
+    if head.is_empty() {
+        format!("{}\n", body)
+    } else {
+        format!("{}\n{}\n", head, body)
+    }
+}
+
+/// Byte index of the first line start at or after the `chars`-th character of `text`.
+fn line_boundary_at(text: &str, chars: usize) -> usize {
+    let byte = text.char_indices().nth(chars).map(|(i, _)| i).unwrap_or(text.len());
+    if byte == 0 || text[..byte].ends_with('\n') {
+        return byte;
+    }
+    text[byte..].find('\n').map(|i| byte + i + 1).unwrap_or(text.len())
+}
+
+/// A YAML double-quoted string holding `value`.
+fn quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_chapter_file(ch_dir: &Path, book_id: &str, ch_id: &str, title: &str, sort_order: u32) -> Result<(), AppError> {
+    std::fs::create_dir_all(ch_dir)?;
+    let now = Utc::now();
+    std::fs::write(
+        ch_dir.join(CHAPTER_FILE),
+        format!(
+            "---\ntype: chapter\nbook: {}\nchapter: {}\ntitle: {}\nsort_order: {}\ncreated: {}\nmodified: {}\n---\n\n# {}\n\n",
+            book_id, ch_id, quoted(title), sort_order, now.format("%Y-%m-%d"), now.format("%Y-%m-%d"), title
+        ),
+    )?;
     Ok(())
 }
 
@@ -312,3 +887,283 @@ fn copy_dir_recursive(src: &PathBuf, dst: &PathBuf) -> Result<(), AppError> {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestProject, BOOK};
+
+    fn chapter_ids(book: &Book) -> Vec<(String, String)> {
+        book.sorted_chapters().iter().map(|c| (c.id.clone(), c.title.clone())).collect()
+    }
+
+    fn owned(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(id, title)| (id.to_string(), title.to_string())).collect()
+    }
+
+    #[test]
+    fn test_chapter_ids_are_not_reused() {
+        let project = TestProject::new("chapters");
+        let dir = project.dir.clone();
+        for title in ["One", "Two", "Three"] {
+            create_chapter(dir.clone(), BOOK.into(), title.into()).unwrap();
+        }
+        // ch-02 removed by hand: neither its id nor a stray folder's may be handed out again
+        book_repo::update_book(&dir, BOOK, |book| {
+            book.chapters.retain(|c| c.id != "ch-02");
+            Ok(())
+        })
+        .unwrap();
+        let chapters_dir = manuscript::chapters_dir(&dir, BOOK);
+        std::fs::remove_dir_all(chapters_dir.join("ch-02")).unwrap();
+        std::fs::create_dir_all(chapters_dir.join("ch-05")).unwrap();
+        let four = create_chapter(dir.clone(), BOOK.into(), "Four".into()).unwrap();
+        assert_eq!(four.id, "ch-06");
+    }
+
+    #[test]
+    fn test_moved_scene_takes_a_free_id() {
+        let project = TestProject::with_scene("chapters");
+        let dir = project.dir.clone();
+        create_chapter(dir.clone(), BOOK.into(), "Crossing".into()).unwrap();
+        create_scene(dir.clone(), BOOK.into(), "ch-02".into(), "Ferry".into(), "action".into()).unwrap();
+
+        // ch-02 already has a scene-01, so the moved scene gets a new id
+        let moved = move_scene(dir.clone(), BOOK.into(), "scene-01".into(), "ch-01".into(), "ch-02".into(), 0, None).unwrap();
+        assert_eq!((moved.id.as_str(), moved.sort_order), ("scene-02", 1));
+        assert!(project.scene_dir("ch-02", "scene-02").join(DRAFT_FILE).exists());
+        assert!(!project.scene_dir("ch-01", "scene-01").exists());
+    }
+
+    #[test]
+    fn test_renumber_follows_reading_order() {
+        let project = TestProject::new("chapters");
+        let dir = project.dir.clone();
+        for title in ["One", "Two", "Three"] {
+            create_chapter(dir.clone(), BOOK.into(), title.into()).unwrap();
+        }
+        for title in ["A", "B"] {
+            create_scene(dir.clone(), BOOK.into(), "ch-03".into(), title.into(), "action".into()).unwrap();
+        }
+        reorder_chapters(dir.clone(), BOOK.into(), vec!["ch-03".into(), "ch-01".into(), "ch-02".into()]).unwrap();
+        reorder_scenes(dir.clone(), BOOK.into(), "ch-03".into(), vec!["scene-02".into(), "scene-01".into()]).unwrap();
+        std::fs::write(
+            dir.join(".context_settings.json"),
+            r#"{"books/book-01/chapters/ch-03/_chapter.md": "exclude", "books/book-01/chapters/ch-03/scene-02/draft.md": "force"}"#,
+        )
+        .unwrap();
+
+        let book = renumber_book(dir.clone(), BOOK.into()).unwrap();
+        assert_eq!(chapter_ids(&book), owned(&[("ch-01", "Three"), ("ch-02", "One"), ("ch-03", "Two")]));
+        let scenes: Vec<(&str, &str)> = book.chapters[0].scenes.iter().map(|s| (s.id.as_str(), s.title.as_str())).collect();
+        assert_eq!(scenes, vec![("scene-01", "B"), ("scene-02", "A")]);
+
+        let draft = std::fs::read_to_string(project.scene_dir("ch-01", "scene-01").join(DRAFT_FILE)).unwrap();
+        assert!(draft.contains("\nchapter: ch-01\nscene: scene-01\n"), "{}", draft);
+        let outline = std::fs::read_to_string(project.scene_dir("ch-01", "scene-01").join(OUTLINE_FILE)).unwrap();
+        assert!(outline.contains("title: \"B\""), "{}", outline);
+        let chapter_md = std::fs::read_to_string(manuscript::chapter_dir(&dir, BOOK, "ch-01").join(CHAPTER_FILE)).unwrap();
+        assert!(chapter_md.contains("title: \"Three\"") && chapter_md.contains("\nchapter: ch-01\n"));
+
+        // Context settings follow the folders, swaps and renamed scenes included
+        let settings = crate::context::assembler::load_context_settings(&dir);
+        assert_eq!(settings.get("books/book-01/chapters/ch-01/_chapter.md").map(String::as_str), Some("exclude"));
+        assert_eq!(settings.get("books/book-01/chapters/ch-01/scene-01/draft.md").map(String::as_str), Some("force"));
+        assert_eq!(settings.len(), 2);
+    }
+
+    #[test]
+    fn test_titles_are_quoted_in_frontmatter() {
+        let project = TestProject::new("chapters");
+        let dir = project.dir.clone();
+        // Titles are quoted so YAML reads them back as written
+        let title = r#"The "Gull" \ Co: 1"#;
+        let chapter = create_chapter(dir.clone(), BOOK.into(), title.into()).unwrap();
+        let scene = create_scene(dir.clone(), BOOK.into(), chapter.id.clone(), title.into(), "action".into()).unwrap();
+        let chapter_dir = manuscript::chapter_dir(&dir, BOOK, &chapter.id);
+        for file in [chapter_dir.join(CHAPTER_FILE), chapter_dir.join(&scene.id).join(OUTLINE_FILE)] {
+            let (frontmatter, _) = crate::frontmatter::parse_frontmatter(&std::fs::read_to_string(file).unwrap());
+            assert_eq!(frontmatter["title"], title);
+        }
+    }
+
+    /// "Harbour" with a two-paragraph draft and an attachment.
+    fn harbour_with_text() -> TestProject {
+        let project = TestProject::with_scene("split");
+        let draft = std::fs::read_to_string(project.draft()).unwrap();
+        let (head, _) = split_frontmatter(&draft);
+        std::fs::write(project.draft(), with_body(head, "The boat came in.\n\nShe was not on it.")).unwrap();
+        let attachments = project.scene_dir("ch-01", "scene-01").join("attachments");
+        std::fs::create_dir_all(&attachments).unwrap();
+        std::fs::write(attachments.join("map.png"), "png").unwrap();
+        project
+    }
+
+    #[test]
+    fn test_split_scene_carries_text_and_files() {
+        let project = harbour_with_text();
+        // An offset inside the first line still splits at a line start
+        let part2 = split_scene(project.dir.clone(), BOOK.into(), "ch-01".into(), "scene-01".into(), 5, None).unwrap();
+        assert_eq!((part2.id.as_str(), part2.title.as_str(), part2.sort_order), ("scene-02", "Arrival (part 2)", 2));
+        let second_dir = project.scene_dir("ch-01", "scene-02");
+        let second = std::fs::read_to_string(second_dir.join(DRAFT_FILE)).unwrap();
+        assert!(second.contains("\nscene: scene-02\n") && second.ends_with("\nShe was not on it.\n"), "{}", second);
+        assert!(!std::fs::read_to_string(project.draft()).unwrap().contains("She was"));
+        assert!(second_dir.join("attachments").join("map.png").exists());
+        assert!(second_dir.join(SNAPSHOTS_DIR).read_dir().unwrap().count() > 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failed_split_scene_keeps_the_draft_whole() {
+        let project = harbour_with_text();
+        let before = std::fs::read_to_string(project.draft()).unwrap();
+        // A dangling link stops the copy of the scene folder partway
+        let attachments = project.scene_dir("ch-01", "scene-01").join("attachments");
+        std::os::unix::fs::symlink(project.dir.join("missing.png"), attachments.join("gone.png")).unwrap();
+
+        assert!(split_scene(project.dir.clone(), BOOK.into(), "ch-01".into(), "scene-01".into(), 5, None).is_err());
+        assert_eq!(std::fs::read_to_string(project.draft()).unwrap(), before);
+        assert!(!project.scene_dir("ch-01", "scene-02").exists());
+        assert_eq!(project.book().chapters[0].scenes.len(), 1);
+    }
+
+    #[test]
+    fn test_split_chapter_follows_it_in_reading_order() {
+        let project = harbour_with_text();
+        let dir = project.dir.clone();
+        create_chapter(dir.clone(), BOOK.into(), "Crossing".into()).unwrap();
+        split_scene(dir.clone(), BOOK.into(), "ch-01".into(), "scene-01".into(), 5, None).unwrap();
+
+        let ashore = split_chapter(dir.clone(), BOOK.into(), "ch-01".into(), "scene-02".into(), Some("Ashore".into())).unwrap();
+        assert_eq!((ashore.id.as_str(), ashore.sort_order), ("ch-03", 2));
+        let moved = std::fs::read_to_string(project.scene_dir("ch-03", "scene-02").join(DRAFT_FILE)).unwrap();
+        assert!(moved.contains("\nchapter: ch-03\n"));
+        assert_eq!(chapter_ids(&project.book()), owned(&[("ch-01", "Harbour"), ("ch-03", "Ashore"), ("ch-02", "Crossing")]));
+    }
+
+    #[test]
+    fn test_merge_puts_the_text_back_together() {
+        let project = harbour_with_text();
+        let dir = project.dir.clone();
+        split_scene(dir.clone(), BOOK.into(), "ch-01".into(), "scene-01".into(), 5, None).unwrap();
+        split_chapter(dir.clone(), BOOK.into(), "ch-01".into(), "scene-02".into(), None).unwrap();
+
+        let merged = merge_chapters(dir.clone(), BOOK.into(), "ch-01".into(), "ch-02".into()).unwrap();
+        assert_eq!(merged.scenes.len(), 2);
+        assert!(project.book().chapter_mut("ch-02").is_err());
+        assert!(!manuscript::chapter_dir(&dir, BOOK, "ch-02").exists());
+
+        let scene = merge_scenes(dir.clone(), BOOK.into(), "ch-01".into(), "scene-01".into(), "scene-02".into()).unwrap();
+        assert_eq!(scene.word_count, 9);
+        let body = std::fs::read_to_string(project.draft()).unwrap();
+        assert!(body.contains("The boat came in.\n\nShe was not on it.\n"), "{}", body);
+        let scene_dir = project.scene_dir("ch-01", "scene-01");
+        assert!(scene_dir.join("attachments").join("map-scene-02.png").exists());
+        assert!(!project.scene_dir("ch-01", "scene-02").exists());
+    }
+
+    #[test]
+    fn test_failed_split_chapter_moves_the_scenes_back() {
+        let project = TestProject::with_scene("split");
+        let dir = project.dir.clone();
+        for title in ["Squall", "Calm"] {
+            create_scene(dir.clone(), BOOK.into(), "ch-01".into(), title.into(), "action".into()).unwrap();
+        }
+        // scene-02 moves, then scene-03 can't
+        std::fs::remove_dir_all(project.scene_dir("ch-01", "scene-03")).unwrap();
+
+        assert!(split_chapter(dir.clone(), BOOK.into(), "ch-01".into(), "scene-02".into(), None).is_err());
+        assert_eq!(project.book().chapters.len(), 1);
+        assert!(project.scene_dir("ch-01", "scene-02").join(DRAFT_FILE).exists());
+        assert!(!manuscript::chapter_dir(&dir, BOOK, "ch-02").exists());
+    }
+
+    #[test]
+    fn test_failed_merge_chapters_moves_the_scenes_back() {
+        let project = TestProject::with_scene("merge");
+        let dir = project.dir.clone();
+        create_chapter(dir.clone(), BOOK.into(), "Crossing".into()).unwrap();
+        create_scene(dir.clone(), BOOK.into(), "ch-02".into(), "Ferry".into(), "action".into()).unwrap();
+        // The notes can't be appended to once the scenes have moved
+        let notes = manuscript::chapter_dir(&dir, BOOK, "ch-01").join(CHAPTER_FILE);
+        std::fs::remove_file(&notes).unwrap();
+        std::fs::create_dir(&notes).unwrap();
+        std::fs::write(manuscript::chapter_dir(&dir, BOOK, "ch-02").join(CHAPTER_FILE), "---\ntitle: \"Crossing\"\n---\n\nRough water.\n").unwrap();
+
+        assert!(merge_chapters(dir.clone(), BOOK.into(), "ch-01".into(), "ch-02".into()).is_err());
+        assert_eq!(project.book().chapters.len(), 2);
+        assert!(project.scene_dir("ch-02", "scene-01").join(DRAFT_FILE).exists());
+        assert!(!project.scene_dir("ch-01", "scene-02").exists());
+    }
+
+    /// book-01 with an empty "Harbour" and "Crossing" holding "Ferry", and book-02
+    /// "Currents" with "Landfall" holding "Dock".
+    fn two_books() -> TestProject {
+        let project = TestProject::new("move-book");
+        let dir = project.dir.clone();
+        project.add_book("book-02", "Currents");
+        create_chapter(dir.clone(), BOOK.into(), "Harbour".into()).unwrap();
+        create_chapter(dir.clone(), BOOK.into(), "Crossing".into()).unwrap();
+        create_scene(dir.clone(), BOOK.into(), "ch-02".into(), "Ferry".into(), "action".into()).unwrap();
+        create_chapter(dir.clone(), "book-02".into(), "Landfall".into()).unwrap();
+        create_scene(dir.clone(), "book-02".into(), "ch-01".into(), "Dock".into(), "action".into()).unwrap();
+        project
+    }
+
+    #[test]
+    fn test_move_chapter_to_another_book() {
+        let project = two_books();
+        let dir = project.dir.clone();
+        let scene_dir = project.scene_dir("ch-02", "scene-01");
+        std::fs::create_dir_all(scene_dir.join(SNAPSHOTS_DIR)).unwrap();
+        std::fs::write(scene_dir.join(SNAPSHOTS_DIR).join("2026-01-01T00-00-00.md"), "old").unwrap();
+        // Keys as the frontend writes them, absolute with backslashes
+        let key = format!("{}\\books\\book-01\\chapters\\ch-02\\scene-01\\outline.md", dir.display());
+        std::fs::write(dir.join(".context_settings.json"), serde_json::json!({ key: "exclude" }).to_string()).unwrap();
+
+        // ch-01 is taken in book-02, so the chapter gets a new id there
+        let moved = move_chapter_to_book(dir.clone(), BOOK.into(), "ch-02".into(), "book-02".into(), 0).unwrap();
+        assert_eq!((moved.id.as_str(), moved.sort_order), ("ch-02", 1));
+        assert_eq!(project.book().chapters.len(), 1);
+        let target = book_repo::load_book(&dir, "book-02").unwrap();
+        let order: Vec<&str> = target.sorted_chapters().iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(order, vec!["Crossing", "Landfall"]);
+
+        let moved_dir = manuscript::scene_dir(&dir, "book-02", "ch-02", "scene-01");
+        let outline = std::fs::read_to_string(moved_dir.join(OUTLINE_FILE)).unwrap();
+        assert!(outline.contains("\nbook: book-02\nchapter: ch-02\nscene: scene-01\n"), "{}", outline);
+        assert!(moved_dir.join(SNAPSHOTS_DIR).join("2026-01-01T00-00-00.md").exists());
+        assert!(!manuscript::chapter_dir(&dir, BOOK, "ch-02").exists());
+        let settings = crate::context::assembler::load_context_settings(&dir);
+        assert_eq!(settings.get("books/book-02/chapters/ch-02/scene-01/outline.md").map(String::as_str), Some("exclude"));
+        assert_eq!(settings.len(), 1);
+    }
+
+    #[test]
+    fn test_move_scene_to_another_book() {
+        let project = two_books();
+        let dir = project.dir.clone();
+        // scene-01 is free in book-01's ch-01
+        let scene = move_scene(dir.clone(), "book-02".into(), "scene-01".into(), "ch-01".into(), "ch-01".into(), 5, Some(BOOK.into())).unwrap();
+        assert_eq!((scene.id.as_str(), scene.sort_order), ("scene-01", 1));
+        let draft = std::fs::read_to_string(project.draft()).unwrap();
+        assert!(draft.contains("\nbook: book-01\nchapter: ch-01\nscene: scene-01\n"), "{}", draft);
+        assert!(book_repo::load_book(&dir, "book-02").unwrap().chapter_mut("ch-01").unwrap().scenes.is_empty());
+    }
+
+    #[test]
+    fn test_failed_move_leaves_both_books() {
+        let project = two_books();
+        let dir = project.dir.clone();
+        project.add_book("book-03", "Shoals");
+        let chapters_dir = manuscript::chapters_dir(&dir, "book-03");
+        std::fs::remove_dir_all(&chapters_dir).unwrap();
+        std::fs::write(&chapters_dir, "not a folder").unwrap();
+
+        assert!(move_chapter_to_book(dir.clone(), BOOK.into(), "ch-02".into(), "book-03".into(), 0).is_err());
+        assert_eq!(project.book().chapters.len(), 2);
+        assert!(book_repo::load_book(&dir, "book-03").unwrap().chapters.is_empty());
+        assert!(project.scene_dir("ch-02", "scene-01").exists());
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use crate::context::vector::{EmbeddingConfig, Quantization};
 use crate::error::AppError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +16,18 @@ fn default_auto() -> String {
     "auto".to_string()
 }
 
+fn default_embedding_provider() -> String {
+    "voyage".to_string()
+}
+
+fn default_quantization() -> String {
+    "none".to_string()
+}
+
+fn default_embedding_concurrency() -> u32 {
+    2
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EditorConfig {
     pub auto_save_interval_seconds: u32,
@@ -54,20 +67,66 @@ impl Default for AiConfig {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorSearchConfig {
     pub enabled: bool,
+    /// Embedding provider: "voyage", "openai" (any OpenAI-compatible server) or "local"
+    #[serde(default = "default_embedding_provider")]
+    pub provider: String,
     pub embedding_model: String,
     #[serde(default)]
     pub embedding_api_key_encrypted: String,
+    /// Base URL for the "openai" provider, e.g. http://localhost:11434/v1
+    #[serde(default)]
+    pub embedding_base_url: String,
+    /// Requested vector size for the "openai" provider; 0 uses the model's default
+    #[serde(default)]
+    pub embedding_dimensions: u32,
+    /// Vector index storage: "none" (float32), "int8" or "binary"
+    #[serde(default = "default_quantization")]
+    pub embedding_quantization: String,
+    /// With "int8" or "binary", also keep each float32 vector to rescore with
+    #[serde(default)]
+    pub embedding_keep_full_precision: bool,
+    /// Embedding requests in flight at once during a full re-index
+    #[serde(default = "default_embedding_concurrency")]
+    pub embedding_concurrency: u32,
+    /// Embedding requests started per minute during a full re-index; 0 = no limit
+    #[serde(default)]
+    pub embedding_requests_per_minute: u32,
     pub auto_index: bool,
     pub max_results_default: u32,
     pub max_search_tokens_default: u64,
 }
 
+impl VectorSearchConfig {
+    /// The settings the vector index and embedding clients work from, with the
+    /// embedding key decoded.
+    pub fn embedding_config(&self) -> Result<EmbeddingConfig, AppError> {
+        Ok(EmbeddingConfig {
+            provider: self.provider.clone(),
+            model: self.embedding_model.clone(),
+            api_key: get_embedding_api_key(self)?,
+            base_url: self.embedding_base_url.trim().to_string(),
+            dimensions: self.embedding_dimensions,
+            quantization: Quantization::from_config(&self.embedding_quantization),
+            keep_full_precision: self.embedding_keep_full_precision,
+            concurrency: self.embedding_concurrency,
+            requests_per_minute: self.embedding_requests_per_minute,
+        })
+    }
+}
+
 impl Default for VectorSearchConfig {
     fn default() -> Self {
         Self {
             enabled: false,
+            provider: default_embedding_provider(),
             embedding_model: "voyage-4".to_string(),
             embedding_api_key_encrypted: String::new(),
+            embedding_base_url: String::new(),
+            embedding_dimensions: 0,
+            embedding_quantization: default_quantization(),
+            embedding_keep_full_precision: false,
+            embedding_concurrency: default_embedding_concurrency(),
+            embedding_requests_per_minute: 0,
             auto_index: true,
             max_results_default: 5,
             max_search_tokens_default: 15000,
@@ -148,7 +207,7 @@ pub fn update_config(config: AppConfig) -> Result<(), AppError> {
     ensure_config_dir()?;
     let path = config_path()?;
     let data = serde_json::to_string_pretty(&config)?;
-    std::fs::write(&path, data)?;
+    crate::file_io::atomic_write(&path, data)?;
     Ok(())
 }
 
@@ -196,6 +255,14 @@ pub fn get_api_key_internal(config: &AppConfig) -> Result<String, AppError> {
     base64_decode(&config.api_key_encrypted)
 }
 
+/// Decode the embedding provider key, or `None` if none is stored.
+pub fn get_embedding_api_key(config: &VectorSearchConfig) -> Result<Option<String>, AppError> {
+    if config.embedding_api_key_encrypted.trim().is_empty() {
+        return Ok(None);
+    }
+    base64_decode(&config.embedding_api_key_encrypted).map(Some)
+}
+
 fn base64_encode(input: &str) -> String {
     use std::io::Write;
     let mut buf = Vec::new();
@@ -0,0 +1,355 @@
+// Project consistency — where project.json and each book.json disagree with the
+// folders on disk, and the repairs that bring them back in line.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::book_repo::{self, Book, Chapter, Scene};
+use crate::error::AppError;
+use crate::frontmatter::{frontmatter_str, set_frontmatter_fields};
+use crate::manuscript::{self, draft_word_count, set_scene_frontmatter_ids, CHAPTER_FILE, DRAFT_FILE, OUTLINE_FILE};
+use super::project::{get_project_metadata, update_project_metadata, BookRef};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Metadata points at content that isn't there
+    Error,
+    /// Content on disk the metadata doesn't know about, or ambiguous ordering
+    Warning,
+    /// Cached values that are out of date
+    Info,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+    BookFolderMissing,
+    UntrackedBook,
+    UnreadableBook,
+    ChapterFolderMissing,
+    UntrackedChapter,
+    DuplicateChapterId,
+    DuplicateChapterSortOrder,
+    SceneFolderMissing,
+    UntrackedScene,
+    DuplicateSceneId,
+    DuplicateSceneSortOrder,
+    WordCountMismatch,
+    FrontmatterMismatch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsistencyIssue {
+    pub severity: Severity,
+    pub kind: IssueKind,
+    pub book_id: String,
+    pub chapter_id: Option<String>,
+    pub scene_id: Option<String>,
+    pub message: String,
+    /// What `repair_project` does about it; None if it needs the user
+    pub repair: Option<String>,
+}
+
+/// Result of `check_project` or `repair_project`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConsistencyReport {
+    pub issues: Vec<ConsistencyIssue>,
+    pub repaired: bool,
+}
+
+/// Report every way the project's metadata has drifted from the files on disk. Read-only.
+#[tauri::command]
+pub fn check_project(project_dir: PathBuf) -> Result<ConsistencyReport, AppError> {
+    inspect(&project_dir, false)
+}
+
+/// Fix what `check_project` finds. With `dry_run` nothing is written and the report
+/// lists the repairs that would be made.
+#[tauri::command]
+pub fn repair_project(project_dir: PathBuf, dry_run: Option<bool>) -> Result<ConsistencyReport, AppError> {
+    let apply = !dry_run.unwrap_or(false);
+    let mut report = inspect(&project_dir, apply)?;
+    report.repaired = apply && report.issues.iter().any(|i| i.repair.is_some());
+    Ok(report)
+}
+
+/// Check the project, and with `apply` repair it, book by book.
+fn inspect(project_dir: &Path, apply: bool) -> Result<ConsistencyReport, AppError> {
+    let mut project = get_project_metadata(project_dir.to_path_buf())?;
+    let books_dir = project_dir.join("books");
+    let mut issues = Vec::new();
+    let mut project_changed = false;
+
+    project.books.retain(|book| {
+        if books_dir.join(&book.id).is_dir() {
+            return true;
+        }
+        issues.push(issue(Severity::Error, IssueKind::BookFolderMissing, &book.id, None, None,
+            format!("\"{}\" is listed in project.json but its folder is missing", book.title),
+            Some("Remove it from project.json")));
+        project_changed = true;
+        false
+    });
+    for book_id in subdirs(&books_dir, "") {
+        if project.books.iter().any(|b| b.id == book_id) {
+            continue;
+        }
+        let Ok(book) = book_repo::load_book(project_dir, &book_id) else { continue };
+        issues.push(issue(Severity::Warning, IssueKind::UntrackedBook, &book_id, None, None,
+            format!("The book folder {} is not listed in project.json", book_id),
+            Some("Add it to project.json")));
+        let sort_order = project.books.iter().map(|b| b.sort_order).max().unwrap_or(0) + 1;
+        project.books.push(BookRef { id: book_id, title: book.title, sort_order, genre_id: String::new() });
+        project_changed = true;
+    }
+    if apply && project_changed {
+        update_project_metadata(project_dir.to_path_buf(), project.clone())?;
+    }
+
+    for book_ref in &project.books {
+        let mut book = match book_repo::load_book(project_dir, &book_ref.id) {
+            Ok(book) => book,
+            Err(e) => {
+                issues.push(issue(Severity::Error, IssueKind::UnreadableBook, &book_ref.id, None, None,
+                    format!("book.json can't be read: {}", e), None));
+                continue;
+            }
+        };
+        let book_issues = inspect_book(project_dir, &mut book, false)?;
+        if apply && book_issues.iter().any(|i| i.repair.is_some()) {
+            // Repair the book as stored when saving, not the copy checked above
+            book_repo::update_book(project_dir, &book_ref.id, |stored| inspect_book(project_dir, stored, true))?;
+        }
+        issues.extend(book_issues);
+    }
+
+    Ok(ConsistencyReport { issues, repaired: false })
+}
+
+/// Check one book against its chapter and scene folders, repairing `book` in memory.
+/// Frontmatter is only rewritten with `apply`.
+fn inspect_book(project_dir: &Path, book: &mut Book, apply: bool) -> Result<Vec<ConsistencyIssue>, AppError> {
+    let book_id = book.id.clone();
+    let chapters_dir = manuscript::chapters_dir(project_dir, &book_id);
+    let mut issues = Vec::new();
+
+    let mut seen = HashSet::new();
+    book.chapters.retain(|ch| {
+        if !seen.insert(ch.id.clone()) {
+            issues.push(issue(Severity::Error, IssueKind::DuplicateChapterId, &book_id, Some(&ch.id), None,
+                format!("Chapter {} is listed twice in book.json", ch.id),
+                Some("Remove the second entry")));
+            return false;
+        }
+        if !chapters_dir.join(&ch.id).is_dir() {
+            issues.push(issue(Severity::Error, IssueKind::ChapterFolderMissing, &book_id, Some(&ch.id), None,
+                format!("Chapter \"{}\" ({}) has no folder", ch.title, ch.id),
+                Some("Remove it from book.json")));
+            return false;
+        }
+        true
+    });
+    for ch_id in subdirs(&chapters_dir, "ch-") {
+        if book.chapters.iter().any(|c| c.id == ch_id) {
+            continue;
+        }
+        let title = frontmatter_str(&chapters_dir.join(&ch_id).join(CHAPTER_FILE), "title").unwrap_or_else(|| ch_id.clone());
+        issues.push(issue(Severity::Warning, IssueKind::UntrackedChapter, &book_id, Some(&ch_id), None,
+            format!("The chapter folder {} is not in book.json", ch_id),
+            Some("Add it at the end of the book")));
+        let sort_order = book.next_chapter_sort_order();
+        book.chapters.push(Chapter::new(&ch_id, &title, sort_order));
+    }
+    if has_duplicates(book.chapters.iter().map(|c| c.sort_order)) {
+        issues.push(issue(Severity::Warning, IssueKind::DuplicateChapterSortOrder, &book_id, None, None,
+            "Several chapters share a position in the reading order".into(),
+            Some("Renumber the reading order")));
+        book.close_chapter_gaps();
+    }
+
+    for chapter in &mut book.chapters {
+        let ch_dir = chapters_dir.join(&chapter.id);
+        issues.extend(inspect_chapter(&book_id, chapter, &ch_dir));
+        for scene in &mut chapter.scenes {
+            let sc_dir = ch_dir.join(&scene.id);
+            let words = draft_word_count(&sc_dir.join(DRAFT_FILE))?;
+            if scene.word_count != words {
+                issues.push(issue(Severity::Info, IssueKind::WordCountMismatch, &book_id, Some(&chapter.id), Some(&scene.id),
+                    format!("book.json counts {} words for \"{}\", the draft has {}", scene.word_count, scene.title, words),
+                    Some("Update the word count")));
+                scene.word_count = words;
+            }
+            if let Some(found) = frontmatter_mismatch(&sc_dir, &book_id, &chapter.id, &scene.id) {
+                issues.push(issue(Severity::Warning, IssueKind::FrontmatterMismatch, &book_id, Some(&chapter.id), Some(&scene.id),
+                    format!("{} names a different book, chapter or scene than its folder", found),
+                    Some("Rewrite its book:, chapter: and scene: fields")));
+                if apply {
+                    set_scene_frontmatter_ids(&sc_dir, &chapter.id, &scene.id)?;
+                    for file in [OUTLINE_FILE, DRAFT_FILE] {
+                        set_frontmatter_fields(&sc_dir.join(file), &[("book", book_id.clone())])?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// Check a chapter's scene list against its scene folders, repairing it in memory.
+fn inspect_chapter(book_id: &str, chapter: &mut Chapter, ch_dir: &Path) -> Vec<ConsistencyIssue> {
+    let mut issues = Vec::new();
+    let ch_id = chapter.id.clone();
+    let mut seen = HashSet::new();
+    chapter.scenes.retain(|sc| {
+        if !seen.insert(sc.id.clone()) {
+            issues.push(issue(Severity::Error, IssueKind::DuplicateSceneId, book_id, Some(&ch_id), Some(&sc.id),
+                format!("Scene {} is listed twice in chapter {}", sc.id, ch_id),
+                Some("Remove the second entry")));
+            return false;
+        }
+        if !ch_dir.join(&sc.id).is_dir() {
+            issues.push(issue(Severity::Error, IssueKind::SceneFolderMissing, book_id, Some(&ch_id), Some(&sc.id),
+                format!("Scene \"{}\" ({}/{}) has no folder", sc.title, ch_id, sc.id),
+                Some("Remove it from book.json")));
+            return false;
+        }
+        true
+    });
+    for sc_id in subdirs(ch_dir, "scene-") {
+        if chapter.scenes.iter().any(|s| s.id == sc_id) {
+            continue;
+        }
+        let outline = ch_dir.join(&sc_id).join(OUTLINE_FILE);
+        let title = frontmatter_str(&outline, "title").unwrap_or_else(|| sc_id.clone());
+        let scene_type = frontmatter_str(&outline, "scene_type").map(|t| t.to_lowercase()).unwrap_or_else(|| "action".into());
+        issues.push(issue(Severity::Warning, IssueKind::UntrackedScene, book_id, Some(&ch_id), Some(&sc_id),
+            format!("The scene folder {}/{} is not in book.json", ch_id, sc_id),
+            Some("Add it at the end of its chapter")));
+        let sort_order = chapter.scenes.iter().map(|s| s.sort_order).max().unwrap_or(0) + 1;
+        chapter.scenes.push(Scene::new(&sc_id, &title, sort_order, &scene_type));
+    }
+    if has_duplicates(chapter.scenes.iter().map(|s| s.sort_order)) {
+        issues.push(issue(Severity::Warning, IssueKind::DuplicateSceneSortOrder, book_id, Some(&ch_id), None,
+            format!("Several scenes in chapter {} share a position in the reading order", ch_id),
+            Some("Renumber the chapter's scenes")));
+        chapter.close_scene_gaps();
+    }
+    issues
+}
+
+/// The first of a scene's outline and draft whose frontmatter ids disagree with its folder.
+fn frontmatter_mismatch(sc_dir: &Path, book_id: &str, ch_id: &str, sc_id: &str) -> Option<String> {
+    [OUTLINE_FILE, DRAFT_FILE].into_iter().find_map(|file| {
+        let path = sc_dir.join(file);
+        let expected = [("book", book_id), ("chapter", ch_id), ("scene", sc_id)];
+        expected
+            .iter()
+            .any(|(key, value)| frontmatter_str(&path, key).is_some_and(|found| found != *value))
+            .then(|| format!("{}/{}/{}", ch_id, sc_id, file))
+    })
+}
+
+
+/// Names of the folders in `dir` starting with `prefix`, sorted.
+fn subdirs(dir: &Path, prefix: &str) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with(prefix) && !name.starts_with('.'))
+        .collect();
+    names.sort();
+    names
+}
+
+fn has_duplicates(values: impl Iterator<Item = u32>) -> bool {
+    let mut seen = HashSet::new();
+    values.into_iter().any(|v| !seen.insert(v))
+}
+
+fn issue(
+    severity: Severity,
+    kind: IssueKind,
+    book_id: &str,
+    chapter_id: Option<&str>,
+    scene_id: Option<&str>,
+    message: String,
+    repair: Option<&str>,
+) -> ConsistencyIssue {
+    ConsistencyIssue {
+        severity,
+        kind,
+        book_id: book_id.to_string(),
+        chapter_id: chapter_id.map(str::to_string),
+        scene_id: scene_id.map(str::to_string),
+        message,
+        repair: repair.map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::chapter::{create_chapter, create_scene};
+    use crate::test_support::{TestProject, BOOK};
+
+    /// A project with one issue of each kind the check below expects.
+    fn drifted_project() -> TestProject {
+        let project = TestProject::with_scene("consistency");
+        let dir = project.dir.clone();
+        project.list_book("book-09", "Lost");
+        create_chapter(dir.clone(), BOOK.into(), "Crossing".into()).unwrap();
+        create_scene(dir.clone(), BOOK.into(), "ch-01".into(), "Tavern".into(), "action".into()).unwrap();
+
+        // Drift: a deleted folder, a stray one, a shared position, a stale count and ids
+        let chapters_dir = manuscript::chapters_dir(&dir, BOOK);
+        std::fs::remove_dir_all(chapters_dir.join("ch-02")).unwrap();
+        std::fs::create_dir_all(chapters_dir.join("ch-01").join("scene-07")).unwrap();
+        book_repo::update_book(&dir, BOOK, |book| {
+            book.chapters[0].scenes[1].sort_order = 1;
+            Ok(())
+        })
+        .unwrap();
+        let content = std::fs::read_to_string(project.draft()).unwrap().replace("scene: scene-01", "scene: scene-04");
+        std::fs::write(project.draft(), format!("{}Four words of prose.\n", content)).unwrap();
+        project
+    }
+
+    #[test]
+    fn test_check_reports_each_kind_of_drift() {
+        let project = drifted_project();
+        let report = check_project(project.dir.clone()).unwrap();
+        let kinds: Vec<IssueKind> = report.issues.iter().map(|i| i.kind).collect();
+        assert_eq!(kinds, vec![
+            IssueKind::BookFolderMissing,
+            IssueKind::ChapterFolderMissing,
+            IssueKind::UntrackedScene,
+            IssueKind::DuplicateSceneSortOrder,
+            IssueKind::WordCountMismatch,
+            IssueKind::FrontmatterMismatch,
+        ]);
+        assert_eq!(report.issues[1].severity, Severity::Error);
+    }
+
+    #[test]
+    fn test_dry_run_repair_writes_nothing() {
+        let project = drifted_project();
+        let book_json = book_repo::book_json_path(&project.dir, BOOK);
+        let before = std::fs::read_to_string(&book_json).unwrap();
+        assert!(!repair_project(project.dir.clone(), Some(true)).unwrap().repaired);
+        assert_eq!(std::fs::read_to_string(&book_json).unwrap(), before);
+    }
+
+    #[test]
+    fn test_repair_fixes_every_issue() {
+        let project = drifted_project();
+        assert!(repair_project(project.dir.clone(), None).unwrap().repaired);
+        assert!(check_project(project.dir.clone()).unwrap().issues.is_empty());
+        let book = project.book();
+        let scenes: Vec<(&str, u32, u64)> = book.chapters[0].scenes.iter().map(|s| (s.id.as_str(), s.sort_order, s.word_count)).collect();
+        assert_eq!(scenes, vec![("scene-01", 1, 4), ("scene-02", 2, 0), ("scene-07", 3, 0)]);
+        assert!(std::fs::read_to_string(project.draft()).unwrap().contains("scene: scene-01"));
+    }
+}
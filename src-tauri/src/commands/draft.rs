@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use chrono::Utc;
 use crate::error::AppError;
+use crate::manuscript::{DRAFT_FILE, SNAPSHOTS_DIR};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DraftSnapshot {
@@ -12,28 +13,43 @@ pub struct DraftSnapshot {
 }
 
 #[tauri::command]
-pub fn save_draft(path: PathBuf, content: String) -> Result<(), AppError> {
+pub fn save_draft(path: PathBuf, content: String, expected_version: Option<String>) -> Result<String, AppError> {
+    // Refuse before snapshotting, so a rejected save leaves no trace
+    if let Some(expected) = &expected_version {
+        crate::file_io::check_version(&path, &content, expected)?;
+    }
+
+    snapshot_draft(&path)?;
+
+    // Write new content
+    let words_before = crate::manuscript::draft_word_count(&path)?;
+    let version = crate::file_io::write_versioned(&path, &content, None)?;
+    super::progress::recompute_after_save(&path);
+    super::writing::record_draft_change(&path, Some(words_before));
+    Ok(version)
+}
+
+/// Copy a draft's current text into its scene's `.drafts/` history before it is
+/// overwritten. A draft that doesn't exist yet has nothing to keep.
+pub fn snapshot_draft(path: &Path) -> Result<(), AppError> {
     let scene_dir = path.parent()
         .ok_or_else(|| AppError::InvalidPath("Cannot determine scene directory".into()))?;
-    let drafts_dir = scene_dir.join(".drafts");
+    let drafts_dir = scene_dir.join(SNAPSHOTS_DIR);
     std::fs::create_dir_all(&drafts_dir)?;
 
-    // Snapshot current version before overwriting
     if path.exists() {
-        let current = std::fs::read_to_string(&path)?;
+        let current = std::fs::read_to_string(path)?;
         let now = Utc::now();
         let snapshot_name = now.format("%Y-%m-%dT%H-%M-%S").to_string() + ".md";
         std::fs::write(drafts_dir.join(&snapshot_name), &current)?;
+        crate::watcher::note_own_write(&drafts_dir.join(&snapshot_name));
     }
-
-    // Write new content
-    std::fs::write(&path, &content)?;
     Ok(())
 }
 
 #[tauri::command]
 pub fn list_drafts(scene_dir: PathBuf) -> Result<Vec<DraftSnapshot>, AppError> {
-    let drafts_dir = scene_dir.join(".drafts");
+    let drafts_dir = scene_dir.join(SNAPSHOTS_DIR);
     if !drafts_dir.exists() {
         return Ok(Vec::new());
     }
@@ -66,7 +82,7 @@ pub fn list_drafts(scene_dir: PathBuf) -> Result<Vec<DraftSnapshot>, AppError> {
 
 #[tauri::command]
 pub fn restore_draft(scene_dir: PathBuf, snapshot_name: String) -> Result<String, AppError> {
-    let snapshot_path = scene_dir.join(".drafts").join(&snapshot_name);
+    let snapshot_path = scene_dir.join(SNAPSHOTS_DIR).join(&snapshot_name);
     if !snapshot_path.exists() {
         return Err(AppError::FileNotFound(snapshot_path.to_string_lossy().to_string()));
     }
@@ -74,11 +90,12 @@ pub fn restore_draft(scene_dir: PathBuf, snapshot_name: String) -> Result<String
     let content = std::fs::read_to_string(&snapshot_path)?;
 
     // Save current draft as a snapshot before restoring
-    let draft_path = scene_dir.join("draft.md");
+    let draft_path = scene_dir.join(DRAFT_FILE);
     if draft_path.exists() {
-        save_draft(draft_path.clone(), content.clone())?;
+        save_draft(draft_path.clone(), content.clone(), None)?;
     } else {
         std::fs::write(&draft_path, &content)?;
+        crate::watcher::note_own_write(&draft_path);
     }
 
     Ok(content)
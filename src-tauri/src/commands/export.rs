@@ -8,9 +8,11 @@
 //   • reveal_export_folder command added
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::Manager;
+use crate::book_repo;
 use crate::error::AppError;
+use crate::manuscript::{CHAPTERS_DIR, DRAFT_FILE};
 
 
 // ── Enums & Options ───────────────────────────────────────────────────────────
@@ -51,17 +53,31 @@ impl Default for ExportOptions {
 }
 
 // ── Sidecar resolution ────────────────────────────────────────────────────────
-// Pandoc and Typst are bundled as resources (Windows-only).
-// At build time they live in src-tauri/binaries/ as pandoc.exe / typst.exe.
+// Pandoc and Typst are bundled as resources.
+// At build time they live in src-tauri/binaries/ as pandoc / typst (pandoc.exe / typst.exe on Windows).
 // At runtime they are in the app resource directory under binaries/.
 
+/// Directories searched after the bundled binaries and PATH. Apps started from the
+/// macOS Dock or a Linux desktop launcher don't inherit the shell's PATH.
+#[cfg(target_os = "macos")]
+const EXTRA_BIN_DIRS: &[&str] = &["/opt/homebrew/bin", "/usr/local/bin"];
+#[cfg(target_os = "linux")]
+const EXTRA_BIN_DIRS: &[&str] = &["/usr/local/bin", "/usr/bin", "/snap/bin"];
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+const EXTRA_BIN_DIRS: &[&str] = &[];
+
+/// File name of an executable on this platform, e.g. `pandoc.exe` on Windows.
+fn sidecar_file_name(name: &str) -> String {
+    format!("{}{}", name, std::env::consts::EXE_SUFFIX)
+}
+
 fn find_sidecar(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, AppError> {
-    let bin_name = format!("{}.exe", name);
+    let bin_name = sidecar_file_name(name);
 
     // Production: resource_dir()/binaries/
     if let Ok(resource_dir) = app.path().resource_dir() {
         let path = resource_dir.join("binaries").join(&bin_name);
-        if path.exists() {
+        if path.is_file() {
             return Ok(path);
         }
     }
@@ -70,19 +86,22 @@ fn find_sidecar(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, AppError>
     let dev_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("binaries")
         .join(&bin_name);
-    if dev_path.exists() {
+    if dev_path.is_file() {
         return Ok(dev_path);
     }
 
-    // Last resort: system PATH
+    // Last resort: system PATH, then the usual install locations
     if let Ok(output) = std::process::Command::new(&bin_name).arg("--version").output() {
         if output.status.success() {
             return Ok(PathBuf::from(bin_name));
         }
     }
+    if let Some(path) = EXTRA_BIN_DIRS.iter().map(|dir| Path::new(dir).join(&bin_name)).find(|p| p.is_file()) {
+        return Ok(path);
+    }
 
     Err(AppError::ExportError(format!(
-        "Cannot find bundled {}.exe", name
+        "Cannot find {}: bundle it in binaries/ or install it on your PATH", bin_name
     )))
 }
 
@@ -207,40 +226,20 @@ pub fn export_book(
     }
 
     // Chapters & scenes
-    let book_json_path = book_dir.join("book.json");
-    let book_data: serde_json::Value =
-        serde_json::from_str(&std::fs::read_to_string(&book_json_path)?)?;
-
-    if let Some(chapters) = book_data.get("chapters").and_then(|v| v.as_array()) {
-        let mut sorted_chapters: Vec<_> = chapters.iter().collect();
-        sorted_chapters
-            .sort_by_key(|ch| ch.get("sort_order").and_then(|v| v.as_u64()).unwrap_or(0));
-
-        for ch in sorted_chapters {
-            let ch_id = ch.get("id").and_then(|v| v.as_str()).unwrap_or("");
-            let ch_title = ch.get("title").and_then(|v| v.as_str()).unwrap_or("");
-
-            if options.include_chapter_headings && !ch_title.is_empty() {
-                parts.push(format!("# {}\n\n", ch_title));
-            }
+    let book = book_repo::load_book(&project_dir, &book_id)?;
+    for ch in book.sorted_chapters() {
+        if options.include_chapter_headings && !ch.title.is_empty() {
+            parts.push(format!("# {}\n\n", ch.title));
+        }
 
-            if let Some(scenes) = ch.get("scenes").and_then(|v| v.as_array()) {
-                let mut sorted_scenes: Vec<_> = scenes.iter().collect();
-                sorted_scenes
-                    .sort_by_key(|sc| sc.get("sort_order").and_then(|v| v.as_u64()).unwrap_or(0));
-
-                for sc in sorted_scenes {
-                    let sc_id = sc.get("id").and_then(|v| v.as_str()).unwrap_or("");
-                    let draft_path =
-                        book_dir.join("chapters").join(ch_id).join(sc_id).join("draft.md");
-                    if draft_path.exists() {
-                        let content = std::fs::read_to_string(&draft_path)?;
-                        let (_, body) = parse_frontmatter_simple(&content);
-                        if !body.trim().is_empty() {
-                            parts.push(body);
-                            parts.push("\n\n".to_string());
-                        }
-                    }
+        for sc in ch.sorted_scenes() {
+            let draft_path = book_dir.join(CHAPTERS_DIR).join(&ch.id).join(&sc.id).join(DRAFT_FILE);
+            if draft_path.exists() {
+                let content = std::fs::read_to_string(&draft_path)?;
+                let (_, body) = parse_frontmatter_simple(&content);
+                if !body.trim().is_empty() {
+                    parts.push(body);
+                    parts.push("\n\n".to_string());
                 }
             }
         }
@@ -384,9 +383,8 @@ pub fn export_book(
             std::fs::write(&temp_md, &combined)?;
 
             // Pull title & author from book.json for ePub metadata
-            let epub_title =
-                book_data.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled");
-            let epub_author = book_data.get("author").and_then(|v| v.as_str()).unwrap_or("");
+            let epub_title = if book.title.is_empty() { "Untitled" } else { &book.title };
+            let epub_author = &book.author;
 
             cmd.arg(&temp_md)
                 .arg("-o")
@@ -450,9 +448,8 @@ pub fn export_book(
             pandoc_cmd.arg(format!("--variable=papersize:{}", paper));
 
             // Pull title & author
-            let pdf_title =
-                book_data.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled");
-            let pdf_author = book_data.get("author").and_then(|v| v.as_str()).unwrap_or("");
+            let pdf_title = if book.title.is_empty() { "Untitled" } else { &book.title };
+            let pdf_author = &book.author;
             pandoc_cmd
                 .arg(format!("--metadata=title:{}", pdf_title))
                 .arg(format!("--metadata=author:{}", pdf_author));
@@ -1,12 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use crate::error::AppError;
+use crate::frontmatter::parse_frontmatter;
+use crate::manuscript::{self, draft_word_count, DRAFT_FILE};
+use crate::project_path::ProjectPath;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileContent {
     pub frontmatter: serde_json::Value,
     pub body: String,
     pub path: String,
+    /// Version of the file as read; pass it back to `write_file` to detect conflicts
+    pub version: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,21 +23,6 @@ pub struct FileEntry {
     pub size: Option<u64>,
 }
 
-fn parse_frontmatter(content: &str) -> (serde_json::Value, String) {
-    if content.starts_with("---\n") || content.starts_with("---\r\n") {
-        let rest = &content[4..];
-        if let Some(end) = rest.find("\n---") {
-            let yaml_str = &rest[..end];
-            let body = rest[end + 4..].trim_start_matches('\n').trim_start_matches('\r');
-            if let Ok(yaml_val) = serde_yaml::from_str::<serde_yaml::Value>(yaml_str) {
-                let json_val = serde_json::to_value(&yaml_val).unwrap_or(serde_json::Value::Null);
-                return (json_val, body.to_string());
-            }
-        }
-    }
-    (serde_json::Value::Null, content.to_string())
-}
-
 fn serialize_frontmatter(frontmatter: &serde_json::Value, body: &str) -> String {
     if frontmatter.is_null() || (frontmatter.is_object() && frontmatter.as_object().unwrap().is_empty()) {
         return body.to_string();
@@ -65,7 +55,7 @@ fn infer_file_type(path: &PathBuf) -> Option<String> {
         if name_lower == "profile.md" { return Some("character-profile".to_string()); }
         if name_lower == "entry.md" { return Some("world-entry".to_string()); }
         if name_lower == "brainstorm.md" { return Some("brainstorm".to_string()); }
-        if name_lower == "draft.md" { return Some("element-draft".to_string()); }
+        if name_lower == DRAFT_FILE { return Some("element-draft".to_string()); }
         if name_lower == "relationship-dynamics.md" { return Some("relationship-dynamics".to_string()); }
     }
     None
@@ -82,17 +72,28 @@ pub fn read_file(path: PathBuf) -> Result<FileContent, AppError> {
         frontmatter,
         body,
         path: path.to_string_lossy().to_string(),
+        version: crate::file_io::remember_version(&content),
     })
 }
 
 #[tauri::command]
-pub fn write_file(path: PathBuf, frontmatter: serde_json::Value, body: String) -> Result<(), AppError> {
+pub fn write_file(
+    path: PathBuf,
+    frontmatter: serde_json::Value,
+    body: String,
+    expected_version: Option<String>,
+) -> Result<String, AppError> {
     if let Some(parent) = path.parent() {
         std::fs::create_dir_all(parent)?;
     }
     let content = serialize_frontmatter(&frontmatter, &body);
-    std::fs::write(&path, content)?;
-    Ok(())
+    // The editor saves scene drafts through here too; a count that can't be read
+    // leaves the writing history to use the last one it saw
+    let words_before = draft_word_count(&path).ok();
+    let version = crate::file_io::write_versioned(&path, &content, expected_version.as_deref())?;
+    super::progress::recompute_after_save(&path);
+    super::writing::record_draft_change(&path, words_before);
+    Ok(version)
 }
 
 #[tauri::command]
@@ -108,8 +109,7 @@ pub fn create_from_template(
     for (key, value) in &variables {
         content = content.replace(&format!("{{{{{}}}}}", key), value);
     }
-    std::fs::write(&path, content)?;
-    Ok(())
+    crate::file_io::atomic_write(&path, content)
 }
 
 #[tauri::command]
@@ -163,6 +163,8 @@ pub fn rename_entry(from: PathBuf, to: PathBuf) -> Result<(), AppError> {
         return Err(AppError::FileNotFound(from.to_string_lossy().to_string()));
     }
     std::fs::rename(&from, &to)?;
+    crate::watcher::note_own_write(&from);
+    crate::watcher::note_own_write(&to);
     Ok(())
 }
 
@@ -173,6 +175,7 @@ pub fn delete_entry(path: PathBuf) -> Result<(), AppError> {
     }
     // Send to OS recycle bin so users can recover deleted files
     trash::delete(&path).map_err(|e| AppError::General(format!("Failed to move to recycle bin: {}", e)))?;
+    crate::watcher::note_own_write(&path);
     Ok(())
 }
 
@@ -185,6 +188,8 @@ pub fn move_entry(from: PathBuf, to: PathBuf) -> Result<(), AppError> {
         std::fs::create_dir_all(parent)?;
     }
     std::fs::rename(&from, &to)?;
+    crate::watcher::note_own_write(&from);
+    crate::watcher::note_own_write(&to);
     Ok(())
 }
 
@@ -195,8 +200,7 @@ pub fn get_word_count(path: PathBuf) -> Result<u64, AppError> {
     }
     let content = std::fs::read_to_string(&path)?;
     let (_, body) = parse_frontmatter(&content);
-    let count = body.split_whitespace().count() as u64;
-    Ok(count)
+    Ok(manuscript::word_count(&body))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -222,81 +226,42 @@ pub struct SceneWordCount {
 
 #[tauri::command]
 pub fn get_book_word_count(project_dir: PathBuf, book_id: String) -> Result<WordCountSummary, AppError> {
-    let book_dir = project_dir.join("books").join(&book_id);
-    let book_json_path = book_dir.join("book.json");
-    if !book_json_path.exists() {
-        return Err(AppError::BookNotFound(book_id));
-    }
+    let book = crate::book_repo::load_book(&project_dir, &book_id)?;
+    let target = if book.target_word_count > 0 { book.target_word_count } else { 80000 };
 
-    let book_data: serde_json::Value =
-        serde_json::from_str(&std::fs::read_to_string(&book_json_path)?)?;
-    let target = book_data.get("target_word_count")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(80000);
-
-    let chapters_dir = book_dir.join("chapters");
+    // Chapters and scenes as book.json lists them, in reading order
     let mut chapters = Vec::new();
     let mut book_total: u64 = 0;
-
-    if chapters_dir.exists() {
-        let mut ch_entries: Vec<_> = std::fs::read_dir(&chapters_dir)?
-            .filter_map(|e| e.ok())
-            .filter(|e| e.path().is_dir())
-            .collect();
-        ch_entries.sort_by_key(|e| e.file_name());
-
-        for ch_entry in ch_entries {
-            let ch_path = ch_entry.path();
-            let ch_id = ch_entry.file_name().to_string_lossy().to_string();
-            let ch_title = ch_id.clone(); // Could read from _chapter.md
-            let mut ch_wc: u64 = 0;
-            let mut scenes = Vec::new();
-
-            let mut sc_entries: Vec<_> = std::fs::read_dir(&ch_path)?
-                .filter_map(|e| e.ok())
-                .filter(|e| e.path().is_dir() && e.file_name().to_string_lossy().starts_with("scene-"))
-                .collect();
-            sc_entries.sort_by_key(|e| e.file_name());
-
-            for sc_entry in sc_entries {
-                let sc_id = sc_entry.file_name().to_string_lossy().to_string();
-                let draft_path = sc_entry.path().join("draft.md");
-                let wc = if draft_path.exists() {
-                    let content = std::fs::read_to_string(&draft_path)?;
-                    let (_, body) = parse_frontmatter(&content);
-                    body.split_whitespace().count() as u64
-                } else {
-                    0
-                };
-                ch_wc += wc;
-                scenes.push(SceneWordCount { scene_id: sc_id, word_count: wc });
-            }
-
-            book_total += ch_wc;
-            chapters.push(ChapterWordCount {
-                chapter_id: ch_id,
-                chapter_title: ch_title,
-                word_count: ch_wc,
-                scenes,
-            });
+    for chapter in book.sorted_chapters() {
+        let ch_dir = manuscript::chapter_dir(&project_dir, &book_id, &chapter.id);
+        let mut ch_wc: u64 = 0;
+        let mut scenes = Vec::new();
+        for scene in chapter.sorted_scenes() {
+            let wc = draft_word_count(&ch_dir.join(&scene.id).join(DRAFT_FILE))?;
+            ch_wc += wc;
+            scenes.push(SceneWordCount { scene_id: scene.id.clone(), word_count: wc });
         }
+
+        book_total += ch_wc;
+        chapters.push(ChapterWordCount {
+            chapter_id: chapter.id.clone(),
+            chapter_title: chapter.title.clone(),
+            word_count: ch_wc,
+            scenes,
+        });
     }
 
     Ok(WordCountSummary { book_total, target, chapters })
 }
 
-/// Load the set of excluded file paths from .context_settings.json.
-fn load_excluded_paths(project_dir: &PathBuf) -> std::collections::HashSet<String> {
-    let settings_path = project_dir.join(".context_settings.json");
-    if let Ok(content) = std::fs::read_to_string(&settings_path) {
-        if let Ok(map) = serde_json::from_str::<std::collections::HashMap<String, String>>(&content) {
-            return map.into_iter()
-                .filter(|(_, mode)| mode == "exclude")
-                .map(|(path, _)| path.replace('/', "\\"))
-                .collect();
-        }
-    }
-    std::collections::HashSet::new()
+/// Load the set of excluded file paths from .context_settings.json, as native absolute paths.
+fn load_excluded_paths(project_dir: &PathBuf) -> std::collections::HashSet<PathBuf> {
+    crate::context::assembler::load_context_settings(project_dir)
+        .into_iter()
+        .filter(|(_, mode)| mode == "exclude")
+        .filter_map(|(rel, _)| ProjectPath::new(&rel).ok())
+        .map(|rel| rel.to_absolute(project_dir))
+        .collect()
 }
 
 /// Count words in ALL .md files recursively within a book directory.
@@ -324,7 +289,7 @@ pub fn get_project_total_doc_words(project_dir: PathBuf) -> Result<u64, AppError
     Ok(count_md_words_recursive(&project_dir, &excluded))
 }
 
-fn count_md_words_recursive(dir: &PathBuf, excluded: &std::collections::HashSet<String>) -> u64 {
+fn count_md_words_recursive(dir: &PathBuf, excluded: &std::collections::HashSet<PathBuf>) -> u64 {
     let mut total: u64 = 0;
     let entries = match std::fs::read_dir(dir) {
         Ok(e) => e,
@@ -335,13 +300,12 @@ fn count_md_words_recursive(dir: &PathBuf, excluded: &std::collections::HashSet<
         if path.is_dir() {
             total += count_md_words_recursive(&path, excluded);
         } else if path.extension().map(|e| e == "md").unwrap_or(false) {
-            let path_str = path.to_string_lossy().to_string();
-            if excluded.contains(&path_str) {
+            if excluded.contains(&path) {
                 continue;
             }
             if let Ok(content) = std::fs::read_to_string(&path) {
                 let (_, body) = parse_frontmatter(&content);
-                total += body.split_whitespace().count() as u64;
+                total += manuscript::word_count(&body);
             }
         }
     }
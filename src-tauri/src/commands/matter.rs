@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use chrono::Utc;
+use crate::book_repo;
 use crate::error::AppError;
 use super::templates::load_template;
 
@@ -172,20 +173,12 @@ fn update_matter_in_book_json(
     field: &str, // "front_matter" or "back_matter"
     value: bool,
 ) -> Result<(), AppError> {
-    let book_json_path = project_dir.join("books").join(book_id).join("book.json");
-    if !book_json_path.exists() {
+    if !book_repo::book_json_path(project_dir, book_id).exists() {
         return Ok(());
     }
-    let mut book_data: serde_json::Value =
-        serde_json::from_str(&std::fs::read_to_string(&book_json_path)?)?;
-
-    let key = subtype.replace('-', "_");
-    if let Some(matter) = book_data.get_mut(field) {
-        matter[&key] = serde_json::Value::Bool(value);
-    } else {
-        book_data[field] = serde_json::json!({ key: value });
-    }
-
-    std::fs::write(&book_json_path, serde_json::to_string_pretty(&book_data)?)?;
-    Ok(())
+    book_repo::update_book(project_dir, book_id, |book| {
+        let matter = if field == "front_matter" { &mut book.front_matter } else { &mut book.back_matter };
+        matter.insert(subtype.replace('-', "_"), serde_json::Value::Bool(value));
+        Ok(())
+    })
 }
@@ -4,6 +4,9 @@ pub mod filesystem;
 pub mod draft;
 pub mod attachment;
 pub mod chapter;
+pub mod consistency;
+pub mod progress;
+pub mod writing;
 pub mod matter;
 pub mod agent;
 pub mod config;
@@ -57,7 +57,7 @@ fn ensure_models_config() -> Result<PathBuf, AppError> {
     std::fs::create_dir_all(&dir)?;
     let path = dir.join("models.toml");
     if !path.exists() {
-        std::fs::write(&path, default_models_toml())?;
+        crate::file_io::atomic_write(&path, default_models_toml())?;
     }
     Ok(path)
 }
@@ -77,6 +77,16 @@ pub fn get_models_config_path() -> Result<String, AppError> {
     Ok(path.to_string_lossy().to_string())
 }
 
+/// All model IDs from the user's models.toml, falling back to the bundled
+/// defaults when the user file can't be read or parsed.
+pub fn known_model_ids() -> Vec<String> {
+    let config = get_models_config()
+        .or_else(|_| toml::from_str::<ModelsConfig>(default_models_toml()).map_err(AppError::from));
+    config
+        .map(|c| c.models.into_iter().map(|m| m.id).collect())
+        .unwrap_or_default()
+}
+
 /// Look up pricing for a model by ID. Returns (input_rate, output_rate) per million tokens.
 /// If input_tokens > 200K and the model has long_context pricing, use that tier.
 pub fn get_model_rates(model_id: &str, input_tokens: u64) -> (f64, f64) {
@@ -0,0 +1,411 @@
+// Phase progress — derives each book's `phase_progress` from the files of the five
+// Sapling phases, following the completion criteria in SPEC §5.4.
+
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use serde_json::{json, Value};
+use crate::book_repo::{self, Book, PhaseProgress};
+use crate::error::AppError;
+use crate::manuscript::{self, DRAFT_FILE, OUTLINE_FILE};
+use crate::project_path::ProjectPath;
+use crate::frontmatter::parse_frontmatter;
+
+const SEED_DIR: &str = "phase-1-seed";
+const ROOT_DIR: &str = "phase-2-root";
+const SPROUT_DIR: &str = "phase-3-sprout";
+
+/// Seed element folders; the progress key is the slug with underscores.
+const SEED_ELEMENTS: &[&str] = &["premise", "theme", "protagonist", "central-conflict", "story-world", "emotional-promise"];
+const BEATS_TOTAL: u32 = 21;
+const JOURNEY_STAGES: u32 = 8;
+
+/// Frontmatter statuses that mark an outline as still being worked on, unless its
+/// body already states the goal and the conflict.
+const OUTLINE_PENDING: &[&str] = &["not_started", "empty", "in_progress"];
+
+/// The phases whose progress is derived from files, by the book folder they live in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Seed,
+    Root,
+    Sprout,
+    /// FLOURISH and BLOOM, both counted from the scenes in `chapters/`
+    Manuscript,
+}
+
+const ALL_PHASES: &[Phase] = &[Phase::Seed, Phase::Root, Phase::Sprout, Phase::Manuscript];
+
+impl Phase {
+    fn of_dir(dir: &str) -> Option<Phase> {
+        match dir {
+            SEED_DIR => Some(Phase::Seed),
+            ROOT_DIR => Some(Phase::Root),
+            SPROUT_DIR => Some(Phase::Sprout),
+            manuscript::CHAPTERS_DIR => Some(Phase::Manuscript),
+            _ => None,
+        }
+    }
+}
+
+/// Recompute a book's phase progress from its files and save it if it changed.
+#[tauri::command]
+pub fn recompute_phase_progress(project_dir: PathBuf, book_id: String) -> Result<PhaseProgress, AppError> {
+    recompute(&project_dir, &book_id, ALL_PHASES)
+}
+
+/// Recompute the progress of the phase a just-saved file belongs to, if any. Call
+/// it once book.json is saved, not from inside an `update_book` edit, whose save
+/// would overwrite it. Failures only warn, so they never fail the save.
+pub fn recompute_after_save(path: &Path) {
+    let Some((project_dir, book_id, dir)) = owning_book(path) else { return };
+    let Some(phase) = Phase::of_dir(&dir) else { return };
+    if let Err(e) = recompute(&project_dir, &book_id, &[phase]) {
+        eprintln!("Warning: failed to update phase progress of {}: {}", book_id, e);
+    }
+}
+
+/// Recompute `phases` and save book.json, unless nothing changed.
+fn recompute(project_dir: &Path, book_id: &str, phases: &[Phase]) -> Result<PhaseProgress, AppError> {
+    let mut book = book_repo::load_book(project_dir, book_id)?;
+    let before = serde_json::to_value(&book.phase_progress)?;
+    evaluate(project_dir, &mut book, phases);
+    if serde_json::to_value(&book.phase_progress)? == before {
+        return Ok(book.phase_progress);
+    }
+    book_repo::update_book(project_dir, book_id, |book| {
+        evaluate(project_dir, book, phases);
+        Ok(book.phase_progress.clone())
+    })
+}
+
+/// The project directory, book id and top-level book folder of a file in `books/{book}/`.
+fn owning_book(path: &Path) -> Option<(PathBuf, String, String)> {
+    let project_dir = manuscript::project_of(path)?;
+    let rel = ProjectPath::from_absolute(&project_dir, path)?;
+    let mut parts = rel.as_str().split('/').skip(1);
+    let (book_id, dir) = (parts.next()?.to_string(), parts.next()?.to_string());
+    Some((project_dir, book_id, dir))
+}
+
+fn evaluate(project_dir: &Path, book: &mut Book, phases: &[Phase]) {
+    let now = Utc::now().to_rfc3339();
+    for phase in phases {
+        match phase {
+            Phase::Seed => evaluate_seed(project_dir, book, &now),
+            Phase::Root => evaluate_root(project_dir, book, &now),
+            Phase::Sprout => evaluate_sprout(project_dir, book, &now),
+            Phase::Manuscript => evaluate_manuscript(project_dir, book, &now),
+        }
+    }
+}
+
+/// SEED: six element drafts, then the story foundation built from them.
+fn evaluate_seed(project_dir: &Path, book: &mut Book, now: &str) {
+    let seed_dir = manuscript::book_dir(project_dir, &book.id).join(SEED_DIR);
+    let seed = &mut book.phase_progress.seed;
+    for slug in SEED_ELEMENTS {
+        let draft = seed_dir.join(slug).join(DRAFT_FILE);
+        let done = match *slug {
+            "protagonist" => read_body(&draft).is_some_and(|body| body_has_content(&body) && has_want_and_need(&body)),
+            _ => has_content(&draft),
+        };
+        seed.elements.insert(slug.replace('-', "_"), done);
+    }
+    seed.deliverables.insert("logline".into(), has_content(&seed_dir.join("logline.md")));
+    seed.deliverables.insert("story_foundation".into(), has_content(&seed_dir.join("story-foundation.md")));
+    let started = seed.elements.values().chain(seed.deliverables.values()).any(|&done| done)
+        || has_any_file(&seed_dir);
+    let complete = SEED_ELEMENTS.iter().all(|slug| seed.elements[&slug.replace('-', "_")])
+        && seed.deliverables["story_foundation"];
+    set_status(&mut seed.status, &mut seed.completed_at, started, complete, now);
+}
+
+/// ROOT: all 21 beats, from their drafts or the structure outline's beat sections.
+fn evaluate_root(project_dir: &Path, book: &mut Book, now: &str) {
+    let root_dir = manuscript::book_dir(project_dir, &book.id).join(ROOT_DIR);
+    let outline_path = root_dir.join("story-structure-outline.md");
+    let outlined_beats = beats_in_outline(&read_body(&outline_path).unwrap_or_default());
+    let root = &mut book.phase_progress.root;
+    root.beats_total = BEATS_TOTAL;
+    root.beats_drafted = (1..=BEATS_TOTAL)
+        .filter(|n| {
+            outlined_beats.contains(n)
+                || subdirs(&root_dir, &format!("beat-{:02}-", n)).iter().any(|d| has_content(&d.join(DRAFT_FILE)))
+        })
+        .count() as u32;
+    root.deliverables.insert("story_structure_outline".into(), has_content(&outline_path));
+    let started = root.beats_drafted > 0 || has_any_file(&root_dir);
+    let complete = root.beats_drafted == BEATS_TOTAL;
+    set_status(&mut root.status, &mut root.completed_at, started, complete, now);
+}
+
+/// SPROUT: the protagonist's 8-stage journey; other journeys are tracked but optional.
+fn evaluate_sprout(project_dir: &Path, book: &mut Book, now: &str) {
+    let sprout_dir = manuscript::book_dir(project_dir, &book.id).join(SPROUT_DIR);
+    let sprout = &mut book.phase_progress.sprout;
+    let mut journeys = Vec::new();
+    for character_dir in subdirs(&sprout_dir, "") {
+        let slug = character_dir.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let stages = (1..=JOURNEY_STAGES)
+            .filter(|n| subdirs(&character_dir, &format!("stage-{}-", n)).iter().any(|d| has_content(&d.join(DRAFT_FILE))))
+            .count() as u32;
+        let entry = sprout.characters.entry(slug.clone()).or_insert_with(|| json!({}));
+        if !entry.is_object() {
+            *entry = json!({});
+        }
+        entry["stages_completed"] = json!(stages);
+        entry["stages_total"] = json!(JOURNEY_STAGES);
+        entry["journey_map"] = json!(has_content(&character_dir.join("journey-map.md")));
+        journeys.push((slug, stages));
+    }
+    sprout.characters.retain(|slug, _| journeys.iter().any(|(s, _)| s == slug));
+    sprout.deliverables.insert("relationship_dynamics".into(), has_content(&sprout_dir.join("relationship-dynamics.md")));
+    // Without a profile marked `role: protagonist`, any finished journey counts
+    let protagonists: Vec<&(String, u32)> = journeys.iter().filter(|(slug, _)| is_protagonist(project_dir, slug)).collect();
+    let candidates = if protagonists.is_empty() { journeys.iter().collect() } else { protagonists };
+    let started = journeys.iter().any(|(_, stages)| *stages > 0) || has_any_file(&sprout_dir);
+    let complete = candidates.iter().any(|(_, stages)| *stages == JOURNEY_STAGES);
+    set_status(&mut sprout.status, &mut sprout.completed_at, started, complete, now);
+}
+
+/// FLOURISH and BLOOM: an outline and a draft for every scene in book.json. A draft
+/// counts once it has prose, whatever its status (SPEC §5.4).
+fn evaluate_manuscript(project_dir: &Path, book: &mut Book, now: &str) {
+    let (mut outlined, mut drafted, mut total) = (0, 0, 0);
+    for chapter in &book.chapters {
+        for scene in &chapter.scenes {
+            let sc_dir = manuscript::scene_dir(project_dir, &book.id, &chapter.id, &scene.id);
+            total += 1;
+            outlined += is_outlined(&sc_dir.join(OUTLINE_FILE)) as u32;
+            drafted += has_content(&sc_dir.join(DRAFT_FILE)) as u32;
+        }
+    }
+    let has_chapters = !book.chapters.is_empty();
+    let progress = &mut book.phase_progress;
+    let flourish = &mut progress.flourish;
+    flourish.scenes_outlined = outlined;
+    let complete = total > 0 && outlined == total;
+    set_status(&mut flourish.status, &mut flourish.completed_at, has_chapters, complete, now);
+
+    let bloom = &mut progress.bloom;
+    bloom.scenes_drafted = drafted;
+    bloom.scenes_total = total;
+    let complete = total > 0 && drafted == total;
+    set_status(&mut bloom.status, &mut bloom.completed_at, drafted > 0, complete, now);
+}
+
+/// Move a phase between "not_started", "in_progress" and "complete". `completed_at`
+/// keeps the first time the phase was completed and is cleared if it no longer is.
+fn set_status(status: &mut String, completed_at: &mut Option<String>, started: bool, complete: bool, now: &str) {
+    *status = if complete {
+        "complete"
+    } else if started {
+        "in_progress"
+    } else {
+        "not_started"
+    }
+    .to_string();
+    if !complete {
+        *completed_at = None;
+    } else if completed_at.is_none() {
+        *completed_at = Some(now.to_string());
+    }
+}
+
+/// A scene outline counts once its status moves past the pending ones, or once it
+/// states the character goal and the conflict (SPEC §5.4).
+fn is_outlined(path: &Path) -> bool {
+    let Ok(content) = std::fs::read_to_string(path) else { return false };
+    let (frontmatter, body) = parse_frontmatter(&content);
+    match frontmatter.get("status").and_then(Value::as_str) {
+        Some(status) if !OUTLINE_PENDING.contains(&status) => true,
+        _ => ["goal", "conflict"].iter().all(|section| section_has_content(&body, section)),
+    }
+}
+
+/// Whether a protagonist profile says what the character wants and what they need
+/// (SPEC §5.4), as words or headings.
+fn has_want_and_need(body: &str) -> bool {
+    let words: Vec<String> = body.split(|c: char| !c.is_alphanumeric()).map(str::to_lowercase).collect();
+    ["want", "need"].iter().all(|word| words.iter().any(|w| w == word || *w == format!("{}s", word)))
+}
+
+/// Whether the first heading mentioning `word` has text under it.
+fn section_has_content(body: &str, word: &str) -> bool {
+    let mut lines = body.lines().skip_while(|l| !(l.starts_with('#') && l.to_lowercase().contains(word)));
+    lines.next().is_some() && lines.take_while(|l| !l.starts_with('#')).any(|l| !l.trim().is_empty())
+}
+
+/// Numbers of the "Beat N" headings in a structure outline that have text under them.
+fn beats_in_outline(body: &str) -> Vec<u32> {
+    let mut beats = Vec::new();
+    let mut current = None;
+    for line in body.lines() {
+        if line.starts_with('#') {
+            let title = line.trim_start_matches('#').trim().to_lowercase();
+            current = title
+                .strip_prefix("beat")
+                .map(|rest| rest.trim_start().chars().take_while(char::is_ascii_digit).collect::<String>())
+                .and_then(|n| n.parse::<u32>().ok());
+        } else if let Some(n) = current {
+            if !line.trim().is_empty() && !beats.contains(&n) {
+                beats.push(n);
+            }
+        }
+    }
+    beats
+}
+
+fn is_protagonist(project_dir: &Path, slug: &str) -> bool {
+    let Ok(content) = std::fs::read_to_string(project_dir.join("characters").join(slug).join("profile.md")) else {
+        return false;
+    };
+    parse_frontmatter(&content).0.get("role").and_then(Value::as_str) == Some("protagonist")
+}
+
+fn read_body(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    Some(parse_frontmatter(&content).1)
+}
+
+/// A file has content when its body has any line besides headings.
+fn has_content(path: &Path) -> bool {
+    read_body(path).is_some_and(|body| body_has_content(&body))
+}
+
+fn body_has_content(body: &str) -> bool {
+    body.lines().any(|l| !l.trim().is_empty() && !l.starts_with('#'))
+}
+
+/// Whether any file exists under `dir`, e.g. a brainstorm started before any draft.
+fn has_any_file(dir: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else { return false };
+    entries.filter_map(|e| e.ok()).any(|e| {
+        let path = e.path();
+        if path.is_dir() { has_any_file(&path) } else { path.extension().is_some_and(|ext| ext == "md") }
+    })
+}
+
+/// The folders in `dir` whose names start with `prefix`, sorted.
+fn subdirs(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir() && e.file_name().to_string_lossy().starts_with(prefix))
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| e.path())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::chapter::create_scene;
+    use crate::test_support::{TestProject, BOOK};
+
+    fn write_seed_elements(project: &TestProject) {
+        let seed_dir = project.book_dir().join(SEED_DIR);
+        for slug in SEED_ELEMENTS {
+            let body = if *slug == "protagonist" { "## Want\nTo leave.\n\n## Need\nTo stay.\n" } else { "Done.\n" };
+            std::fs::create_dir_all(seed_dir.join(slug)).unwrap();
+            std::fs::write(seed_dir.join(slug).join(DRAFT_FILE), format!("---\nstatus: generated\n---\n\n{}", body)).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_seed_and_root_follow_phase_files() {
+        let project = TestProject::new("progress");
+        write_seed_elements(&project);
+        let root_dir = project.book_dir().join(ROOT_DIR);
+        std::fs::create_dir_all(root_dir.join("beat-03-inciting-incident")).unwrap();
+        std::fs::write(root_dir.join("beat-03-inciting-incident").join(DRAFT_FILE), "The storm hits.\n").unwrap();
+        std::fs::write(
+            root_dir.join("story-structure-outline.md"),
+            "# Outline\n\n## Act 1\n\n### Beat 1: Opening Image\nA harbour.\n\n### Beat 2: Daily Life\n\n### BEAT 3: Inciting Incident\nStorm.\n",
+        ).unwrap();
+
+        let progress = recompute_phase_progress(project.dir.clone(), BOOK.into()).unwrap();
+        assert_eq!(progress.seed.status, "in_progress");
+        assert!(progress.seed.elements.values().all(|&done| done));
+        assert_eq!(progress.root.beats_drafted, 2);
+        assert!(progress.root.deliverables["story_structure_outline"]);
+        assert_eq!(progress.flourish.status, "not_started");
+    }
+
+    #[test]
+    fn test_protagonist_needs_a_want_and_a_need() {
+        let project = TestProject::new("progress");
+        write_seed_elements(&project);
+        let protagonist = project.book_dir().join(SEED_DIR).join("protagonist").join(DRAFT_FILE);
+        std::fs::write(&protagonist, "Mara keeps the lighthouse and wants to leave.\n").unwrap();
+        let progress = recompute_phase_progress(project.dir.clone(), BOOK.into()).unwrap();
+        assert!(!progress.seed.elements["protagonist"]);
+
+        std::fs::write(&protagonist, "Mara keeps the lighthouse and wants to leave. She needs to stay.\n").unwrap();
+        let progress = recompute_phase_progress(project.dir.clone(), BOOK.into()).unwrap();
+        assert!(progress.seed.elements["protagonist"]);
+    }
+
+    #[test]
+    fn test_saving_a_deliverable_updates_only_its_phase() {
+        let project = TestProject::with_scene("progress");
+        write_seed_elements(&project);
+
+        crate::commands::filesystem::write_file(
+            project.book_dir().join(SEED_DIR).join("story-foundation.md"),
+            serde_json::json!({ "status": "generated" }), "Everything.\n".into(), None,
+        ).unwrap();
+        let progress = project.book().phase_progress;
+        assert_eq!(progress.seed.status, "complete");
+        assert!(progress.seed.completed_at.is_some());
+        // The scene already in book.json is left for a manuscript save to count
+        assert_eq!(progress.flourish.status, "not_started");
+        assert_eq!(progress.bloom.scenes_total, 0);
+    }
+
+    #[test]
+    fn test_saving_a_draft_updates_the_manuscript_phases() {
+        let project = TestProject::with_scene("progress");
+        create_scene(project.dir.clone(), BOOK.into(), "ch-01".into(), "Squall".into(), "reaction".into()).unwrap();
+        let draft = std::fs::read_to_string(project.draft()).unwrap();
+        crate::commands::draft::save_draft(project.draft(), format!("{}The ferry docks.\n", draft), None).unwrap();
+
+        let progress = project.book().phase_progress;
+        assert_eq!(progress.flourish.status, "in_progress");
+        assert_eq!(progress.flourish.scenes_outlined, 0);
+        assert_eq!((progress.bloom.scenes_drafted, progress.bloom.scenes_total), (1, 2));
+        assert_eq!(progress.bloom.status, "in_progress");
+        assert_eq!(progress.seed.status, "not_started");
+    }
+
+    #[test]
+    fn test_draft_with_prose_counts_while_drafting() {
+        let project = TestProject::with_scene("progress");
+        std::fs::write(project.draft(), "---\nstatus: drafting\n---\n\nThe ferry docks.\n").unwrap();
+        let progress = recompute_phase_progress(project.dir.clone(), BOOK.into()).unwrap();
+        assert_eq!((progress.bloom.scenes_drafted, progress.bloom.status.as_str()), (1, "complete"));
+    }
+
+    #[test]
+    fn test_outline_counts_with_content_or_status() {
+        let project = TestProject::with_scene("progress");
+        create_scene(project.dir.clone(), BOOK.into(), "ch-01".into(), "Squall".into(), "reaction".into()).unwrap();
+        let progress = recompute_phase_progress(project.dir.clone(), BOOK.into()).unwrap();
+        assert_eq!(progress.flourish.scenes_outlined, 0);
+
+        // An outline with a goal and a conflict counts, as does an explicit status
+        let outline = project.scene_dir("ch-01", "scene-01").join(OUTLINE_FILE);
+        let content = std::fs::read_to_string(&outline).unwrap()
+            .replace("### Character Goal\n", "### Character Goal\nGet home.\n")
+            .replace("### Mounting Conflict\n", "### Mounting Conflict\nThe tide.\n");
+        std::fs::write(&outline, content).unwrap();
+        let second = project.scene_dir("ch-01", "scene-02").join(OUTLINE_FILE);
+        let content = std::fs::read_to_string(&second).unwrap().replace("status: not_started", "status: outlined");
+        std::fs::write(&second, content).unwrap();
+        let progress = recompute_phase_progress(project.dir.clone(), BOOK.into()).unwrap();
+        assert_eq!(progress.flourish.scenes_outlined, 2);
+        assert_eq!(progress.flourish.status, "complete");
+    }
+}
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use chrono::{DateTime, Utc};
+use crate::book_repo::{self, Book};
 use crate::error::AppError;
 use super::templates::load_template;
 
@@ -28,10 +29,56 @@ pub struct ProjectMetadata {
     pub world_sections: Vec<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub active_book_id: Option<String>,
+    /// Other projects read as context sources, e.g. for a shared universe
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub linked_projects: Vec<LinkedProject>,
     #[serde(skip)]
     pub directory: PathBuf,
 }
 
+/// Another project whose files and index are read, never written, as context.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkedProject {
+    /// Short name used in `@name/...` context patterns and to tag search results
+    pub name: String,
+    /// Project directory, absolute or relative to this project
+    pub path: String,
+}
+
+/// Linked projects that exist on disk, as (name, directory). Invalid names,
+/// missing directories and links back to this project are skipped with a warning.
+pub fn linked_project_dirs(project_dir: &std::path::Path) -> Vec<(String, PathBuf)> {
+    #[derive(Deserialize)]
+    struct Links {
+        #[serde(default)]
+        linked_projects: Vec<LinkedProject>,
+    }
+
+    let links = std::fs::read_to_string(project_dir.join("project.json"))
+        .ok()
+        .and_then(|data| serde_json::from_str::<Links>(&data).ok())
+        .map(|l| l.linked_projects)
+        .unwrap_or_default();
+    let this = project_dir.canonicalize().unwrap_or_else(|_| project_dir.to_path_buf());
+
+    let mut dirs: Vec<(String, PathBuf)> = Vec::new();
+    for link in links {
+        let valid_name = !link.name.is_empty()
+            && link.name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+        let dir = project_dir.join(&link.path);
+        if !valid_name || dirs.iter().any(|(n, _)| *n == link.name) {
+            eprintln!("Warning: linked project name \"{}\" must be unique and use only letters, digits, - and _", link.name);
+        } else if !dir.join("project.json").exists() {
+            eprintln!("Warning: linked project \"{}\" not found at {}", link.name, dir.display());
+        } else if dir.canonicalize().map(|d| d == this).unwrap_or(false) {
+            eprintln!("Warning: linked project \"{}\" is this project", link.name);
+        } else {
+            dirs.push((link.name, dir));
+        }
+    }
+    dirs
+}
+
 fn default_world_sections() -> Vec<String> {
     vec!["locations".to_string(), "items".to_string()]
 }
@@ -69,7 +116,7 @@ fn update_recent(name: &str, path: &PathBuf) -> Result<(), AppError> {
     if let Some(parent) = rp_path.parent() {
         std::fs::create_dir_all(parent)?;
     }
-    std::fs::write(&rp_path, serde_json::to_string_pretty(&recents)?)?;
+    crate::file_io::atomic_write(&rp_path, serde_json::to_string_pretty(&recents)?)?;
     Ok(())
 }
 
@@ -109,11 +156,12 @@ pub fn create_project(
         books: Vec::new(),
         world_sections: default_world_sections(),
         active_book_id: None,
+        linked_projects: Vec::new(),
         directory: directory.clone(),
     };
 
     let project_json = directory.join("project.json");
-    std::fs::write(&project_json, serde_json::to_string_pretty(&metadata)?)?;
+    crate::file_io::atomic_write(&project_json, serde_json::to_string_pretty(&metadata)?)?;
 
     // Create overview/brainstorm.md using template system
     let body = load_template(directory.clone(), "brainstorm-project".to_string(), HashMap::new())
@@ -162,7 +210,7 @@ pub fn create_book_dirs(
         "phase-4-flourish/act-1",
         "phase-4-flourish/act-2",
         "phase-4-flourish/act-3",
-        "phase-5-bloom",
+        crate::manuscript::CHAPTERS_DIR,
         "front-matter",
         "back-matter",
         "notes",
@@ -171,79 +219,21 @@ pub fn create_book_dirs(
         std::fs::create_dir_all(book_dir.join(d))?;
     }
 
-    let now = Utc::now();
-    let date_str = now.format("%Y-%m-%d").to_string();
+    let date_str = Utc::now().format("%Y-%m-%d").to_string();
     let book_id = book_dir.file_name().unwrap().to_string_lossy().to_string();
 
-    let book_meta = serde_json::json!({
-        "version": "1.0.0",
-        "id": book_id,
-        "title": title,
-        "author": author,
-        "genre_id": genre_id,
-        "sub_genre_id": sub_genre_id,
-        "sort_order": sort_order,
-        "created": now,
-        "modified": now,
-        "target_word_count": target_word_count,
-        "current_word_count": 0,
-        "phase_progress": {
-            "seed": {
-                "status": "not_started",
-                "elements": {
-                    "premise": false,
-                    "theme": false,
-                    "protagonist": false,
-                    "central_conflict": false,
-                    "story_world": false,
-                    "emotional_promise": false
-                },
-                "deliverables": {
-                    "logline": false,
-                    "story_foundation": false
-                }
-            },
-            "root": {
-                "status": "not_started",
-                "beats_drafted": 0,
-                "beats_total": 21,
-                "deliverables": {
-                    "story_structure_outline": false
-                }
-            },
-            "sprout": {
-                "status": "not_started",
-                "characters": {},
-                "deliverables": {
-                    "relationship_dynamics": false
-                }
-            },
-            "flourish": {
-                "status": "not_started",
-                "scenes_outlined": 0
-            },
-            "bloom": {
-                "status": "not_started",
-                "scenes_drafted": 0,
-                "scenes_total": 0
-            }
-        },
-        "chapters": [],
-        "front_matter": {},
-        "back_matter": {},
-        "settings": {
-            "perspective": perspective,
-            "tense": tense
-        }
-    });
-    std::fs::write(
-        book_dir.join("book.json"),
-        serde_json::to_string_pretty(&book_meta)?,
-    )?;
+    let project_dir = book_dir.parent().and_then(|p| p.parent()).unwrap_or(book_dir).to_path_buf();
+    let mut book = Book::new(&book_id, title, sort_order);
+    book.author = author.to_string();
+    book.genre_id = genre_id.to_string();
+    book.sub_genre_id = sub_genre_id.to_string();
+    book.target_word_count = target_word_count;
+    book.settings.perspective = perspective.to_string();
+    book.settings.tense = tense.to_string();
+    book_repo::save_book(&project_dir, &book)?;
 
     // Create book overview brainstorm.md using template system
     let scope = book_id;
-    let project_dir = book_dir.parent().and_then(|p| p.parent()).unwrap_or(book_dir).to_path_buf();
     let body = load_template(project_dir, "brainstorm-book".to_string(), HashMap::new())
         .unwrap_or_else(|_| "# Book Brainstorm\n\nWrite freely below.\n\n---\n\n".to_string());
     let brainstorm_content = format!(
@@ -271,6 +261,12 @@ pub fn open_project(directory: PathBuf) -> Result<ProjectMetadata, AppError> {
     let mut metadata: ProjectMetadata = serde_json::from_str(&data)?;
     metadata.directory = directory.clone();
 
+    for book in &metadata.books {
+        if let Err(e) = crate::manuscript::migrate_legacy_layout(&directory, &book.id) {
+            eprintln!("Warning: failed to migrate manuscript layout of {}: {}", book.id, e);
+        }
+    }
+
     update_recent(&metadata.name, &directory)?;
     Ok(metadata)
 }
@@ -307,7 +303,7 @@ pub fn update_project_metadata(
     metadata: ProjectMetadata,
 ) -> Result<(), AppError> {
     let project_json = project_dir.join("project.json");
-    std::fs::write(&project_json, serde_json::to_string_pretty(&metadata)?)?;
+    crate::file_io::atomic_write(&project_json, serde_json::to_string_pretty(&metadata)?)?;
     Ok(())
 }
 
@@ -320,7 +316,7 @@ pub fn delete_project(directory: PathBuf) -> Result<(), AppError> {
         let mut recents: Vec<RecentProject> = serde_json::from_str(&data)?;
         let path_str = directory.to_string_lossy().to_string();
         recents.retain(|r| r.path != path_str);
-        std::fs::write(&rp_path, serde_json::to_string_pretty(&recents)?)?;
+        crate::file_io::atomic_write(&rp_path, serde_json::to_string_pretty(&recents)?)?;
     }
 
     // Delete the project directory
@@ -331,6 +327,7 @@ pub fn delete_project(directory: PathBuf) -> Result<(), AppError> {
     Ok(())
 }
 
+/// Watch a project in addition to any already watched.
 #[tauri::command]
 pub fn start_file_watcher(
     app: tauri::AppHandle,
@@ -338,3 +335,24 @@ pub fn start_file_watcher(
 ) -> Result<(), AppError> {
     crate::watcher::start_watcher(app, project_dir)
 }
+
+/// Watch only this project, stopping the watchers of any others (e.g. when opening a project).
+#[tauri::command]
+pub fn switch_file_watcher(
+    app: tauri::AppHandle,
+    project_dir: PathBuf,
+) -> Result<(), AppError> {
+    crate::watcher::switch_watcher(app, project_dir)
+}
+
+/// Stop watching a project, or every project when `project_dir` is omitted.
+#[tauri::command]
+pub fn stop_file_watcher(project_dir: Option<PathBuf>) -> Result<(), AppError> {
+    match project_dir {
+        Some(dir) => {
+            crate::watcher::stop_watcher(&dir);
+        }
+        None => crate::watcher::stop_all_watchers(),
+    }
+    Ok(())
+}
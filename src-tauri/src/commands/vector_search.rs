@@ -1,14 +1,18 @@
 use std::path::PathBuf;
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::Ordering;
 use crate::error::AppError;
-use crate::context::vector::{self, SearchResult, IndexStatus};
+use crate::context::vector::{self, SearchMode, SearchResult, IndexStatus, RelatedContent, RelatedMode, ReindexEstimate, IndexIntegrityReport, KnownEntity, EntityAppearance, EntityCooccurrence};
 use crate::context::vector::db;
-use crate::context::vector::embeddings::VoyageClient;
+use crate::context::vector::embeddings;
 use crate::context::vector::indexer;
 use crate::context::assembler::load_context_settings;
 
-/// Search the project index. Used by chat /search and Context Settings.
+/// Search the project index and those of linked projects. Used by chat /search and Context Settings.
 /// Respects .context_settings.json exclusions by default.
+/// `mode` defaults to hybrid when an embedding provider is usable, lexical otherwise.
+/// `query` may contain filter syntax (`entity:character book:book-01 ch:3..7 project:this "exact phrase"`,
+/// see `query::parse_query`); those filters are combined with the explicit ones.
 #[tauri::command]
 pub async fn vector_search(
     project_dir: PathBuf,
@@ -17,17 +21,40 @@ pub async fn vector_search(
     filter_entity_types: Option<Vec<String>>,
     filter_book_id: Option<String>,
     respect_context_settings: Option<bool>,
+    mode: Option<SearchMode>,
 ) -> Result<Vec<SearchResult>, AppError> {
     let config = crate::commands::config::get_config()?;
     if !config.vector_search.enabled {
         return Err(AppError::VectorSearch("Vector search is not enabled".into()));
     }
 
-    let api_key = get_voyage_api_key(&config)?;
-    let client = VoyageClient::new(api_key, config.vector_search.embedding_model.clone());
+    let embedding = config.vector_search.embedding_config()?;
+    indexer::sync_vector_index(&project_dir, &embedding)?;
+    let client = indexer::client_for_index(&project_dir, &embedding)?;
+    let mode = mode.unwrap_or(if client.is_some() {
+        SearchMode::Hybrid
+    } else {
+        SearchMode::Lexical
+    });
+    let client = match mode {
+        SearchMode::Lexical => None,
+        _ => Some(client.ok_or_else(|| AppError::Embedding(
+            "Embedding API key not set. Enter your API key in Settings → Vector Search.".into()
+        ))?),
+    };
+
+    let parsed = vector::query::parse_query(&query)?;
+    if parsed.text.trim().is_empty() {
+        return Err(AppError::VectorSearch("Search query has no search text".into()));
+    }
+    let mut search_filters = parsed.filters;
+    search_filters.entity_types.extend(filter_entity_types.unwrap_or_default());
+    // A book written in the query takes precedence over the parameter
+    if search_filters.book_id.is_none() {
+        search_filters.book_id = filter_book_id;
+    }
 
     let max = max_results.unwrap_or(config.vector_search.max_results_default);
-    let entity_filters = filter_entity_types.unwrap_or_default();
     let respect = respect_context_settings.unwrap_or(true);
 
     let excluded: HashMap<String, String> = if respect {
@@ -40,17 +67,52 @@ pub async fn vector_search(
 
     vector::search::search(
         &project_dir,
-        &query,
-        &client,
+        &parsed.text,
+        client.as_deref(),
+        mode,
         max,
-        &entity_filters,
-        filter_book_id.as_deref(),
+        &search_filters,
         &excluded,
         &already_loaded,
+        &vector::rerank::ResultShaping::default(),
     )
     .await
 }
 
+/// Find content related to an indexed file ("more like this"), using the file's own
+/// embeddings as the query. In `similar_passages` mode, reports passages in other files
+/// that are nearly identical to passages in this one (default threshold 0.9).
+#[tauri::command]
+pub async fn find_related(
+    project_dir: PathBuf,
+    file_path: String,
+    mode: Option<RelatedMode>,
+    max_results: Option<u32>,
+    min_similarity: Option<f32>,
+    respect_context_settings: Option<bool>,
+) -> Result<RelatedContent, AppError> {
+    let config = crate::commands::config::get_config()?;
+    if !config.vector_search.enabled {
+        return Err(AppError::VectorSearch("Vector search is not enabled".into()));
+    }
+    indexer::sync_vector_index(&project_dir, &config.vector_search.embedding_config()?)?;
+
+    let excluded: HashMap<String, String> = if respect_context_settings.unwrap_or(true) {
+        load_context_settings(&project_dir)
+    } else {
+        HashMap::new()
+    };
+
+    vector::related::find_related(
+        &project_dir,
+        &file_path,
+        mode.unwrap_or_default(),
+        max_results.unwrap_or(config.vector_search.max_results_default),
+        min_similarity.unwrap_or(vector::related::DEFAULT_PASSAGE_THRESHOLD),
+        &excluded,
+    )
+}
+
 /// Get the current index status for the Settings UI.
 #[tauri::command]
 pub async fn get_index_status(
@@ -66,6 +128,17 @@ pub async fn get_index_status(
         .map(|g| *g)
         .unwrap_or(false);
 
+    let embedding = db::get_embedding_signature(&conn)?;
+    let quantization = db::get_vector_index_layout(&conn)?
+        .map(|(_, q)| q)
+        .unwrap_or_default()
+        .as_str()
+        .to_string();
+    let reembed_progress = crate::commands::config::get_config()
+        .ok()
+        .and_then(|config| config.vector_search.embedding_config().ok())
+        .and_then(|embedding| indexer::reembed_status(&project_dir, &embedding).ok().flatten());
+
     Ok(IndexStatus {
         total_files,
         total_chunks,
@@ -73,10 +146,38 @@ pub async fn get_index_status(
         total_cost_usd: total_cost,
         is_indexing,
         index_progress: None,
+        embedding,
+        reembed_progress,
+        quantization,
+        storage_bytes: db::index_storage_bytes(&project_dir),
     })
 }
 
+/// Chunks re-embedded per step when the embedding model changed.
+const REEMBED_BATCH: u32 = 512;
+
+/// Dry run of `reindex_project`: chunk every file and report how much would be
+/// embedded and what it would cost, without calling the provider or writing the index.
+#[tauri::command]
+pub async fn estimate_reindex(
+    project_dir: PathBuf,
+) -> Result<ReindexEstimate, AppError> {
+    let config = crate::commands::config::get_config()?;
+    let client = embeddings::client_from_config(&config.vector_search.embedding_config()?)?;
+    let files = indexer::collect_indexable_files(&project_dir);
+    indexer::estimate_reindex(&project_dir, &files, client.as_deref())
+}
+
+/// Stop a running re-index after the embedding requests already in flight.
+/// Files finished so far stay indexed.
+#[tauri::command]
+pub async fn cancel_reindex() -> Result<(), AppError> {
+    indexer::CANCEL_REQUESTED.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
 /// Trigger a full re-index of the project.
+/// Without a usable embedding provider only the lexical index is built.
 #[tauri::command]
 pub async fn reindex_project(
     app: tauri::AppHandle,
@@ -89,112 +190,132 @@ pub async fn reindex_project(
         return Err(AppError::VectorSearch("Vector search is not enabled".into()));
     }
 
-    let api_key = get_voyage_api_key(&config)?;
-    let client = VoyageClient::new(api_key, config.vector_search.embedding_model.clone());
+    let embedding = config.vector_search.embedding_config()?;
+    let client = embeddings::client_from_config(&embedding)?;
 
-    // Set indexing flag
-    if let Ok(mut flag) = indexer::IS_INDEXING.lock() {
-        *flag = true;
-    }
+    // Cleared when the guard drops, even if indexing fails or panics
+    let _guard = indexer::IndexingGuard::acquire()
+        .ok_or_else(|| AppError::IndexError("The project is already being indexed".into()))?;
 
     let files = indexer::collect_indexable_files(&project_dir);
     let total = files.len() as u32;
-    let mut total_chunks: u32 = 0;
-    let mut total_embedded: u32 = 0;
-    let mut total_tokens: u64 = 0;
+    let options = indexer::ReindexOptions::from_config(&embedding);
 
-    for (i, rel_path) in files.iter().enumerate() {
-        // Emit progress event
+    let summary = indexer::reindex_files(&project_dir, &files, client.as_deref(), &options, |done, rel_path| {
         let _ = app.emit("vector:indexing_progress", serde_json::json!({
-            "files_processed": i as u32,
+            "files_processed": done,
             "files_total": total,
             "current_file": rel_path,
         }));
+    })
+    .await?;
 
-        match indexer::index_file(&project_dir, rel_path, &client).await {
-            Ok(result) => {
-                total_chunks += result.chunks_total;
-                total_embedded += result.chunks_embedded;
-                total_tokens += result.tokens_used;
-            }
-            Err(e) => {
-                eprintln!("Warning: failed to index {}: {}", rel_path, e);
-            }
-        }
+    if let Err(e) = indexer::sync_vector_index(&project_dir, &embedding) {
+        eprintln!("Warning: failed to update vector index: {}", e);
     }
 
-    // Clear indexing flag
-    if let Ok(mut flag) = indexer::IS_INDEXING.lock() {
-        *flag = false;
+    // Bring vectors from a previous embedding model up to date
+    if let Some(client) = client.as_deref().filter(|_| !summary.cancelled) {
+        while !indexer::CANCEL_REQUESTED.load(Ordering::SeqCst) {
+            match indexer::reembed_step(&project_dir, client, REEMBED_BATCH).await {
+                Ok(Some(progress)) => {
+                    let _ = app.emit("vector:reembed_progress", &progress);
+                    if progress.complete {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    eprintln!("Warning: re-embed failed: {}", e);
+                    break;
+                }
+            }
+        }
     }
+    let cancelled = summary.cancelled || indexer::CANCEL_REQUESTED.load(Ordering::SeqCst);
 
     let _ = app.emit("vector:indexing_complete", serde_json::json!({
         "total_files": total,
-        "total_chunks": total_chunks,
-        "total_embedded": total_embedded,
-        "total_tokens": total_tokens,
+        "total_chunks": summary.chunks_total,
+        "total_embedded": summary.chunks_embedded,
+        "total_tokens": summary.tokens_used,
+        "files_failed": summary.files_failed,
+        "cancelled": cancelled,
     }));
 
     Ok(())
 }
 
-/// Clear the index completely.
+/// Check the index for orphaned rows, files deleted or changed on disk and
+/// embeddings of the wrong size. With `repair`, fix them, re-embedding only the
+/// chunks that need it; the report then describes what was found before repair.
 #[tauri::command]
-pub async fn clear_index(
+pub async fn verify_index(
     project_dir: PathBuf,
-) -> Result<(), AppError> {
-    let conn = db::open_index(&project_dir)?;
-    db::init_schema(&conn)?;
-    db::clear_all(&conn)?;
-    Ok(())
+    repair: Option<bool>,
+) -> Result<IndexIntegrityReport, AppError> {
+    if !repair.unwrap_or(false) {
+        return vector::integrity::verify_index(&project_dir);
+    }
+
+    let config = crate::commands::config::get_config()?;
+    let embedding = config.vector_search.embedding_config()?;
+    let client = embeddings::client_from_config(&embedding)?;
+    let _guard = indexer::IndexingGuard::acquire()
+        .ok_or_else(|| AppError::IndexError("The project is already being indexed".into()))?;
+    let options = indexer::ReindexOptions::from_config(&embedding);
+    vector::integrity::repair_index(&project_dir, client.as_deref(), &options).await
 }
 
-// ─── Helpers ───
+/// Characters, locations and items whose mentions are tracked.
+#[tauri::command]
+pub async fn list_entities(
+    project_dir: PathBuf,
+) -> Result<Vec<KnownEntity>, AppError> {
+    let conn = vector::mentions::open_synced(&project_dir)?;
+    db::get_entities(&conn)
+}
 
-/// Retrieve the Voyage API key from config, decrypting it.
-fn get_voyage_api_key(config: &crate::commands::config::AppConfig) -> Result<String, AppError> {
-    let encrypted = &config.vector_search.embedding_api_key_encrypted;
-    if encrypted.is_empty() {
-        return Err(AppError::Embedding(
-            "Voyage API key not set. Enter your Voyage AI API key in Settings → Vector Search.".into()
-        ));
-    }
-    // Reuse the same base64 decode from config module
-    // For now, a simple decode — the key is stored with the same obfuscation as the main API key
-    // Simple base64 decode inline (matches config.rs pattern)
-    decode_api_key(encrypted)
+/// Every scene draft or outline mentioning an entity, in story order.
+/// `entity` is a slug, name or alias; `file_type` ("scene_draft" or
+/// "scene_outline") restricts the files searched.
+#[tauri::command]
+pub async fn entity_appearances(
+    project_dir: PathBuf,
+    entity: String,
+    file_type: Option<String>,
+) -> Result<Vec<EntityAppearance>, AppError> {
+    vector::mentions::entity_appearances(&project_dir, &entity, file_type.as_deref())
 }
 
-fn decode_api_key(encoded: &str) -> Result<String, AppError> {
-    let input = encoded.trim().as_bytes();
-    if input.is_empty() {
-        return Err(AppError::Embedding("Empty API key".into()));
-    }
-    if input.len() % 4 != 0 {
-        return Err(AppError::Embedding("Invalid encoded API key".into()));
-    }
-    let mut output = Vec::with_capacity(input.len() * 3 / 4);
-    for chunk in input.chunks(4) {
-        let vals: Vec<u8> = chunk.iter().map(|&c| b64_val(c)).collect();
-        output.push((vals[0] << 2) | (vals[1] >> 4));
-        if chunk[2] != b'=' {
-            output.push((vals[1] << 4) | (vals[2] >> 2));
-        }
-        if chunk[3] != b'=' {
-            output.push((vals[2] << 6) | vals[3]);
-        }
-    }
-    String::from_utf8(output)
-        .map_err(|e| AppError::Embedding(format!("Invalid UTF-8 in API key: {}", e)))
+/// The first file in story order that mentions an entity.
+#[tauri::command]
+pub async fn entity_first_appearance(
+    project_dir: PathBuf,
+    entity: String,
+    file_type: Option<String>,
+) -> Result<Option<EntityAppearance>, AppError> {
+    let appearances = vector::mentions::entity_appearances(&project_dir, &entity, file_type.as_deref())?;
+    Ok(appearances.into_iter().next())
 }
 
-fn b64_val(c: u8) -> u8 {
-    match c {
-        b'A'..=b'Z' => c - b'A',
-        b'a'..=b'z' => c - b'a' + 26,
-        b'0'..=b'9' => c - b'0' + 52,
-        b'+' => 62,
-        b'/' => 63,
-        _ => 0,
-    }
+/// Entities mentioned in the same files as an entity, with how many files they share.
+#[tauri::command]
+pub async fn entity_cooccurrences(
+    project_dir: PathBuf,
+    entity: String,
+    file_type: Option<String>,
+) -> Result<Vec<EntityCooccurrence>, AppError> {
+    vector::mentions::entity_cooccurrences(&project_dir, &entity, file_type.as_deref())
+}
+
+/// Clear the index completely.
+#[tauri::command]
+pub async fn clear_index(
+    project_dir: PathBuf,
+) -> Result<(), AppError> {
+    let conn = db::open_index(&project_dir)?;
+    db::init_schema(&conn)?;
+    db::clear_all(&conn)?;
+    Ok(())
 }
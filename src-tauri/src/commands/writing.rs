@@ -0,0 +1,572 @@
+// Writing history — daily word counts per book and scene, and writing sessions, kept
+// in `.saipling/writing-history.json`. Saves from the editor and edits made in other
+// editors (seen by the file watcher) both feed it.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use crate::book_repo;
+use crate::error::AppError;
+use crate::manuscript::{self, draft_word_count, ManuscriptFile};
+use crate::project_path::ProjectPath;
+use super::filesystem::get_book_word_count;
+
+/// Saves further apart than this start a new session.
+const SESSION_GAP_MINUTES: i64 = 30;
+/// Sessions kept per book; older ones are dropped.
+const MAX_SESSIONS: usize = 1000;
+/// Days of recent history the projected completion date is based on.
+const PROJECTION_DAYS: i64 = 14;
+
+/// Serialises updates to the history file between commands and the watcher.
+static HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WritingHistory {
+    pub books: BTreeMap<String, BookHistory>,
+    /// Last seen word count of each scene draft, by project-relative path
+    pub draft_counts: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookHistory {
+    /// Keyed by local date
+    pub days: BTreeMap<NaiveDate, DayTotals>,
+    /// Word count of each scene ("ch-01/scene-02") at the end of each day it changed
+    pub scenes: BTreeMap<String, BTreeMap<NaiveDate, u64>>,
+    pub sessions: Vec<WritingSession>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DayTotals {
+    /// Book word count after the day's last change
+    pub words: u64,
+    pub added: u64,
+    pub deleted: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WritingSession {
+    /// RFC 3339, local time
+    pub started: String,
+    pub ended: String,
+    pub words_added: u64,
+    pub words_deleted: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyWordCount {
+    pub date: NaiveDate,
+    pub words: u64,
+    pub added: u64,
+    pub deleted: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WritingStats {
+    pub total_words: u64,
+    /// 0 if the book has no target
+    pub target_word_count: u64,
+    pub daily_goal: u32,
+    pub added_today: u64,
+    pub deleted_today: u64,
+    pub goal_met_today: bool,
+    /// Days in a row meeting the daily goal (any words without one), up to today;
+    /// today not counting yet doesn't break it
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// Net words per day over the last two weeks
+    pub average_daily_words: i64,
+    pub remaining_words: u64,
+    /// When the target is reached at the recent pace; None without a target or progress
+    pub projected_completion: Option<NaiveDate>,
+}
+
+// ─── Commands ───
+
+#[tauri::command]
+pub fn get_writing_stats(project_dir: PathBuf, book_id: String) -> Result<WritingStats, AppError> {
+    let book = book_repo::load_book(&project_dir, &book_id)?;
+    let total_words = get_book_word_count(project_dir.clone(), book_id.clone())?.book_total;
+    let history = load_history(&project_dir)?.books.remove(&book_id).unwrap_or_default();
+    Ok(stats_at(&history, total_words, book.target_word_count, book.settings.daily_word_goal, Local::now().date_naive()))
+}
+
+/// Daily history of a book, or of one scene when `scene` ("ch-01/scene-02") is given.
+#[tauri::command]
+pub fn get_word_count_history(project_dir: PathBuf, book_id: String, scene: Option<String>) -> Result<Vec<DailyWordCount>, AppError> {
+    let history = load_history(&project_dir)?.books.remove(&book_id).unwrap_or_default();
+    let Some(scene) = scene else {
+        return Ok(history.days.into_iter()
+            .map(|(date, day)| DailyWordCount { date, words: day.words, added: day.added, deleted: day.deleted })
+            .collect());
+    };
+    let mut previous = None;
+    Ok(history.scenes.get(&scene).into_iter().flatten()
+        .map(|(&date, &words)| {
+            let before = previous.replace(words).unwrap_or(words);
+            DailyWordCount { date, words, added: words.saturating_sub(before), deleted: before.saturating_sub(words) }
+        })
+        .collect())
+}
+
+/// A book's writing sessions, newest first.
+#[tauri::command]
+pub fn list_writing_sessions(project_dir: PathBuf, book_id: String, limit: Option<usize>) -> Result<Vec<WritingSession>, AppError> {
+    let history = load_history(&project_dir)?.books.remove(&book_id).unwrap_or_default();
+    Ok(history.sessions.into_iter().rev().take(limit.unwrap_or(usize::MAX)).collect())
+}
+
+#[tauri::command]
+pub fn set_daily_word_goal(project_dir: PathBuf, book_id: String, goal: u32) -> Result<(), AppError> {
+    book_repo::update_book(&project_dir, &book_id, |book| {
+        book.settings.daily_word_goal = goal;
+        Ok(())
+    })
+}
+
+// ─── Recording ───
+
+/// Record a change to a scene draft. `words_before` is the draft's count before the
+/// change when the caller knows it; otherwise the last count seen is used, and a
+/// draft seen for the first time only sets the baseline. Failures only warn.
+pub fn record_draft_change(draft: &Path, words_before: Option<u64>) {
+    let Some(project_dir) = manuscript::project_of(draft) else { return };
+    if let Err(e) = record_at(&project_dir, draft, words_before, Local::now()) {
+        eprintln!("Warning: failed to record writing history for {}: {}", draft.display(), e);
+    }
+}
+
+fn record_at(project_dir: &Path, draft: &Path, words_before: Option<u64>, now: DateTime<Local>) -> Result<(), AppError> {
+    let Some(rel) = ProjectPath::from_absolute(project_dir, draft) else { return Ok(()) };
+    let Some(location) = manuscript::locate(rel.as_str()).filter(|l| l.file == ManuscriptFile::Draft) else {
+        return Ok(());
+    };
+    let words = draft_word_count(draft)?;
+
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut history = load_history(project_dir)?;
+    let previous = words_before.or_else(|| history.draft_counts.get(rel.as_str()).copied()).unwrap_or(words);
+    history.draft_counts.insert(rel.to_string(), words);
+    let (added, deleted) = (words.saturating_sub(previous), previous.saturating_sub(words));
+
+    let today = now.date_naive();
+    let book_total = book_total(project_dir, &location.book_id, &history, today, words as i64 - previous as i64)?;
+    let book = history.books.entry(location.book_id).or_default();
+    let day = book.days.entry(today).or_default();
+    day.words = book_total;
+    day.added += added;
+    day.deleted += deleted;
+    let scene_id = location.scene_id.unwrap_or_default();
+    book.scenes.entry(format!("{}/{}", location.chapter_id, scene_id)).or_default().insert(today, words);
+
+    if added + deleted > 0 {
+        let now_str = now.to_rfc3339();
+        let gap = Duration::minutes(SESSION_GAP_MINUTES);
+        match book.sessions.last_mut() {
+            Some(session) if DateTime::parse_from_rfc3339(&session.ended).is_ok_and(|end| now.signed_duration_since(end) < gap) => {
+                session.ended = now_str;
+                session.words_added += added;
+                session.words_deleted += deleted;
+            }
+            _ => book.sessions.push(WritingSession {
+                started: now_str.clone(),
+                ended: now_str,
+                words_added: added,
+                words_deleted: deleted,
+            }),
+        }
+        let excess = book.sessions.len().saturating_sub(MAX_SESSIONS);
+        book.sessions.drain(..excess);
+    }
+    save_history(project_dir, &history)
+}
+
+/// Record scene or chapter folders moved, each `from → to`, in order, within a book or
+/// into another one. The counts and daily history of their drafts follow them, and a
+/// move between books changes both books' totals for the day. Failures only warn.
+pub fn record_drafts_moved(project_dir: &Path, moves: &[(PathBuf, PathBuf)]) {
+    let moves: Vec<(&Path, Option<&Path>, u64)> = moves.iter()
+        .map(|(from, to)| (from.as_path(), Some(to.as_path()), draft_words_under(to)))
+        .collect();
+    if let Err(e) = record_moves_at(project_dir, &moves, Local::now().date_naive()) {
+        eprintln!("Warning: failed to record writing history for moved scenes: {}", e);
+    }
+}
+
+/// Record a deleted scene or chapter folder whose drafts held `words`, as counted by
+/// `draft_words_under` before it went. The book's total for the day drops by them;
+/// deleting isn't counted as words written or deleted. Failures only warn.
+pub fn record_drafts_removed(project_dir: &Path, dir: &Path, words: u64) {
+    if let Err(e) = record_moves_at(project_dir, &[(dir, None, words)], Local::now().date_naive()) {
+        eprintln!("Warning: failed to record writing history for {}: {}", dir.display(), e);
+    }
+}
+
+/// Record scene drafts whose text a split or merge moved between scenes. Their
+/// current counts become the baseline for the next change, and the history of the
+/// scene folder `merged` into them, if any, is dropped. Nothing counts as written,
+/// and the book's total stays as it was. Failures only warn.
+pub fn record_drafts_rearranged(project_dir: &Path, drafts: &[PathBuf], merged: Option<&Path>) {
+    if let Err(e) = record_rearranged_at(project_dir, drafts, merged, Local::now().date_naive()) {
+        eprintln!("Warning: failed to record writing history for rearranged scenes: {}", e);
+    }
+}
+
+/// Words in the scene drafts under a scene or chapter folder.
+pub fn draft_words_under(dir: &Path) -> u64 {
+    let own = draft_word_count(&dir.join(manuscript::DRAFT_FILE)).unwrap_or(0);
+    let Ok(entries) = std::fs::read_dir(dir) else { return own };
+    own + entries.filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.is_dir() && !path.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+        .map(|path| draft_words_under(&path))
+        .sum::<u64>()
+}
+
+/// Apply folder moves (or deletions, without a `to`) holding the given words, in order.
+fn record_moves_at(project_dir: &Path, moves: &[(&Path, Option<&Path>, u64)], today: NaiveDate) -> Result<(), AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut history = load_history(project_dir)?;
+    for &(from, to, words) in moves {
+        let Some((from_rel, from_book, from_key)) = folder_in_book(project_dir, from) else { continue };
+        let to = to.and_then(|to| folder_in_book(project_dir, to));
+        let counts = take_under(&mut history.draft_counts, &from_rel);
+        let scenes = take_under(&mut history.books.entry(from_book.clone()).or_default().scenes, &from_key);
+        if let Some((to_rel, to_book, to_key)) = &to {
+            for (rest, count) in counts {
+                history.draft_counts.insert(format!("{}{}", to_rel, rest), count);
+            }
+            let target = history.books.entry(to_book.clone()).or_default();
+            for (rest, days) in scenes {
+                target.scenes.insert(format!("{}{}", to_key, rest), days);
+            }
+        }
+
+        let changes = match to {
+            // Within a book the total stays as it was
+            Some((_, to_book, _)) if to_book == from_book => vec![],
+            Some((_, to_book, _)) => vec![(from_book, -(words as i64)), (to_book, words as i64)],
+            None => vec![(from_book, -(words as i64))],
+        };
+        for (book_id, delta) in changes {
+            let total = book_total(project_dir, &book_id, &history, today, delta)?;
+            history.books.entry(book_id).or_default().days.entry(today).or_default().words = total;
+        }
+    }
+    save_history(project_dir, &history)
+}
+
+fn record_rearranged_at(project_dir: &Path, drafts: &[PathBuf], merged: Option<&Path>, today: NaiveDate) -> Result<(), AppError> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut history = load_history(project_dir)?;
+    if let Some((rel, book_id, key)) = merged.and_then(|dir| folder_in_book(project_dir, dir)) {
+        take_under(&mut history.draft_counts, &rel);
+        take_under(&mut history.books.entry(book_id).or_default().scenes, &key);
+    }
+    for draft in drafts {
+        let Some((rel, book_id, key)) = draft.parent().and_then(|dir| folder_in_book(project_dir, dir)) else { continue };
+        let words = draft_word_count(draft)?;
+        history.draft_counts.insert(format!("{}/{}", rel, manuscript::DRAFT_FILE), words);
+        history.books.entry(book_id).or_default().scenes.entry(key).or_default().insert(today, words);
+    }
+    save_history(project_dir, &history)
+}
+
+/// Remove the entries of `map` whose "/"-separated key is `prefix` or lies under it,
+/// returning each with the rest of its key after `prefix`.
+fn take_under<V>(map: &mut BTreeMap<String, V>, prefix: &str) -> Vec<(String, V)> {
+    let keys: Vec<String> = map.keys().filter(|key| is_under(key, prefix)).cloned().collect();
+    keys.into_iter()
+        .filter_map(|key| map.remove(&key).map(|value| (key[prefix.len()..].to_string(), value)))
+        .collect()
+}
+
+/// A book's word count after a change of `delta` words: its last recorded total up
+/// to `today` plus the change. A book without history yet is counted from its
+/// drafts, which already include the change.
+fn book_total(project_dir: &Path, book_id: &str, history: &WritingHistory, today: NaiveDate, delta: i64) -> Result<u64, AppError> {
+    match history.books.get(book_id).and_then(|book| book.days.range(..=today).next_back()) {
+        Some((_, day)) => Ok((day.words as i64 + delta).max(0) as u64),
+        None => Ok(get_book_word_count(project_dir.to_path_buf(), book_id.to_string())?.book_total),
+    }
+}
+
+/// The project-relative path, book id and "ch-01[/scene-02]" key of a chapter or scene folder.
+fn folder_in_book(project_dir: &Path, dir: &Path) -> Option<(String, String, String)> {
+    let rel = ProjectPath::from_absolute(project_dir, dir)?.to_string();
+    let mut parts = rel.splitn(4, '/');
+    let (books, book_id, chapters, key) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    if books != "books" || chapters != manuscript::CHAPTERS_DIR {
+        return None;
+    }
+    let (book_id, key) = (book_id.to_string(), key.to_string());
+    Some((rel, book_id, key))
+}
+
+/// Whether a "/"-separated `key` is `prefix` or lies under it.
+fn is_under(key: &str, prefix: &str) -> bool {
+    key.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+// ─── Statistics ───
+
+fn stats_at(history: &BookHistory, total_words: u64, target: u64, goal: u32, today: NaiveDate) -> WritingStats {
+    let today_totals = history.days.get(&today).copied().unwrap_or_default();
+    let counts = |day: &DayTotals| if goal > 0 { day.added >= goal as u64 } else { day.added > 0 };
+
+    let mut longest_streak = 0;
+    let mut run = 0;
+    let mut last: Option<NaiveDate> = None;
+    for (&date, day) in &history.days {
+        if !counts(day) {
+            run = 0;
+            continue;
+        }
+        run = if last.is_some_and(|l| l.succ_opt() == Some(date)) { run + 1 } else { 1 };
+        last = Some(date);
+        longest_streak = longest_streak.max(run);
+    }
+    let mut current_streak = 0;
+    let mut date = if counts(&today_totals) { today } else { today.pred_opt().unwrap_or(today) };
+    while history.days.get(&date).is_some_and(counts) {
+        current_streak += 1;
+        let Some(previous) = date.pred_opt() else { break };
+        date = previous;
+    }
+
+    // Net words per day since the start of the window, or of the history if shorter
+    let window_start = today - Duration::days(PROJECTION_DAYS - 1);
+    let baseline = history.days.range(..window_start).next_back().map(|(&date, day)| (date.succ_opt().unwrap_or(date), day.words))
+        .or_else(|| history.days.iter().next().map(|(&date, day)| (date, (day.words + day.deleted).saturating_sub(day.added))));
+    let average_daily_words = baseline
+        .map(|(start, words)| {
+            let days = (today - start.max(window_start)).num_days() + 1;
+            (total_words as i64 - words as i64) / days.max(1)
+        })
+        .unwrap_or(0);
+
+    let remaining_words = target.saturating_sub(total_words);
+    let projected_completion = match (target, remaining_words) {
+        (0, _) => None,
+        (_, 0) => Some(today),
+        (_, remaining) if average_daily_words > 0 => {
+            let days = (remaining as i64 + average_daily_words - 1) / average_daily_words;
+            Some(today + Duration::days(days))
+        }
+        _ => None,
+    };
+
+    WritingStats {
+        total_words,
+        target_word_count: target,
+        daily_goal: goal,
+        added_today: today_totals.added,
+        deleted_today: today_totals.deleted,
+        goal_met_today: goal > 0 && today_totals.added >= goal as u64,
+        current_streak,
+        longest_streak,
+        average_daily_words,
+        remaining_words,
+        projected_completion,
+    }
+}
+
+// ─── Storage ───
+
+fn history_path(project_dir: &Path) -> PathBuf {
+    project_dir.join(".saipling").join("writing-history.json")
+}
+
+fn load_history(project_dir: &Path) -> Result<WritingHistory, AppError> {
+    let path = history_path(project_dir);
+    if !path.exists() {
+        return Ok(WritingHistory::default());
+    }
+    Ok(serde_json::from_str(&std::fs::read_to_string(&path)?)?)
+}
+
+fn save_history(project_dir: &Path, history: &WritingHistory) -> Result<(), AppError> {
+    let path = history_path(project_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::file_io::atomic_write(&path, serde_json::to_string_pretty(history)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::commands::chapter::{create_chapter, create_scene, delete_scene, move_scene, renumber_book, reorder_chapters, split_scene};
+    use crate::test_support::{TestProject, BOOK};
+
+    /// Five saves of the one scene over three days of March 2026.
+    fn recorded_project() -> TestProject {
+        let project = TestProject::with_scene("writing");
+        let draft = project.draft();
+        let mut before = Some(0);
+        let mut write = |words: usize, day: u32, hour: u32, minute: u32| {
+            std::fs::write(&draft, format!("---\nstatus: drafting\n---\n\n{}", "word ".repeat(words))).unwrap();
+            let at = Local.with_ymd_and_hms(2026, 3, day, hour, minute, 0).unwrap();
+            record_at(&project.dir, &draft, before.take(), at).unwrap();
+        };
+        write(100, 1, 10, 0);
+        write(150, 1, 10, 20);
+        write(140, 1, 11, 30);
+        write(300, 2, 9, 0);
+        // Without a count from the caller (an external edit) the last one seen is used
+        write(350, 4, 21, 0);
+        project
+    }
+
+    fn book_history(project: &TestProject) -> BookHistory {
+        load_history(&project.dir).unwrap().books.remove(BOOK).unwrap()
+    }
+
+    fn march(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_days_and_sessions_follow_saves() {
+        let history = book_history(&recorded_project());
+        assert_eq!(history.days[&march(1)], DayTotals { words: 140, added: 150, deleted: 10 });
+        assert_eq!(history.days[&march(4)], DayTotals { words: 350, added: 50, deleted: 0 });
+        let sessions: Vec<(u64, u64)> = history.sessions.iter().map(|s| (s.words_added, s.words_deleted)).collect();
+        assert_eq!(sessions, vec![(150, 0), (0, 10), (160, 0), (50, 0)]);
+    }
+
+    #[test]
+    fn test_scene_history_by_day() {
+        let project = recorded_project();
+        let scene = get_word_count_history(project.dir.clone(), BOOK.into(), Some("ch-01/scene-01".into())).unwrap();
+        let scene: Vec<(u64, u64)> = scene.iter().map(|d| (d.words, d.added)).collect();
+        assert_eq!(scene, vec![(140, 0), (300, 160), (350, 50)]);
+    }
+
+    #[test]
+    fn test_editor_saves_count_as_writing() {
+        let project = TestProject::with_scene("writing");
+        let draft = crate::commands::filesystem::read_file(project.draft()).unwrap();
+        let body = format!("{}The ferry docks at dawn.\n", draft.body);
+        crate::commands::filesystem::write_file(project.draft(), draft.frontmatter, body, Some(draft.version)).unwrap();
+
+        let history = book_history(&project);
+        let day = history.days.values().next_back().unwrap();
+        assert_eq!((day.words, day.added, day.deleted), (5, 5, 0));
+        assert_eq!(history.sessions.len(), 1);
+    }
+
+    #[test]
+    fn test_deleted_scene_lowers_the_day_total() {
+        let project = recorded_project();
+        delete_scene(project.dir.clone(), BOOK.into(), "ch-01".into(), "scene-01".into()).unwrap();
+
+        let history = load_history(&project.dir).unwrap();
+        assert!(history.draft_counts.is_empty());
+        let book = &history.books[BOOK];
+        let (&date, &day) = book.days.iter().next_back().unwrap();
+        assert!(date > march(4));
+        assert_eq!(day, DayTotals { words: 0, added: 0, deleted: 0 });
+        assert!(book.scenes.is_empty());
+    }
+
+    #[test]
+    fn test_scene_moved_within_the_book_keeps_the_total() {
+        let project = recorded_project();
+        create_chapter(project.dir.clone(), BOOK.into(), "Crossing".into()).unwrap();
+        move_scene(project.dir.clone(), BOOK.into(), "scene-01".into(), "ch-01".into(), "ch-02".into(), 0, None).unwrap();
+
+        let history = load_history(&project.dir).unwrap();
+        let draft = "books/book-01/chapters/ch-02/scene-01/draft.md";
+        assert_eq!(history.draft_counts.keys().collect::<Vec<_>>(), vec![draft]);
+        let book = &history.books[BOOK];
+        assert_eq!(book.days.values().next_back().unwrap().words, 350);
+        assert_eq!(book.scenes.keys().collect::<Vec<_>>(), vec!["ch-02/scene-01"]);
+    }
+
+    #[test]
+    fn test_scene_moved_to_another_book_takes_its_words() {
+        let project = recorded_project();
+        project.add_book("book-02", "Currents");
+        create_chapter(project.dir.clone(), "book-02".into(), "Landfall".into()).unwrap();
+        move_scene(project.dir.clone(), BOOK.into(), "scene-01".into(), "ch-01".into(), "ch-01".into(), 0, Some("book-02".into())).unwrap();
+
+        let history = load_history(&project.dir).unwrap();
+        assert_eq!(history.draft_counts["books/book-02/chapters/ch-01/scene-01/draft.md"], 350);
+        let (source, target) = (&history.books[BOOK], &history.books["book-02"]);
+        assert_eq!(source.days.values().next_back().unwrap().words, 0);
+        assert!(source.scenes.is_empty());
+        assert_eq!(target.days.values().next_back().unwrap().words, 350);
+        assert_eq!(target.scenes["ch-01/scene-01"].len(), 3);
+    }
+
+    #[test]
+    fn test_renumbered_scenes_keep_their_history() {
+        let project = recorded_project();
+        create_chapter(project.dir.clone(), BOOK.into(), "Crossing".into()).unwrap();
+        create_scene(project.dir.clone(), BOOK.into(), "ch-02".into(), "Ferry".into(), "action".into()).unwrap();
+        let ferry = project.scene_dir("ch-02", "scene-01").join(manuscript::DRAFT_FILE);
+        std::fs::write(&ferry, format!("---\nstatus: drafting\n---\n\n{}", "wave ".repeat(20))).unwrap();
+        record_at(&project.dir, &ferry, Some(0), Local.with_ymd_and_hms(2026, 3, 5, 9, 0, 0).unwrap()).unwrap();
+
+        // Crossing and Harbour swap ids
+        reorder_chapters(project.dir.clone(), BOOK.into(), vec!["ch-02".into(), "ch-01".into()]).unwrap();
+        renumber_book(project.dir.clone(), BOOK.into()).unwrap();
+        let history = load_history(&project.dir).unwrap();
+        assert_eq!(history.draft_counts["books/book-01/chapters/ch-01/scene-01/draft.md"], 20);
+        assert_eq!(history.draft_counts["books/book-01/chapters/ch-02/scene-01/draft.md"], 350);
+        let scenes = &history.books[BOOK].scenes;
+        assert_eq!(scenes["ch-01/scene-01"].keys().collect::<Vec<_>>(), vec![&march(5)]);
+        assert_eq!(scenes["ch-02/scene-01"].len(), 3);
+
+        // An edit in another editor is measured against the scene's own count
+        let harbour = project.scene_dir("ch-02", "scene-01").join(manuscript::DRAFT_FILE);
+        let content = std::fs::read_to_string(&harbour).unwrap();
+        std::fs::write(&harbour, format!("{}more words\n", content)).unwrap();
+        record_at(&project.dir, &harbour, None, Local.with_ymd_and_hms(2026, 3, 6, 9, 0, 0).unwrap()).unwrap();
+        assert_eq!(book_history(&project).days[&march(6)], DayTotals { words: 372, added: 2, deleted: 0 });
+    }
+
+    #[test]
+    fn test_split_scene_resets_the_baselines() {
+        let project = TestProject::with_scene("writing");
+        let draft = project.draft();
+        let body = format!("{}\n\n{}", "word ".repeat(100).trim(), "word ".repeat(50).trim());
+        std::fs::write(&draft, format!("---\nstatus: drafting\n---\n\n{}\n", body)).unwrap();
+        record_at(&project.dir, &draft, Some(0), Local.with_ymd_and_hms(2026, 3, 1, 9, 0, 0).unwrap()).unwrap();
+        split_scene(project.dir.clone(), BOOK.into(), "ch-01".into(), "scene-01".into(), 1, None).unwrap();
+        assert_eq!(load_history(&project.dir).unwrap().draft_counts["books/book-01/chapters/ch-01/scene-01/draft.md"], 100);
+
+        // Edits in another editor after the split count only what they change
+        let second = project.scene_dir("ch-01", "scene-02").join(manuscript::DRAFT_FILE);
+        let content = std::fs::read_to_string(&second).unwrap();
+        std::fs::write(&second, format!("{}five more words right here\n", content)).unwrap();
+        let at = Local.with_ymd_and_hms(2026, 3, 2, 9, 0, 0).unwrap();
+        record_at(&project.dir, &second, None, at).unwrap();
+        record_at(&project.dir, &draft, None, at).unwrap();
+        assert_eq!(book_history(&project).days[&march(2)], DayTotals { words: 155, added: 5, deleted: 0 });
+    }
+
+    #[test]
+    fn test_streaks_and_projection() {
+        let history = book_history(&recorded_project());
+        // The streak survives a day that hasn't reached the goal yet
+        let stats = stats_at(&history, 350, 1000, 100, march(3));
+        assert_eq!((stats.current_streak, stats.longest_streak), (2, 2));
+        let stats = stats_at(&history, 350, 1000, 100, march(4));
+        assert_eq!((stats.current_streak, stats.longest_streak), (0, 2));
+        assert_eq!((stats.added_today, stats.goal_met_today), (50, false));
+        // 350 words over four days, 650 to go
+        assert_eq!(stats.average_daily_words, 87);
+        assert_eq!(stats.remaining_words, 650);
+        assert_eq!(stats.projected_completion, Some(march(12)));
+    }
+}
@@ -3,15 +3,11 @@ use std::path::PathBuf;
 use serde::Serialize;
 use crate::error::AppError;
 use crate::commands::config::get_config;
+use crate::project_path::ProjectPath;
 use super::skills::SkillDefinition;
 use super::tokens::estimate_tokens;
 use super::vector;
 
-/// Normalize a relative path to always use forward slashes for display consistency.
-fn normalize_rel_path(rel: &std::path::Path) -> String {
-    rel.display().to_string().replace('\\', "/")
-}
-
 #[derive(Debug, Clone, Serialize)]
 pub struct AssembledContext {
     pub system_prompt: String,
@@ -27,15 +23,29 @@ pub struct LoadedFile {
     pub tokens: u64,
 }
 
+/// A linked project's name, directory and context settings.
+type LinkedSource = (String, PathBuf, HashMap<String, String>);
+
 /// Resolve a context path pattern against the project directory.
 /// Supports `{book}` placeholder, `**` recursive glob, and simple `*` glob in the last segment.
-fn resolve_paths(pattern: &str, project_dir: &PathBuf, book_id: Option<&str>) -> Vec<PathBuf> {
+/// A pattern starting with `@name/` resolves against the linked project `name`.
+fn resolve_paths(pattern: &str, project_dir: &PathBuf, book_id: Option<&str>, linked: &[LinkedSource]) -> Vec<PathBuf> {
     let resolved = if let Some(bid) = book_id {
         pattern.replace("{book}", &format!("books/{}", bid))
     } else {
         pattern.replace("{book}", "books/_default")
     };
 
+    if let Some(rest) = resolved.strip_prefix('@') {
+        return match rest.split_once('/') {
+            Some((name, rel)) => match linked.iter().find(|(n, _, _)| n == name) {
+                Some((_, dir, _)) => resolve_paths(rel, dir, book_id, &[]),
+                None => Vec::new(),
+            },
+            None => Vec::new(),
+        };
+    }
+
     // Handle ** recursive glob (e.g. "world/**/entry.md" or "characters/**/profile.md")
     if resolved.contains("**") {
         return resolve_recursive_glob(&resolved, project_dir);
@@ -161,16 +171,13 @@ pub fn load_context_settings(project_dir: &PathBuf) -> HashMap<String, String> {
     let settings_path = project_dir.join(".context_settings.json");
     if let Ok(content) = std::fs::read_to_string(&settings_path) {
         if let Ok(raw_map) = serde_json::from_str::<HashMap<String, String>>(&content) {
-            let prefix = project_dir.to_string_lossy().to_string();
             let mut normalized = HashMap::new();
             for (path, mode) in raw_map {
-                let rel = if path.starts_with(&prefix) {
-                    path[prefix.len()..].trim_start_matches('\\').trim_start_matches('/')
-                } else {
-                    &path
-                };
-                let key = rel.replace('\\', "/");
-                normalized.insert(key, mode);
+                let rel = ProjectPath::from_absolute(project_dir, std::path::Path::new(&path))
+                    .or_else(|| ProjectPath::new(&path).ok());
+                if let Some(rel) = rel {
+                    normalized.insert(rel.into(), mode);
+                }
             }
             return normalized;
         }
@@ -178,6 +185,38 @@ pub fn load_context_settings(project_dir: &PathBuf) -> HashMap<String, String> {
     HashMap::new()
 }
 
+/// Carry the context settings of everything under `from` over to the same paths under
+/// `to`, after a chapter or scene folder moved. Keys keep the form the frontend wrote.
+pub fn move_context_settings(project_dir: &std::path::Path, from: &ProjectPath, to: &ProjectPath) -> Result<(), AppError> {
+    let settings_path = project_dir.join(".context_settings.json");
+    let Ok(content) = std::fs::read_to_string(&settings_path) else { return Ok(()) };
+    let Ok(raw_map) = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&content) else {
+        return Ok(());
+    };
+    let mut changed = false;
+    let mut updated = serde_json::Map::new();
+    for (key, mode) in raw_map {
+        let rel = ProjectPath::from_absolute(project_dir, std::path::Path::new(&key))
+            .or_else(|| ProjectPath::new(&key).ok())
+            .filter(|rel| rel.is_within(from))
+            .filter(|rel| key.len() >= rel.as_str().len() && key.is_char_boundary(key.len() - rel.as_str().len()));
+        let Some(rel) = rel else {
+            updated.insert(key, mode);
+            continue;
+        };
+        // The key ends with the relative path, written with the key's own separator
+        let separator = if key.contains('\\') { "\\" } else { "/" };
+        let moved = format!("{}{}", to.as_str(), &rel.as_str()[from.as_str().len()..]);
+        let prefix = &key[..key.len() - rel.as_str().len()];
+        updated.insert(format!("{}{}", prefix, moved.replace('/', separator)), mode);
+        changed = true;
+    }
+    if changed {
+        crate::file_io::atomic_write(&settings_path, serde_json::to_string_pretty(&updated)?)?;
+    }
+    Ok(())
+}
+
 /// Check if a file should be excluded based on context settings.
 /// Returns true if the file is explicitly excluded.
 pub fn is_excluded(rel_path: &str, settings: &HashMap<String, String>) -> bool {
@@ -199,6 +238,13 @@ pub fn assemble_context(
     let mut context_parts: Vec<String> = Vec::new();
     let mut loaded_canonical: HashSet<PathBuf> = HashSet::new();
     let ctx_settings = load_context_settings(project_dir);
+    let linked: Vec<LinkedSource> = crate::commands::project::linked_project_dirs(project_dir)
+        .into_iter()
+        .map(|(name, dir)| {
+            let settings = load_context_settings(&dir);
+            (name, dir, settings)
+        })
+        .collect();
 
     // Helper closure to load a file respecting context settings
     let try_load = |path: &PathBuf, total: &mut u64, max: u64,
@@ -212,8 +258,22 @@ pub fn assemble_context(
         if loaded.contains(&canonical) {
             return false;
         }
-        let rel = path.strip_prefix(project_dir).unwrap_or(path);
-        let rel_str = normalize_rel_path(rel);
+        // Files of linked projects are labelled `@name/path` and honour that project's exclusions too
+        let linked_rel = linked.iter().find_map(|(name, dir, settings)| {
+            ProjectPath::from_absolute(dir, path).map(|rel| (name, rel.to_string(), settings))
+        });
+        let rel_str = match linked_rel {
+            Some((name, rel, settings)) => {
+                if is_excluded(&rel, settings) {
+                    return false;
+                }
+                format!("@{}/{}", name, rel)
+            }
+            None => match ProjectPath::from_absolute(project_dir, path) {
+                Some(rel) => rel.to_string(),
+                None => path.to_string_lossy().to_string(),
+            },
+        };
         if is_excluded(&rel_str, &ctx_settings) {
             return false;
         }
@@ -233,7 +293,7 @@ pub fn assemble_context(
 
     // 1. Always-include files
     for pattern in &skill.context.always_include {
-        let paths = resolve_paths(pattern, project_dir, book_id);
+        let paths = resolve_paths(pattern, project_dir, book_id, &linked);
         for path in paths {
             try_load(&path, &mut total_tokens, max_tokens, &mut loaded_canonical, &mut context_parts, &mut files_loaded);
         }
@@ -243,7 +303,7 @@ pub fn assemble_context(
     if book_id.is_some() {
         if let Some(when_book) = &skill.context.when_book {
             for pattern in &when_book.include {
-                let paths = resolve_paths(pattern, project_dir, book_id);
+                let paths = resolve_paths(pattern, project_dir, book_id, &linked);
                 for path in paths {
                     try_load(&path, &mut total_tokens, max_tokens, &mut loaded_canonical, &mut context_parts, &mut files_loaded);
                 }
@@ -254,7 +314,7 @@ pub fn assemble_context(
     // 3. Optional files (include if they exist)
     if let Some(optional) = &skill.context.optional {
         for pattern in &optional.include_if_exists {
-            let paths = resolve_paths(pattern, project_dir, book_id);
+            let paths = resolve_paths(pattern, project_dir, book_id, &linked);
             for path in paths {
                 try_load(&path, &mut total_tokens, max_tokens, &mut loaded_canonical, &mut context_parts, &mut files_loaded);
             }
@@ -266,7 +326,10 @@ pub fn assemble_context(
         if mode != "force" {
             continue;
         }
-        let full_path = project_dir.join(rel_path.replace('/', "\\"));
+        let Ok(rel_path) = ProjectPath::new(rel_path) else {
+            continue;
+        };
+        let full_path = rel_path.to_absolute(project_dir);
         if !full_path.exists() {
             continue;
         }
@@ -312,7 +375,7 @@ pub async fn enrich_with_search(
         Ok(c) => c,
         Err(_) => return Vec::new(),
     };
-    if !config.vector_search.enabled || config.vector_search.embedding_api_key_encrypted.is_empty() {
+    if !config.vector_search.enabled {
         return Vec::new();
     }
 
@@ -334,17 +397,38 @@ pub async fn enrich_with_search(
     let max_results = skill_vs.map(|vs| vs.max_results).unwrap_or(config.vector_search.max_results_default);
     let max_search_tokens = skill_vs.map(|vs| vs.max_search_tokens).unwrap_or(config.vector_search.max_search_tokens_default);
     let filter_entity_types = skill_vs.map(|vs| vs.filter_entity_types.clone()).unwrap_or_default();
-
-    // Decode API key
-    let api_key = match decode_base64_key(&config.vector_search.embedding_api_key_encrypted) {
-        Some(k) => k,
-        None => return Vec::new(),
+    let embedding = match config.vector_search.embedding_config() {
+        Ok(e) => e,
+        Err(e) => {
+            eprintln!("Warning: {}", e);
+            return Vec::new();
+        }
+    };
+    let shaping = skill_vs
+        .map(|vs| vector::rerank::ResultShaping {
+            mmr_lambda: vs.mmr_lambda,
+            max_per_file: vs.max_per_file,
+            merge_adjacent: vs.merge_adjacent,
+            reranker: vector::rerank::reranker_from_name(&vs.reranker, &embedding).unwrap_or_else(|e| {
+                eprintln!("Warning: {}", e);
+                None
+            }),
+        })
+        .unwrap_or_default();
+
+    // Without a usable embedding provider, fall back to lexical search
+    if let Err(e) = vector::indexer::sync_vector_index(project_dir, &embedding) {
+        eprintln!("Warning: {}", e);
+    }
+    let client = vector::indexer::client_for_index(project_dir, &embedding).unwrap_or_else(|e| {
+        eprintln!("Warning: {}", e);
+        None
+    });
+    let search_mode = if client.is_some() {
+        vector::SearchMode::Hybrid
+    } else {
+        vector::SearchMode::Lexical
     };
-
-    let client = vector::embeddings::VoyageClient::new(
-        api_key,
-        config.vector_search.embedding_model.clone(),
-    );
 
     // Build the set of already-loaded file paths for deduplication
     let already_loaded: HashSet<String> = assembled.files_loaded.iter()
@@ -358,12 +442,17 @@ pub async fn enrich_with_search(
     let results = match vector::search::search(
         project_dir,
         query,
-        &client,
+        client.as_deref(),
+        search_mode,
         max_results,
-        &filter_entity_types,
-        book_id,
+        &vector::SearchFilters {
+            entity_types: filter_entity_types,
+            book_id: book_id.map(String::from),
+            ..Default::default()
+        },
         &ctx_settings,
         &already_loaded,
+        &shaping,
     ).await {
         Ok(r) => r,
         Err(e) => {
@@ -386,7 +475,11 @@ pub async fn enrich_with_search(
             break;
         }
         let section_label = result.section_heading.as_deref().unwrap_or("full file");
-        let header = format!("--- {} (SEARCH: {}) ---", result.file_path, section_label);
+        let path = match &result.project {
+            Some(project) => format!("@{}/{}", project, result.file_path),
+            None => result.file_path.clone(),
+        };
+        let header = format!("--- {} (SEARCH: {}) ---", path, section_label);
         let part = format!("{}\n{}", header, result.content_preview);
         let part_tokens = estimate_tokens(&part).unwrap_or(part.len() / 4) as u64;
 
@@ -423,34 +516,48 @@ pub async fn enrich_with_search(
     included_results
 }
 
-/// Decode a base64-encoded API key string.
-fn decode_base64_key(encoded: &str) -> Option<String> {
-    let trimmed = encoded.trim();
-    if trimmed.is_empty() {
-        return None;
-    }
-    // Use the same base64 decoding as the general crate approach
-    let decoded = trimmed.as_bytes();
-    if decoded.len() % 4 != 0 {
-        return None;
-    }
-    let mut output = Vec::with_capacity(decoded.len() * 3 / 4);
-    for chunk in decoded.chunks(4) {
-        let vals: Vec<u8> = chunk.iter().map(|&c| match c {
-            b'A'..=b'Z' => c - b'A',
-            b'a'..=b'z' => c - b'a' + 26,
-            b'0'..=b'9' => c - b'0' + 52,
-            b'+' => 62,
-            b'/' => 63,
-            _ => 0,
-        }).collect();
-        output.push((vals[0] << 2) | (vals[1] >> 4));
-        if chunk[2] != b'=' {
-            output.push((vals[1] << 4) | (vals[2] >> 2));
-        }
-        if chunk[3] != b'=' {
-            output.push((vals[2] << 6) | vals[3]);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assemble_context_nested_and_forced_files() {
+        let dir = std::env::temp_dir().join(format!("saipling-assemble-{}", uuid::Uuid::new_v4()));
+        let scene = dir.join("books").join("book-01").join("ch-01");
+        std::fs::create_dir_all(&scene).unwrap();
+        std::fs::create_dir_all(dir.join("characters").join("sarah-chen")).unwrap();
+        std::fs::write(dir.join("characters").join("sarah-chen").join("profile.md"), "Sarah").unwrap();
+        std::fs::write(scene.join("notes.md"), "Forced").unwrap();
+        // Settings keys are absolute paths, joined by the frontend with backslashes
+        let forced = format!("{}\\books\\book-01\\ch-01\\notes.md", dir.display());
+        std::fs::write(
+            dir.join(".context_settings.json"),
+            serde_json::json!({ forced: "force" }).to_string(),
+        )
+        .unwrap();
+
+        let skill: SkillDefinition = toml::from_str(
+            r#"
+            [skill]
+            name = "test"
+            display_name = "Test"
+            description = ""
+            default_model = "m"
+            temperature = 0.5
+
+            [context]
+            always_include = ["characters/**/profile.md"]
+            max_context_tokens = 10000
+
+            [system_prompt]
+            template = "Prompt"
+            "#,
+        )
+        .unwrap();
+        let assembled = assemble_context(&skill, &dir, None, None, None).unwrap();
+        let loaded: Vec<&str> = assembled.files_loaded.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(loaded, vec!["characters/sarah-chen/profile.md", "books/book-01/ch-01/notes.md"]);
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
-    String::from_utf8(output).ok()
 }
@@ -30,11 +30,27 @@ pub struct SkillVectorSearchConfig {
     pub max_search_tokens: u64,
     #[serde(default)]
     pub filter_entity_types: Vec<String>,
+    /// MMR trade-off between relevance (1.0) and variety (lower)
+    #[serde(default = "default_mmr_lambda")]
+    pub mmr_lambda: f32,
+    /// Most results taken from any one file (0 = no cap)
+    #[serde(default = "default_max_per_file")]
+    pub max_per_file: u32,
+    /// Join results that are neighbouring chunks of the same file
+    #[serde(default = "default_true")]
+    pub merge_adjacent: bool,
+    /// "none", "lexical" or a Voyage rerank model such as "rerank-2.5"
+    #[serde(default = "default_reranker")]
+    pub reranker: String,
 }
 
 fn default_auto_mode() -> String { "auto".to_string() }
 fn default_max_results() -> u32 { 5 }
 fn default_max_search_tokens() -> u64 { 15000 }
+fn default_mmr_lambda() -> f32 { 0.7 }
+fn default_max_per_file() -> u32 { 2 }
+fn default_true() -> bool { true }
+fn default_reranker() -> String { "none".to_string() }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillContext {
@@ -73,7 +89,8 @@ pub fn load_skill(skill_name: &str, app_skills_dir: &PathBuf) -> Result<SkillDef
         return Err(AppError::FileNotFound(format!("Skill not found: {}", skill_name)));
     }
     let content = std::fs::read_to_string(&path)?;
-    let skill: SkillDefinition = toml::from_str(&content)?;
+    let skill: SkillDefinition = toml::from_str(&content)
+        .map_err(|e| AppError::Config(format!("Invalid skill {}.toml: {}", skill_name, e)))?;
     Ok(skill)
 }
 
@@ -82,17 +99,344 @@ pub fn list_skills(app_skills_dir: &PathBuf) -> Result<Vec<SkillMeta>, AppError>
     if !app_skills_dir.exists() {
         return Ok(skills);
     }
-    for entry in std::fs::read_dir(app_skills_dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        if path.extension().map(|e| e == "toml").unwrap_or(false) {
-            if let Ok(content) = std::fs::read_to_string(&path) {
-                if let Ok(skill) = toml::from_str::<SkillDefinition>(&content) {
-                    skills.push(skill.skill);
-                }
+    for path in skill_files(app_skills_dir)? {
+        if let Ok(content) = std::fs::read_to_string(&path) {
+            match toml::from_str::<SkillDefinition>(&content) {
+                Ok(skill) => skills.push(skill.skill),
+                Err(e) => eprintln!(
+                    "Warning: skipping skill {}: {}",
+                    path.file_name().unwrap_or_default().to_string_lossy(),
+                    e.message()
+                ),
             }
         }
     }
     skills.sort_by(|a, b| a.name.cmp(&b.name));
     Ok(skills)
 }
+
+// ─── Validation ───
+
+/// Template variables substituted by the agent before a prompt is sent.
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "genre_context",
+    "existing_foundation_context",
+    "writing_style_notes",
+    "pov",
+    "tense",
+];
+
+/// Accepted values for `[context.vector_search] mode`.
+pub const VECTOR_SEARCH_MODES: &[&str] = &["auto", "always", "never"];
+
+/// A single problem found in a skill definition file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkillIssue {
+    pub file: String,
+    pub line: Option<usize>,
+    pub severity: String, // "error" or "warning"
+    pub message: String,
+}
+
+/// Lint every `.toml` file in the skills directory.
+/// `known_models` are the model IDs from models.toml; pass an empty slice to skip the model check.
+pub fn validate_skills(app_skills_dir: &PathBuf, known_models: &[String]) -> Result<Vec<SkillIssue>, AppError> {
+    let mut issues = Vec::new();
+    if !app_skills_dir.exists() {
+        return Ok(issues);
+    }
+    for path in skill_files(app_skills_dir)? {
+        let file = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let content = std::fs::read_to_string(&path)?;
+        issues.extend(validate_skill_source(&file, &content, known_models));
+    }
+    Ok(issues)
+}
+
+/// Lint the source of a single skill file.
+pub fn validate_skill_source(file: &str, content: &str, known_models: &[String]) -> Vec<SkillIssue> {
+    let mut issues = Vec::new();
+    let issue = |line: Option<usize>, severity: &str, message: String| SkillIssue {
+        file: file.to_string(),
+        line,
+        severity: severity.to_string(),
+        message,
+    };
+
+    let skill: SkillDefinition = match toml::from_str(content) {
+        Ok(s) => s,
+        Err(e) => {
+            let line = e.span().map(|span| line_of_offset(content, span.start));
+            issues.push(issue(line, "error", e.message().to_string()));
+            return issues;
+        }
+    };
+
+    // Model ID must exist in models.toml (prefix match, as in get_model_rates)
+    let model = &skill.skill.default_model;
+    if !known_models.is_empty() && !known_models.iter().any(|m| m == model || model.starts_with(m.as_str())) {
+        issues.push(issue(
+            find_key_line(content, "default_model"),
+            "warning",
+            format!("Unknown model \"{}\" (not listed in models.toml)", model),
+        ));
+    }
+
+    let temperature = skill.skill.temperature;
+    if !(0.0..=1.0).contains(&temperature) {
+        issues.push(issue(
+            find_key_line(content, "temperature"),
+            "error",
+            format!("Temperature {} is outside the valid range 0.0–1.0", temperature),
+        ));
+    }
+
+    for var in template_variables(&skill.system_prompt.template) {
+        if !TEMPLATE_VARIABLES.contains(&var.as_str()) {
+            issues.push(issue(
+                find_line(content, &format!("{{{}}}", var)),
+                "warning",
+                format!("Unknown template variable {{{}}} will be sent to the model verbatim", var),
+            ));
+        }
+    }
+
+    if let Some(vs) = &skill.context.vector_search {
+        if !VECTOR_SEARCH_MODES.contains(&vs.mode.as_str()) {
+            issues.push(issue(
+                find_key_line(content, "mode"),
+                "error",
+                format!(
+                    "Invalid vector_search.mode \"{}\" (expected one of: {})",
+                    vs.mode,
+                    VECTOR_SEARCH_MODES.join(", ")
+                ),
+            ));
+        }
+        if !(0.0..=1.0).contains(&vs.mmr_lambda) {
+            issues.push(issue(
+                find_key_line(content, "mmr_lambda"),
+                "error",
+                format!("Invalid vector_search.mmr_lambda {} (expected 0.0 to 1.0)", vs.mmr_lambda),
+            ));
+        }
+        if !crate::context::vector::rerank::is_known_reranker(&vs.reranker) {
+            issues.push(issue(
+                find_key_line(content, "reranker"),
+                "warning",
+                format!(
+                    "Unknown vector_search.reranker \"{}\" will be ignored (expected none, lexical or a rerank-* model)",
+                    vs.reranker
+                ),
+            ));
+        }
+    }
+
+    let mut patterns: Vec<(&str, bool)> = skill.context.always_include.iter().map(|p| (p.as_str(), false)).collect();
+    if let Some(when_book) = &skill.context.when_book {
+        patterns.extend(when_book.include.iter().map(|p| (p.as_str(), true)));
+    }
+    if let Some(optional) = &skill.context.optional {
+        patterns.extend(optional.include_if_exists.iter().map(|p| (p.as_str(), false)));
+    }
+    for (pattern, in_when_book) in patterns {
+        if let Some(reason) = dead_pattern_reason(pattern, in_when_book) {
+            issues.push(issue(
+                find_line(content, &format!("\"{}\"", pattern)),
+                "warning",
+                format!("Context pattern \"{}\" can never match: {}", pattern, reason),
+            ));
+        }
+    }
+
+    issues
+}
+
+/// Explain why a context pattern can never resolve to a file, or None if it looks usable.
+/// Mirrors the matching rules in `assembler::resolve_paths`.
+fn dead_pattern_reason(pattern: &str, in_when_book: bool) -> Option<String> {
+    for var in template_variables(pattern) {
+        if var != "book" {
+            return Some(format!("unsupported placeholder {{{}}}", var));
+        }
+    }
+    if pattern.contains("{book}") && !in_when_book {
+        return Some("{book} is only resolved in [context.when_book]".to_string());
+    }
+    if pattern.matches("**").count() > 1 {
+        return Some("only one ** is supported".to_string());
+    }
+    let segments: Vec<&str> = pattern.split('/').collect();
+    for (i, segment) in segments.iter().enumerate() {
+        if *segment == "**" || !segment.contains('*') {
+            continue;
+        }
+        if i + 1 != segments.len() {
+            return Some("* is only supported in the file name".to_string());
+        }
+        let stars = segment.matches('*').count();
+        if stars > 1 || !(segment.starts_with('*') || segment.ends_with('*')) {
+            return Some("* must be at the start or end of the file name".to_string());
+        }
+    }
+    None
+}
+
+/// Collect `{identifier}` placeholders from a template string.
+fn template_variables(template: &str) -> Vec<String> {
+    let mut vars = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('}') else { break };
+        let name = &rest[..end];
+        if !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            if !vars.iter().any(|v| v == name) {
+                vars.push(name.to_string());
+            }
+            rest = &rest[end + 1..];
+        }
+    }
+    vars
+}
+
+/// 1-based line number of the first line containing `needle`.
+fn find_line(content: &str, needle: &str) -> Option<usize> {
+    content.lines().position(|l| l.contains(needle)).map(|i| i + 1)
+}
+
+/// 1-based line number of the first `key = ...` assignment.
+fn find_key_line(content: &str, key: &str) -> Option<usize> {
+    content
+        .lines()
+        .position(|l| {
+            l.trim_start()
+                .strip_prefix(key)
+                .map(|rest| rest.trim_start().starts_with('='))
+                .unwrap_or(false)
+        })
+        .map(|i| i + 1)
+}
+
+/// 1-based line number of a byte offset.
+fn line_of_offset(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+fn skill_files(app_skills_dir: &PathBuf) -> Result<Vec<PathBuf>, AppError> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(app_skills_dir)? {
+        let path = entry?.path();
+        if path.extension().map(|e| e == "toml").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = r#"
+[skill]
+name = "test"
+display_name = "Test"
+description = "A test skill"
+default_model = "claude-sonnet-4-6"
+temperature = 0.7
+
+[context]
+always_include = ["overview/overview.md"]
+max_context_tokens = 1000
+
+[context.when_book]
+include = ["{book}/phase-1-seed/**/draft.md"]
+
+[context.vector_search]
+enabled = true
+mode = "auto"
+
+[system_prompt]
+template = """
+Hello. {genre_context}
+"""
+"#;
+
+    fn models() -> Vec<String> {
+        vec!["claude-sonnet-4-6".to_string()]
+    }
+
+    #[test]
+    fn test_valid_skill_has_no_issues() {
+        let issues = validate_skill_source("test.toml", VALID, &models());
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_parse_error_reports_line() {
+        let broken = VALID.replace("temperature = 0.7", "temperature = ");
+        let issues = validate_skill_source("test.toml", &broken, &models());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, "error");
+        assert_eq!(issues[0].line, Some(7));
+    }
+
+    #[test]
+    fn test_semantic_checks() {
+        let bad = VALID
+            .replace("claude-sonnet-4-6", "claude-sonet-4-6")
+            .replace("0.7", "1.7")
+            .replace("mode = \"auto\"", "mode = \"sometimes\"")
+            .replace("{genre_context}", "{genre}")
+            .replace("\"overview/overview.md\"", "\"{book}/overview.md\"");
+        let issues = validate_skill_source("test.toml", &bad, &models());
+        let lines: Vec<Option<usize>> = issues.iter().map(|i| i.line).collect();
+        assert_eq!(issues.len(), 5, "{:?}", issues);
+        assert!(lines.contains(&Some(6))); // model
+        assert!(lines.contains(&Some(7))); // temperature
+        assert!(lines.contains(&Some(10))); // dead pattern
+        assert!(lines.contains(&Some(18))); // mode
+        assert!(lines.contains(&Some(22))); // template variable
+    }
+
+    #[test]
+    fn test_vector_search_shaping_checks() {
+        let bad = VALID.replace(
+            "mode = \"auto\"",
+            "mode = \"auto\"\nmmr_lambda = 1.5\nreranker = \"cohere\"",
+        );
+        let issues = validate_skill_source("test.toml", &bad, &models());
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert_eq!(issues[0].severity, "error");
+        assert_eq!(issues[0].line, Some(19));
+        assert_eq!(issues[1].severity, "warning");
+        assert_eq!(issues[1].line, Some(20));
+    }
+
+    #[test]
+    fn test_bundled_skills_are_valid() {
+        let models: toml::Value = toml::from_str(include_str!("../../defaults/models.toml")).unwrap();
+        let ids: Vec<String> = models["models"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|m| m["id"].as_str().map(String::from))
+            .collect();
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("skills");
+        let issues = validate_skills(&dir, &ids).unwrap();
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_dead_patterns() {
+        assert!(dead_pattern_reason("characters/**/profile.md", false).is_none());
+        assert!(dead_pattern_reason("overview/*.md", false).is_none());
+        assert!(dead_pattern_reason("{book}/overview/*.md", true).is_none());
+        assert!(dead_pattern_reason("{chapter}/draft.md", true).is_some());
+        assert!(dead_pattern_reason("world/*/entry.md", false).is_some());
+        assert!(dead_pattern_reason("notes/a*b.md", false).is_some());
+        assert!(dead_pattern_reason("a/**/b/**/c.md", false).is_some());
+    }
+}
@@ -1,6 +1,7 @@
 use sha2::{Sha256, Digest};
 use std::collections::HashMap;
 use std::path::Path;
+use crate::manuscript::{self, ManuscriptFile};
 
 /// A chunk produced from a single file
 #[derive(Debug, Clone)]
@@ -22,6 +23,10 @@ pub struct ChunkMetadata {
     pub chapter_id: Option<String>,
     pub entity_type: Option<String>,
     pub entity_name: Option<String>,
+    /// Scene `pov_character`, as a slug
+    pub pov_character: Option<String>,
+    /// Scene `status` (not_started, outlined, drafted, revised)
+    pub status: Option<String>,
 }
 
 /// Split a markdown file into chunks based on its structure.
@@ -130,6 +135,12 @@ pub fn chunk_file(content: &str, rel_path: &str) -> Vec<Chunk> {
     chunks
 }
 
+/// Metadata for a whole file, from its path and frontmatter.
+pub fn file_metadata(content: &str, rel_path: &str) -> ChunkMetadata {
+    let (frontmatter, _) = split_frontmatter(content);
+    parse_frontmatter_metadata(&frontmatter, &classify_file(rel_path))
+}
+
 /// Compute SHA-256 hash of content
 pub fn sha256(content: &str) -> String {
     let mut hasher = Sha256::new();
@@ -160,7 +171,7 @@ fn make_chunk(index: u32, heading: Option<&str>, content: &str, metadata: ChunkM
 }
 
 /// Split frontmatter from body. Returns (frontmatter_str, body_str).
-fn split_frontmatter(content: &str) -> (String, String) {
+pub fn split_frontmatter(content: &str) -> (String, String) {
     let trimmed = content.trim_start();
     if !trimmed.starts_with("---") {
         return (String::new(), content.to_string());
@@ -274,6 +285,14 @@ fn parse_frontmatter_metadata(frontmatter: &str, base: &ChunkMetadata) -> ChunkM
             metadata.book_id = Some(s.clone());
         }
     }
+    if metadata.chapter_id.is_none() {
+        metadata.chapter_id = fm.get("chapter").filter(|c| c.starts_with("ch-")).cloned();
+    }
+    metadata.pov_character = fm
+        .get("pov_character")
+        .filter(|p| !p.is_empty())
+        .map(|p| p.to_lowercase().replace(' ', "-"));
+    metadata.status = fm.get("status").filter(|s| !s.is_empty()).cloned();
 
     metadata
 }
@@ -292,6 +311,8 @@ fn classify_file(rel_path: &str) -> ChunkMetadata {
         chapter_id: None,
         entity_type: None,
         entity_name: None,
+        pov_character: None,
+        status: None,
     };
 
     if components.is_empty() {
@@ -361,17 +382,22 @@ fn classify_file(rel_path: &str) -> ChunkMetadata {
             } else if phase_dir == "phase-4-flourish" {
                 metadata.file_type = "scene_outline".to_string();
                 metadata.entity_type = Some("scene_outline".to_string());
-            } else if phase_dir == "phase-5-bloom" {
-                metadata.file_type = "scene_draft".to_string();
-                metadata.entity_type = Some("scene_draft".to_string());
-                // Extract chapter_id from ch-XX
-                if components.len() >= 4 {
-                    metadata.chapter_id = Some(components[3].to_string());
-                }
-                // Build entity_name from chapter + scene
-                if components.len() >= 5 {
-                    let scene_name = components[4].strip_suffix(".md").unwrap_or(components[4]);
-                    metadata.entity_name = Some(format!("{}-{}", components[3], scene_name));
+            } else if phase_dir == manuscript::CHAPTERS_DIR {
+                if let Some(location) = manuscript::locate(rel_path) {
+                    let file_type = match location.file {
+                        ManuscriptFile::ChapterNotes => "chapter",
+                        ManuscriptFile::Outline => "scene_outline",
+                        ManuscriptFile::Draft => "scene_draft",
+                        ManuscriptFile::Other => "unknown",
+                    };
+                    metadata.file_type = file_type.to_string();
+                    if matches!(location.file, ManuscriptFile::Outline | ManuscriptFile::Draft) {
+                        metadata.entity_type = Some(file_type.to_string());
+                    }
+                    // Scenes are named ch-XX-scene-YY
+                    metadata.entity_name = location.scene_id.as_ref()
+                        .map(|scene_id| format!("{}-{}", location.chapter_id, scene_id));
+                    metadata.chapter_id = Some(location.chapter_id);
                 }
             } else if phase_dir == "front-matter" {
                 metadata.file_type = "front_matter".to_string();
@@ -409,11 +435,18 @@ mod tests {
 
     #[test]
     fn test_classify_scene_draft() {
-        let m = classify_file("books/book-01/phase-5-bloom/ch-03/scene-02.md");
+        let m = classify_file("books/book-01/chapters/ch-03/scene-02/draft.md");
         assert_eq!(m.file_type, "scene_draft");
         assert_eq!(m.book_id.as_deref(), Some("book-01"));
         assert_eq!(m.chapter_id.as_deref(), Some("ch-03"));
         assert_eq!(m.entity_name.as_deref(), Some("ch-03-scene-02"));
+
+        let m = classify_file("books/book-01/chapters/ch-03/scene-02/outline.md");
+        assert_eq!(m.file_type, "scene_outline");
+        assert_eq!(m.entity_name.as_deref(), Some("ch-03-scene-02"));
+        let m = classify_file("books/book-01/chapters/ch-03/_chapter.md");
+        assert_eq!(m.file_type, "chapter");
+        assert_eq!(m.chapter_id.as_deref(), Some("ch-03"));
     }
 
     #[test]
@@ -448,6 +481,16 @@ mod tests {
         assert_eq!(chunks[2].section_heading.as_deref(), Some("## Want"));
     }
 
+    #[test]
+    fn test_scene_frontmatter_metadata() {
+        let content = "---\ntype: scene-outline\nbook: book-01\nchapter: ch-04\npov_character: \"Sarah Chen\"\nstatus: drafted\n---\n\n## Beats\n\nShe runs.";
+        let chunks = chunk_file(content, "books/book-01/phase-4-flourish/ch-04/scene-01.md");
+        let m = &chunks[0].metadata;
+        assert_eq!(m.chapter_id.as_deref(), Some("ch-04"));
+        assert_eq!(m.pov_character.as_deref(), Some("sarah-chen"));
+        assert_eq!(m.status.as_deref(), Some("drafted"));
+    }
+
     #[test]
     fn test_sha256() {
         let hash = sha256("hello world");
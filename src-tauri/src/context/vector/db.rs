@@ -1,6 +1,13 @@
 use std::path::PathBuf;
-use rusqlite::Connection;
+use std::sync::Once;
+use rusqlite::{Connection, OptionalExtension};
 use crate::error::AppError;
+use super::{EntityAppearance, EntityCooccurrence, EmbeddingSignature, KnownEntity, Quantization, SearchFilters};
+use super::chunker::ChunkMetadata;
+use super::embeddings::{bytes_to_embedding, embedding_to_bytes};
+
+/// Version of the index layout, kept in `PRAGMA user_version` (and mirrored in `index_meta`).
+pub const SCHEMA_VERSION: u32 = 5;
 
 const SCHEMA_SQL: &str = r#"
 -- Tracks indexed files and their state
@@ -9,10 +16,12 @@ CREATE TABLE IF NOT EXISTS indexed_files (
     content_hash  TEXT NOT NULL,
     file_type     TEXT NOT NULL,
     last_indexed  TEXT NOT NULL,
-    chunk_count   INTEGER NOT NULL DEFAULT 0
+    chunk_count   INTEGER NOT NULL DEFAULT 0,
+    modified      TEXT
 );
 
--- Stores individual chunks with embeddings
+-- Stores individual chunks. The vector index holds their embeddings; `embedding`
+-- keeps a full-precision copy only for a quantized index that asks for one
 CREATE TABLE IF NOT EXISTS chunks (
     id            INTEGER PRIMARY KEY AUTOINCREMENT,
     file_path     TEXT NOT NULL,
@@ -34,6 +43,8 @@ CREATE TABLE IF NOT EXISTS chunk_metadata (
     chapter_id    TEXT,
     entity_type   TEXT,
     entity_name   TEXT,
+    pov_character TEXT,
+    status        TEXT,
 
     FOREIGN KEY (chunk_id) REFERENCES chunks(id) ON DELETE CASCADE
 );
@@ -47,14 +58,59 @@ CREATE TABLE IF NOT EXISTS embedding_log (
     cost_usd      REAL NOT NULL
 );
 
+-- Full-text index over chunk content for lexical (BM25) search.
+-- rowid matches chunks.id; rows are removed by the trigger below.
+CREATE VIRTUAL TABLE IF NOT EXISTS chunks_fts USING fts5(
+    section_heading,
+    content,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER IF NOT EXISTS chunks_fts_delete AFTER DELETE ON chunks BEGIN
+    DELETE FROM chunks_fts WHERE rowid = old.id;
+END;
+
+-- Index-wide key/value metadata: schema version, the embedding model in use
+-- and the target model of a pending re-embed
+CREATE TABLE IF NOT EXISTS index_meta (
+    key           TEXT PRIMARY KEY,
+    value         TEXT NOT NULL
+);
+
+-- Embeddings from the new model while a re-embed is in progress.
+-- Swapped into chunks once every chunk has one, so the old vectors stay usable until then.
+CREATE TABLE IF NOT EXISTS reembed_staging (
+    chunk_id      INTEGER PRIMARY KEY,
+    embedding     BLOB NOT NULL,
+
+    FOREIGN KEY (chunk_id) REFERENCES chunks(id) ON DELETE CASCADE
+);
+
 CREATE INDEX IF NOT EXISTS idx_chunks_file ON chunks(file_path);
 CREATE INDEX IF NOT EXISTS idx_metadata_book ON chunk_metadata(book_id);
 CREATE INDEX IF NOT EXISTS idx_metadata_entity ON chunk_metadata(entity_type, entity_name);
 "#;
 
+static VEC_EXTENSION: Once = Once::new();
+
+type SqliteExtensionInit = unsafe extern "C" fn(
+    *mut rusqlite::ffi::sqlite3,
+    *mut *const std::os::raw::c_char,
+    *const rusqlite::ffi::sqlite3_api_routines,
+) -> std::os::raw::c_int;
+
+/// Register sqlite-vec (statically linked) so every connection opened afterwards has `vec0`.
+fn register_vec_extension() {
+    VEC_EXTENSION.call_once(|| unsafe {
+        let init = std::mem::transmute::<*const (), SqliteExtensionInit>(sqlite_vec::sqlite3_vec_init as *const ());
+        rusqlite::ffi::sqlite3_auto_extension(Some(init));
+    });
+}
+
 /// Open (or create) the project's vector index database.
 /// The DB lives at `{project_dir}/.saipling/index.db`.
 pub fn open_index(project_dir: &PathBuf) -> Result<Connection, AppError> {
+    register_vec_extension();
     let db_dir = project_dir.join(".saipling");
     std::fs::create_dir_all(&db_dir)?;
     let db_path = db_dir.join("index.db");
@@ -68,12 +124,797 @@ pub fn open_index(project_dir: &PathBuf) -> Result<Connection, AppError> {
     Ok(conn)
 }
 
-/// Create all tables and indexes if they don't exist.
+/// Open another project's index without writing to it, e.g. a linked project.
+/// Returns None if it has no index yet; fails if its schema isn't this version's,
+/// since it can only be migrated by opening that project.
+pub fn open_index_read_only(project_dir: &std::path::Path) -> Result<Option<Connection>, AppError> {
+    let Some(conn) = open_index_unmigrated(project_dir)? else {
+        return Ok(None);
+    };
+    let version = stored_schema_version(&conn)?;
+    if version != SCHEMA_VERSION {
+        return Err(AppError::IndexError(format!(
+            "index schema {} (expected {}); open that project once to update it",
+            version, SCHEMA_VERSION
+        )));
+    }
+    Ok(Some(conn))
+}
+
+/// Open an index read-only at whatever schema version it has, or None if there is none.
+pub fn open_index_unmigrated(project_dir: &std::path::Path) -> Result<Option<Connection>, AppError> {
+    let db_path = project_dir.join(".saipling").join("index.db");
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    register_vec_extension();
+    let conn = Connection::open_with_flags(
+        &db_path,
+        rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY | rusqlite::OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    Ok(Some(conn))
+}
+
+/// The schema version the index was last migrated to.
+pub fn stored_schema_version(conn: &Connection) -> Result<u32, AppError> {
+    Ok(conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+}
+
+/// Bring the schema up to `SCHEMA_VERSION`, running each migration newer than the
+/// database's `user_version` in its own transaction. Every migration is idempotent,
+/// so indexes created before versioning (user_version 0) simply run them all.
 pub fn init_schema(conn: &Connection) -> Result<(), AppError> {
+    let version = stored_schema_version(conn)?;
+    if version > SCHEMA_VERSION {
+        return Err(AppError::IndexError(format!(
+            "The search index was created by a newer version of sAIpling (schema {}); clear it to rebuild",
+            version
+        )));
+    }
+    for &(target, migrate) in MIGRATIONS.iter().filter(|(target, _)| *target > version) {
+        let tx = conn.unchecked_transaction()?;
+        migrate(&tx)?;
+        tx.pragma_update(None, "user_version", target)?;
+        set_meta(&tx, "schema_version", &target.to_string())?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+type Migration = fn(&Connection) -> Result<(), AppError>;
+
+/// Schema migrations, by the version they bring the database to.
+const MIGRATIONS: &[(u32, Migration)] = &[
+    (1, migrate_v1_tables),
+    (2, migrate_v2_filter_columns),
+    (3, migrate_v3_cleanup_triggers),
+    (4, migrate_v4_entity_mentions),
+    (5, migrate_v5_vector_sizes),
+];
+
+fn migrate_v1_tables(conn: &Connection) -> Result<(), AppError> {
     conn.execute_batch(SCHEMA_SQL)?;
     Ok(())
 }
 
+/// Columns for the search filters; files indexed before them get values on their next index.
+fn migrate_v2_filter_columns(conn: &Connection) -> Result<(), AppError> {
+    add_column_if_missing(conn, "indexed_files", "modified", "TEXT")?;
+    add_column_if_missing(conn, "chunk_metadata", "pov_character", "TEXT")?;
+    add_column_if_missing(conn, "chunk_metadata", "status", "TEXT")?;
+    Ok(())
+}
+
+/// Cascade deletes with triggers, which — unlike the foreign keys — work on
+/// connections without `PRAGMA foreign_keys`, then remove rows already orphaned.
+fn migrate_v3_cleanup_triggers(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS indexed_files_delete AFTER DELETE ON indexed_files BEGIN
+             DELETE FROM chunks WHERE file_path = old.file_path;
+         END;
+         CREATE TRIGGER IF NOT EXISTS chunks_delete AFTER DELETE ON chunks BEGIN
+             DELETE FROM chunk_metadata WHERE chunk_id = old.id;
+             DELETE FROM reembed_staging WHERE chunk_id = old.id;
+         END;",
+    )?;
+    delete_orphans(conn)?;
+    Ok(())
+}
+
+/// Known characters, locations and items, and where each is mentioned in the manuscript.
+fn migrate_v4_entity_mentions(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS entities (
+             entity_type   TEXT NOT NULL,
+             slug          TEXT NOT NULL,
+             name          TEXT NOT NULL,
+             aliases       TEXT NOT NULL,
+             source_path   TEXT NOT NULL UNIQUE,
+             source_hash   TEXT NOT NULL,
+             PRIMARY KEY (entity_type, slug)
+         );
+         CREATE TABLE IF NOT EXISTS entity_mentions (
+             id            INTEGER PRIMARY KEY AUTOINCREMENT,
+             file_path     TEXT NOT NULL,
+             entity_type   TEXT NOT NULL,
+             entity_slug   TEXT NOT NULL,
+             matched_text  TEXT NOT NULL,
+             char_offset   INTEGER NOT NULL,
+             book_id       TEXT,
+             chapter_id    TEXT,
+             scene_id      TEXT,
+             file_type     TEXT NOT NULL
+         );
+         -- Which manuscript files have been scanned, at which content and set of entities
+         CREATE TABLE IF NOT EXISTS mention_files (
+             file_path     TEXT PRIMARY KEY,
+             content_hash  TEXT NOT NULL,
+             entity_set    TEXT NOT NULL
+         );
+         CREATE INDEX IF NOT EXISTS idx_mentions_entity ON entity_mentions(entity_type, entity_slug);
+         CREATE INDEX IF NOT EXISTS idx_mentions_file ON entity_mentions(file_path);
+         CREATE TRIGGER IF NOT EXISTS indexed_files_delete_mentions AFTER DELETE ON indexed_files BEGIN
+             DELETE FROM entity_mentions WHERE file_path = old.file_path;
+             DELETE FROM mention_files WHERE file_path = old.file_path;
+             DELETE FROM entities WHERE source_path = old.file_path;
+         END;",
+    )?;
+    Ok(())
+}
+
+/// Record each chunk's vector size, so its full-precision copy in `chunks.embedding`
+/// can be dropped once the vector index holds it. The next `sync_vector_index`
+/// drops the copies that aren't kept.
+fn migrate_v5_vector_sizes(conn: &Connection) -> Result<(), AppError> {
+    add_column_if_missing(conn, "chunks", "dimensions", "INTEGER NOT NULL DEFAULT 0")?;
+    conn.execute_batch("UPDATE chunks SET dimensions = length(embedding) / 4 WHERE dimensions = 0")?;
+    Ok(())
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, sql_type: &str) -> Result<(), AppError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|name| name.ok())
+        .any(|name| name == column);
+    if !exists {
+        conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, sql_type))?;
+    }
+    Ok(())
+}
+
+// ─── Index metadata ───
+
+fn get_meta(conn: &Connection, key: &str) -> Result<Option<String>, AppError> {
+    let value = conn
+        .query_row("SELECT value FROM index_meta WHERE key = ?1", [key], |row| row.get::<_, String>(0))
+        .optional()?;
+    Ok(value)
+}
+
+fn set_meta(conn: &Connection, key: &str, value: &str) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT INTO index_meta (key, value) VALUES (?1, ?2)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        rusqlite::params![key, value],
+    )?;
+    Ok(())
+}
+
+fn get_signature(conn: &Connection, prefix: &str) -> Result<Option<EmbeddingSignature>, AppError> {
+    let provider = get_meta(conn, &format!("{}_provider", prefix))?;
+    let model = get_meta(conn, &format!("{}_model", prefix))?;
+    let dimensions = get_meta(conn, &format!("{}_dimensions", prefix))?;
+    Ok(match (provider, model) {
+        (Some(provider), Some(model)) => Some(EmbeddingSignature {
+            provider,
+            model,
+            dimensions: dimensions.and_then(|d| d.parse().ok()).unwrap_or(0),
+        }),
+        _ => None,
+    })
+}
+
+fn set_signature(conn: &Connection, prefix: &str, sig: &EmbeddingSignature) -> Result<(), AppError> {
+    set_meta(conn, &format!("{}_provider", prefix), &sig.provider)?;
+    set_meta(conn, &format!("{}_model", prefix), &sig.model)?;
+    set_meta(conn, &format!("{}_dimensions", prefix), &sig.dimensions.to_string())?;
+    Ok(())
+}
+
+/// The model that produced the embeddings stored in `chunks`, if recorded.
+pub fn get_embedding_signature(conn: &Connection) -> Result<Option<EmbeddingSignature>, AppError> {
+    get_signature(conn, "embedding")
+}
+
+/// Record the model that produced the embeddings stored in `chunks`.
+pub fn set_embedding_signature(conn: &Connection, sig: &EmbeddingSignature) -> Result<(), AppError> {
+    set_signature(conn, "embedding", sig)
+}
+
+/// The model the staged re-embed vectors were made with, if a re-embed is pending.
+pub fn get_reembed_target(conn: &Connection) -> Result<Option<EmbeddingSignature>, AppError> {
+    get_signature(conn, "reembed")
+}
+
+/// Start (or restart) a re-embed towards `sig`, discarding vectors staged for another model.
+pub fn set_reembed_target(conn: &Connection, sig: &EmbeddingSignature) -> Result<(), AppError> {
+    conn.execute("DELETE FROM reembed_staging", [])?;
+    set_signature(conn, "reembed", sig)
+}
+
+/// Vector size of the stored embeddings, from any one chunk.
+pub fn get_embedding_dimensions(conn: &Connection) -> Result<Option<usize>, AppError> {
+    let dimensions = conn
+        .query_row("SELECT dimensions FROM chunks WHERE dimensions > 0 LIMIT 1", [], |row| row.get::<_, i64>(0))
+        .optional()?;
+    Ok(dimensions.map(|d| d as usize))
+}
+
+// ─── Re-embedding ───
+
+/// Stage a new-model embedding for a chunk.
+pub fn insert_staged_embedding(conn: &Connection, chunk_id: i64, embedding: &[u8]) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO reembed_staging (chunk_id, embedding) VALUES (?1, ?2)",
+        rusqlite::params![chunk_id, embedding],
+    )?;
+    Ok(())
+}
+
+/// Chunks that still need a staged embedding, as (chunk_id, full text, token_count).
+/// Falls back to the preview for chunks missing from the lexical index.
+pub fn get_chunks_to_reembed(conn: &Connection, limit: u32) -> Result<Vec<(i64, String, u32)>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT c.id, COALESCE(f.content, c.content_preview), c.token_count
+         FROM chunks c
+         LEFT JOIN chunks_fts f ON f.rowid = c.id
+         LEFT JOIN reembed_staging s ON s.chunk_id = c.id
+         WHERE s.chunk_id IS NULL
+         ORDER BY c.id
+         LIMIT ?1"
+    )?;
+    let rows = stmt.query_map([limit], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, u32>(2)?))
+    })?;
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
+/// Chunks not yet staged for a pending re-embed, and their total tokens.
+pub fn get_reembed_remaining(conn: &Connection) -> Result<(u32, u64), AppError> {
+    let (chunks, tokens): (u32, i64) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(c.token_count), 0)
+         FROM chunks c
+         LEFT JOIN reembed_staging s ON s.chunk_id = c.id
+         WHERE s.chunk_id IS NULL",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    Ok((chunks, tokens as u64))
+}
+
+/// Vector size of the staged embeddings, from any one chunk.
+pub fn get_staged_dimensions(conn: &Connection) -> Result<Option<usize>, AppError> {
+    let len = conn
+        .query_row("SELECT length(embedding) FROM reembed_staging LIMIT 1", [], |row| row.get::<_, i64>(0))
+        .optional()?;
+    Ok(len.map(|l| l as usize / 4))
+}
+
+/// Staged and total chunk counts for the pending re-embed.
+pub fn get_reembed_counts(conn: &Connection) -> Result<(u32, u32), AppError> {
+    let staged: u32 = conn.query_row("SELECT COUNT(*) FROM reembed_staging", [], |row| row.get(0))?;
+    let total: u32 = conn.query_row("SELECT COUNT(*) FROM chunks", [], |row| row.get(0))?;
+    Ok((staged, total))
+}
+
+/// Swap every staged embedding into `chunks` in one transaction and record the new model.
+pub fn commit_reembed(conn: &Connection, sig: &EmbeddingSignature) -> Result<(), AppError> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute_batch(
+        "UPDATE chunks SET embedding = (SELECT s.embedding FROM reembed_staging s WHERE s.chunk_id = chunks.id),
+                           dimensions = (SELECT length(s.embedding) / 4 FROM reembed_staging s WHERE s.chunk_id = chunks.id)
+         WHERE id IN (SELECT chunk_id FROM reembed_staging);
+         DELETE FROM reembed_staging;
+         DELETE FROM index_meta WHERE key LIKE 'reembed_%';"
+    )?;
+    set_embedding_signature(&tx, sig)?;
+    match get_embedding_dimensions(&tx)? {
+        Some(dimensions) => {
+            rebuild_vector_index(&tx, dimensions)?;
+        }
+        // Nothing embedded yet — the first insert creates the table
+        None => drop_vector_table(&tx)?,
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+// ─── Vector index (sqlite-vec) ───
+
+/// Record how the vector index should store embeddings. The next
+/// `ensure_vector_index` migrates an index stored another way.
+pub fn set_quantization(conn: &Connection, quantization: Quantization) -> Result<(), AppError> {
+    set_meta(conn, "quantization", quantization.as_str())
+}
+
+/// The requested storage for the vector index.
+pub fn get_quantization(conn: &Connection) -> Result<Quantization, AppError> {
+    Ok(Quantization::from_config(&get_meta(conn, "quantization")?.unwrap_or_default()))
+}
+
+/// Storage the vector index was built with, as (dimensions, quantization).
+pub fn get_vector_index_layout(conn: &Connection) -> Result<Option<(usize, Quantization)>, AppError> {
+    let dimensions = get_meta(conn, "vec_dimensions")?.and_then(|d| d.parse().ok());
+    let quantization = Quantization::from_config(&get_meta(conn, "vec_quantization")?.unwrap_or_default());
+    Ok(dimensions.map(|d| (d, quantization)))
+}
+
+/// Binary quantization packs 8 dimensions per byte; other sizes fall back to int8.
+fn effective_quantization(dimensions: usize, quantization: Quantization) -> Quantization {
+    if quantization == Quantization::Binary && !dimensions.is_multiple_of(8) {
+        Quantization::Int8
+    } else {
+        quantization
+    }
+}
+
+/// SQL expression converting a float32 vector parameter to the index's storage type.
+fn quantize_sql(param: &str, quantization: Quantization) -> String {
+    match quantization {
+        Quantization::None => param.to_string(),
+        Quantization::Int8 => format!("vec_quantize_int8({}, 'unit')", param),
+        Quantization::Binary => format!("vec_quantize_binary({})", param),
+    }
+}
+
+/// Create the `vec0` table for `dimensions`-sized vectors in the requested storage,
+/// replacing any existing one. Book and entity type are metadata columns so KNN
+/// queries filter during the lookup; NULLs are stored as '' since vec0 metadata
+/// columns can't hold them.
+fn create_vector_table(conn: &Connection, dimensions: usize) -> Result<Quantization, AppError> {
+    let quantization = effective_quantization(dimensions, get_quantization(conn)?);
+    let column = match quantization {
+        Quantization::None => format!("float[{}] distance_metric=cosine", dimensions),
+        Quantization::Int8 => format!("int8[{}] distance_metric=cosine", dimensions),
+        Quantization::Binary => format!("bit[{}]", dimensions),
+    };
+    conn.execute_batch(&format!(
+        "DROP TRIGGER IF EXISTS vec_chunks_delete;
+         DROP TABLE IF EXISTS vec_chunks;
+         CREATE VIRTUAL TABLE vec_chunks USING vec0(
+             chunk_id INTEGER PRIMARY KEY,
+             embedding {},
+             book_id TEXT,
+             entity_type TEXT
+         );
+         CREATE TRIGGER vec_chunks_delete AFTER DELETE ON chunks BEGIN
+             DELETE FROM vec_chunks WHERE chunk_id = old.id;
+         END;",
+        column
+    ))?;
+    set_meta(conn, "vec_dimensions", &dimensions.to_string())?;
+    set_meta(conn, "vec_quantization", quantization.as_str())?;
+    Ok(quantization)
+}
+
+/// Remove the `vec0` table and its recorded layout.
+fn drop_vector_table(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch(
+        "DROP TRIGGER IF EXISTS vec_chunks_delete;
+         DROP TABLE IF EXISTS vec_chunks;
+         DELETE FROM index_meta WHERE key IN ('vec_dimensions', 'vec_quantization');"
+    )?;
+    Ok(())
+}
+
+/// Rebuild the `vec0` table at `dimensions` in the requested storage, from each
+/// chunk's full-precision copy or, without one, its vector in the current table.
+/// Vectors read back from a quantized table keep its loss of precision. Chunks
+/// whose vector is nowhere are marked unembedded, so their next index embeds them;
+/// their files are returned.
+fn rebuild_vector_index(conn: &Connection, dimensions: usize) -> Result<Vec<String>, AppError> {
+    conn.execute_batch(
+        "DROP TABLE IF EXISTS temp.vec_rebuild;
+         CREATE TEMP TABLE vec_rebuild (chunk_id INTEGER PRIMARY KEY, embedding BLOB NOT NULL);"
+    )?;
+    conn.execute(
+        "INSERT INTO temp.vec_rebuild (chunk_id, embedding)
+         SELECT id, embedding FROM chunks WHERE dimensions = ?1 AND length(embedding) = ?2",
+        [dimensions as i64, (dimensions * 4) as i64],
+    )?;
+    let target = effective_quantization(dimensions, get_quantization(conn)?);
+    if let Some((_, from)) = get_vector_index_layout(conn)?.filter(|(d, _)| *d == dimensions) {
+        let ids: Vec<i64> = {
+            let mut stmt = conn.prepare(
+                "SELECT id FROM chunks WHERE dimensions = ?1 AND id NOT IN (SELECT chunk_id FROM temp.vec_rebuild)"
+            )?;
+            let rows = stmt.query_map([dimensions as i64], |row| row.get(0))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let restored = read_index_vectors(conn, &ids)?;
+        if !restored.is_empty() && from.bits() < target.bits() {
+            eprintln!(
+                "Warning: vector index rebuilt from {} vectors; re-index the project to restore full precision",
+                from.as_str()
+            );
+        }
+        let mut insert = conn.prepare("INSERT INTO temp.vec_rebuild (chunk_id, embedding) VALUES (?1, ?2)")?;
+        for (id, embedding) in restored {
+            insert.execute(rusqlite::params![id, embedding])?;
+        }
+    }
+
+    let quantization = create_vector_table(conn, dimensions)?;
+    conn.execute_batch(&format!(
+        "INSERT INTO vec_chunks (chunk_id, embedding, book_id, entity_type)
+         SELECT r.chunk_id, {}, COALESCE(m.book_id, ''), COALESCE(m.entity_type, '')
+         FROM temp.vec_rebuild r
+         LEFT JOIN chunk_metadata m ON m.chunk_id = r.chunk_id",
+        quantize_sql("r.embedding", quantization)
+    ))?;
+
+    let lost: Vec<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT DISTINCT file_path FROM chunks
+             WHERE dimensions = ?1 AND id NOT IN (SELECT chunk_id FROM temp.vec_rebuild)
+             ORDER BY file_path"
+        )?;
+        let rows = stmt.query_map([dimensions as i64], |row| row.get(0))?;
+        rows.collect::<Result<_, _>>()?
+    };
+    conn.execute(
+        "UPDATE chunks SET embedding = x'', dimensions = 0
+         WHERE dimensions = ?1 AND id NOT IN (SELECT chunk_id FROM temp.vec_rebuild)",
+        [dimensions as i64],
+    )?;
+    conn.execute_batch("DROP TABLE temp.vec_rebuild;")?;
+    drop_unkept_copies(conn)?;
+    Ok(lost)
+}
+
+/// Make sure the `vec0` table exists and matches the stored embeddings and the
+/// requested quantization. Indexes built before it existed, whose vectors changed
+/// size, or stored another way are rebuilt once. Returns whether it was rebuilt.
+pub fn ensure_vector_index(conn: &Connection) -> Result<bool, AppError> {
+    if let Some(dimensions) = get_embedding_dimensions(conn)? {
+        let wanted = (dimensions, effective_quantization(dimensions, get_quantization(conn)?));
+        if get_vector_index_layout(conn)? != Some(wanted) || !table_exists(conn, "vec_chunks")? {
+            rebuild_vector_index(conn, dimensions)?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Add a chunk's embedding to the vector index, creating it on first use. Once the
+/// index holds it, the chunk's full-precision copy is cleared unless it is kept.
+pub fn insert_chunk_vector(
+    conn: &Connection,
+    chunk_id: i64,
+    embedding: &[u8],
+    book_id: Option<&str>,
+    entity_type: Option<&str>,
+) -> Result<(), AppError> {
+    let dimensions = embedding.len() / 4;
+    let quantization = match get_vector_index_layout(conn)? {
+        Some((d, q)) if d == dimensions => q,
+        Some(_) => return Ok(()), // another model's size — reconciled by ensure_vector_index
+        None => create_vector_table(conn, dimensions)?,
+    };
+    conn.execute(
+        &format!(
+            "INSERT INTO vec_chunks (chunk_id, embedding, book_id, entity_type) VALUES (?1, {}, ?3, ?4)",
+            quantize_sql("?2", quantization)
+        ),
+        rusqlite::params![chunk_id, embedding, book_id.unwrap_or(""), entity_type.unwrap_or("")],
+    )?;
+    if !keeps_full_precision(conn)? {
+        conn.execute("UPDATE chunks SET embedding = x'' WHERE id = ?1", [chunk_id])?;
+    }
+    Ok(())
+}
+
+/// Refresh the book and entity type a chunk's vector is filtered by in the KNN lookup.
+pub fn update_vector_filters(
+    conn: &Connection,
+    chunk_id: i64,
+    book_id: Option<&str>,
+    entity_type: Option<&str>,
+) -> Result<(), AppError> {
+    if !table_exists(conn, "vec_chunks")? {
+        return Ok(());
+    }
+    conn.execute(
+        "UPDATE vec_chunks SET book_id = ?2, entity_type = ?3 WHERE chunk_id = ?1",
+        rusqlite::params![chunk_id, book_id.unwrap_or(""), entity_type.unwrap_or("")],
+    )?;
+    Ok(())
+}
+
+/// Record whether a quantized index keeps a full-precision copy of each vector in
+/// `chunks` to rescore with. Only vectors embedded while it is on have one.
+pub fn set_keep_full_precision(conn: &Connection, keep: bool) -> Result<(), AppError> {
+    set_meta(conn, "keep_full_precision", if keep { "true" } else { "false" })
+}
+
+/// Whether chunks keep a full-precision copy of their vector: only for a quantized
+/// index that asks for one, since a float32 index holds the vectors exactly.
+fn keeps_full_precision(conn: &Connection) -> Result<bool, AppError> {
+    Ok(get_quantization(conn)? != Quantization::None
+        && get_meta(conn, "keep_full_precision")?.as_deref() == Some("true"))
+}
+
+/// Clear the full-precision copies that aren't kept and whose vector the index
+/// holds. Returns how many were cleared.
+pub fn drop_unkept_copies(conn: &Connection) -> Result<usize, AppError> {
+    if keeps_full_precision(conn)? || !table_exists(conn, "vec_chunks")? {
+        return Ok(0);
+    }
+    let Some((dimensions, _)) = get_vector_index_layout(conn)? else {
+        return Ok(0);
+    };
+    let cleared = conn.execute(
+        "UPDATE chunks SET embedding = x''
+         WHERE length(embedding) > 0 AND dimensions = ?1 AND id IN (SELECT chunk_id FROM vec_chunks)",
+        [dimensions as i64],
+    )?;
+    Ok(cleared)
+}
+
+/// Give the space of dropped vectors back to the file system.
+pub fn vacuum(conn: &Connection) -> Result<(), AppError> {
+    conn.execute_batch("VACUUM;")?;
+    Ok(())
+}
+
+/// Embeddings of the given chunks as float32 bytes, from their full-precision copy
+/// or else the vector index; those from a quantized index are approximate.
+pub fn get_chunk_embeddings(
+    conn: &Connection,
+    chunk_ids: &[i64],
+) -> Result<std::collections::HashMap<i64, Vec<u8>>, AppError> {
+    let mut map = std::collections::HashMap::new();
+    let mut from_index = Vec::new();
+    let mut stmt = conn.prepare("SELECT embedding, dimensions FROM chunks WHERE id = ?1")?;
+    for &id in chunk_ids {
+        match stmt.query_row([id], |row| Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, i64>(1)?))).optional()? {
+            Some((embedding, _)) if !embedding.is_empty() => {
+                map.insert(id, embedding);
+            }
+            Some((_, dimensions)) if dimensions > 0 => from_index.push(id),
+            _ => {}
+        }
+    }
+    map.extend(read_index_vectors(conn, &from_index)?);
+    Ok(map)
+}
+
+/// Vectors of the given chunks read back from the vector index as float32 bytes.
+fn read_index_vectors(
+    conn: &Connection,
+    chunk_ids: &[i64],
+) -> Result<std::collections::HashMap<i64, Vec<u8>>, AppError> {
+    let mut map = std::collections::HashMap::new();
+    let Some((dimensions, quantization)) = get_vector_index_layout(conn)? else {
+        return Ok(map);
+    };
+    if chunk_ids.is_empty() || !table_exists(conn, "vec_chunks")? {
+        return Ok(map);
+    }
+    let mut stmt = conn.prepare("SELECT embedding FROM vec_chunks WHERE chunk_id = ?1")?;
+    for &id in chunk_ids {
+        if let Some(stored) = stmt.query_row([id], |row| row.get::<_, Vec<u8>>(0)).optional()? {
+            map.insert(id, embedding_to_bytes(&dequantize(&stored, dimensions, quantization)));
+        }
+    }
+    Ok(map)
+}
+
+/// Fill in the embedding of rows without a full-precision copy from the vector index.
+fn fill_from_index(conn: &Connection, rows: &mut [ChunkRow]) -> Result<(), AppError> {
+    let ids: Vec<i64> = rows.iter().filter(|r| r.embedding.is_empty()).map(|r| r.id).collect();
+    let mut vectors = read_index_vectors(conn, &ids)?;
+    for row in rows.iter_mut().filter(|r| r.embedding.is_empty()) {
+        if let Some(embedding) = vectors.remove(&row.id) {
+            row.embedding = embedding;
+        }
+    }
+    Ok(())
+}
+
+/// Turn a vector as stored by `vec0` back into floats. int8 values map to the
+/// middle of their bucket, so quantizing the result gives the same bytes; bits
+/// become ±1, which keeps their cosine similarities.
+fn dequantize(stored: &[u8], dimensions: usize, quantization: Quantization) -> Vec<f32> {
+    match quantization {
+        Quantization::None => bytes_to_embedding(stored),
+        Quantization::Int8 => {
+            let step = 2.0 / 255.0;
+            stored
+                .iter()
+                .map(|&b| {
+                    let q = b as i8 as f32;
+                    let centre = if q == 0.0 { 0.0 } else { q + 0.5 * q.signum() };
+                    (centre + 128.0) * step - 1.0
+                })
+                .collect()
+        }
+        Quantization::Binary => (0..dimensions)
+            .map(|i| if stored.get(i / 8).is_some_and(|b| b & (1 << (i % 8)) != 0) { 1.0 } else { -1.0 })
+            .collect(),
+    }
+}
+
+/// Full text of the given chunks, keyed by chunk ID (missing IDs are skipped).
+pub fn get_chunk_texts(
+    conn: &Connection,
+    chunk_ids: &[i64],
+) -> Result<std::collections::HashMap<i64, String>, AppError> {
+    let mut map = std::collections::HashMap::new();
+    let mut stmt = conn.prepare("SELECT content FROM chunks_fts WHERE rowid = ?1")?;
+    for &id in chunk_ids {
+        if let Ok(content) = stmt.query_row([id], |row| row.get::<_, String>(0)) {
+            map.insert(id, content);
+        }
+    }
+    Ok(map)
+}
+
+/// Size of the index database on disk, including its write-ahead log.
+pub fn index_storage_bytes(project_dir: &std::path::Path) -> u64 {
+    let db_dir = project_dir.join(".saipling");
+    ["index.db", "index.db-wal", "index.db-shm"]
+        .iter()
+        .filter_map(|name| std::fs::metadata(db_dir.join(name)).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// K-nearest-neighbour lookup in the vector index, closest first.
+/// Book and entity type filters are applied inside the lookup, so `k` counts only
+/// chunks matching those; the remaining filters run on the `k` neighbours. Returns
+/// each surviving chunk with its cosine distance (0 = identical), plus the number of
+/// neighbours found before those remaining filters. The embedding field of the
+/// returned rows is left empty.
+pub fn vector_search(
+    conn: &Connection,
+    query_embedding: &[u8],
+    k: u32,
+    filters: &SearchFilters,
+) -> Result<(Vec<(ChunkRow, f64)>, usize), AppError> {
+    let quantization = get_vector_index_layout(conn)?.map(|(_, q)| q).unwrap_or_default();
+    let mut knn_filters = String::new();
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(query_embedding.to_vec()), Box::new(k)];
+    if let Some(ref book_id) = filters.book_id {
+        knn_filters.push_str(" AND book_id = ?");
+        params.push(Box::new(book_id.clone()));
+    }
+    if !filters.entity_types.is_empty() {
+        knn_filters.push_str(&format!(" AND entity_type IN ({})", placeholders(filters.entity_types.len())));
+        for entity_type in &filters.entity_types {
+            params.push(Box::new(entity_type.clone()));
+        }
+    }
+    let (where_sql, filter_params) = filter_clause(filters);
+    params.extend(filter_params);
+
+    let sql = format!(
+        "WITH knn AS (
+             SELECT chunk_id, distance FROM vec_chunks
+             WHERE embedding MATCH {} AND k = ?{}
+         )
+         SELECT c.id, c.file_path, c.chunk_index, c.section_heading, c.content_preview,
+                c.token_count, m.book_id, m.entity_type, m.entity_name, knn.distance,
+                (SELECT COUNT(*) FROM knn) AS neighbours
+         FROM knn
+         JOIN chunks c ON c.id = knn.chunk_id
+         LEFT JOIN chunk_metadata m ON m.chunk_id = c.id
+         WHERE 1 = 1{}
+         ORDER BY knn.distance",
+        quantize_sql("?", quantization),
+        knn_filters,
+        where_sql
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut neighbours = 0usize;
+    let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        Ok((
+            ChunkRow {
+                id: row.get(0)?,
+                file_path: row.get(1)?,
+                chunk_index: row.get(2)?,
+                section_heading: row.get(3)?,
+                content_preview: row.get(4)?,
+                token_count: row.get(5)?,
+                embedding: Vec::new(),
+                book_id: row.get(6)?,
+                entity_type: row.get(7)?,
+                entity_name: row.get(8)?,
+            },
+            row.get::<_, f64>(9)?,
+            row.get::<_, i64>(10)?,
+        ))
+    })?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        let (chunk, distance, count) = row?;
+        neighbours = count as usize;
+        results.push((chunk, distance));
+    }
+    Ok((results, neighbours))
+}
+
+/// SQL conditions (each starting with " AND ") over `chunks c` and `chunk_metadata m`
+/// for every set filter, with their parameters in order.
+pub fn filter_clause(filters: &SearchFilters) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut sql = String::new();
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+    let mut any_of = |sql: &mut String, column: &str, values: &[String]| {
+        if !values.is_empty() {
+            sql.push_str(&format!(" AND {} IN ({})", column, placeholders(values.len())));
+            for value in values {
+                params.push(Box::new(value.clone()));
+            }
+        }
+    };
+    any_of(&mut sql, "m.entity_type", &filters.entity_types);
+    any_of(&mut sql, "m.entity_name", &filters.entity_names);
+    any_of(&mut sql, "m.status", &filters.statuses);
+    if let Some(ref book_id) = filters.book_id {
+        any_of(&mut sql, "m.book_id", std::slice::from_ref(book_id));
+    }
+    if let Some(ref pov) = filters.pov_character {
+        any_of(&mut sql, "m.pov_character", std::slice::from_ref(pov));
+    }
+
+    // Chapter numbers follow reading order, so the range is resolved to ids beforehand
+    if filters.chapter_from.is_some() || filters.chapter_to.is_some() {
+        if filters.chapters.is_empty() {
+            sql.push_str(" AND 0");
+        } else {
+            let pairs = vec!["(?, ?)"; filters.chapters.len()].join(", ");
+            sql.push_str(&format!(" AND (m.book_id, m.chapter_id) IN (VALUES {})", pairs));
+            for (book_id, chapter_id) in &filters.chapters {
+                params.push(Box::new(book_id.clone()));
+                params.push(Box::new(chapter_id.clone()));
+            }
+        }
+    }
+
+    if !filters.file_types.is_empty() || filters.modified_after.is_some() {
+        sql.push_str(" AND c.file_path IN (SELECT file_path FROM indexed_files WHERE 1 = 1");
+        if !filters.file_types.is_empty() {
+            sql.push_str(&format!(" AND file_type IN ({})", placeholders(filters.file_types.len())));
+            for file_type in &filters.file_types {
+                params.push(Box::new(file_type.clone()));
+            }
+        }
+        if let Some(ref after) = filters.modified_after {
+            sql.push_str(" AND modified >= ?");
+            params.push(Box::new(after.clone()));
+        }
+        sql.push(')');
+    }
+
+    for phrase in &filters.phrases {
+        sql.push_str(" AND c.id IN (SELECT rowid FROM chunks_fts WHERE chunks_fts MATCH ?)");
+        params.push(Box::new(format!("\"{}\"", phrase.replace('"', ""))));
+    }
+
+    (sql, params)
+}
+
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
 /// Insert or update an indexed file record.
 pub fn upsert_indexed_file(
     conn: &Connection,
@@ -102,6 +943,58 @@ pub fn delete_file_data(conn: &Connection, file_path: &str) -> Result<(), AppErr
     Ok(())
 }
 
+/// Re-key every indexed file under `from_dir` (relative, forward slashes) to the same
+/// path under `to_dir`, keeping its chunks and vectors. Mentions of the moved files
+/// are dropped for the next mention sync to rescan. Chunks moved into another
+/// book's folder take its id, in their metadata and in the vector index's book
+/// filter. Returns the new paths.
+pub fn move_indexed_files(conn: &Connection, from_dir: &str, to_dir: &str) -> Result<Vec<String>, AppError> {
+    let tx = conn.unchecked_transaction()?;
+    // `chunks` references `indexed_files` without ON UPDATE; check at commit instead
+    tx.pragma_update(None, "defer_foreign_keys", "ON")?;
+    let to_book = book_of_path(to_dir).filter(|book| book_of_path(from_dir) != Some(*book));
+    let has_vectors = table_exists(&tx, "vec_chunks")?;
+    let mut moved = Vec::new();
+    for old_path in get_all_indexed_paths(&tx)? {
+        let Some(rest) = old_path.strip_prefix(from_dir).filter(|r| r.is_empty() || r.starts_with('/')) else {
+            continue;
+        };
+        let new_path = format!("{}{}", to_dir, rest);
+        tx.execute("DELETE FROM indexed_files WHERE file_path = ?1", [&new_path])?;
+        tx.execute("UPDATE indexed_files SET file_path = ?2 WHERE file_path = ?1", rusqlite::params![old_path, new_path])?;
+        tx.execute("UPDATE chunks SET file_path = ?2 WHERE file_path = ?1", rusqlite::params![old_path, new_path])?;
+        tx.execute("DELETE FROM entity_mentions WHERE file_path = ?1", [&old_path])?;
+        tx.execute("DELETE FROM mention_files WHERE file_path = ?1", [&old_path])?;
+        if let Some(book_id) = to_book {
+            tx.execute(
+                "UPDATE chunk_metadata SET book_id = ?2 WHERE chunk_id IN (SELECT id FROM chunks WHERE file_path = ?1)",
+                rusqlite::params![new_path, book_id],
+            )?;
+            if has_vectors {
+                let chunk_ids: Vec<i64> = tx
+                    .prepare("SELECT id FROM chunks WHERE file_path = ?1")?
+                    .query_map([&new_path], |row| row.get(0))?
+                    .collect::<Result<_, _>>()?;
+                for chunk_id in chunk_ids {
+                    tx.execute(
+                        "UPDATE vec_chunks SET book_id = ?2 WHERE chunk_id = ?1",
+                        rusqlite::params![chunk_id, book_id],
+                    )?;
+                }
+            }
+        }
+        moved.push(new_path);
+    }
+    tx.commit()?;
+    Ok(moved)
+}
+
+/// The book a relative path under `books/<id>/` belongs to.
+fn book_of_path(rel_path: &str) -> Option<&str> {
+    let mut parts = rel_path.split('/');
+    (parts.next() == Some("books")).then(|| parts.next()).flatten().filter(|id| !id.is_empty())
+}
+
 /// Delete all chunks for a file (called before re-inserting updated chunks).
 pub fn delete_chunks_for_file(conn: &Connection, file_path: &str) -> Result<(), AppError> {
     // chunk_metadata rows cascade-delete via FK on chunk_id
@@ -109,7 +1002,8 @@ pub fn delete_chunks_for_file(conn: &Connection, file_path: &str) -> Result<(),
     Ok(())
 }
 
-/// Insert a single chunk and return its row id.
+/// Insert a single chunk and return its row id. `embedding` is kept until
+/// `insert_chunk_vector` has added it to the vector index.
 pub fn insert_chunk(
     conn: &Connection,
     file_path: &str,
@@ -121,9 +1015,9 @@ pub fn insert_chunk(
     embedding: &[u8],
 ) -> Result<i64, AppError> {
     conn.execute(
-        "INSERT INTO chunks (file_path, chunk_index, section_heading, content_hash, content_preview, token_count, embedding)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        rusqlite::params![file_path, chunk_index, section_heading, content_hash, content_preview, token_count, embedding],
+        "INSERT INTO chunks (file_path, chunk_index, section_heading, content_hash, content_preview, token_count, embedding, dimensions)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![file_path, chunk_index, section_heading, content_hash, content_preview, token_count, embedding, embedding.len() / 4],
     )?;
     Ok(conn.last_insert_rowid())
 }
@@ -132,19 +1026,82 @@ pub fn insert_chunk(
 pub fn insert_chunk_metadata(
     conn: &Connection,
     chunk_id: i64,
-    book_id: Option<&str>,
-    chapter_id: Option<&str>,
-    entity_type: Option<&str>,
-    entity_name: Option<&str>,
+    metadata: &ChunkMetadata,
+) -> Result<(), AppError> {
+    conn.execute(
+        "INSERT OR REPLACE INTO chunk_metadata
+           (chunk_id, book_id, chapter_id, entity_type, entity_name, pov_character, status)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            chunk_id,
+            metadata.book_id,
+            metadata.chapter_id,
+            metadata.entity_type,
+            metadata.entity_name,
+            metadata.pov_character,
+            metadata.status,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Insert the full text of a chunk into the lexical index.
+pub fn insert_chunk_text(
+    conn: &Connection,
+    chunk_id: i64,
+    section_heading: Option<&str>,
+    content: &str,
 ) -> Result<(), AppError> {
     conn.execute(
-        "INSERT INTO chunk_metadata (chunk_id, book_id, chapter_id, entity_type, entity_name)
-         VALUES (?1, ?2, ?3, ?4, ?5)",
-        rusqlite::params![chunk_id, book_id, chapter_id, entity_type, entity_name],
+        "INSERT INTO chunks_fts (rowid, section_heading, content) VALUES (?1, ?2, ?3)",
+        rusqlite::params![chunk_id, section_heading, content],
+    )?;
+    Ok(())
+}
+
+/// Whether a file's chunks have rows in the lexical index.
+/// Indexes created before the lexical index existed have none.
+pub fn has_chunk_text(conn: &Connection, file_path: &str) -> Result<bool, AppError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(
+            SELECT 1 FROM chunks c JOIN chunks_fts f ON f.rowid = c.id
+            WHERE c.file_path = ?1
+         )",
+        [file_path],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
+/// Record when a file was last modified (RFC 3339), for `SearchFilters.modified_after`.
+pub fn set_file_modified(conn: &Connection, file_path: &str, modified: &str) -> Result<(), AppError> {
+    conn.execute(
+        "UPDATE indexed_files SET modified = ?2 WHERE file_path = ?1",
+        rusqlite::params![file_path, modified],
     )?;
     Ok(())
 }
 
+/// Whether a file was indexed before its modified date and frontmatter metadata were stored.
+pub fn has_stale_metadata(conn: &Connection, file_path: &str) -> Result<bool, AppError> {
+    let stale: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM indexed_files WHERE file_path = ?1 AND modified IS NULL)",
+        [file_path],
+        |row| row.get(0),
+    )?;
+    Ok(stale)
+}
+
+/// Whether any chunk of a file was stored without an embedding (lexical-only indexing).
+pub fn has_missing_embeddings(conn: &Connection, file_path: &str) -> Result<bool, AppError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM chunks WHERE file_path = ?1 AND dimensions = 0)",
+        [file_path],
+        |row| row.get(0),
+    )?;
+    Ok(exists)
+}
+
 /// Log an embedding API call for cost tracking.
 pub fn log_embedding_call(
     conn: &Connection,
@@ -206,33 +1163,144 @@ pub fn get_all_indexed_paths(conn: &Connection) -> Result<std::collections::Hash
     Ok(set)
 }
 
-/// Get all chunk hashes for a file, keyed by chunk_index.
-pub fn get_chunk_hashes(conn: &Connection, file_path: &str) -> Result<std::collections::HashMap<u32, String>, AppError> {
-    let mut stmt = conn.prepare("SELECT chunk_index, content_hash FROM chunks WHERE file_path = ?1")?;
+/// Get all chunk hashes and stored embeddings for a file, keyed by chunk_index.
+/// Embeddings are as `get_chunk_embeddings` returns them; empty if there is none.
+pub fn get_chunk_hashes(
+    conn: &Connection,
+    file_path: &str,
+) -> Result<std::collections::HashMap<u32, (String, Vec<u8>)>, AppError> {
+    let mut stmt = conn.prepare("SELECT chunk_index, content_hash, id FROM chunks WHERE file_path = ?1")?;
+    let rows = stmt.query_map([file_path], |row| {
+        Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+    })?;
+    let rows: Vec<(u32, String, i64)> = rows.collect::<Result<_, _>>()?;
+    let ids: Vec<i64> = rows.iter().map(|(_, _, id)| *id).collect();
+    let mut embeddings = get_chunk_embeddings(conn, &ids)?;
+    Ok(rows
+        .into_iter()
+        .map(|(idx, hash, id)| (idx, (hash, embeddings.remove(&id).unwrap_or_default())))
+        .collect())
+}
+
+/// Get all chunk row ids for a file, keyed by chunk_index.
+pub fn get_chunk_ids(conn: &Connection, file_path: &str) -> Result<std::collections::HashMap<u32, i64>, AppError> {
+    let mut stmt = conn.prepare("SELECT chunk_index, id FROM chunks WHERE file_path = ?1")?;
     let mut map = std::collections::HashMap::new();
     let rows = stmt.query_map([file_path], |row| {
-        Ok((row.get::<_, u32>(0)?, row.get::<_, String>(1)?))
+        Ok((row.get::<_, u32>(0)?, row.get::<_, i64>(1)?))
     })?;
     for row in rows {
-        let (idx, hash) = row?;
-        map.insert(idx, hash);
+        let (idx, id) = row?;
+        map.insert(idx, id);
     }
     Ok(map)
 }
 
+/// Run a full-text query against the lexical index, best matches first.
+/// Returns each chunk with its BM25 score (lower is better, as reported by SQLite).
+/// The embedding field of the returned rows is left empty.
+pub fn lexical_search(
+    conn: &Connection,
+    fts_query: &str,
+    limit: u32,
+    filters: &SearchFilters,
+) -> Result<Vec<(ChunkRow, f64)>, AppError> {
+    let (where_sql, filter_params) = filter_clause(filters);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT c.id, c.file_path, c.chunk_index, c.section_heading, c.content_preview,
+                c.token_count, m.book_id, m.entity_type, m.entity_name, bm25(chunks_fts) AS score
+         FROM chunks_fts
+         JOIN chunks c ON c.id = chunks_fts.rowid
+         LEFT JOIN chunk_metadata m ON m.chunk_id = c.id
+         WHERE chunks_fts MATCH ?{}
+         ORDER BY score
+         LIMIT ?",
+        where_sql
+    ))?;
+    let mut params: Vec<Box<dyn rusqlite::ToSql>> = vec![Box::new(fts_query.to_string())];
+    params.extend(filter_params);
+    params.push(Box::new(limit));
+
+    let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        Ok((
+            ChunkRow {
+                id: row.get(0)?,
+                file_path: row.get(1)?,
+                chunk_index: row.get(2)?,
+                section_heading: row.get(3)?,
+                content_preview: row.get(4)?,
+                token_count: row.get(5)?,
+                embedding: Vec::new(),
+                book_id: row.get(6)?,
+                entity_type: row.get(7)?,
+                entity_name: row.get(8)?,
+            },
+            row.get::<_, f64>(9)?,
+        ))
+    })?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
 /// Retrieve all chunk embeddings and metadata for brute-force similarity search.
 /// Returns (chunk_id, file_path, chunk_index, section_heading, content_preview, token_count, embedding_bytes, book_id, entity_type, entity_name).
 pub fn get_all_chunks_for_search(
     conn: &Connection,
+    filters: &SearchFilters,
 ) -> Result<Vec<ChunkRow>, AppError> {
+    let (where_sql, params) = filter_clause(filters);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT c.id, c.file_path, c.chunk_index, c.section_heading, c.content_preview,
+                c.token_count, c.embedding, m.book_id, m.entity_type, m.entity_name
+         FROM chunks c
+         LEFT JOIN chunk_metadata m ON m.chunk_id = c.id
+         WHERE 1 = 1{}",
+        where_sql
+    ))?;
+
+    let rows = stmt.query_map(rusqlite::params_from_iter(params.iter()), |row| {
+        Ok(ChunkRow {
+            id: row.get(0)?,
+            file_path: row.get(1)?,
+            chunk_index: row.get(2)?,
+            section_heading: row.get(3)?,
+            content_preview: row.get(4)?,
+            token_count: row.get(5)?,
+            embedding: row.get(6)?,
+            book_id: row.get(7)?,
+            entity_type: row.get(8)?,
+            entity_name: row.get(9)?,
+        })
+    })?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    // Used when the vector index lookup failed, so it may not be readable either
+    if let Err(e) = fill_from_index(conn, &mut results) {
+        eprintln!("Warning: failed to read vectors from the vector index: {}", e);
+    }
+    Ok(results)
+}
+
+/// All chunks of one file with their embeddings, in order.
+/// Matches the path with either slash direction.
+pub fn get_file_chunks(conn: &Connection, file_path: &str) -> Result<Vec<ChunkRow>, AppError> {
     let mut stmt = conn.prepare(
         "SELECT c.id, c.file_path, c.chunk_index, c.section_heading, c.content_preview,
                 c.token_count, c.embedding, m.book_id, m.entity_type, m.entity_name
          FROM chunks c
-         LEFT JOIN chunk_metadata m ON m.chunk_id = c.id"
+         LEFT JOIN chunk_metadata m ON m.chunk_id = c.id
+         WHERE replace(c.file_path, '\\', '/') = ?1
+         ORDER BY c.chunk_index"
     )?;
 
-    let rows = stmt.query_map([], |row| {
+    let rows = stmt.query_map([file_path.replace('\\', "/")], |row| {
         Ok(ChunkRow {
             id: row.get(0)?,
             file_path: row.get(1)?,
@@ -251,6 +1319,7 @@ pub fn get_all_chunks_for_search(
     for row in rows {
         results.push(row?);
     }
+    fill_from_index(conn, &mut results)?;
     Ok(results)
 }
 
@@ -269,13 +1338,366 @@ pub struct ChunkRow {
     pub entity_name: Option<String>,
 }
 
+// ─── Integrity ───
+
+/// Rows whose chunk or file no longer exists, as (table, condition selecting them).
+/// The vector table is only checked when it exists.
+const ORPHAN_CHECKS: &[(&str, &str)] = &[
+    ("chunks", "file_path NOT IN (SELECT file_path FROM indexed_files)"),
+    ("chunk_metadata", "chunk_id NOT IN (SELECT id FROM chunks)"),
+    ("chunks_fts", "rowid NOT IN (SELECT id FROM chunks)"),
+    ("reembed_staging", "chunk_id NOT IN (SELECT id FROM chunks)"),
+    ("vec_chunks", "chunk_id NOT IN (SELECT id FROM chunks)"),
+    ("entity_mentions", "file_path NOT IN (SELECT file_path FROM indexed_files)"),
+    ("mention_files", "file_path NOT IN (SELECT file_path FROM indexed_files)"),
+    ("entities", "source_path NOT IN (SELECT file_path FROM indexed_files)"),
+];
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool, AppError> {
+    let count: u32 = conn.query_row(
+        "SELECT COUNT(*) FROM sqlite_master WHERE name = ?1",
+        [table],
+        |row| row.get(0),
+    )?;
+    Ok(count > 0)
+}
+
+/// Number of orphaned rows across the index tables.
+pub fn count_orphans(conn: &Connection) -> Result<u32, AppError> {
+    let mut total = 0;
+    for (table, condition) in ORPHAN_CHECKS {
+        if table_exists(conn, table)? {
+            let count: u32 = conn.query_row(
+                &format!("SELECT COUNT(*) FROM {} WHERE {}", table, condition),
+                [],
+                |row| row.get(0),
+            )?;
+            total += count;
+        }
+    }
+    Ok(total)
+}
+
+/// Delete orphaned rows, chunks first so their dependants are caught too.
+pub fn delete_orphans(conn: &Connection) -> Result<(), AppError> {
+    for (table, condition) in ORPHAN_CHECKS {
+        if table_exists(conn, table)? {
+            conn.execute(&format!("DELETE FROM {} WHERE {}", table, condition), [])?;
+        }
+    }
+    Ok(())
+}
+
+/// Every indexed file with its recorded content hash, and whether its chunk rows
+/// match the recorded chunk count.
+pub fn get_indexed_file_hashes(conn: &Connection) -> Result<Vec<(String, String, bool)>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT f.file_path, f.content_hash,
+                f.chunk_count = (SELECT COUNT(*) FROM chunks c WHERE c.file_path = f.file_path)
+         FROM indexed_files f
+         ORDER BY f.file_path"
+    )?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, bool>(2)?))
+    })?;
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
+/// Forget a file's content hash so its next index rewrites its chunks.
+/// Unchanged chunks keep their embeddings.
+pub fn invalidate_file_hash(conn: &Connection, file_path: &str) -> Result<(), AppError> {
+    conn.execute("UPDATE indexed_files SET content_hash = '' WHERE file_path = ?1", [file_path])?;
+    Ok(())
+}
+
+/// Files with embeddings of a size other than `dimensions`, and how many such chunks.
+pub fn get_wrong_size_embeddings(conn: &Connection, dimensions: usize) -> Result<(Vec<String>, u32), AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT file_path, COUNT(*) FROM chunks
+         WHERE dimensions > 0 AND dimensions != ?1
+         GROUP BY file_path
+         ORDER BY file_path"
+    )?;
+    let rows = stmt.query_map([dimensions as i64], |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
+    })?;
+    let mut files = Vec::new();
+    let mut chunks = 0;
+    for row in rows {
+        let (file, count) = row?;
+        files.push(file);
+        chunks += count;
+    }
+    Ok((files, chunks))
+}
+
+/// Drop embeddings of a size other than `dimensions`; their chunks are re-embedded
+/// the next time their file is indexed.
+pub fn clear_wrong_size_embeddings(conn: &Connection, dimensions: usize) -> Result<(), AppError> {
+    if table_exists(conn, "vec_chunks")? {
+        conn.execute(
+            "DELETE FROM vec_chunks WHERE chunk_id IN
+               (SELECT id FROM chunks WHERE dimensions > 0 AND dimensions != ?1)",
+            [dimensions as i64],
+        )?;
+    }
+    conn.execute(
+        "UPDATE chunks SET embedding = x'', dimensions = 0 WHERE dimensions > 0 AND dimensions != ?1",
+        [dimensions as i64],
+    )?;
+    Ok(())
+}
+
+/// Chunks with an embedding of the vector index's size but no row in it.
+pub fn count_missing_vectors(conn: &Connection) -> Result<u32, AppError> {
+    let Some((dimensions, _)) = get_vector_index_layout(conn)? else {
+        return Ok(0);
+    };
+    if !table_exists(conn, "vec_chunks")? {
+        return Ok(0);
+    }
+    let count = conn.query_row(
+        "SELECT COUNT(*) FROM chunks
+         WHERE dimensions = ?1 AND id NOT IN (SELECT chunk_id FROM vec_chunks)",
+        [dimensions as i64],
+        |row| row.get(0),
+    )?;
+    Ok(count)
+}
+
+/// Rebuild the vector index at the size of the stored embeddings. Returns the files
+/// with chunks whose vector was lost and needs embedding again.
+pub fn rebuild_vector_index_from_chunks(conn: &Connection) -> Result<Vec<String>, AppError> {
+    match get_embedding_dimensions(conn)? {
+        Some(dimensions) => rebuild_vector_index(conn, dimensions),
+        None => Ok(Vec::new()),
+    }
+}
+
+// ─── Entity mentions ───
+
+/// Every indexed file with its content hash.
+pub fn get_indexed_files(conn: &Connection) -> Result<Vec<(String, String)>, AppError> {
+    let mut stmt = conn.prepare("SELECT file_path, content_hash FROM indexed_files ORDER BY file_path")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
+/// Content hash each entity was last read at, keyed by its source file.
+pub fn get_entity_source_hashes(conn: &Connection) -> Result<std::collections::HashMap<String, String>, AppError> {
+    let mut stmt = conn.prepare("SELECT source_path, source_hash FROM entities")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    let mut map = std::collections::HashMap::new();
+    for row in rows {
+        let (path, hash) = row?;
+        map.insert(path, hash);
+    }
+    Ok(map)
+}
+
+/// Insert or replace the entity defined by `entity.source_path`.
+pub fn upsert_entity(conn: &Connection, entity: &KnownEntity, source_hash: &str) -> Result<(), AppError> {
+    conn.execute("DELETE FROM entities WHERE source_path = ?1", [&entity.source_path])?;
+    conn.execute(
+        "INSERT OR REPLACE INTO entities (entity_type, slug, name, aliases, source_path, source_hash)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        rusqlite::params![
+            entity.entity_type,
+            entity.slug,
+            entity.name,
+            serde_json::to_string(&entity.aliases).unwrap_or_else(|_| "[]".into()),
+            entity.source_path,
+            source_hash,
+        ],
+    )?;
+    Ok(())
+}
+
+/// All known entities, by type and slug.
+pub fn get_entities(conn: &Connection) -> Result<Vec<KnownEntity>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT entity_type, slug, name, aliases, source_path FROM entities ORDER BY entity_type, slug"
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let aliases: String = row.get(3)?;
+        Ok(KnownEntity {
+            entity_type: row.get(0)?,
+            slug: row.get(1)?,
+            name: row.get(2)?,
+            aliases: serde_json::from_str(&aliases).unwrap_or_default(),
+            source_path: row.get(4)?,
+        })
+    })?;
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
+/// Indexed manuscript files whose mentions are missing or were found with
+/// other content or another set of entities.
+pub fn get_stale_mention_files(
+    conn: &Connection,
+    file_types: &[&str],
+    entity_set: &str,
+) -> Result<Vec<(String, String)>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT f.file_path, f.content_hash FROM indexed_files f
+         LEFT JOIN mention_files m ON m.file_path = f.file_path
+         WHERE f.file_type IN ({})
+           AND (m.file_path IS NULL OR m.content_hash != f.content_hash OR m.entity_set != ?)
+         ORDER BY f.file_path",
+        placeholders(file_types.len())
+    ))?;
+    let mut params: Vec<&dyn rusqlite::ToSql> = file_types.iter().map(|t| t as &dyn rusqlite::ToSql).collect();
+    params.push(&entity_set);
+    let rows = stmt.query_map(params.as_slice(), |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
+/// One mention found in a manuscript file.
+pub struct MentionRow<'a> {
+    pub entity_type: &'a str,
+    pub entity_slug: &'a str,
+    pub matched_text: &'a str,
+    pub char_offset: usize,
+}
+
+/// Replace a file's mentions and record what they were found with.
+pub fn replace_file_mentions(
+    conn: &Connection,
+    file_path: &str,
+    metadata: &ChunkMetadata,
+    scene_id: Option<&str>,
+    mentions: &[MentionRow],
+    content_hash: &str,
+    entity_set: &str,
+) -> Result<(), AppError> {
+    conn.execute("DELETE FROM entity_mentions WHERE file_path = ?1", [file_path])?;
+    let mut stmt = conn.prepare(
+        "INSERT INTO entity_mentions
+           (file_path, entity_type, entity_slug, matched_text, char_offset, book_id, chapter_id, scene_id, file_type)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"
+    )?;
+    for mention in mentions {
+        stmt.execute(rusqlite::params![
+            file_path,
+            mention.entity_type,
+            mention.entity_slug,
+            mention.matched_text,
+            mention.char_offset as i64,
+            metadata.book_id,
+            metadata.chapter_id,
+            scene_id,
+            metadata.file_type,
+        ])?;
+    }
+    conn.execute(
+        "INSERT OR REPLACE INTO mention_files (file_path, content_hash, entity_set) VALUES (?1, ?2, ?3)",
+        rusqlite::params![file_path, content_hash, entity_set],
+    )?;
+    Ok(())
+}
+
+/// Book, chapter, scene and file; `mentions::entity_appearances` puts the chapters
+/// and scenes in reading order afterwards.
+const STORY_ORDER: &str = "book_id, chapter_id, scene_id, file_path";
+
+/// Files mentioning an entity, in story order, optionally of one file type.
+pub fn get_entity_appearances(
+    conn: &Connection,
+    entity_type: &str,
+    slug: &str,
+    file_type: Option<&str>,
+) -> Result<Vec<EntityAppearance>, AppError> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT file_path, file_type, book_id, chapter_id, scene_id, COUNT(*), MIN(char_offset)
+         FROM entity_mentions
+         WHERE entity_type = ?1 AND entity_slug = ?2 AND (?3 IS NULL OR file_type = ?3)
+         GROUP BY file_path
+         ORDER BY {}",
+        STORY_ORDER
+    ))?;
+    let rows = stmt.query_map(rusqlite::params![entity_type, slug, file_type], |row| {
+        Ok(EntityAppearance {
+            file_path: row.get(0)?,
+            file_type: row.get(1)?,
+            book_id: row.get(2)?,
+            chapter_id: row.get(3)?,
+            scene_id: row.get(4)?,
+            mention_count: row.get(5)?,
+            first_offset: row.get(6)?,
+        })
+    })?;
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
+/// Other entities mentioned in the same files as an entity, with the number of
+/// files they share, most shared first.
+pub fn get_entity_cooccurrences(
+    conn: &Connection,
+    entity_type: &str,
+    slug: &str,
+    file_type: Option<&str>,
+) -> Result<Vec<EntityCooccurrence>, AppError> {
+    let mut stmt = conn.prepare(
+        "SELECT o.entity_type, o.entity_slug, COALESCE(e.name, o.entity_slug), COUNT(DISTINCT o.file_path) AS shared
+         FROM entity_mentions o
+         LEFT JOIN entities e ON e.entity_type = o.entity_type AND e.slug = o.entity_slug
+         WHERE o.file_path IN (
+                 SELECT file_path FROM entity_mentions
+                 WHERE entity_type = ?1 AND entity_slug = ?2 AND (?3 IS NULL OR file_type = ?3))
+           AND NOT (o.entity_type = ?1 AND o.entity_slug = ?2)
+         GROUP BY o.entity_type, o.entity_slug
+         ORDER BY shared DESC, o.entity_type, o.entity_slug"
+    )?;
+    let rows = stmt.query_map(rusqlite::params![entity_type, slug, file_type], |row| {
+        Ok(EntityCooccurrence {
+            entity_type: row.get(0)?,
+            slug: row.get(1)?,
+            name: row.get(2)?,
+            shared_files: row.get(3)?,
+        })
+    })?;
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
+}
+
 /// Clear the entire index (all tables).
 pub fn clear_all(conn: &Connection) -> Result<(), AppError> {
     conn.execute_batch(
         "DELETE FROM chunk_metadata;
          DELETE FROM chunks;
+         DELETE FROM chunks_fts;
          DELETE FROM indexed_files;
          DELETE FROM embedding_log;
+         DELETE FROM reembed_staging;
+         DELETE FROM entity_mentions;
+         DELETE FROM mention_files;
+         DELETE FROM entities;
+         DELETE FROM index_meta WHERE key != 'schema_version';
+         DROP TRIGGER IF EXISTS vec_chunks_delete;
+         DROP TABLE IF EXISTS vec_chunks;
          VACUUM;"
     )?;
     Ok(())
@@ -1,5 +1,7 @@
+use std::collections::HashMap;
 use async_trait::async_trait;
 use crate::error::AppError;
+use super::{EmbeddingConfig, EmbeddingSignature};
 
 /// Provider-agnostic embedding client trait.
 /// Allows swapping Voyage for another provider without touching calling code.
@@ -11,10 +13,16 @@ pub trait EmbeddingClient: Send + Sync {
     /// Embed a single query. May use a different input_type for queries vs documents.
     async fn embed_query(&self, query: &str) -> Result<Vec<f32>, AppError>;
 
-    /// Returns the dimensionality of the embedding vectors.
-    #[allow(dead_code)]
+    /// Returns the dimensionality of the embedding vectors, or 0 when the
+    /// model's default size is used and only known once a vector comes back.
     fn dimensions(&self) -> usize;
 
+    /// Provider name as used in `VectorSearchConfig.provider`.
+    fn provider(&self) -> &str;
+
+    /// Model name as used in `VectorSearchConfig.embedding_model`.
+    fn model(&self) -> &str;
+
     /// Returns the cost per million tokens for logging.
     fn cost_per_million_tokens(&self) -> f64;
 }
@@ -134,7 +142,15 @@ impl EmbeddingClient for VoyageClient {
     }
 
     fn dimensions(&self) -> usize {
-        1024
+        0
+    }
+
+    fn provider(&self) -> &str {
+        "voyage"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
     }
 
     fn cost_per_million_tokens(&self) -> f64 {
@@ -142,7 +158,305 @@ impl EmbeddingClient for VoyageClient {
     }
 }
 
-/// Serialize a Vec<f32> embedding into bytes (little-endian f32s) for SQLite BLOB storage.
+/// Client for any server speaking the OpenAI `/v1/embeddings` protocol —
+/// OpenAI itself, Ollama, LM Studio, text-embeddings-inference, etc.
+pub struct OpenAiCompatibleClient {
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    dimensions: usize,
+    http_client: reqwest::Client,
+}
+
+impl OpenAiCompatibleClient {
+    /// `base_url` includes the version segment, e.g. `http://localhost:11434/v1`.
+    /// `dimensions` of 0 leaves the vector size to the model.
+    pub fn new(base_url: String, api_key: Option<String>, model: String, dimensions: usize) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+            model,
+            dimensions,
+            http_client: reqwest::Client::new(),
+        }
+    }
+
+    async fn request(&self, input: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        let mut body = serde_json::json!({
+            "model": self.model,
+            "input": input,
+            "encoding_format": "float"
+        });
+        if self.dimensions > 0 {
+            body["dimensions"] = serde_json::json!(self.dimensions);
+        }
+
+        let mut req = self.http_client
+            .post(format!("{}/embeddings", self.base_url))
+            .header("Content-Type", "application/json")
+            .json(&body);
+        if let Some(key) = &self.api_key {
+            req = req.header("Authorization", format!("Bearer {}", key));
+        }
+
+        let resp = req
+            .send()
+            .await
+            .map_err(|e| AppError::Embedding(format!("Embedding request to {} failed: {}", self.base_url, e)))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let error_body = resp.text().await.unwrap_or_default();
+            return Err(AppError::Embedding(format!(
+                "Embedding API error ({}): {}",
+                status, error_body
+            )));
+        }
+
+        let json: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| AppError::Embedding(format!("Failed to parse embedding response: {}", e)))?;
+
+        parse_openai_embeddings(&json, input.len())
+    }
+}
+
+/// Extract vectors from an OpenAI-style `{"data": [{"index", "embedding"}]}` body,
+/// ordered by `index` since servers are not required to preserve input order.
+fn parse_openai_embeddings(json: &serde_json::Value, expected: usize) -> Result<Vec<Vec<f32>>, AppError> {
+    let data = json
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or_else(|| AppError::Embedding("Missing 'data' array in embedding response".into()))?;
+
+    let mut indexed = Vec::with_capacity(data.len());
+    for (pos, item) in data.iter().enumerate() {
+        let index = item.get("index").and_then(|i| i.as_u64()).map(|i| i as usize).unwrap_or(pos);
+        let embedding = item
+            .get("embedding")
+            .and_then(|e| e.as_array())
+            .ok_or_else(|| AppError::Embedding("Missing 'embedding' in response item".into()))?
+            .iter()
+            .filter_map(|v| v.as_f64().map(|f| f as f32))
+            .collect::<Vec<f32>>();
+        indexed.push((index, embedding));
+    }
+    indexed.sort_by_key(|(i, _)| *i);
+
+    if indexed.len() != expected {
+        return Err(AppError::Embedding(format!(
+            "Embedding response returned {} vectors for {} inputs",
+            indexed.len(), expected
+        )));
+    }
+    Ok(indexed.into_iter().map(|(_, e)| e).collect())
+}
+
+#[async_trait]
+impl EmbeddingClient for OpenAiCompatibleClient {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.request(texts).await
+    }
+
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>, AppError> {
+        self.request(&[query.to_string()])
+            .await?
+            .pop()
+            .ok_or_else(|| AppError::Embedding("Missing embedding in response".into()))
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn provider(&self) -> &str {
+        "openai"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Self-hosted servers are free; list prices apply only to api.openai.com.
+    fn cost_per_million_tokens(&self) -> f64 {
+        if !self.base_url.contains("api.openai.com") {
+            return 0.0;
+        }
+        match self.model.as_str() {
+            "text-embedding-3-small" => 0.02,
+            "text-embedding-3-large" => 0.13,
+            "text-embedding-ada-002" => 0.10,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Model name that selects the built-in offline embedder in `VectorSearchConfig.embedding_model`.
+pub const LOCAL_MODEL: &str = "local-ngram";
+
+const LOCAL_DIMENSIONS: usize = 512;
+
+/// Whether `model` selects the built-in offline embedder.
+pub fn is_local_model(model: &str) -> bool {
+    model == LOCAL_MODEL
+}
+
+/// Offline embedding client — no network, no key, CPU only.
+///
+/// Projects text into a fixed-size vector by feature hashing: lower-cased words,
+/// word bigrams and character trigrams are hashed (FNV-1a) into signed buckets,
+/// weighted by sublinear term frequency and L2-normalised. Deterministic across
+/// runs and platforms, so stored embeddings stay comparable. Character trigrams
+/// let invented names and inflections match partially.
+pub struct LocalEmbeddingClient {
+    dimensions: usize,
+}
+
+impl LocalEmbeddingClient {
+    pub fn new() -> Self {
+        Self { dimensions: LOCAL_DIMENSIONS }
+    }
+
+    fn embed(&self, text: &str) -> Vec<f32> {
+        let mut counts: HashMap<u64, f32> = HashMap::new();
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .map(|w| w.trim_matches('\'').to_lowercase())
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        for (i, word) in words.iter().enumerate() {
+            *counts.entry(fnv1a(&["w:", word])).or_default() += 1.0;
+            if let Some(next) = words.get(i + 1) {
+                *counts.entry(fnv1a(&["b:", word, " ", next])).or_default() += 1.0;
+            }
+            // Character trigrams over the padded word: "^el", "ele", ..., "na$"
+            let padded: Vec<char> = format!("^{}$", word).chars().collect();
+            for tri in padded.windows(3) {
+                let tri: String = tri.iter().collect();
+                *counts.entry(fnv1a(&["c:", &tri])).or_default() += 0.5;
+            }
+        }
+
+        let mut vector = vec![0.0f32; self.dimensions];
+        for (hash, tf) in counts {
+            let bucket = (hash % self.dimensions as u64) as usize;
+            let sign = if (hash >> 63) == 0 { 1.0 } else { -1.0 };
+            vector[bucket] += sign * (1.0 + tf.ln());
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for v in &mut vector {
+                *v /= norm;
+            }
+        }
+        vector
+    }
+}
+
+impl Default for LocalEmbeddingClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 64-bit FNV-1a over the concatenated parts.
+fn fnv1a(parts: &[&str]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for part in parts {
+        for byte in part.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    }
+    hash
+}
+
+#[async_trait]
+impl EmbeddingClient for LocalEmbeddingClient {
+    async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+        Ok(texts.iter().map(|t| self.embed(t)).collect())
+    }
+
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>, AppError> {
+        Ok(self.embed(query))
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn provider(&self) -> &str {
+        "local"
+    }
+
+    fn model(&self) -> &str {
+        LOCAL_MODEL
+    }
+
+    fn cost_per_million_tokens(&self) -> f64 {
+        0.0
+    }
+}
+
+// ─── Factory ───
+
+/// Build the embedding client selected by `EmbeddingConfig.provider`.
+///
+/// Returns `Ok(None)` when the provider needs a key that isn't set — callers
+/// then fall back to lexical-only search and indexing.
+pub fn client_from_config(config: &EmbeddingConfig) -> Result<Option<Box<dyn EmbeddingClient>>, AppError> {
+    if is_local_model(&config.model) {
+        return Ok(Some(Box::new(LocalEmbeddingClient::new())));
+    }
+    let api_key = config.api_key.clone();
+    match config.provider.as_str() {
+        "local" => Ok(Some(Box::new(LocalEmbeddingClient::new()))),
+        "voyage" | "" => Ok(api_key.map(|key| {
+            Box::new(VoyageClient::new(key, config.model.clone())) as Box<dyn EmbeddingClient>
+        })),
+        "openai" => {
+            let base_url = if config.base_url.is_empty() {
+                "https://api.openai.com/v1".to_string()
+            } else {
+                config.base_url.clone()
+            };
+            // OpenAI proper needs a key; self-hosted servers usually don't
+            if api_key.is_none() && base_url.contains("api.openai.com") {
+                return Ok(None);
+            }
+            Ok(Some(Box::new(OpenAiCompatibleClient::new(
+                base_url,
+                api_key,
+                config.model.clone(),
+                config.dimensions as usize,
+            ))))
+        }
+        other => Err(AppError::Config(format!("Unknown embedding provider: {}", other))),
+    }
+}
+
+/// Build a client for the model recorded in an index signature, reusing the
+/// key and endpoint from `config`. Used to keep searching an index whose
+/// embeddings were made with a model other than the configured one.
+pub fn client_for_signature(
+    config: &EmbeddingConfig,
+    sig: &EmbeddingSignature,
+) -> Result<Option<Box<dyn EmbeddingClient>>, AppError> {
+    let mut config = config.clone();
+    if config.provider != sig.provider {
+        config.dimensions = 0;
+    }
+    config.provider = sig.provider.clone();
+    config.model = sig.model.clone();
+    client_from_config(&config)
+}
+
 pub fn embedding_to_bytes(embedding: &[f32]) -> Vec<u8> {
     let mut bytes = Vec::with_capacity(embedding.len() * 4);
     for &val in embedding {
@@ -188,6 +502,7 @@ mod tests {
     use super::*;
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_embedding_roundtrip() {
         let original = vec![1.0f32, -0.5, 0.25, 3.14];
         let bytes = embedding_to_bytes(&original);
@@ -209,4 +524,57 @@ mod tests {
         let sim = cosine_similarity(&a, &b);
         assert!(sim.abs() < 1e-6);
     }
+
+    #[test]
+    fn test_local_embedding_is_deterministic_and_normalised() {
+        let client = LocalEmbeddingClient::new();
+        let a = client.embed("Elena crossed the broken bridge at dawn.");
+        let b = client.embed("Elena crossed the broken bridge at dawn.");
+        assert_eq!(a, b);
+        assert_eq!(a.len(), client.dimensions());
+        let norm: f32 = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_local_embedding_ranks_related_text_higher() {
+        let client = LocalEmbeddingClient::new();
+        let query = client.embed("Okafor at the tavern");
+        let related = client.embed("Captain Okafor drank alone in the tavern by the docks.");
+        let unrelated = client.embed("The magic system draws power from moonlight.");
+        assert!(cosine_similarity(&query, &related) > cosine_similarity(&query, &unrelated));
+    }
+
+    #[test]
+    fn test_parse_openai_embeddings_orders_by_index() {
+        let json = serde_json::json!({
+            "data": [
+                { "index": 1, "embedding": [0.0, 1.0] },
+                { "index": 0, "embedding": [1.0, 0.0] }
+            ]
+        });
+        let vectors = parse_openai_embeddings(&json, 2).unwrap();
+        assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert!(parse_openai_embeddings(&json, 3).is_err());
+    }
+
+    #[test]
+    fn test_client_from_config() {
+        let mut config = EmbeddingConfig { provider: "voyage".into(), model: "voyage-4".into(), ..Default::default() };
+        assert!(client_from_config(&config).unwrap().is_none(), "voyage without a key");
+
+        config.provider = "openai".into();
+        assert!(client_from_config(&config).unwrap().is_none(), "api.openai.com without a key");
+
+        config.base_url = "http://localhost:11434/v1".into();
+        config.model = "nomic-embed-text".into();
+        let client = client_from_config(&config).unwrap().expect("self-hosted needs no key");
+        assert_eq!(client.cost_per_million_tokens(), 0.0);
+
+        config.provider = "local".into();
+        assert!(client_from_config(&config).unwrap().is_some());
+
+        config.provider = "nope".into();
+        assert!(client_from_config(&config).is_err());
+    }
 }
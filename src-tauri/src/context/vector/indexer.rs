@@ -1,13 +1,17 @@
 use std::path::PathBuf;
 use std::collections::HashMap;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::error::AppError;
+use crate::project_path::ProjectPath;
 use super::db;
 use super::chunker;
 use super::embeddings::{self, EmbeddingClient};
+use super::mentions;
+use super::{EmbeddingConfig, EmbeddingSignature, ReembedProgress, ReindexEstimate};
 
 const MAX_BATCH_SIZE: usize = 128;
 
@@ -20,17 +24,81 @@ pub static PENDING_FILES: Lazy<Mutex<HashMap<String, Instant>>> =
 pub static DELETED_FILES: Lazy<Mutex<Vec<String>>> =
     Lazy::new(|| Mutex::new(Vec::new()));
 
-/// Whether a full re-index is currently in progress.
+/// Whether a full re-index or a background indexer pass is currently in progress.
 pub static IS_INDEXING: Lazy<Mutex<bool>> =
     Lazy::new(|| Mutex::new(false));
 
-/// Index a single file: chunk it, embed changed chunks, store in SQLite.
-pub async fn index_file(
-    project_dir: &PathBuf,
+/// Set by `cancel_reindex`; checked before each embedding request of a re-index.
+pub static CANCEL_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Holds `IS_INDEXING` for the length of a re-index or background pass. Dropping it — including while
+/// unwinding from a panic — clears the flag.
+pub struct IndexingGuard(());
+
+impl IndexingGuard {
+    /// Take the indexing flag, or None if a re-index is already running.
+    pub fn acquire() -> Option<Self> {
+        let mut flag = IS_INDEXING.lock().unwrap_or_else(|e| e.into_inner());
+        if *flag {
+            return None;
+        }
+        *flag = true;
+        CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+        Some(IndexingGuard(()))
+    }
+}
+
+impl Drop for IndexingGuard {
+    fn drop(&mut self) {
+        *IS_INDEXING.lock().unwrap_or_else(|e| e.into_inner()) = false;
+        CANCEL_REQUESTED.store(false, Ordering::SeqCst);
+    }
+}
+
+/// What indexing one file will do, worked out without writing anything.
+enum FilePlan {
+    /// Indexed and fully embedded already
+    Unchanged,
+    /// Chunks unchanged, but indexed before the lexical text or filter metadata existed
+    Backfill { content: String, text: bool, metadata: bool },
+    /// Chunks to (re)write
+    Update {
+        file_hash: String,
+        chunks: Vec<chunker::Chunk>,
+        /// Vectors of unchanged chunks, kept from the previous index, by position
+        reused: HashMap<usize, Vec<f32>>,
+        /// Positions of chunks that need a new vector
+        to_embed: Vec<usize>,
+    },
+}
+
+struct PlannedFile {
+    rel_path: String,
+    full_path: PathBuf,
+    plan: FilePlan,
+}
+
+impl PlannedFile {
+    fn texts_to_embed(&self) -> Vec<(usize, String)> {
+        match &self.plan {
+            FilePlan::Update { chunks, to_embed, .. } => {
+                to_embed.iter().map(|&i| (i, chunks[i].content.clone())).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Work out how to bring a file's index entries up to date.
+/// `embed` is whether vectors can be made; `staging` whether they go to a pending re-embed.
+fn plan_file(
+    conn: &rusqlite::Connection,
+    project_dir: &std::path::Path,
     rel_path: &str,
-    client: &dyn EmbeddingClient,
-) -> Result<IndexFileResult, AppError> {
-    let full_path = project_dir.join(rel_path.replace('/', "\\"));
+    embed: bool,
+    staging: bool,
+) -> Result<PlannedFile, AppError> {
+    let full_path = ProjectPath::new(rel_path)?.to_absolute(project_dir);
     if !full_path.exists() {
         return Err(AppError::FileNotFound(format!("File not found: {}", rel_path)));
     }
@@ -39,85 +107,111 @@ pub async fn index_file(
         .map_err(|e| AppError::IndexError(format!("Failed to read {}: {}", rel_path, e)))?;
 
     let file_hash = chunker::sha256(&content);
+    let planned = |plan| PlannedFile { rel_path: rel_path.to_string(), full_path: full_path.clone(), plan };
 
-    let conn = db::open_index(project_dir)?;
-    db::init_schema(&conn)?;
-
-    // Check if file is unchanged
-    if let Some(existing_hash) = db::get_file_hash(&conn, rel_path)? {
-        if existing_hash == file_hash {
-            return Ok(IndexFileResult {
-                chunks_total: 0,
-                chunks_embedded: 0,
-                tokens_used: 0,
-            });
+    // Check if file is unchanged (and fully embedded, if we can embed)
+    if let Some(existing_hash) = db::get_file_hash(conn, rel_path)? {
+        let needs_embeddings = embed && !staging && db::has_missing_embeddings(conn, rel_path)?;
+        if existing_hash == file_hash && !needs_embeddings {
+            let text = !db::has_chunk_text(conn, rel_path)?;
+            let metadata = db::has_stale_metadata(conn, rel_path)?;
+            return Ok(planned(if text || metadata {
+                FilePlan::Backfill { content, text, metadata }
+            } else {
+                FilePlan::Unchanged
+            }));
         }
     }
 
-    // Chunk the file
     let chunks = chunker::chunk_file(&content, rel_path);
     if chunks.is_empty() {
-        return Ok(IndexFileResult {
-            chunks_total: 0,
-            chunks_embedded: 0,
-            tokens_used: 0,
-        });
+        return Ok(planned(FilePlan::Unchanged));
     }
 
-    // Get existing chunk hashes to skip unchanged chunks
-    let existing_hashes = db::get_chunk_hashes(&conn, rel_path)?;
-
-    // Determine which chunks need new embeddings
-    let mut chunks_to_embed: Vec<(usize, &chunker::Chunk)> = Vec::new();
+    // Keep the vectors of chunks whose text hasn't changed. During a re-embed these
+    // are the old model's and still go to `chunks`; new vectors are staged for every chunk.
+    let existing = db::get_chunk_hashes(conn, rel_path)?;
+    let mut reused = HashMap::new();
+    let mut to_embed = Vec::new();
     for (i, chunk) in chunks.iter().enumerate() {
-        let needs_embed = match existing_hashes.get(&chunk.chunk_index) {
-            Some(old_hash) => old_hash != &chunk.content_hash,
-            None => true,
-        };
-        if needs_embed {
-            chunks_to_embed.push((i, chunk));
+        match existing.get(&chunk.chunk_index) {
+            Some((hash, bytes)) if hash == &chunk.content_hash && !bytes.is_empty() => {
+                reused.insert(i, embeddings::bytes_to_embedding(bytes));
+                if staging {
+                    to_embed.push(i);
+                }
+            }
+            _ if embed => to_embed.push(i),
+            _ => {}
         }
     }
 
-    // Delete old chunks and re-insert all (simpler than partial updates)
-    db::delete_chunks_for_file(&conn, rel_path)?;
-
-    // Batch embed changed chunks
-    let mut all_embeddings: HashMap<usize, Vec<f32>> = HashMap::new();
+    Ok(planned(FilePlan::Update { file_hash, chunks, reused, to_embed }))
+}
 
-    for batch in chunks_to_embed.chunks(MAX_BATCH_SIZE) {
-        let texts: Vec<String> = batch.iter().map(|(_, c)| c.content.clone()).collect();
-        let batch_embeddings = client.embed_batch(&texts).await?;
-        for (j, (orig_idx, _)) in batch.iter().enumerate() {
-            if j < batch_embeddings.len() {
-                all_embeddings.insert(*orig_idx, batch_embeddings[j].clone());
+/// Write a planned file to the index in one transaction. `vectors` holds the new
+/// embeddings by chunk position; any missing are stored empty (lexical only).
+fn write_file(
+    conn: &rusqlite::Connection,
+    planned: PlannedFile,
+    mut vectors: HashMap<usize, Vec<f32>>,
+    client: Option<&dyn EmbeddingClient>,
+    staging: bool,
+) -> Result<IndexFileResult, AppError> {
+    let rel_path = planned.rel_path.as_str();
+    let (file_hash, chunks, mut reused) = match planned.plan {
+        FilePlan::Unchanged => return Ok(IndexFileResult::default()),
+        FilePlan::Backfill { content, text, metadata } => {
+            if text {
+                backfill_chunk_text(conn, &content, rel_path)?;
             }
+            if metadata {
+                backfill_chunk_metadata(conn, &content, rel_path)?;
+                db::set_file_modified(conn, rel_path, &file_modified(&planned.full_path))?;
+            }
+            return Ok(IndexFileResult::default());
         }
-    }
+        FilePlan::Update { file_hash, chunks, reused, .. } => (file_hash, chunks, reused),
+    };
+
+    let tx = conn.unchecked_transaction()?;
+
+    // Delete old chunks and re-insert all (simpler than partial updates)
+    db::delete_chunks_for_file(&tx, rel_path)?;
 
     // Upsert the indexed_files record FIRST (chunks have FK to this)
     let file_type = chunks.first().map(|c| c.metadata.file_type.as_str()).unwrap_or("unknown");
-    db::upsert_indexed_file(&conn, rel_path, &file_hash, file_type, chunks.len() as u32)?;
+    db::upsert_indexed_file(&tx, rel_path, &file_hash, file_type, chunks.len() as u32)?;
+    db::set_file_modified(&tx, rel_path, &file_modified(&planned.full_path))?;
 
-    // Insert all chunks (with embeddings for changed ones, placeholder for unchanged)
-    let mut total_tokens: u64 = 0;
+    let mut signature_recorded = staging || client.is_none() || db::get_embedding_signature(&tx)?.is_some();
+    let mut chunks_embedded: u32 = 0;
+    let mut tokens_embedded: u64 = 0;
 
     for (i, chunk) in chunks.iter().enumerate() {
-        // Get embedding: either freshly computed or we need to embed it
-        let embedding_vec = if let Some(emb) = all_embeddings.get(&i) {
-            emb.clone()
+        let fresh = vectors.remove(&i);
+        if fresh.is_some() {
+            chunks_embedded += 1;
+            tokens_embedded += chunk.token_count as u64;
+        }
+
+        // Staged vectors stay out of `chunks` until the re-embed completes
+        let (stored, staged) = if staging {
+            (reused.remove(&i).unwrap_or_default(), fresh)
         } else {
-            // This chunk was unchanged but we deleted all chunks above,
-            // so we need to re-embed it too. This only happens if the file changed
-            // but some individual chunks didn't.
-            let single = client.embed_batch(&[chunk.content.clone()]).await?;
-            single.into_iter().next().unwrap_or_default()
+            (fresh.or_else(|| reused.remove(&i)).unwrap_or_default(), None)
         };
 
-        let embedding_bytes = embeddings::embedding_to_bytes(&embedding_vec);
+        if !signature_recorded && !stored.is_empty() {
+            if let Some(client) = client {
+                db::set_embedding_signature(&tx, &signature_of(client, stored.len()))?;
+                signature_recorded = true;
+            }
+        }
 
+        let embedding_bytes = embeddings::embedding_to_bytes(&stored);
         let chunk_id = db::insert_chunk(
-            &conn,
+            &tx,
             rel_path,
             chunk.chunk_index,
             chunk.section_heading.as_deref(),
@@ -127,41 +221,596 @@ pub async fn index_file(
             &embedding_bytes,
         )?;
 
-        db::insert_chunk_metadata(
-            &conn,
-            chunk_id,
-            chunk.metadata.book_id.as_deref(),
-            chunk.metadata.chapter_id.as_deref(),
-            chunk.metadata.entity_type.as_deref(),
-            chunk.metadata.entity_name.as_deref(),
-        )?;
+        db::insert_chunk_metadata(&tx, chunk_id, &chunk.metadata)?;
+        db::insert_chunk_text(&tx, chunk_id, chunk.section_heading.as_deref(), &chunk.content)?;
 
-        total_tokens += chunk.token_count as u64;
+        if !stored.is_empty() {
+            db::insert_chunk_vector(
+                &tx,
+                chunk_id,
+                &embedding_bytes,
+                chunk.metadata.book_id.as_deref(),
+                chunk.metadata.entity_type.as_deref(),
+            )?;
+        }
+
+        if let Some(staged) = staged.filter(|v| !v.is_empty()) {
+            db::insert_staged_embedding(&tx, chunk_id, &embeddings::embedding_to_bytes(&staged))?;
+        }
     }
 
     // Log embedding cost
-    let chunks_embedded_count = all_embeddings.len() as u32;
-    if chunks_embedded_count > 0 {
-        let cost = (total_tokens as f64 / 1_000_000.0) * client.cost_per_million_tokens();
-        db::log_embedding_call(&conn, total_tokens, chunks_embedded_count, cost)?;
+    if let Some(client) = client.filter(|_| chunks_embedded > 0) {
+        let cost = (tokens_embedded as f64 / 1_000_000.0) * client.cost_per_million_tokens();
+        db::log_embedding_call(&tx, tokens_embedded, chunks_embedded, cost)?;
     }
 
+    tx.commit()?;
+
     Ok(IndexFileResult {
         chunks_total: chunks.len() as u32,
-        chunks_embedded: chunks_embedded_count,
-        tokens_used: total_tokens,
+        chunks_embedded,
+        tokens_used: tokens_embedded,
     })
 }
 
+/// Whether new vectors from `client` must be staged, starting a re-embed if so.
+fn begin_staging(conn: &rusqlite::Connection, client: Option<&dyn EmbeddingClient>) -> Result<bool, AppError> {
+    match client {
+        Some(c) if embedding_mismatch(conn, c)?.is_some() => {
+            start_reembed(conn, c)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Index a single file: chunk it, embed changed chunks, store in SQLite.
+/// Without an embedding client the chunks are stored for lexical search only;
+/// they are embedded the next time the file is indexed with a client.
+pub async fn index_file(
+    project_dir: &PathBuf,
+    rel_path: &str,
+    client: Option<&dyn EmbeddingClient>,
+) -> Result<IndexFileResult, AppError> {
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+
+    // While the index holds another model's vectors, new vectors are staged for the re-embed
+    let staging = begin_staging(&conn, client)?;
+    let planned = plan_file(&conn, project_dir, rel_path, client.is_some(), staging)?;
+
+    let mut vectors: HashMap<usize, Vec<f32>> = HashMap::new();
+    if let Some(client) = client {
+        let pending = planned.texts_to_embed();
+        for batch in pending.chunks(MAX_BATCH_SIZE) {
+            let texts: Vec<String> = batch.iter().map(|(_, t)| t.clone()).collect();
+            let batch_embeddings = client.embed_batch(&texts).await?;
+            for ((i, _), vector) in batch.iter().zip(batch_embeddings) {
+                vectors.insert(*i, vector);
+            }
+        }
+    }
+
+    let result = write_file(&conn, planned, vectors, client, staging)?;
+    if let Err(e) = mentions::sync_mentions(&conn, project_dir) {
+        eprintln!("Warning: failed to update entity mentions: {}", e);
+    }
+    Ok(result)
+}
+
+// ─── Full re-index ───
+
+/// Limits for the embedding requests of a full re-index.
+pub struct ReindexOptions {
+    /// Requests in flight at once (at least 1)
+    pub concurrency: usize,
+    /// Requests started per minute; 0 = no limit
+    pub requests_per_minute: u32,
+}
+
+impl ReindexOptions {
+    pub fn from_config(config: &EmbeddingConfig) -> Self {
+        Self {
+            concurrency: config.concurrency.max(1) as usize,
+            requests_per_minute: config.requests_per_minute,
+        }
+    }
+}
+
+/// Outcome of `reindex_files`.
+#[derive(Debug, Default)]
+pub struct ReindexSummary {
+    pub files_failed: u32,
+    pub chunks_total: u32,
+    pub chunks_embedded: u32,
+    pub tokens_used: u64,
+    pub cancelled: bool,
+}
+
+/// Spaces request starts evenly to stay under a requests-per-minute limit.
+struct RateLimiter {
+    interval: Option<Duration>,
+    next_start: tokio::sync::Mutex<tokio::time::Instant>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_minute: u32) -> Self {
+        Self {
+            interval: (requests_per_minute > 0).then(|| Duration::from_secs(60) / requests_per_minute),
+            next_start: tokio::sync::Mutex::new(tokio::time::Instant::now()),
+        }
+    }
+
+    async fn wait(&self) {
+        let Some(interval) = self.interval else { return };
+        let start = {
+            let mut next = self.next_start.lock().await;
+            let start = (*next).max(tokio::time::Instant::now());
+            *next = start + interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+    }
+}
+
+/// Index many files, batching the chunks of different files into shared embedding
+/// requests of up to `MAX_BATCH_SIZE`, with at most `options.concurrency` requests in
+/// flight. Each file is written as soon as all its vectors have arrived, so a cancelled
+/// or failed run keeps the files it finished. `on_file` is called after each file with
+/// the number of files done and the file's path.
+pub async fn reindex_files(
+    project_dir: &PathBuf,
+    files: &[String],
+    client: Option<&dyn EmbeddingClient>,
+    options: &ReindexOptions,
+    mut on_file: impl FnMut(u32, &str),
+) -> Result<ReindexSummary, AppError> {
+    use futures_util::StreamExt;
+
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+    let staging = begin_staging(&conn, client)?;
+
+    let mut summary = ReindexSummary::default();
+    let mut files_done: u32 = 0;
+    let mut record = |summary: &mut ReindexSummary, result: Result<IndexFileResult, AppError>, rel_path: &str| {
+        match result {
+            Ok(r) => {
+                summary.chunks_total += r.chunks_total;
+                summary.chunks_embedded += r.chunks_embedded;
+                summary.tokens_used += r.tokens_used;
+            }
+            Err(e) => {
+                eprintln!("Warning: failed to index {}: {}", rel_path, e);
+                summary.files_failed += 1;
+            }
+        }
+        files_done += 1;
+        on_file(files_done, rel_path);
+    };
+
+    // Plan every file; those with nothing to embed are written straight away
+    let mut planned: Vec<Option<PlannedFile>> = Vec::new();
+    let mut queue: Vec<(usize, usize, String)> = Vec::new();
+    for rel_path in files {
+        match plan_file(&conn, project_dir, rel_path, client.is_some(), staging) {
+            Ok(file) => {
+                let texts = if client.is_some() { file.texts_to_embed() } else { Vec::new() };
+                if texts.is_empty() {
+                    let result = write_file(&conn, file, HashMap::new(), client, staging);
+                    record(&mut summary, result, rel_path);
+                } else {
+                    queue.extend(texts.into_iter().map(|(i, text)| (planned.len(), i, text)));
+                    planned.push(Some(file));
+                }
+            }
+            Err(e) => record(&mut summary, Err(e), rel_path),
+        }
+    }
+    let Some(client) = client else {
+        sync_mentions_after_reindex(&conn, project_dir);
+        return Ok(summary);
+    };
+
+    let mut remaining: Vec<usize> = vec![0; planned.len()];
+    for (f, _, _) in &queue {
+        remaining[*f] += 1;
+    }
+    let mut vectors: Vec<HashMap<usize, Vec<f32>>> = vec![HashMap::new(); planned.len()];
+    let mut failed: Vec<bool> = vec![false; planned.len()];
+
+    let limiter = RateLimiter::new(options.requests_per_minute);
+    let batches: Vec<Vec<(usize, usize, String)>> = queue
+        .chunks(MAX_BATCH_SIZE)
+        .map(|b| b.to_vec())
+        .collect();
+    let mut responses = futures_util::stream::iter(batches.into_iter().map(|batch| {
+        let limiter = &limiter;
+        async move {
+            if CANCEL_REQUESTED.load(Ordering::SeqCst) {
+                return (batch, None);
+            }
+            limiter.wait().await;
+            if CANCEL_REQUESTED.load(Ordering::SeqCst) {
+                return (batch, None);
+            }
+            let texts: Vec<String> = batch.iter().map(|(_, _, t)| t.clone()).collect();
+            let result = client.embed_batch(&texts).await;
+            (batch, Some(result))
+        }
+    }))
+    .buffer_unordered(options.concurrency.max(1));
+
+    while let Some((batch, result)) = responses.next().await {
+        // Requests already in flight when the run is cancelled still finish and are written
+        let Some(result) = result else {
+            summary.cancelled = true;
+            continue;
+        };
+        let embedded = match result {
+            Ok(v) if v.len() == batch.len() => Some(v),
+            Ok(_) => {
+                eprintln!("Warning: embedding provider returned the wrong number of vectors");
+                None
+            }
+            Err(e) => {
+                eprintln!("Warning: embedding request failed: {}", e);
+                None
+            }
+        };
+        let mut finished: Vec<usize> = Vec::new();
+        match embedded {
+            Some(embedded) => {
+                for ((f, i, _), vector) in batch.into_iter().zip(embedded) {
+                    vectors[f].insert(i, vector);
+                    remaining[f] -= 1;
+                    if remaining[f] == 0 {
+                        finished.push(f);
+                    }
+                }
+            }
+            None => {
+                for (f, _, _) in batch {
+                    failed[f] = true;
+                    remaining[f] -= 1;
+                    if remaining[f] == 0 {
+                        finished.push(f);
+                    }
+                }
+            }
+        }
+        for f in finished {
+            let Some(file) = planned[f].take() else { continue };
+            let rel_path = file.rel_path.clone();
+            let result = if failed[f] {
+                // Left as it was; picked up again by the next run
+                Err(AppError::Embedding("embedding request failed".into()))
+            } else {
+                write_file(&conn, file, std::mem::take(&mut vectors[f]), Some(client), staging)
+            };
+            record(&mut summary, result, &rel_path);
+        }
+    }
+
+    sync_mentions_after_reindex(&conn, project_dir);
+    Ok(summary)
+}
+
+fn sync_mentions_after_reindex(conn: &rusqlite::Connection, project_dir: &std::path::Path) {
+    if let Err(e) = mentions::sync_mentions(conn, project_dir) {
+        eprintln!("Warning: failed to update entity mentions: {}", e);
+    }
+}
+
+/// Cost preview for a re-index: what `reindex_files` would embed, without embedding
+/// or writing anything.
+pub fn estimate_reindex(
+    project_dir: &PathBuf,
+    files: &[String],
+    client: Option<&dyn EmbeddingClient>,
+) -> Result<ReindexEstimate, AppError> {
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+    let staging = match client {
+        Some(c) => embedding_mismatch(&conn, c)?.is_some(),
+        None => false,
+    };
+
+    let mut estimate = ReindexEstimate {
+        files_total: files.len() as u32,
+        provider: client.map(|c| c.provider().to_string()),
+        model: client.map(|c| c.model().to_string()),
+        ..Default::default()
+    };
+    for rel_path in files {
+        let file = match plan_file(&conn, project_dir, rel_path, client.is_some(), staging) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("Warning: failed to read {}: {}", rel_path, e);
+                continue;
+            }
+        };
+        if let FilePlan::Update { chunks, to_embed, .. } = &file.plan {
+            estimate.files_changed += 1;
+            estimate.chunks_total += chunks.len() as u32;
+            estimate.chunks_to_embed += to_embed.len() as u32;
+            estimate.tokens_to_embed += to_embed.iter().map(|&i| chunks[i].token_count as u64).sum::<u64>();
+        }
+    }
+
+    // The rest of the index still needs the new model's vectors
+    if staging {
+        let (chunks, tokens) = db::get_reembed_remaining(&conn)?;
+        estimate.reembed_chunks = chunks;
+        estimate.tokens_to_embed += tokens;
+    }
+
+    if let Some(client) = client {
+        estimate.estimated_cost_usd = (estimate.tokens_to_embed as f64 / 1_000_000.0) * client.cost_per_million_tokens();
+    }
+    Ok(estimate)
+}
+
+/// Populate the lexical index for an already-embedded file whose chunks are unchanged.
+fn backfill_chunk_text(conn: &rusqlite::Connection, content: &str, rel_path: &str) -> Result<(), AppError> {
+    let chunk_ids = db::get_chunk_ids(conn, rel_path)?;
+    for chunk in chunker::chunk_file(content, rel_path) {
+        if let Some(&chunk_id) = chunk_ids.get(&chunk.chunk_index) {
+            db::insert_chunk_text(conn, chunk_id, chunk.section_heading.as_deref(), &chunk.content)?;
+        }
+    }
+    Ok(())
+}
+
+/// Refresh the filter metadata of an already-indexed file whose chunks are unchanged.
+fn backfill_chunk_metadata(conn: &rusqlite::Connection, content: &str, rel_path: &str) -> Result<(), AppError> {
+    let chunk_ids = db::get_chunk_ids(conn, rel_path)?;
+    for chunk in chunker::chunk_file(content, rel_path) {
+        if let Some(&chunk_id) = chunk_ids.get(&chunk.chunk_index) {
+            db::insert_chunk_metadata(conn, chunk_id, &chunk.metadata)?;
+            db::update_vector_filters(
+                conn,
+                chunk_id,
+                chunk.metadata.book_id.as_deref(),
+                chunk.metadata.entity_type.as_deref(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// A file's modification time as RFC 3339 (UTC), or now if it can't be read.
+fn file_modified(path: &std::path::Path) -> String {
+    let modified = std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(chrono::DateTime::<chrono::Utc>::from)
+        .unwrap_or_else(|_| chrono::Utc::now());
+    modified.to_rfc3339()
+}
+
+// ─── Embedding model tracking ───
+
+/// Signature for vectors produced by `client` with the given size.
+fn signature_of(client: &dyn EmbeddingClient, dimensions: usize) -> EmbeddingSignature {
+    EmbeddingSignature {
+        provider: client.provider().to_string(),
+        model: client.model().to_string(),
+        dimensions,
+    }
+}
+
+/// Compare `client` against the model recorded for the index's embeddings.
+/// Returns the recorded signature when they differ, meaning the stored vectors
+/// can't be compared with the client's and the index needs a re-embed.
+pub fn embedding_mismatch(
+    conn: &rusqlite::Connection,
+    client: &dyn EmbeddingClient,
+) -> Result<Option<EmbeddingSignature>, AppError> {
+    let stored = match db::get_embedding_signature(conn)? {
+        Some(sig) => sig,
+        None => match db::get_embedding_dimensions(conn)? {
+            // Nothing embedded yet — the first write records the signature
+            None => return Ok(None),
+            // Indexes from before signatures were recorded were built with Voyage,
+            // the only provider at the time, most likely with the default model
+            Some(dimensions) => {
+                let model = if client.provider() == "voyage" { client.model() } else { "voyage-4" };
+                let sig = EmbeddingSignature {
+                    provider: "voyage".to_string(),
+                    model: model.to_string(),
+                    dimensions,
+                };
+                db::set_embedding_signature(conn, &sig)?;
+                sig
+            }
+        },
+    };
+
+    let same_dimensions = client.dimensions() == 0
+        || stored.dimensions == 0
+        || client.dimensions() == stored.dimensions;
+    if stored.provider == client.provider() && stored.model == client.model() && same_dimensions {
+        Ok(None)
+    } else {
+        Ok(Some(stored))
+    }
+}
+
+/// Point the pending re-embed at `client`'s model, unless it already is.
+fn start_reembed(conn: &rusqlite::Connection, client: &dyn EmbeddingClient) -> Result<(), AppError> {
+    let current = db::get_reembed_target(conn)?;
+    let same_target = current
+        .map(|t| t.provider == client.provider() && t.model == client.model())
+        .unwrap_or(false);
+    if !same_target {
+        eprintln!(
+            "Warning: index embeddings don't match {} / {} — re-embedding in the background",
+            client.provider(),
+            client.model()
+        );
+        db::set_reembed_target(conn, &signature_of(client, client.dimensions()))?;
+    }
+    Ok(())
+}
+
+/// Advance the re-embed of an index built with another model by up to `limit` chunks.
+///
+/// New vectors go to a staging table while searches keep using the old ones; once
+/// every chunk has a staged vector they are swapped in with one transaction.
+/// Returns `None` when the index already matches `client`.
+pub async fn reembed_step(
+    project_dir: &PathBuf,
+    client: &dyn EmbeddingClient,
+    limit: u32,
+) -> Result<Option<ReembedProgress>, AppError> {
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+
+    if embedding_mismatch(&conn, client)?.is_none() {
+        return Ok(None);
+    }
+    start_reembed(&conn, client)?;
+
+    let pending = db::get_chunks_to_reembed(&conn, limit)?;
+    let mut tokens_used: u64 = 0;
+    for batch in pending.chunks(MAX_BATCH_SIZE) {
+        let texts: Vec<String> = batch.iter().map(|(_, text, _)| text.clone()).collect();
+        let vectors = client.embed_batch(&texts).await?;
+        for ((chunk_id, _, token_count), vector) in batch.iter().zip(vectors) {
+            db::insert_staged_embedding(&conn, *chunk_id, &embeddings::embedding_to_bytes(&vector))?;
+            tokens_used += *token_count as u64;
+        }
+    }
+    if !pending.is_empty() {
+        let cost = (tokens_used as f64 / 1_000_000.0) * client.cost_per_million_tokens();
+        db::log_embedding_call(&conn, tokens_used, pending.len() as u32, cost)?;
+    }
+
+    let (staged, total) = db::get_reembed_counts(&conn)?;
+    let complete = staged >= total;
+    if complete {
+        // Models sized by their default only report it through the vectors themselves
+        let dimensions = db::get_staged_dimensions(&conn)?.unwrap_or(client.dimensions());
+        db::commit_reembed(&conn, &signature_of(client, dimensions))?;
+    }
+
+    Ok(Some(ReembedProgress {
+        chunks_done: staged,
+        chunks_total: total,
+        complete,
+    }))
+}
+
+/// Progress of the re-embed towards the configured model, or `None` if the
+/// index already matches it (or no embedding provider is configured).
+pub fn reembed_status(
+    project_dir: &PathBuf,
+    config: &EmbeddingConfig,
+) -> Result<Option<ReembedProgress>, AppError> {
+    let client = match embeddings::client_from_config(config)? {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+    if embedding_mismatch(&conn, client.as_ref())?.is_none() {
+        return Ok(None);
+    }
+    let staged = match db::get_reembed_target(&conn)? {
+        Some(t) if t.provider == client.provider() && t.model == client.model() => db::get_reembed_counts(&conn)?.0,
+        _ => 0,
+    };
+    let total = db::get_reembed_counts(&conn)?.1;
+    Ok(Some(ReembedProgress {
+        chunks_done: staged,
+        chunks_total: total,
+        complete: false,
+    }))
+}
+
+/// The client to search the index with: the configured one, or — while a
+/// re-embed to the configured model is pending — one for the model the
+/// stored vectors came from, so semantic search keeps working meanwhile.
+pub fn client_for_index(
+    project_dir: &PathBuf,
+    config: &EmbeddingConfig,
+) -> Result<Option<Box<dyn EmbeddingClient>>, AppError> {
+    let client = match embeddings::client_from_config(config)? {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+    match embedding_mismatch(&conn, client.as_ref())? {
+        None => Ok(Some(client)),
+        Some(stored) => {
+            eprintln!(
+                "Warning: index embeddings were made with {} / {}, not {} / {}; searching with the old model until the re-embed completes",
+                stored.provider, stored.model, client.provider(), client.model()
+            );
+            embeddings::client_for_signature(config, &stored)
+        }
+    }
+}
+
+/// Apply the configured storage to the project's vector index: an index stored
+/// another way is rebuilt, and full-precision copies no longer kept are dropped.
+pub fn sync_vector_index(project_dir: &PathBuf, config: &EmbeddingConfig) -> Result<(), AppError> {
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+    db::set_quantization(&conn, config.quantization)?;
+    db::set_keep_full_precision(&conn, config.keep_full_precision)?;
+    let rebuilt = db::ensure_vector_index(&conn)?;
+    if db::drop_unkept_copies(&conn)? > 0 || rebuilt {
+        db::vacuum(&conn)?;
+    }
+    Ok(())
+}
+
 /// Remove all index entries for a file.
 pub fn deindex_file(project_dir: &PathBuf, rel_path: &str) -> Result<(), AppError> {
     let conn = db::open_index(project_dir)?;
     db::init_schema(&conn)?;
     db::delete_file_data(&conn, rel_path)?;
+    mentions::sync_mentions(&conn, project_dir)?;
+    Ok(())
+}
+
+/// Remove every indexed file under a directory (relative, forward slashes),
+/// e.g. after the directory was deleted or moved away.
+pub fn deindex_dir(project_dir: &PathBuf, rel_dir: &str) -> Result<(), AppError> {
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+    let dir = ProjectPath::new(rel_dir)?;
+    for rel_path in db::get_all_indexed_paths(&conn)? {
+        if ProjectPath::new(&rel_path).is_ok_and(|p| p.is_within(&dir)) {
+            db::delete_file_data(&conn, &rel_path)?;
+        }
+    }
+    mentions::sync_mentions(&conn, project_dir)?;
+    Ok(())
+}
+
+/// Point the index entries of a moved directory at its new location, so the moved
+/// files keep their embeddings. Does nothing if the project has no index yet.
+pub fn move_indexed_dir(project_dir: &PathBuf, from_rel: &str, to_rel: &str) -> Result<(), AppError> {
+    if !project_dir.join(".saipling").join("index.db").exists() {
+        return Ok(());
+    }
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+    for rel_path in db::move_indexed_files(&conn, from_rel, to_rel)? {
+        // Book and chapter ids in the search filters follow the new path
+        if let Ok(content) = ProjectPath::new(&rel_path)?.read_to_string(project_dir) {
+            backfill_chunk_metadata(&conn, &content, &rel_path)?;
+        }
+    }
     Ok(())
 }
 
 /// Result of indexing a single file
+#[derive(Debug, Default)]
 pub struct IndexFileResult {
     pub chunks_total: u32,
     pub chunks_embedded: u32,
@@ -197,10 +846,258 @@ fn collect_files_recursive(root: &PathBuf, dir: &PathBuf, files: &mut Vec<String
         if path.is_dir() {
             collect_files_recursive(root, &path, files);
         } else if path.extension().map(|e| e == "md").unwrap_or(false) {
-            if let Ok(rel) = path.strip_prefix(root) {
-                let rel_str = rel.to_string_lossy().replace('\\', "/");
-                files.push(rel_str);
+            if let Some(rel) = ProjectPath::from_absolute(root, &path) {
+                files.push(rel.into());
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::embeddings::LocalEmbeddingClient;
+    use super::super::SearchFilters;
+    use async_trait::async_trait;
+
+    /// Stand-in for a different embedding model: constant 8-dimensional vectors,
+    /// sized by its default like Voyage, so `dimensions()` is 0.
+    struct OtherModel;
+
+    #[async_trait]
+    impl EmbeddingClient for OtherModel {
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+            Ok(texts.iter().map(|_| vec![0.5; 8]).collect())
+        }
+        async fn embed_query(&self, _query: &str) -> Result<Vec<f32>, AppError> {
+            Ok(vec![0.5; 8])
+        }
+        fn dimensions(&self) -> usize {
+            0
+        }
+        fn provider(&self) -> &str {
+            "openai"
+        }
+        fn model(&self) -> &str {
+            "other"
+        }
+        fn cost_per_million_tokens(&self) -> f64 {
+            0.0
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nested_paths_index_and_deindex() {
+        let dir = std::env::temp_dir().join(format!("saipling-nested-{}", uuid::Uuid::new_v4()));
+        let rel_path = "characters/sarah-chen/profile.md";
+        std::fs::create_dir_all(dir.join("characters").join("sarah-chen")).unwrap();
+        std::fs::write(dir.join("characters").join("sarah-chen").join("profile.md"), "# Sarah Chen\n\nA harbour pilot.\n").unwrap();
+
+        assert_eq!(collect_indexable_files(&dir), vec![rel_path.to_string()]);
+        let result = index_file(&dir, rel_path, None).await.unwrap();
+        assert!(result.chunks_total > 0);
+
+        deindex_dir(&dir, "characters").unwrap();
+        let conn = db::open_index(&dir).unwrap();
+        assert!(db::get_all_indexed_paths(&conn).unwrap().is_empty());
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_moved_dir_keeps_its_vectors() {
+        let dir = std::env::temp_dir().join(format!("saipling-move-index-{}", uuid::Uuid::new_v4()));
+        let from = dir.join("books").join("book-01").join("chapters").join("ch-02");
+        std::fs::create_dir_all(&from).unwrap();
+        std::fs::write(from.join("_chapter.md"), "# The Crossing\n\nThe ferry leaves at dawn.\n").unwrap();
+        let old_path = "books/book-01/chapters/ch-02/_chapter.md";
+        index_file(&dir, old_path, Some(&OtherModel)).await.unwrap();
+
+        let to = dir.join("books").join("book-02").join("chapters").join("ch-01");
+        std::fs::create_dir_all(to.parent().unwrap()).unwrap();
+        // Re-keyed before the folder moves, so the new book comes from the path alone
+        move_indexed_dir(&dir, "books/book-01/chapters/ch-02", "books/book-02/chapters/ch-01").unwrap();
+        std::fs::rename(&from, &to).unwrap();
+
+        let new_path = "books/book-02/chapters/ch-01/_chapter.md";
+        let conn = db::open_index(&dir).unwrap();
+        assert_eq!(db::get_all_indexed_paths(&conn).unwrap(), [new_path.to_string()].into());
+        let chunks = db::get_file_chunks(&conn, new_path).unwrap();
+        assert!(!chunks.is_empty() && chunks.iter().all(|c| !c.embedding.is_empty()));
+        assert!(chunks.iter().all(|c| c.book_id.as_deref() == Some("book-02")));
+        // The KNN lookup filters by the new book too
+        let filters = SearchFilters { book_id: Some("book-02".to_string()), ..Default::default() };
+        let (found, _) = db::vector_search(&conn, &chunks[0].embedding, 10, &filters).unwrap();
+        assert_eq!(found.len(), chunks.len());
+        // Nothing left to embed at the new path
+        let result = index_file(&dir, new_path, Some(&OtherModel)).await.unwrap();
+        assert_eq!(result.chunks_embedded, 0);
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_model_change_reembeds_without_dropping_old_vectors() {
+        let dir = std::env::temp_dir().join(format!("saipling-reembed-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("notes.md"),
+            "## One\n\nThe harbour at dawn.\n\n## Two\n\nThe tavern at night.\n",
+        )
+        .unwrap();
+
+        let local = LocalEmbeddingClient::new();
+        index_file(&dir, "notes.md", Some(&local)).await.unwrap();
+        let conn = db::open_index(&dir).unwrap();
+        let original = db::get_embedding_signature(&conn).unwrap().unwrap();
+        assert_eq!(original.model, embeddings::LOCAL_MODEL);
+        assert_eq!(original.dimensions, local.dimensions());
+
+        assert_eq!(embedding_mismatch(&conn, &OtherModel).unwrap(), Some(original.clone()));
+        assert!(embedding_mismatch(&conn, &local).unwrap().is_none());
+
+        // One chunk per step: the old vectors stay in place until the last one is staged
+        let mut steps = 0;
+        loop {
+            let progress = reembed_step(&dir, &OtherModel, 1).await.unwrap().unwrap();
+            steps += 1;
+            if progress.complete {
+                break;
+            }
+            assert_eq!(db::get_embedding_signature(&conn).unwrap(), Some(original.clone()));
+            assert_eq!(db::get_embedding_dimensions(&conn).unwrap(), Some(local.dimensions()));
+        }
+        assert!(steps > 1);
+
+        let updated = db::get_embedding_signature(&conn).unwrap().unwrap();
+        assert_eq!((updated.model.as_str(), updated.dimensions), ("other", 8));
+        assert_eq!(db::get_embedding_dimensions(&conn).unwrap(), Some(8));
+        assert_eq!(db::get_vector_index_layout(&conn).unwrap().map(|(d, _)| d), Some(8));
+        assert!(reembed_step(&dir, &OtherModel, 1).await.unwrap().is_none());
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Local embeddings that count requests and charge $1 per million tokens.
+    /// With `cancel_on_first`, the first request cancels the run and the others are slower.
+    #[derive(Default)]
+    struct CountingModel {
+        requests: std::sync::atomic::AtomicUsize,
+        cancel_on_first: bool,
+        inner: LocalEmbeddingClient,
+    }
+
+    #[async_trait]
+    impl EmbeddingClient for CountingModel {
+        async fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, AppError> {
+            let request = self.requests.fetch_add(1, Ordering::SeqCst);
+            if self.cancel_on_first && request == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                CANCEL_REQUESTED.store(true, Ordering::SeqCst);
+            } else if self.cancel_on_first {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            self.inner.embed_batch(texts).await
+        }
+        async fn embed_query(&self, query: &str) -> Result<Vec<f32>, AppError> {
+            self.inner.embed_query(query).await
+        }
+        fn dimensions(&self) -> usize {
+            self.inner.dimensions()
+        }
+        fn provider(&self) -> &str {
+            "local"
+        }
+        fn model(&self) -> &str {
+            embeddings::LOCAL_MODEL
+        }
+        fn cost_per_million_tokens(&self) -> f64 {
+            1.0
+        }
+    }
+
+    /// Serializes the tests that set the global `CANCEL_REQUESTED`.
+    static CANCEL_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+    #[tokio::test]
+    async fn test_reindex_estimate_batching_and_cancel() {
+        let _lock = CANCEL_LOCK.lock().await;
+        let dir = std::env::temp_dir().join(format!("saipling-reindex-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.md"), "## One\n\nThe harbour at dawn.\n\n## Two\n\nThe tavern at night.\n").unwrap();
+        std::fs::write(dir.join("b.md"), "## Three\n\nA storm over the cliffs.\n").unwrap();
+        let files = vec!["a.md".to_string(), "b.md".to_string()];
+        let client = CountingModel::default();
+        let options = ReindexOptions { concurrency: 2, requests_per_minute: 0 };
+
+        // The dry run embeds and writes nothing
+        let estimate = estimate_reindex(&dir, &files, Some(&client)).unwrap();
+        assert_eq!((estimate.files_total, estimate.files_changed), (2, 2));
+        assert_eq!(estimate.chunks_to_embed, estimate.chunks_total);
+        assert!(estimate.tokens_to_embed > 0 && estimate.estimated_cost_usd > 0.0);
+        assert_eq!(client.requests.load(Ordering::SeqCst), 0);
+
+        // Chunks of both files share one request
+        let guard = IndexingGuard::acquire().unwrap();
+        assert!(IndexingGuard::acquire().is_none());
+        let summary = reindex_files(&dir, &files, Some(&client), &options, |_, _| {}).await.unwrap();
+        assert_eq!(client.requests.load(Ordering::SeqCst), 1);
+        assert_eq!(summary.chunks_embedded, estimate.chunks_to_embed);
+        assert_eq!(summary.tokens_used, estimate.tokens_to_embed);
+        assert!(!summary.cancelled && summary.files_failed == 0);
+
+        // Only the edited section needs a new vector
+        std::fs::write(dir.join("a.md"), "## One\n\nThe harbour at dawn.\n\n## Two\n\nThe tavern at midnight.\n").unwrap();
+        let estimate = estimate_reindex(&dir, &files, Some(&client)).unwrap();
+        assert_eq!((estimate.files_changed, estimate.chunks_to_embed), (1, 1));
+
+        // A cancelled run leaves the file for next time
+        CANCEL_REQUESTED.store(true, Ordering::SeqCst);
+        let summary = reindex_files(&dir, &files, Some(&client), &options, |_, _| {}).await.unwrap();
+        assert!(summary.cancelled);
+        assert_eq!(client.requests.load(Ordering::SeqCst), 1);
+        assert_eq!(estimate_reindex(&dir, &files, Some(&client)).unwrap().chunks_to_embed, 1);
+
+        drop(guard);
+        assert!(!*IS_INDEXING.lock().unwrap());
+        assert!(!CANCEL_REQUESTED.load(Ordering::SeqCst));
+
+        let summary = reindex_files(&dir, &files, Some(&client), &options, |_, _| {}).await.unwrap();
+        assert_eq!(summary.chunks_embedded, 1);
+        assert_eq!(estimate_reindex(&dir, &files, Some(&client)).unwrap().files_changed, 0);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_keeps_batches_in_flight() {
+        let _lock = CANCEL_LOCK.lock().await;
+        let dir = std::env::temp_dir().join(format!("saipling-cancel-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // One full batch each for x.md and y.md, and a third batch for z.md
+        let sections = |name: &str| -> String {
+            (0..MAX_BATCH_SIZE).map(|i| format!("## {} {}\n\nSection {} of {}.\n\n", name, i, i, name)).collect()
+        };
+        std::fs::write(dir.join("x.md"), sections("X")).unwrap();
+        std::fs::write(dir.join("y.md"), sections("Y")).unwrap();
+        std::fs::write(dir.join("z.md"), "## Z\n\nThe last section.\n").unwrap();
+        let files = vec!["x.md".to_string(), "y.md".to_string(), "z.md".to_string()];
+        let client = CountingModel { cancel_on_first: true, ..Default::default() };
+        let options = ReindexOptions { concurrency: 2, requests_per_minute: 0 };
+
+        // x.md's request cancels the run while y.md's is still in flight; z.md's never starts
+        let guard = IndexingGuard::acquire().unwrap();
+        let summary = reindex_files(&dir, &files, Some(&client), &options, |_, _| {}).await.unwrap();
+        drop(guard);
+        assert!(summary.cancelled);
+        assert_eq!(client.requests.load(Ordering::SeqCst), 2);
+        assert_eq!(summary.chunks_embedded as usize, 2 * MAX_BATCH_SIZE);
+        let estimate = estimate_reindex(&dir, &files, Some(&client)).unwrap();
+        assert_eq!((estimate.files_changed, estimate.chunks_to_embed), (1, 1));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
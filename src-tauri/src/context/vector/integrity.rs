@@ -0,0 +1,199 @@
+use std::path::PathBuf;
+use std::collections::BTreeSet;
+
+use crate::error::AppError;
+use crate::project_path::ProjectPath;
+use super::chunker;
+use super::db;
+use super::embeddings::EmbeddingClient;
+use super::indexer::{self, ReindexOptions};
+use super::IndexIntegrityReport;
+
+/// Check the index against itself and the files on disk. Read-only: an index at an
+/// older schema version isn't migrated, only reported; `repair_index` migrates it.
+pub fn verify_index(project_dir: &std::path::Path) -> Result<IndexIntegrityReport, AppError> {
+    let Some(conn) = db::open_index_unmigrated(project_dir)? else {
+        return Ok(IndexIntegrityReport { schema_version: db::SCHEMA_VERSION, ..Default::default() });
+    };
+    let schema_version = db::stored_schema_version(&conn)?;
+    if schema_version != db::SCHEMA_VERSION {
+        return Ok(IndexIntegrityReport { schema_version, ..Default::default() });
+    }
+    let (report, _) = inspect(&conn, project_dir)?;
+    Ok(report)
+}
+
+/// Fix what `verify_index` finds: orphans are deleted, files gone from disk are
+/// removed, and changed files or wrong-sized embeddings are re-indexed. Only
+/// chunks whose text changed or whose vector was dropped are embedded again.
+/// Without `client` the re-indexed chunks are left for lexical search only.
+pub async fn repair_index(
+    project_dir: &PathBuf,
+    client: Option<&dyn EmbeddingClient>,
+    options: &ReindexOptions,
+) -> Result<IndexIntegrityReport, AppError> {
+    let (mut report, wrong_size_files, lost_vector_files) = {
+        let conn = db::open_index(project_dir)?;
+        db::init_schema(&conn)?;
+        let (report, wrong_size_files) = inspect(&conn, project_dir)?;
+        if report.is_healthy() {
+            return Ok(report);
+        }
+        let lost_vector_files = repair_rows(&conn, &report)?;
+        (report, wrong_size_files, lost_vector_files)
+    };
+
+    let files: Vec<String> = report
+        .changed_files
+        .iter()
+        .chain(wrong_size_files.iter())
+        .chain(lost_vector_files.iter())
+        .cloned()
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if !files.is_empty() {
+        let summary = indexer::reindex_files(project_dir, &files, client, options, |_, _| {}).await?;
+        report.chunks_reembedded = summary.chunks_embedded;
+    }
+
+    report.repaired = true;
+    Ok(report)
+}
+
+/// The repairs that need no embedding, in one transaction, then the vector index.
+/// Returns the files with vectors that couldn't be restored.
+fn repair_rows(conn: &rusqlite::Connection, report: &IndexIntegrityReport) -> Result<Vec<String>, AppError> {
+    let tx = conn.unchecked_transaction()?;
+    db::delete_orphans(&tx)?;
+    for rel_path in &report.missing_files {
+        db::delete_file_data(&tx, rel_path)?;
+    }
+    if let Some(sig) = db::get_embedding_signature(&tx)? {
+        db::clear_wrong_size_embeddings(&tx, sig.dimensions)?;
+    }
+    for rel_path in &report.changed_files {
+        db::invalidate_file_hash(&tx, rel_path)?;
+    }
+    tx.commit()?;
+
+    if report.missing_vectors > 0 {
+        return db::rebuild_vector_index_from_chunks(conn);
+    }
+    Ok(Vec::new())
+}
+
+/// Build the report, plus the files holding wrong-sized embeddings.
+fn inspect(
+    conn: &rusqlite::Connection,
+    project_dir: &std::path::Path,
+) -> Result<(IndexIntegrityReport, Vec<String>), AppError> {
+    let mut report = IndexIntegrityReport {
+        schema_version: db::SCHEMA_VERSION,
+        orphaned_rows: db::count_orphans(conn)?,
+        missing_vectors: db::count_missing_vectors(conn)?,
+        ..Default::default()
+    };
+
+    for (rel_path, hash, chunks_match) in db::get_indexed_file_hashes(conn)? {
+        match ProjectPath::new(&rel_path).and_then(|p| p.read_to_string(project_dir)) {
+            Ok(content) => {
+                if !chunks_match || chunker::sha256(&content) != hash {
+                    report.changed_files.push(rel_path);
+                }
+            }
+            Err(_) => report.missing_files.push(rel_path),
+        }
+    }
+
+    let mut wrong_size_files = Vec::new();
+    if let Some(sig) = db::get_embedding_signature(conn)? {
+        let (files, chunks) = db::get_wrong_size_embeddings(conn, sig.dimensions)?;
+        report.wrong_size_embeddings = chunks;
+        wrong_size_files = files.into_iter().filter(|f| !report.missing_files.contains(f)).collect();
+    }
+
+    Ok((report, wrong_size_files))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::vector::embeddings::LocalEmbeddingClient;
+
+    #[tokio::test]
+    async fn test_verify_and_repair_index() {
+        let dir = std::env::temp_dir().join(format!("saipling-integrity-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.md"), "## One\n\nThe harbour at dawn.\n\n## Two\n\nThe tavern at night.\n").unwrap();
+        std::fs::write(dir.join("b.md"), "## Three\n\nA storm over the cliffs.\n\n## Four\n\nGulls on the pier.\n").unwrap();
+        std::fs::write(dir.join("c.md"), "## Five\n\nThe lighthouse keeper.\n").unwrap();
+        let files: Vec<String> = ["a.md", "b.md", "c.md"].iter().map(|f| f.to_string()).collect();
+        let client = LocalEmbeddingClient::new();
+        let options = ReindexOptions { concurrency: 1, requests_per_minute: 0 };
+        indexer::reindex_files(&dir, &files, Some(&client), &options, |_, _| {}).await.unwrap();
+
+        let report = verify_index(&dir).unwrap();
+        assert!(report.is_healthy(), "{:?}", report);
+        assert_eq!(report.schema_version, db::SCHEMA_VERSION);
+
+        {
+            let conn = db::open_index(&dir).unwrap();
+            let version: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap();
+            assert_eq!(version, db::SCHEMA_VERSION);
+
+            // Without foreign keys the triggers still cascade a file's rows
+            conn.pragma_update(None, "foreign_keys", "OFF").unwrap();
+            conn.execute("DELETE FROM indexed_files WHERE file_path = 'c.md'", []).unwrap();
+            assert_eq!(db::count_orphans(&conn).unwrap(), 0);
+            db::upsert_indexed_file(&conn, "c.md", "h", "md", 0).unwrap();
+
+            conn.execute("INSERT INTO chunk_metadata (chunk_id, book_id) VALUES (9999, 'book-01')", []).unwrap();
+            conn.execute("INSERT INTO chunks_fts (rowid, section_heading, content) VALUES (9999, NULL, 'stray')", []).unwrap();
+            conn.execute(
+                "UPDATE chunks SET dimensions = 2 WHERE file_path = 'a.md' AND chunk_index = 0",
+                [],
+            )
+            .unwrap();
+        }
+        std::fs::remove_file(dir.join("c.md")).unwrap();
+        std::fs::write(dir.join("b.md"), "## Three\n\nA storm over the cliffs.\n\n## Four\n\nGulls on the harbour wall.\n").unwrap();
+
+        let report = verify_index(&dir).unwrap();
+        assert_eq!(report.orphaned_rows, 2);
+        assert_eq!(report.missing_files, vec!["c.md"]);
+        assert_eq!(report.changed_files, vec!["b.md"]);
+        assert_eq!(report.wrong_size_embeddings, 1);
+        assert!(!report.repaired);
+
+        // Only the edited section and the dropped vector are embedded again
+        let repaired = repair_index(&dir, Some(&client), &options).await.unwrap();
+        assert!(repaired.repaired);
+        assert_eq!(repaired.chunks_reembedded, 2);
+
+        let report = verify_index(&dir).unwrap();
+        assert!(report.is_healthy(), "{:?}", report);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_verify_reports_old_schema_without_migrating() {
+        let dir = std::env::temp_dir().join(format!("saipling-integrity-old-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        {
+            let conn = db::open_index(&dir).unwrap();
+            db::init_schema(&conn).unwrap();
+            conn.pragma_update(None, "user_version", 2).unwrap();
+        }
+
+        let report = verify_index(&dir).unwrap();
+        assert_eq!(report.schema_version, 2);
+        assert!(!report.is_healthy());
+        let conn = db::open_index(&dir).unwrap();
+        assert_eq!(db::stored_schema_version(&conn).unwrap(), 2);
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,409 @@
+use std::path::PathBuf;
+use std::collections::HashMap;
+
+use crate::error::AppError;
+use crate::project_path::ProjectPath;
+use super::chunker;
+use super::db;
+use super::{EntityAppearance, EntityCooccurrence, KnownEntity};
+
+/// File types scanned for mentions.
+const MANUSCRIPT_FILE_TYPES: &[&str] = &["scene_draft", "scene_outline"];
+
+/// Shortest name or alias matched; shorter ones are mostly noise.
+const MIN_TERM_CHARS: usize = 2;
+
+/// Bring entities and mentions up to date with the index. Entities are read from
+/// changed character profiles and location/item entries; manuscript files are
+/// rescanned when their content or the set of entity names changed. Works from
+/// the files on disk only — no embedding provider is involved.
+pub fn sync_mentions(conn: &rusqlite::Connection, project_dir: &std::path::Path) -> Result<(), AppError> {
+    let indexed = db::get_indexed_files(conn)?;
+    let known = db::get_entity_source_hashes(conn)?;
+    for (rel_path, hash) in &indexed {
+        if known.get(rel_path) == Some(hash) {
+            continue;
+        }
+        let Some((entity_type, slug)) = entity_kind(rel_path) else { continue };
+        match ProjectPath::new(rel_path).and_then(|p| p.read_to_string(project_dir)) {
+            Ok(content) => {
+                let entity = parse_entity(&content, rel_path, entity_type, &slug);
+                db::upsert_entity(conn, &entity, hash)?;
+            }
+            Err(e) => eprintln!("Warning: failed to read {}: {}", rel_path, e),
+        }
+    }
+
+    let entities = db::get_entities(conn)?;
+    let entity_set = entity_set_signature(&entities);
+    let stale = db::get_stale_mention_files(conn, MANUSCRIPT_FILE_TYPES, &entity_set)?;
+    if stale.is_empty() {
+        return Ok(());
+    }
+
+    let terms = build_terms(&entities);
+    let tx = conn.unchecked_transaction()?;
+    for (rel_path, hash) in stale {
+        let content = match ProjectPath::new(&rel_path).and_then(|p| p.read_to_string(project_dir)) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Warning: failed to read {}: {}", rel_path, e);
+                continue;
+            }
+        };
+        let metadata = chunker::file_metadata(&content, &rel_path);
+        let (_, body) = chunker::split_frontmatter(&content);
+        let body_start = content[..content.len() - body.len()].chars().count();
+
+        let found = find_mentions(&body, &terms);
+        let rows: Vec<db::MentionRow> = found
+            .iter()
+            .map(|&(term, offset)| {
+                let entity = &entities[terms[term].entity];
+                db::MentionRow {
+                    entity_type: &entity.entity_type,
+                    entity_slug: &entity.slug,
+                    matched_text: &terms[term].text,
+                    char_offset: body_start + offset,
+                }
+            })
+            .collect();
+        let scene_id = crate::manuscript::locate(&rel_path).and_then(|l| l.scene_id);
+        db::replace_file_mentions(&tx, &rel_path, &metadata, scene_id.as_deref(), &rows, &hash, &entity_set)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Open the index with mentions up to date, for querying.
+pub fn open_synced(project_dir: &PathBuf) -> Result<rusqlite::Connection, AppError> {
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+    sync_mentions(&conn, project_dir)?;
+    Ok(conn)
+}
+
+/// Find an entity by slug (optionally `type:slug`), name or alias, ignoring case,
+/// or else by a first name only one character has.
+pub fn resolve_entity(conn: &rusqlite::Connection, query: &str) -> Result<KnownEntity, AppError> {
+    let query = query.trim();
+    let (wanted_type, wanted) = match query.split_once(':') {
+        Some((t, rest)) if ["character", "location", "item"].contains(&t) => (Some(t), rest.trim()),
+        _ => (None, query),
+    };
+    let wanted = wanted.to_lowercase();
+    let candidates: Vec<KnownEntity> = db::get_entities(conn)?
+        .into_iter()
+        .filter(|e| wanted_type.is_none_or(|t| e.entity_type == t))
+        .collect();
+
+    let exact = candidates.iter().position(|e| {
+        e.slug == wanted
+            || e.name.to_lowercase() == wanted
+            || e.aliases.iter().any(|a| a.to_lowercase() == wanted)
+    });
+    let by_first_name = || {
+        let matches: Vec<usize> = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| {
+                e.entity_type == "character"
+                    && e.name.split_whitespace().next().map(str::to_lowercase).as_deref() == Some(wanted.as_str())
+            })
+            .map(|(i, _)| i)
+            .collect();
+        (matches.len() == 1).then(|| matches[0])
+    };
+    exact
+        .or_else(by_first_name)
+        .map(|i| candidates[i].clone())
+        .ok_or_else(|| AppError::VectorSearch(format!("Unknown character, location or item \"{}\"", query)))
+}
+
+/// Every manuscript file mentioning the entity, in story order.
+pub fn entity_appearances(
+    project_dir: &PathBuf,
+    entity: &str,
+    file_type: Option<&str>,
+) -> Result<Vec<EntityAppearance>, AppError> {
+    let conn = open_synced(project_dir)?;
+    let entity = resolve_entity(&conn, entity)?;
+    let mut appearances = db::get_entity_appearances(&conn, &entity.entity_type, &entity.slug, file_type)?;
+
+    // Chapters and scenes missing from book.json go after the others, in id order
+    let order = crate::book_repo::ReadingOrder::load(project_dir);
+    appearances.sort_by_cached_key(|a| {
+        let book = a.book_id.as_deref().unwrap_or_default();
+        let chapter = a.chapter_id.as_deref().unwrap_or_default();
+        let scene = a.scene_id.as_deref().unwrap_or_default();
+        (
+            book.to_string(),
+            order.chapter_number(book, chapter).unwrap_or(u32::MAX),
+            order.scene_number(book, chapter, scene).unwrap_or(u32::MAX),
+        )
+    });
+    Ok(appearances)
+}
+
+/// Entities that share files with the given one, most shared first.
+pub fn entity_cooccurrences(
+    project_dir: &PathBuf,
+    entity: &str,
+    file_type: Option<&str>,
+) -> Result<Vec<EntityCooccurrence>, AppError> {
+    let conn = open_synced(project_dir)?;
+    let entity = resolve_entity(&conn, entity)?;
+    db::get_entity_cooccurrences(&conn, &entity.entity_type, &entity.slug, file_type)
+}
+
+/// The entity a file defines, from its path: `characters/<slug>/profile.md`,
+/// `world/locations/<slug>/entry.md` or `world/items/<slug>/entry.md`.
+fn entity_kind(rel_path: &str) -> Option<(&'static str, String)> {
+    let path = rel_path.replace('\\', "/");
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        ["characters", slug, "profile.md"] => Some(("character", slug.to_string())),
+        ["world", "locations", slug, "entry.md"] => Some(("location", slug.to_string())),
+        ["world", "items", slug, "entry.md"] => Some(("item", slug.to_string())),
+        _ => None,
+    }
+}
+
+/// Name from frontmatter `name`, the first `# ` heading or the slug, and
+/// aliases from frontmatter `aliases` (a `[a, b]` list, comma list or YAML list).
+fn parse_entity(content: &str, rel_path: &str, entity_type: &str, slug: &str) -> KnownEntity {
+    let (frontmatter, body) = chunker::split_frontmatter(content);
+    let name = frontmatter_value(&frontmatter, "name")
+        .filter(|n| !n.is_empty())
+        .or_else(|| {
+            body.lines()
+                .find_map(|l| l.strip_prefix("# "))
+                .map(|h| h.trim().to_string())
+        })
+        .unwrap_or_else(|| title_case(slug));
+
+    KnownEntity {
+        entity_type: entity_type.to_string(),
+        slug: slug.to_string(),
+        name,
+        aliases: frontmatter_list(&frontmatter, "aliases"),
+        source_path: rel_path.replace('\\', "/"),
+    }
+}
+
+fn frontmatter_value(frontmatter: &str, key: &str) -> Option<String> {
+    frontmatter.lines().find_map(|line| {
+        let (k, v) = line.split_once(':')?;
+        (k.trim() == key && !line.starts_with(' ')).then(|| unquote(v))
+    })
+}
+
+fn frontmatter_list(frontmatter: &str, key: &str) -> Vec<String> {
+    let mut lines = frontmatter.lines().skip_while(|line| {
+        line.split_once(':').map(|(k, _)| k.trim() != key || line.starts_with(' ')).unwrap_or(true)
+    });
+    let Some(first) = lines.next() else { return Vec::new() };
+    let inline = first.split_once(':').map(|(_, v)| v.trim()).unwrap_or("");
+
+    let items: Vec<String> = if inline.is_empty() {
+        lines
+            .map(str::trim)
+            .take_while(|l| l.starts_with("- "))
+            .map(|l| unquote(&l[2..]))
+            .collect()
+    } else {
+        let inline = inline.trim_start_matches('[').trim_end_matches(']');
+        inline.split(',').map(unquote).collect()
+    };
+    items.into_iter().filter(|i| !i.is_empty()).collect()
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches('"').trim_matches('\'').trim().to_string()
+}
+
+fn title_case(slug: &str) -> String {
+    slug.split('-')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars.next().map(|c| c.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Changes whenever any entity's names change, so stale scans can be found.
+fn entity_set_signature(entities: &[KnownEntity]) -> String {
+    let lines: Vec<String> = entities
+        .iter()
+        .map(|e| format!("{}:{}={}|{}", e.entity_type, e.slug, e.name, e.aliases.join("|")))
+        .collect();
+    chunker::sha256(&lines.join("\n"))
+}
+
+/// A name matched in the text, and the index of its entity.
+struct Term {
+    text: String,
+    entity: usize,
+}
+
+/// Names and aliases to look for, longest first so "Elena Voss" wins over "Elena".
+/// A character's first name is matched on its own unless another entity uses it.
+fn build_terms(entities: &[KnownEntity]) -> Vec<Term> {
+    let mut owners: HashMap<String, usize> = HashMap::new();
+    for (i, entity) in entities.iter().enumerate() {
+        for text in std::iter::once(&entity.name).chain(entity.aliases.iter()) {
+            owners.entry(text.trim().to_string()).or_insert(i);
+        }
+    }
+
+    let mut first_names: HashMap<String, Vec<usize>> = HashMap::new();
+    for (i, entity) in entities.iter().enumerate().filter(|(_, e)| e.entity_type == "character") {
+        let mut words = entity.name.split_whitespace();
+        if let (Some(first), Some(_)) = (words.next(), words.next()) {
+            if first.chars().count() >= 3 && first.starts_with(char::is_uppercase) {
+                first_names.entry(first.to_string()).or_default().push(i);
+            }
+        }
+    }
+    for (first, users) in first_names {
+        if users.len() == 1 {
+            owners.entry(first).or_insert(users[0]);
+        }
+    }
+
+    let mut terms: Vec<Term> = owners
+        .into_iter()
+        .filter(|(text, _)| text.chars().count() >= MIN_TERM_CHARS)
+        .map(|(text, entity)| Term { text, entity })
+        .collect();
+    terms.sort_by(|a, b| b.text.len().cmp(&a.text.len()).then_with(|| a.text.cmp(&b.text)));
+    terms
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whole-word, case-sensitive matches as (term index, character offset).
+/// Matches don't overlap; possessives like "Elena's" still match.
+fn find_mentions(text: &str, terms: &[Term]) -> Vec<(usize, usize)> {
+    let mut found = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut skip_to = 0;
+
+    for (char_offset, (byte, c)) in text.char_indices().enumerate() {
+        let word_start = is_word_char(c) && !prev.is_some_and(is_word_char);
+        prev = Some(c);
+        if byte < skip_to || !word_start {
+            continue;
+        }
+        let rest = &text[byte..];
+        let matched = terms.iter().position(|t| {
+            rest.starts_with(&t.text) && !rest[t.text.len()..].chars().next().is_some_and(is_word_char)
+        });
+        if let Some(term) = matched {
+            found.push((term, char_offset));
+            skip_to = byte + terms[term].text.len();
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &std::path::Path, rel_path: &str, content: &str) {
+        let path = dir.join(rel_path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_entity_profile_parsing() {
+        let entity = parse_entity(
+            "---\ntype: character-profile\nname: Elena Voss\naliases: [Ellie, \"The Captain\"]\n---\n\n## Role\n",
+            "characters/elena-voss/profile.md",
+            "character",
+            "elena-voss",
+        );
+        assert_eq!(entity.name, "Elena Voss");
+        assert_eq!(entity.aliases, vec!["Ellie", "The Captain"]);
+
+        let entity = parse_entity("---\naliases:\n  - Old Tom\n  - Tom\nstatus: draft\n---\n", "characters/thomas-reed/profile.md", "character", "thomas-reed");
+        assert_eq!(entity.name, "Thomas Reed");
+        assert_eq!(entity.aliases, vec!["Old Tom", "Tom"]);
+        assert_eq!(entity_kind("world/locations/gilded-anchor/entry.md"), Some(("location", "gilded-anchor".to_string())));
+        assert_eq!(entity_kind("world/culture/entry.md"), None);
+    }
+
+    #[test]
+    fn test_mentions_indexed_offline() {
+        let dir = std::env::temp_dir().join(format!("saipling-mentions-{}", uuid::Uuid::new_v4()));
+        write(&dir, "characters/elena-voss/profile.md", "---\nname: Elena Voss\naliases: [The Captain]\n---\n\n## Role\n\nSmuggler.\n");
+        write(&dir, "characters/marta/profile.md", "# Marta\n\n## Role\n\nInnkeeper.\n");
+        write(&dir, "world/locations/gilded-anchor/entry.md", "---\nname: The Gilded Anchor\n---\n");
+        let ch1 = "books/book-01/chapters/ch-01/scene-01/draft.md";
+        let ch2 = "books/book-01/chapters/ch-02/scene-01/draft.md";
+        let ch10 = "books/book-01/chapters/ch-10/scene-02/draft.md";
+        write(&dir, ch10, "Elena's ship left the harbour.");
+        write(&dir, ch2, "---\npov_character: Marta\n---\nMarta waited at The Gilded Anchor. The Captain was late.");
+        write(&dir, ch1, "Marta polished glasses in The Gilded Anchor. Elena Voss walked in; Elenaria did not.");
+
+        let conn = db::open_index(&dir).unwrap();
+        db::init_schema(&conn).unwrap();
+        for rel_path in [ch10, ch2, ch1, "characters/elena-voss/profile.md", "characters/marta/profile.md", "world/locations/gilded-anchor/entry.md"] {
+            let content = std::fs::read_to_string(dir.join(rel_path)).unwrap();
+            let file_type = chunker::file_metadata(&content, rel_path).file_type;
+            db::upsert_indexed_file(&conn, rel_path, &chunker::sha256(&content), &file_type, 0).unwrap();
+        }
+        drop(conn);
+
+        let elena = entity_appearances(&dir, "Elena", None).unwrap();
+        let files: Vec<&str> = elena.iter().map(|a| a.file_path.as_str()).collect();
+        assert_eq!(files, vec![ch1, ch2, ch10], "story order, via name, alias and possessive");
+        assert_eq!(elena[0].chapter_id.as_deref(), Some("ch-01"));
+        assert_eq!(elena[0].scene_id.as_deref(), Some("scene-01"));
+        assert_eq!(elena[0].mention_count, 1, "Elenaria is not Elena");
+        assert_eq!(elena[0].first_offset as usize, "Marta polished glasses in The Gilded Anchor. ".chars().count());
+
+        // Offsets count from the start of the file, past the frontmatter
+        let marta = entity_appearances(&dir, "character:marta", None).unwrap();
+        let in_ch2 = marta.iter().find(|a| a.file_path == ch2).unwrap();
+        assert_eq!(in_ch2.first_offset as usize, "---\npov_character: Marta\n---\n".chars().count());
+
+        let together = entity_cooccurrences(&dir, "elena-voss", None).unwrap();
+        assert_eq!(together[0].slug, "marta");
+        assert_eq!(together[0].shared_files, 2);
+        assert_eq!(together[1].name, "The Gilded Anchor");
+
+        // Story order follows book.json once the chapters are listed there
+        let mut book = crate::book_repo::Book::new("book-01", "Tides", 1);
+        for (i, chapter_id) in ["ch-10", "ch-01", "ch-02"].iter().enumerate() {
+            book.chapters.push(crate::book_repo::Chapter::new(chapter_id, chapter_id, i as u32 + 1));
+        }
+        crate::book_repo::save_book(&dir, &book).unwrap();
+        let files: Vec<String> = entity_appearances(&dir, "Elena", None).unwrap().into_iter().map(|a| a.file_path).collect();
+        assert_eq!(files, vec![ch10, ch1, ch2]);
+
+        // A new alias is picked up by rescanning the manuscript
+        write(&dir, "characters/marta/profile.md", "---\naliases: [the innkeeper]\n---\n# Marta\n");
+        {
+            let conn = db::open_index(&dir).unwrap();
+            let content = std::fs::read_to_string(dir.join("characters/marta/profile.md")).unwrap();
+            db::upsert_indexed_file(&conn, "characters/marta/profile.md", &chunker::sha256(&content), "character", 0).unwrap();
+        }
+        write(&dir, ch10, "Elena's ship left the harbour; the innkeeper watched.");
+        {
+            let conn = db::open_index(&dir).unwrap();
+            let content = std::fs::read_to_string(dir.join(ch10)).unwrap();
+            db::upsert_indexed_file(&conn, ch10, &chunker::sha256(&content), "scene_draft", 0).unwrap();
+        }
+        assert_eq!(entity_appearances(&dir, "The Innkeeper", None).unwrap().len(), 3);
+
+        assert!(entity_appearances(&dir, "Nobody", None).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -3,9 +3,128 @@ pub mod chunker;
 pub mod embeddings;
 pub mod indexer;
 pub mod search;
+pub mod rerank;
+pub mod query;
+pub mod related;
+pub mod integrity;
+pub mod mentions;
 
 use serde::{Deserialize, Serialize};
 
+/// How search results are ranked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// BM25 over the full-text index — no embedding call needed
+    Lexical,
+    /// Cosine similarity against the query embedding
+    Semantic,
+    /// Both rankings merged with reciprocal-rank fusion
+    Hybrid,
+}
+
+/// Restrictions on which chunks a search may return. Every set field must match;
+/// list fields match any of their values. All of them are applied in SQL.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchFilters {
+    pub entity_types: Vec<String>,
+    pub file_types: Vec<String>,
+    /// Entity slugs, e.g. "sarah-chen"
+    pub entity_names: Vec<String>,
+    pub book_id: Option<String>,
+    /// Inclusive chapter number range; chunks outside chapters never match
+    pub chapter_from: Option<u32>,
+    pub chapter_to: Option<u32>,
+    /// The chapters that range covers, as (book id, chapter id), resolved against
+    /// book.json by `search::resolve_chapters`; an unresolved range matches nothing
+    #[serde(skip)]
+    pub chapters: Vec<(String, String)>,
+    /// Scene `pov_character` from frontmatter, compared as a slug
+    pub pov_character: Option<String>,
+    /// Scene `status` from frontmatter
+    pub statuses: Vec<String>,
+    /// Only files last modified on or after this date (YYYY-MM-DD or RFC 3339)
+    pub modified_after: Option<String>,
+    /// Exact phrases the chunk text must contain
+    pub phrases: Vec<String>,
+    /// Projects to search: linked project names, or `search::THIS_PROJECT`.
+    /// Empty searches this project and every linked one
+    pub projects: Vec<String>,
+}
+
+/// The embedding settings the vector layer works from, resolved from the app
+/// config by `VectorSearchConfig::embedding_config` with the key already decoded.
+#[derive(Debug, Clone, Default)]
+pub struct EmbeddingConfig {
+    /// "voyage", "openai" (any OpenAI-compatible server) or "local"
+    pub provider: String,
+    pub model: String,
+    pub api_key: Option<String>,
+    /// Base URL for the "openai" provider; empty uses api.openai.com
+    pub base_url: String,
+    /// Requested vector size for the "openai" provider; 0 uses the model's default
+    pub dimensions: u32,
+    pub quantization: Quantization,
+    /// Also keep float32 copies of the vectors of a quantized index, for rescoring
+    pub keep_full_precision: bool,
+    /// Embedding requests in flight at once during a full re-index
+    pub concurrency: u32,
+    /// Embedding requests started per minute during a full re-index; 0 = no limit
+    pub requests_per_minute: u32,
+}
+
+/// How vectors are stored in the sqlite-vec index, which is the only place they are
+/// kept unless `EmbeddingConfig.keep_full_precision` asks a quantized index to also
+/// keep float32 copies, to rescore the top candidates of a lookup with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Quantization {
+    /// float32 — 4 bytes per dimension
+    #[default]
+    None,
+    /// int8 — 1 byte per dimension
+    Int8,
+    /// One bit per dimension (sign), compared by Hamming distance
+    Binary,
+}
+
+impl Quantization {
+    /// Parse `VectorSearchConfig.embedding_quantization`; unknown values store full precision.
+    pub fn from_config(value: &str) -> Self {
+        match value {
+            "int8" => Quantization::Int8,
+            "binary" => Quantization::Binary,
+            _ => Quantization::None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Quantization::None => "none",
+            Quantization::Int8 => "int8",
+            Quantization::Binary => "binary",
+        }
+    }
+
+    /// Bits stored per dimension.
+    pub fn bits(&self) -> u32 {
+        match self {
+            Quantization::None => 32,
+            Quantization::Int8 => 8,
+            Quantization::Binary => 1,
+        }
+    }
+
+    /// Candidates fetched per wanted result before rescoring them.
+    pub fn oversampling(&self) -> usize {
+        match self {
+            Quantization::None => 1,
+            Quantization::Int8 => 2,
+            Quantization::Binary => 8,
+        }
+    }
+}
+
 /// A single search result returned to the assembler / frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
@@ -13,10 +132,102 @@ pub struct SearchResult {
     pub section_heading: Option<String>,
     pub similarity_score: f32,
     pub content_preview: String,
+    /// Full chunk text, or several adjacent chunks joined into one excerpt
+    #[serde(default)]
+    pub content: String,
+    /// Position of the (first) chunk within its file
+    #[serde(default)]
+    pub chunk_index: u32,
     pub token_count: u64,
     pub entity_type: Option<String>,
     pub entity_name: Option<String>,
     pub book_id: Option<String>,
+    /// Linked project the result came from; None for the current project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
+
+/// What `find_related` looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RelatedMode {
+    /// Other files about the same things, grouped by entity type
+    #[default]
+    Related,
+    /// Near-duplicate passages in other files — possible repetition
+    SimilarPassages,
+}
+
+/// Related files of one entity type, best first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedGroup {
+    /// e.g. "character", "world", "scene_draft"; "other" when the file has none
+    pub entity_type: String,
+    pub results: Vec<SearchResult>,
+}
+
+/// A passage of the source file and a very similar passage elsewhere.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarPassage {
+    pub source_chunk_index: u32,
+    pub source_heading: Option<String>,
+    pub source_preview: String,
+    /// The passage found elsewhere; `similarity_score` is their cosine similarity
+    pub passage: SearchResult,
+}
+
+/// Result of `find_related`; only the part for the requested mode is filled.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelatedContent {
+    pub groups: Vec<RelatedGroup>,
+    pub similar_passages: Vec<SimilarPassage>,
+}
+
+/// A character, location or item whose mentions are tracked in the manuscript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KnownEntity {
+    /// "character", "location" or "item"
+    pub entity_type: String,
+    pub slug: String,
+    pub name: String,
+    /// Other names from the profile's `aliases` frontmatter
+    pub aliases: Vec<String>,
+    /// The profile or entry file that defines it
+    pub source_path: String,
+}
+
+/// A manuscript file in which an entity is mentioned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityAppearance {
+    pub file_path: String,
+    /// "scene_draft" or "scene_outline"
+    pub file_type: String,
+    pub book_id: Option<String>,
+    pub chapter_id: Option<String>,
+    /// File name without extension, e.g. "scene-03"
+    pub scene_id: Option<String>,
+    pub mention_count: u32,
+    /// Character offset of the first mention in the file
+    pub first_offset: u32,
+}
+
+/// Another entity mentioned in files shared with the queried one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityCooccurrence {
+    pub entity_type: String,
+    pub slug: String,
+    pub name: String,
+    /// Number of files mentioning both
+    pub shared_files: u32,
+}
+
+/// Identifies the model that produced a set of embeddings.
+/// Vectors are only comparable when the whole signature matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingSignature {
+    pub provider: String,
+    pub model: String,
+    pub dimensions: usize,
 }
 
 /// Index status for the settings UI
@@ -28,6 +239,14 @@ pub struct IndexStatus {
     pub total_cost_usd: f64,
     pub is_indexing: bool,
     pub index_progress: Option<IndexProgress>,
+    /// Model the stored embeddings were made with, if any
+    pub embedding: Option<EmbeddingSignature>,
+    /// Set while the configured model differs from `embedding` and a re-embed is pending
+    pub reembed_progress: Option<ReembedProgress>,
+    /// How the vector index stores embeddings: "none", "int8" or "binary"
+    pub quantization: String,
+    /// Size of index.db on disk, including its write-ahead log
+    pub storage_bytes: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,3 +255,62 @@ pub struct IndexProgress {
     pub files_total: u32,
     pub current_file: String,
 }
+
+/// Dry-run result for a re-index: what would be embedded and roughly what it costs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReindexEstimate {
+    pub files_total: u32,
+    /// Files that are new or changed since they were last indexed
+    pub files_changed: u32,
+    /// Chunks in the changed files
+    pub chunks_total: u32,
+    /// Chunks that need a new vector; unchanged chunks keep theirs
+    pub chunks_to_embed: u32,
+    /// Tokens to embed, including any pending re-embed
+    pub tokens_to_embed: u64,
+    /// Chunks still waiting for a re-embed after an embedding model change
+    pub reembed_chunks: u32,
+    pub estimated_cost_usd: f64,
+    /// Configured embedding provider and model; None when none is usable
+    pub provider: Option<String>,
+    pub model: Option<String>,
+}
+
+/// Result of `verify_index`: problems found, and whether they were repaired.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexIntegrityReport {
+    /// Schema version of the index on disk; an older one is only checked once migrated
+    pub schema_version: u32,
+    /// Chunks, metadata, full text, vectors and staged embeddings whose chunk or file is gone
+    pub orphaned_rows: u32,
+    /// Indexed files no longer on disk
+    pub missing_files: Vec<String>,
+    /// Indexed files whose content changed since, or whose chunk rows don't match the record
+    pub changed_files: Vec<String>,
+    /// Chunks whose embedding isn't the size of the index's model
+    pub wrong_size_embeddings: u32,
+    /// Chunks with a usable embedding but no entry in the vector index
+    pub missing_vectors: u32,
+    pub repaired: bool,
+    /// Chunks embedded during the repair; unchanged chunks keep their vectors
+    pub chunks_reembedded: u32,
+}
+
+impl IndexIntegrityReport {
+    pub fn is_healthy(&self) -> bool {
+        self.schema_version == db::SCHEMA_VERSION
+            && self.orphaned_rows == 0
+            && self.missing_files.is_empty()
+            && self.changed_files.is_empty()
+            && self.wrong_size_embeddings == 0
+            && self.missing_vectors == 0
+    }
+}
+
+/// Progress of a background re-embed after the embedding model changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReembedProgress {
+    pub chunks_done: u32,
+    pub chunks_total: u32,
+    pub complete: bool,
+}
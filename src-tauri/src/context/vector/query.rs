@@ -0,0 +1,199 @@
+use crate::error::AppError;
+use super::SearchFilters;
+
+/// A search query split into the text to rank by and the filters to apply.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedQuery {
+    /// Free text, including the words of quoted phrases
+    pub text: String,
+    pub filters: SearchFilters,
+}
+
+/// Parse the search box syntax, e.g.
+/// `entity:character book:book-01 ch:3..7 "the broken mirror"`.
+///
+/// - `entity:` entity type, `type:` file type, `name:` entity slug, `status:` scene
+///   status — comma-separated values match any of them
+/// - `book:` book ID, `pov:` POV character
+/// - `ch:` chapter number or range (`3`, `3..7`, `3..`, `..7`)
+/// - `after:` modified on or after a date (`2025-06-01`)
+/// - `project:` linked project name, or `this` for the current project (comma-separated)
+/// - `"..."` an exact phrase the chunk must contain
+///
+/// Anything else, including unknown `key:value` words, is search text.
+pub fn parse_query(input: &str) -> Result<ParsedQuery, AppError> {
+    let mut parsed = ParsedQuery::default();
+    let mut words: Vec<String> = Vec::new();
+
+    for token in tokenize(input) {
+        if token.quoted {
+            if !token.value.trim().is_empty() {
+                words.push(token.value.clone());
+                parsed.filters.phrases.push(token.value);
+            }
+            continue;
+        }
+        let Some(key) = token.key else {
+            words.push(token.value);
+            continue;
+        };
+        let value = token.value.as_str();
+        let filters = &mut parsed.filters;
+        match key.to_lowercase().as_str() {
+            "entity" => filters.entity_types.extend(split_list(value)),
+            "type" => filters.file_types.extend(split_list(value)),
+            "name" => filters.entity_names.extend(split_list(value).map(|v| slugify(&v))),
+            "status" => filters.statuses.extend(split_list(value)),
+            "book" => filters.book_id = Some(value.to_string()),
+            "pov" => filters.pov_character = Some(slugify(value)),
+            "ch" | "chapter" => {
+                let (from, to) = parse_chapter_range(value)?;
+                filters.chapter_from = from;
+                filters.chapter_to = to;
+            }
+            "after" => filters.modified_after = Some(parse_date(value)?),
+            "project" => filters.projects.extend(split_list(value)),
+            _ => words.push(format!("{}:{}", key, value)),
+        }
+    }
+
+    parsed.text = words.join(" ");
+    Ok(parsed)
+}
+
+/// Lower-case a name and join its words with hyphens, matching the slugs stored in the index.
+pub fn slugify(name: &str) -> String {
+    name.trim().to_lowercase().replace(' ', "-")
+}
+
+struct Token {
+    key: Option<String>,
+    value: String,
+    /// A bare quoted phrase (a quoted filter value is not)
+    quoted: bool,
+}
+
+/// Split on whitespace, keeping quoted runs together. `key:"two words"` is one token.
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '"' {
+            chars.next();
+            let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+            tokens.push(Token { key: None, value: phrase, quoted: true });
+            continue;
+        }
+
+        let mut word = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+            chars.next();
+            if c == '"' && word.ends_with(':') {
+                let value: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                word.push_str(&value);
+                break;
+            }
+            word.push(c);
+        }
+
+        match word.split_once(':') {
+            Some((key, value)) if !key.is_empty() && !value.is_empty() => tokens.push(Token {
+                key: Some(key.to_string()),
+                value: value.to_string(),
+                quoted: false,
+            }),
+            _ => tokens.push(Token { key: None, value: word, quoted: false }),
+        }
+    }
+
+    tokens
+}
+
+fn split_list(value: &str) -> impl Iterator<Item = String> + '_ {
+    value.split(',').map(str::trim).filter(|v| !v.is_empty()).map(String::from)
+}
+
+/// Parse `3`, `3..7`, `3..` or `..7` (a `ch-` prefix is allowed on each bound).
+fn parse_chapter_range(value: &str) -> Result<(Option<u32>, Option<u32>), AppError> {
+    let invalid = || AppError::VectorSearch(format!("Invalid chapter range \"{}\" (expected e.g. 3 or 3..7)", value));
+    let bound = |s: &str| -> Result<Option<u32>, AppError> {
+        let s = s.trim();
+        let s = s.strip_prefix("ch-").unwrap_or(s);
+        if s.is_empty() {
+            return Ok(None);
+        }
+        s.parse::<u32>().map(Some).map_err(|_| invalid())
+    };
+
+    let (from, to) = match value.split_once("..") {
+        Some((from, to)) => (bound(from)?, bound(to)?),
+        None => {
+            let single = bound(value)?.ok_or_else(invalid)?;
+            (Some(single), Some(single))
+        }
+    };
+    if from.is_none() && to.is_none() {
+        return Err(invalid());
+    }
+    if let (Some(f), Some(t)) = (from, to) {
+        if f > t {
+            return Err(invalid());
+        }
+    }
+    Ok((from, to))
+}
+
+/// Accept a YYYY-MM-DD date or a full RFC 3339 timestamp.
+fn parse_date(value: &str) -> Result<String, AppError> {
+    if chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+        || chrono::DateTime::parse_from_rfc3339(value).is_ok()
+    {
+        Ok(value.to_string())
+    } else {
+        Err(AppError::VectorSearch(format!("Invalid date \"{}\" (expected YYYY-MM-DD)", value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query_syntax() {
+        let parsed = parse_query("entity:character book:book-01 ch:3..7 \"the broken mirror\" Sarah").unwrap();
+        assert_eq!(parsed.text, "the broken mirror Sarah");
+        assert_eq!(parsed.filters.entity_types, vec!["character"]);
+        assert_eq!(parsed.filters.book_id.as_deref(), Some("book-01"));
+        assert_eq!((parsed.filters.chapter_from, parsed.filters.chapter_to), (Some(3), Some(7)));
+        assert_eq!(parsed.filters.phrases, vec!["the broken mirror"]);
+    }
+
+    #[test]
+    fn test_parse_query_values() {
+        let parsed = parse_query("pov:\"Sarah Chen\" status:drafted,revised name:Okafor ch:..4 after:2025-06-01 time:3pm").unwrap();
+        assert_eq!(parsed.text, "time:3pm");
+        assert_eq!(parsed.filters.pov_character.as_deref(), Some("sarah-chen"));
+        assert_eq!(parsed.filters.statuses, vec!["drafted", "revised"]);
+        assert_eq!(parsed.filters.entity_names, vec!["okafor"]);
+        assert_eq!((parsed.filters.chapter_from, parsed.filters.chapter_to), (None, Some(4)));
+        assert_eq!(parsed.filters.modified_after.as_deref(), Some("2025-06-01"));
+        assert_eq!(parse_query("ch:5").unwrap().filters.chapter_to, Some(5));
+        assert_eq!(parse_query("project:this,shared-world").unwrap().filters.projects, vec!["this", "shared-world"]);
+    }
+
+    #[test]
+    fn test_parse_query_errors() {
+        assert!(parse_query("ch:7..3").is_err());
+        assert!(parse_query("ch:..").is_err());
+        assert!(parse_query("ch:three").is_err());
+        assert!(parse_query("after:June").is_err());
+    }
+}
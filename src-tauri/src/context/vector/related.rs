@@ -0,0 +1,262 @@
+use std::path::PathBuf;
+use std::collections::HashMap;
+
+use crate::error::AppError;
+use super::db;
+use super::embeddings;
+use super::search;
+use super::{RelatedContent, RelatedGroup, RelatedMode, SearchFilters, SearchResult, SimilarPassage};
+
+/// Passages at least this similar are reported as possible repetition.
+pub const DEFAULT_PASSAGE_THRESHOLD: f32 = 0.9;
+
+/// Neighbours looked up for each passage in similar-passages mode.
+const NEIGHBOURS_PER_PASSAGE: usize = 5;
+
+/// Find content related to an indexed file, using the embeddings already stored
+/// for it as the query — no embedding call is made. The file itself and files
+/// excluded by context settings are left out.
+///
+/// - `Related`: the mean of the file's passage vectors is matched against the index;
+///   each other file appears once (by its best passage), grouped by entity type
+/// - `SimilarPassages`: each passage is matched on its own, and passages elsewhere
+///   with cosine similarity ≥ `min_similarity` are reported, most similar first
+pub fn find_related(
+    project_dir: &PathBuf,
+    file_path: &str,
+    mode: RelatedMode,
+    max_results: u32,
+    min_similarity: f32,
+    excluded_files: &HashMap<String, String>,
+) -> Result<RelatedContent, AppError> {
+    let conn = db::open_index(project_dir)?;
+    db::init_schema(&conn)?;
+
+    let source_path = file_path.replace('\\', "/");
+    let chunks = db::get_file_chunks(&conn, &source_path)?;
+    if chunks.is_empty() {
+        return Err(AppError::IndexError(format!("{} is not indexed", source_path)));
+    }
+    // Frontmatter is near-identical across files of one kind, so it says little about content
+    let passages: Vec<(db::ChunkRow, Vec<f32>)> = chunks
+        .into_iter()
+        .filter(|c| !c.embedding.is_empty() && !is_frontmatter(c))
+        .map(|c| {
+            let vector = embeddings::bytes_to_embedding(&c.embedding);
+            (c, vector)
+        })
+        .collect();
+    if passages.is_empty() {
+        return Err(AppError::Embedding(format!(
+            "{} has no embeddings yet; re-index with an embedding provider",
+            source_path
+        )));
+    }
+
+    let passes_filters = |chunk: &db::ChunkRow| -> bool {
+        let path = chunk.file_path.replace('\\', "/");
+        path != source_path && !is_frontmatter(chunk) && !search::is_excluded_by_settings(&path, excluded_files)
+    };
+    let max_results = max_results.max(1) as usize;
+
+    match mode {
+        RelatedMode::Related => {
+            let vectors: Vec<&[f32]> = passages.iter().map(|(_, v)| v.as_slice()).collect();
+            let query = mean_direction(&vectors);
+            let ranked = search::semantic_ranking(
+                &conn,
+                &query,
+                max_results.saturating_mul(10).max(100),
+                &SearchFilters::default(),
+                &passes_filters,
+            )?;
+            Ok(RelatedContent {
+                groups: group_by_entity_type(&conn, ranked, max_results)?,
+                similar_passages: Vec::new(),
+            })
+        }
+        RelatedMode::SimilarPassages => {
+            let mut similar: Vec<(&db::ChunkRow, f32, db::ChunkRow)> = Vec::new();
+            for (source, vector) in &passages {
+                let ranked = search::semantic_ranking(
+                    &conn,
+                    vector,
+                    NEIGHBOURS_PER_PASSAGE,
+                    &SearchFilters::default(),
+                    &passes_filters,
+                )?;
+                similar.extend(
+                    ranked
+                        .into_iter()
+                        .filter(|(score, _)| *score >= min_similarity)
+                        .map(|(score, chunk)| (source, score, chunk)),
+                );
+            }
+            similar.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+            similar.truncate(max_results);
+
+            let ids: Vec<i64> = similar.iter().map(|(_, _, c)| c.id).collect();
+            let mut texts = db::get_chunk_texts(&conn, &ids)?;
+            let similar_passages = similar
+                .into_iter()
+                .map(|(source, score, chunk)| SimilarPassage {
+                    source_chunk_index: source.chunk_index,
+                    source_heading: source.section_heading.clone(),
+                    source_preview: source.content_preview.clone(),
+                    passage: to_result(score, chunk, &mut texts),
+                })
+                .collect();
+            Ok(RelatedContent {
+                groups: Vec::new(),
+                similar_passages,
+            })
+        }
+    }
+}
+
+/// Keep each file's best passage, group files by entity type (up to `per_group`
+/// each) and order the groups by their best match.
+fn group_by_entity_type(
+    conn: &rusqlite::Connection,
+    ranked: Vec<(f32, db::ChunkRow)>,
+    per_group: usize,
+) -> Result<Vec<RelatedGroup>, AppError> {
+    let mut seen_files = std::collections::HashSet::new();
+    let mut grouped: Vec<(String, Vec<(f32, db::ChunkRow)>)> = Vec::new();
+
+    for (score, chunk) in ranked {
+        if !seen_files.insert(chunk.file_path.clone()) {
+            continue;
+        }
+        let entity_type = chunk.entity_type.clone().unwrap_or_else(|| "other".to_string());
+        let members = match grouped.iter().position(|(t, _)| *t == entity_type) {
+            Some(i) => &mut grouped[i].1,
+            None => {
+                grouped.push((entity_type, Vec::new()));
+                &mut grouped.last_mut().unwrap().1
+            }
+        };
+        if members.len() < per_group {
+            members.push((score, chunk));
+        }
+    }
+
+    let ids: Vec<i64> = grouped.iter().flat_map(|(_, m)| m.iter().map(|(_, c)| c.id)).collect();
+    let mut texts = db::get_chunk_texts(conn, &ids)?;
+    Ok(grouped
+        .into_iter()
+        .map(|(entity_type, members)| RelatedGroup {
+            entity_type,
+            results: members.into_iter().map(|(score, chunk)| to_result(score, chunk, &mut texts)).collect(),
+        })
+        .collect())
+}
+
+/// Normalised mean of unit vectors — the direction the passages share.
+fn mean_direction(vectors: &[&[f32]]) -> Vec<f32> {
+    let dims = vectors.first().map(|v| v.len()).unwrap_or(0);
+    let mut mean = vec![0.0f32; dims];
+    for vector in vectors.iter().filter(|v| v.len() == dims) {
+        let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for (m, x) in mean.iter_mut().zip(vector.iter()) {
+                *m += x / norm;
+            }
+        }
+    }
+    let norm = mean.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        mean.iter_mut().for_each(|x| *x /= norm);
+    }
+    mean
+}
+
+fn is_frontmatter(chunk: &db::ChunkRow) -> bool {
+    chunk.chunk_index == 0 && chunk.section_heading.is_none() && chunk.content_preview.starts_with("---")
+}
+
+fn to_result(score: f32, chunk: db::ChunkRow, texts: &mut HashMap<i64, String>) -> SearchResult {
+    let content = texts.remove(&chunk.id).unwrap_or_default();
+    SearchResult {
+        file_path: chunk.file_path.replace('\\', "/"),
+        section_heading: chunk.section_heading,
+        similarity_score: score,
+        content_preview: chunk.content_preview,
+        content,
+        chunk_index: chunk.chunk_index,
+        token_count: chunk.token_count as u64,
+        entity_type: chunk.entity_type,
+        entity_name: chunk.entity_name,
+        book_id: chunk.book_id,
+        project: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::vector::chunker;
+    use crate::context::vector::embeddings::{EmbeddingClient, LocalEmbeddingClient};
+
+    async fn seeded_project(files: &[(&str, &str)]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("saipling-related-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = db::open_index(&dir).unwrap();
+        db::init_schema(&conn).unwrap();
+        let client = LocalEmbeddingClient::new();
+
+        for (path, content) in files {
+            let chunks = chunker::chunk_file(content, path);
+            let texts: Vec<String> = chunks.iter().map(|c| c.content.clone()).collect();
+            let vectors = client.embed_batch(&texts).await.unwrap();
+            db::upsert_indexed_file(&conn, path, "h", &chunks[0].metadata.file_type, chunks.len() as u32).unwrap();
+            for (chunk, vector) in chunks.iter().zip(vectors) {
+                let bytes = embeddings::embedding_to_bytes(&vector);
+                let id = db::insert_chunk(&conn, path, chunk.chunk_index, chunk.section_heading.as_deref(), "c", &chunk.content_preview, chunk.token_count, &bytes).unwrap();
+                db::insert_chunk_metadata(&conn, id, &chunk.metadata).unwrap();
+                db::insert_chunk_text(&conn, id, chunk.section_heading.as_deref(), &chunk.content).unwrap();
+                db::insert_chunk_vector(&conn, id, &bytes, chunk.metadata.book_id.as_deref(), chunk.metadata.entity_type.as_deref()).unwrap();
+            }
+        }
+        dir
+    }
+
+    const TAVERN: &str = "The Gilded Anchor tavern smelled of tar and spilled ale, its low beams blackened by a century of pipe smoke and its floorboards warped from the harbour damp.";
+
+    #[tokio::test]
+    async fn test_related_and_similar_passages() {
+        let dir = seeded_project(&[
+            ("books/book-01/chapters/ch-01/scene-01/draft.md", &format!("## Arrival\n\n{}\n\n## Meeting\n\nMarta the innkeeper polishes glasses behind the bar.", TAVERN)),
+            ("books/book-01/chapters/ch-07/scene-02/draft.md", &format!("## Return\n\n{} Nothing had changed.", TAVERN)),
+            ("characters/marta/profile.md", "## Role\n\nMarta is the innkeeper of the Gilded Anchor tavern by the harbour, polishing glasses behind the bar."),
+            ("world/places/gilded-anchor/entry.md", "## Description\n\nThe Gilded Anchor is a harbour tavern with blackened beams, pipe smoke and spilled ale."),
+            ("notes/space.md", "## Idea\n\nA starship crew discovers a derelict orbital station near Jupiter."),
+        ]).await;
+        let source = "books/book-01/chapters/ch-01/scene-01/draft.md";
+        let none = HashMap::new();
+
+        let related = find_related(&dir, source, RelatedMode::Related, 5, DEFAULT_PASSAGE_THRESHOLD, &none).unwrap();
+        let files: Vec<&str> = related.groups.iter().flat_map(|g| g.results.iter().map(|r| r.file_path.as_str())).collect();
+        assert!(!files.contains(&source));
+        assert!(files.contains(&"characters/marta/profile.md"));
+        assert!(files.contains(&"world/places/gilded-anchor/entry.md"));
+        let types: Vec<&str> = related.groups.iter().map(|g| g.entity_type.as_str()).collect();
+        assert!(types.contains(&"character") && types.contains(&"world") && types.contains(&"scene_draft"));
+        assert_eq!(related.groups[0].results[0].file_path, "books/book-01/chapters/ch-07/scene-02/draft.md");
+
+        let similar = find_related(&dir, source, RelatedMode::SimilarPassages, 5, DEFAULT_PASSAGE_THRESHOLD, &none).unwrap();
+        assert_eq!(similar.similar_passages.len(), 1, "{:?}", similar.similar_passages);
+        let hit = &similar.similar_passages[0];
+        assert_eq!(hit.source_heading.as_deref(), Some("## Arrival"));
+        assert_eq!(hit.passage.file_path, "books/book-01/chapters/ch-07/scene-02/draft.md");
+        assert!(hit.passage.content.contains("Nothing had changed"));
+
+        // Excluded files are left out
+        let excluded = HashMap::from([("books/book-01/chapters/ch-07/scene-02/draft.md".to_string(), "exclude".to_string())]);
+        let similar = find_related(&dir, source, RelatedMode::SimilarPassages, 5, DEFAULT_PASSAGE_THRESHOLD, &excluded).unwrap();
+        assert!(similar.similar_passages.is_empty());
+
+        assert!(find_related(&dir, "notes/missing.md", RelatedMode::Related, 5, 0.9, &none).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::{HashMap, HashSet};
+use async_trait::async_trait;
+
+use crate::error::AppError;
+use super::EmbeddingConfig;
+use super::db::ChunkRow;
+use super::embeddings::{self, cosine_similarity};
+
+/// Reranker names accepted in `SkillVectorSearchConfig.reranker`,
+/// besides Voyage rerank models (`rerank-*`).
+pub const BUILTIN_RERANKERS: &[&str] = &["none", "lexical"];
+
+/// Re-scores candidate passages against the query, typically with a cross-encoder
+/// that reads query and passage together. Runs on the top candidates only.
+#[async_trait]
+pub trait Reranker: Send + Sync {
+    /// One relevance score per document (higher is better), in input order.
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f32>, AppError>;
+}
+
+/// Voyage AI rerank endpoint (rerank-2.5, rerank-2.5-lite, ...)
+pub struct VoyageReranker {
+    api_key: String,
+    model: String,
+    http_client: reqwest::Client,
+}
+
+impl VoyageReranker {
+    pub fn new(api_key: String, model: String) -> Self {
+        Self {
+            api_key,
+            model,
+            http_client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl Reranker for VoyageReranker {
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f32>, AppError> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let body = serde_json::json!({
+            "model": self.model,
+            "query": query,
+            "documents": documents,
+        });
+
+        let resp = self.http_client
+            .post("https://api.voyageai.com/v1/rerank")
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| AppError::VectorSearch(format!("Voyage rerank request failed: {}", e)))?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let error_body = resp.text().await.unwrap_or_default();
+            return Err(AppError::VectorSearch(format!(
+                "Voyage rerank error ({}): {}",
+                status, error_body
+            )));
+        }
+
+        let json: serde_json::Value = resp
+            .json()
+            .await
+            .map_err(|e| AppError::VectorSearch(format!("Failed to parse Voyage rerank response: {}", e)))?;
+
+        let data = json
+            .get("data")
+            .and_then(|d| d.as_array())
+            .ok_or_else(|| AppError::VectorSearch("Missing 'data' array in Voyage rerank response".into()))?;
+
+        let mut scores = vec![0.0f32; documents.len()];
+        for item in data {
+            let index = item.get("index").and_then(|i| i.as_u64()).unwrap_or(u64::MAX) as usize;
+            let score = item.get("relevance_score").and_then(|s| s.as_f64()).unwrap_or(0.0);
+            if let Some(slot) = scores.get_mut(index) {
+                *slot = score as f32;
+            }
+        }
+        Ok(scores)
+    }
+}
+
+/// Offline reranker: the share of distinct query words that appear in the passage.
+/// Useful without network access to push passages that mention every name in the
+/// query above ones that only match part of it.
+pub struct TermOverlapReranker;
+
+#[async_trait]
+impl Reranker for TermOverlapReranker {
+    async fn rerank(&self, query: &str, documents: &[String]) -> Result<Vec<f32>, AppError> {
+        let terms = word_set(query);
+        if terms.is_empty() {
+            return Ok(vec![0.0; documents.len()]);
+        }
+        Ok(documents
+            .iter()
+            .map(|doc| {
+                let words = word_set(doc);
+                terms.iter().filter(|t| words.contains(*t)).count() as f32 / terms.len() as f32
+            })
+            .collect())
+    }
+}
+
+/// Whether `name` is a reranker `reranker_from_name` understands.
+pub fn is_known_reranker(name: &str) -> bool {
+    BUILTIN_RERANKERS.contains(&name) || name.starts_with("rerank-")
+}
+
+/// Build the reranker named in a skill config. Returns `Ok(None)` for "none", and for
+/// Voyage models when no Voyage key is configured.
+pub fn reranker_from_name(
+    name: &str,
+    config: &EmbeddingConfig,
+) -> Result<Option<Box<dyn Reranker>>, AppError> {
+    match name {
+        "" | "none" => Ok(None),
+        "lexical" => Ok(Some(Box::new(TermOverlapReranker))),
+        model if model.starts_with("rerank-") => {
+            if config.provider != "voyage" {
+                return Ok(None);
+            }
+            Ok(config.api_key.clone().map(|key| {
+                Box::new(VoyageReranker::new(key, model.to_string())) as Box<dyn Reranker>
+            }))
+        }
+        other => Err(AppError::Config(format!("Unknown reranker: {}", other))),
+    }
+}
+
+/// How ranked search results are post-processed before being returned.
+/// The default passes results through unchanged.
+pub struct ResultShaping {
+    /// MMR trade-off: 1.0 ranks purely by relevance, lower values favour variety.
+    pub mmr_lambda: f32,
+    /// Most chunks returned from one file (0 = no cap).
+    pub max_per_file: u32,
+    /// Merge chunks that sit next to each other in the same file into one excerpt.
+    pub merge_adjacent: bool,
+    pub reranker: Option<Box<dyn Reranker>>,
+}
+
+impl Default for ResultShaping {
+    fn default() -> Self {
+        Self {
+            mmr_lambda: 1.0,
+            max_per_file: 0,
+            merge_adjacent: false,
+            reranker: None,
+        }
+    }
+}
+
+impl ResultShaping {
+    /// Whether any stage changes the plain top-K.
+    pub fn is_identity(&self) -> bool {
+        self.mmr_lambda >= 1.0 && self.max_per_file == 0 && !self.merge_adjacent && self.reranker.is_none()
+    }
+}
+
+/// A search candidate with its full chunk text.
+pub struct Candidate {
+    pub score: f32,
+    pub chunk: ChunkRow,
+    pub content: String,
+}
+
+/// Re-score candidates with `reranker` and sort them by the new scores.
+/// On error the candidates are left as they were.
+pub async fn apply_reranker(
+    reranker: &dyn Reranker,
+    query: &str,
+    candidates: &mut [Candidate],
+) -> Result<(), AppError> {
+    let documents: Vec<String> = candidates.iter().map(|c| c.content.clone()).collect();
+    let scores = reranker.rerank(query, &documents).await?;
+    for (candidate, score) in candidates.iter_mut().zip(scores) {
+        candidate.score = score;
+    }
+    candidates.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(())
+}
+
+/// Pick up to `k` candidates by maximal marginal relevance: each step takes the
+/// candidate maximising `λ·relevance − (1−λ)·max similarity to those already picked`,
+/// skipping files that already have `max_per_file` picks. Similarity is cosine over
+/// the chunk embeddings when both have one, word-set overlap otherwise.
+pub fn select_diverse(
+    candidates: Vec<Candidate>,
+    embeddings_by_id: &HashMap<i64, Vec<u8>>,
+    mmr_lambda: f32,
+    max_per_file: u32,
+    k: usize,
+) -> Vec<Candidate> {
+    let vectors: Vec<Option<Vec<f32>>> = candidates
+        .iter()
+        .map(|c| {
+            embeddings_by_id
+                .get(&c.chunk.id)
+                .filter(|b| !b.is_empty())
+                .map(|b| embeddings::bytes_to_embedding(b))
+        })
+        .collect();
+    let words: Vec<HashSet<String>> = if mmr_lambda < 1.0 {
+        candidates.iter().map(|c| word_set(&c.content)).collect()
+    } else {
+        Vec::new()
+    };
+    let similarity = |a: usize, b: usize| -> f32 {
+        match (&vectors[a], &vectors[b]) {
+            (Some(va), Some(vb)) if va.len() == vb.len() => cosine_similarity(va, vb),
+            _ => jaccard(&words[a], &words[b]),
+        }
+    };
+
+    let mut picked: Vec<usize> = Vec::new();
+    let mut per_file: HashMap<&str, u32> = HashMap::new();
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+
+    while picked.len() < k {
+        let mut best: Option<(usize, f32)> = None;
+        for (pos, &i) in remaining.iter().enumerate() {
+            let file = candidates[i].chunk.file_path.as_str();
+            if max_per_file > 0 && per_file.get(file).copied().unwrap_or(0) >= max_per_file {
+                continue;
+            }
+            let score = if mmr_lambda >= 1.0 || picked.is_empty() {
+                candidates[i].score
+            } else {
+                let redundancy = picked.iter().map(|&j| similarity(i, j)).fold(f32::MIN, f32::max);
+                mmr_lambda * candidates[i].score - (1.0 - mmr_lambda) * redundancy
+            };
+            if best.map(|(_, s)| score > s).unwrap_or(true) {
+                best = Some((pos, score));
+            }
+        }
+        let Some((pos, _)) = best else { break };
+        let i = remaining.remove(pos);
+        *per_file.entry(candidates[i].chunk.file_path.as_str()).or_insert(0) += 1;
+        picked.push(i);
+    }
+
+    let mut slots: Vec<Option<Candidate>> = candidates.into_iter().map(Some).collect();
+    picked.into_iter().filter_map(|i| slots[i].take()).collect()
+}
+
+/// Merge picked chunks that are consecutive in the same file into one excerpt,
+/// joining both their full text and their previews. The merged excerpt takes
+/// the place (and best score) of its highest-ranked part.
+pub fn merge_adjacent(candidates: Vec<Candidate>) -> Vec<Candidate> {
+    // Group by file, remembering each chunk's rank
+    let mut by_file: HashMap<String, Vec<(usize, Candidate)>> = HashMap::new();
+    for (rank, c) in candidates.into_iter().enumerate() {
+        by_file.entry(c.chunk.file_path.clone()).or_default().push((rank, c));
+    }
+
+    // (best rank, last chunk index in the run, merged candidate)
+    let mut merged: Vec<(usize, u32, Candidate)> = Vec::new();
+    for (_, mut parts) in by_file {
+        parts.sort_by_key(|(_, c)| c.chunk.chunk_index);
+        let mut current: Option<(usize, u32, Candidate)> = None;
+        for (rank, part) in parts {
+            let index = part.chunk.chunk_index;
+            current = match current {
+                Some((best_rank, last, mut run)) if index == last + 1 => {
+                    run.content = format!("{}\n\n{}", run.content, part.content);
+                    run.chunk.content_preview =
+                        format!("{}\n\n{}", run.chunk.content_preview, part.chunk.content_preview);
+                    run.chunk.token_count += part.chunk.token_count;
+                    run.score = run.score.max(part.score);
+                    Some((best_rank.min(rank), index, run))
+                }
+                previous => {
+                    merged.extend(previous);
+                    Some((rank, index, part))
+                }
+            };
+        }
+        merged.extend(current);
+    }
+
+    merged.sort_by_key(|(rank, _, _)| *rank);
+    merged.into_iter().map(|(_, _, c)| c).collect()
+}
+
+/// Lower-cased words of a text.
+fn word_set(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f32 / union as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: i64, file: &str, index: u32, score: f32, content: &str) -> Candidate {
+        Candidate {
+            score,
+            chunk: ChunkRow {
+                id,
+                file_path: file.to_string(),
+                chunk_index: index,
+                section_heading: None,
+                content_preview: content.to_string(),
+                token_count: 10,
+                embedding: Vec::new(),
+                book_id: None,
+                entity_type: None,
+                entity_name: None,
+            },
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_mmr_prefers_variety() {
+        let candidates = vec![
+            candidate(1, "a.md", 0, 1.0, "the gate of vhaelri opens at dawn"),
+            candidate(2, "b.md", 0, 0.95, "the gate of vhaelri opens at dawn"),
+            candidate(3, "c.md", 0, 0.9, "mira remembers her brother"),
+        ];
+        let picked = select_diverse(candidates, &HashMap::new(), 0.5, 0, 2);
+        let ids: Vec<i64> = picked.iter().map(|c| c.chunk.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+
+        let candidates = vec![
+            candidate(1, "a.md", 0, 1.0, "the gate of vhaelri opens at dawn"),
+            candidate(2, "b.md", 0, 0.95, "the gate of vhaelri opens at dawn"),
+            candidate(3, "c.md", 0, 0.9, "mira remembers her brother"),
+        ];
+        let picked = select_diverse(candidates, &HashMap::new(), 1.0, 0, 2);
+        let ids: Vec<i64> = picked.iter().map(|c| c.chunk.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_per_file_cap() {
+        let candidates = vec![
+            candidate(1, "a.md", 0, 1.0, "one"),
+            candidate(2, "a.md", 1, 0.9, "two"),
+            candidate(3, "a.md", 2, 0.8, "three"),
+            candidate(4, "b.md", 0, 0.1, "four"),
+        ];
+        let picked = select_diverse(candidates, &HashMap::new(), 1.0, 2, 4);
+        let ids: Vec<i64> = picked.iter().map(|c| c.chunk.id).collect();
+        assert_eq!(ids, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_merge_adjacent() {
+        let merged = merge_adjacent(vec![
+            candidate(3, "a.md", 3, 0.9, "third"),
+            candidate(9, "b.md", 0, 0.8, "other"),
+            candidate(2, "a.md", 2, 0.7, "second"),
+            candidate(5, "a.md", 5, 0.6, "fifth"),
+        ]);
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged[0].chunk.chunk_index, 2);
+        assert_eq!(merged[0].content, "second\n\nthird");
+        assert_eq!(merged[0].chunk.content_preview, "second\n\nthird");
+        assert_eq!(merged[0].chunk.token_count, 20);
+        assert_eq!(merged[0].score, 0.9);
+        assert_eq!(merged[1].chunk.file_path, "b.md");
+        assert_eq!(merged[2].content, "fifth");
+    }
+
+    #[tokio::test]
+    async fn test_term_overlap_reranker() {
+        let mut candidates = vec![
+            candidate(1, "a.md", 0, 1.0, "Mira walks alone"),
+            candidate(2, "b.md", 0, 0.5, "Mira and Tobin reach the gate"),
+        ];
+        apply_reranker(&TermOverlapReranker, "Mira Tobin gate", &mut candidates).await.unwrap();
+        assert_eq!(candidates[0].chunk.id, 2);
+        assert_eq!(candidates[0].score, 1.0);
+    }
+}
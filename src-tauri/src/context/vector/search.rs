@@ -3,101 +3,788 @@ use std::collections::HashMap;
 
 use crate::error::AppError;
 use super::db;
+use super::indexer;
 use super::embeddings::{self, EmbeddingClient, cosine_similarity};
-use super::SearchResult;
+use super::rerank::{self, Candidate, ResultShaping};
+use super::{Quantization, SearchFilters, SearchMode, SearchResult};
 
-/// Perform a semantic search across the project index.
+/// Reciprocal-rank fusion constant (the usual k = 60 from Cormack et al.).
+const RRF_K: f32 = 60.0;
+
+/// Largest `k` sqlite-vec accepts in a single KNN query.
+const VEC_MAX_K: usize = 4096;
+
+/// How many times a filtered KNN lookup widens before settling for what survived.
+const MAX_WIDENINGS: u32 = 2;
+
+/// Name of the current project in `SearchFilters::projects`.
+pub const THIS_PROJECT: &str = "this";
+
+/// Perform a lexical, semantic or hybrid search across the project index and
+/// the indexes of linked projects (see `ProjectMetadata::linked_projects`).
+///
+/// Steps, for each index:
+/// 1. Lexical: BM25 over the FTS5 index (no API call)
+/// 2. Semantic: look up the query embedding's nearest chunks in the
+///    sqlite-vec index (book/entity filters applied during the lookup)
+/// 3. Apply filters: `filters` in SQL, then context-settings exclusions
+/// 4. Hybrid: merge both rankings with reciprocal-rank fusion
+/// 5. Shape a wider pool per `shaping`: rerank, diversify with MMR under a
+///    per-file cap, then merge adjacent chunks of the same file
+/// 6. Return top-K results, scores normalised to 0–1 within each index
 ///
-/// Steps:
-/// 1. Embed the query via the EmbeddingClient
-/// 2. Load all chunk embeddings from SQLite
-/// 3. Compute cosine similarity (brute-force for now; sqlite-vec ANN later)
-/// 4. Apply filters (entity_type, book_id)
-/// 5. Return top-K results sorted by similarity
+/// The query is embedded once. Linked indexes are opened read-only and filtered by
+/// their own context settings; one made with another embedding model is searched
+/// lexically only. Results from all indexes are merged by rank with reciprocal-rank
+/// fusion, since their scores aren't comparable, and tagged with their project.
 pub async fn search(
     project_dir: &PathBuf,
     query: &str,
-    client: &dyn EmbeddingClient,
+    client: Option<&dyn EmbeddingClient>,
+    mode: SearchMode,
     max_results: u32,
-    filter_entity_types: &[String],
-    filter_book_id: Option<&str>,
+    filters: &SearchFilters,
     excluded_files: &HashMap<String, String>,
     already_loaded: &std::collections::HashSet<String>,
+    shaping: &ResultShaping,
 ) -> Result<Vec<SearchResult>, AppError> {
-    // 1. Embed the query
-    let query_embedding = client.embed_query(query).await?;
+    let query_embedding = if mode == SearchMode::Lexical {
+        None
+    } else {
+        let client = client.ok_or_else(|| {
+            AppError::Embedding("Semantic search needs an embedding provider; use lexical mode instead".into())
+        })?;
+        Some(client.embed_query(query).await?)
+    };
+    let params = SearchParams {
+        query,
+        query_embedding: query_embedding.as_deref(),
+        mode,
+        max_results,
+        filters,
+        shaping,
+    };
+    let wants = |project: &str| filters.projects.is_empty() || filters.projects.iter().any(|p| p == project);
 
-    // 2. Load all chunks
-    let conn = db::open_index(project_dir)?;
-    db::init_schema(&conn)?;
-    let all_chunks = db::get_all_chunks_for_search(&conn)?;
-
-    if all_chunks.is_empty() {
-        return Ok(Vec::new());
+    let mut per_index: Vec<Vec<SearchResult>> = Vec::new();
+    if wants(THIS_PROJECT) {
+        let conn = db::open_index(project_dir)?;
+        db::init_schema(&conn)?;
+        let passes_filters = |chunk: &db::ChunkRow| -> bool {
+            let rel_path_fwd = chunk.file_path.replace('\\', "/");
+            // Excluded files, and files already loaded by the deterministic assembler
+            !is_excluded_by_settings(&rel_path_fwd, excluded_files) && !already_loaded.contains(&rel_path_fwd)
+        };
+        let filters = resolve_chapters(project_dir, filters);
+        let params = SearchParams { filters: &filters, ..params.clone() };
+        per_index.push(search_index(conn, &params, &passes_filters).await?);
     }
 
-    // 3. Score each chunk
-    let mut scored: Vec<(f32, &db::ChunkRow)> = Vec::with_capacity(all_chunks.len());
-
-    for chunk in &all_chunks {
-        // Filter: excluded files
-        let rel_path_fwd = chunk.file_path.replace('\\', "/");
-        if is_excluded_by_settings(&rel_path_fwd, excluded_files) {
+    for (name, dir) in crate::commands::project::linked_project_dirs(project_dir) {
+        if !wants(&name) {
             continue;
         }
-
-        // Filter: already loaded by deterministic assembler
-        if already_loaded.contains(&rel_path_fwd) {
-            continue;
+        let conn = match db::open_index_read_only(&dir) {
+            Ok(Some(conn)) => conn,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("Warning: can't search linked project {}: {}", name, e);
+                continue;
+            }
+        };
+        let linked_settings = crate::context::assembler::load_context_settings(&dir);
+        let passes_filters = |chunk: &db::ChunkRow| -> bool {
+            let rel_path_fwd = chunk.file_path.replace('\\', "/");
+            !is_excluded_by_settings(&rel_path_fwd, &linked_settings)
+                && !already_loaded.contains(&format!("@{}/{}", name, rel_path_fwd))
+        };
+        // Vectors from another model can't be compared with the query's
+        let comparable = client.is_some_and(|c| matches!(indexer::embedding_mismatch(&conn, c), Ok(None)));
+        let linked_filters = resolve_chapters(&dir, filters);
+        let linked_params = SearchParams { filters: &linked_filters, ..params.clone() };
+        let linked_params = match (mode, comparable) {
+            (SearchMode::Lexical, _) | (_, true) => linked_params,
+            (SearchMode::Hybrid, false) => SearchParams { mode: SearchMode::Lexical, query_embedding: None, ..linked_params },
+            (SearchMode::Semantic, false) => continue,
+        };
+        match search_index(conn, &linked_params, &passes_filters).await {
+            Ok(found) => {
+                per_index.push(found.into_iter().map(|r| SearchResult { project: Some(name.clone()), ..r }).collect())
+            }
+            Err(e) => eprintln!("Warning: search of linked project {} failed: {}", name, e),
         }
+    }
 
-        // Filter: entity type
-        if !filter_entity_types.is_empty() {
-            if let Some(ref et) = chunk.entity_type {
-                if !filter_entity_types.iter().any(|f| f == et) {
-                    continue;
-                }
-            } else {
-                continue;
+    Ok(merge_indexes(per_index, max_results as usize))
+}
+
+/// `filters` with its chapter range resolved to the chapters of `project_dir` that
+/// fall in it, numbered by their place in reading order.
+pub fn resolve_chapters(project_dir: &std::path::Path, filters: &SearchFilters) -> SearchFilters {
+    let mut resolved = filters.clone();
+    if filters.chapter_from.is_some() || filters.chapter_to.is_some() {
+        resolved.chapters = crate::book_repo::ReadingOrder::load(project_dir)
+            .chapters_in_range(filters.chapter_from, filters.chapter_to);
+    }
+    resolved
+}
+
+/// Interleave the results of several indexes by reciprocal rank. Each index's scores
+/// are relative to its own best match, so they order results within it but not across
+/// indexes; results keep them for display. Ties keep this project first.
+fn merge_indexes(per_index: Vec<Vec<SearchResult>>, max_results: usize) -> Vec<SearchResult> {
+    let mut fused: Vec<(f32, SearchResult)> = per_index
+        .into_iter()
+        .flat_map(|results| {
+            results
+                .into_iter()
+                .enumerate()
+                .map(|(rank, result)| (1.0 / (RRF_K + rank as f32 + 1.0), result))
+        })
+        .collect();
+    fused.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    fused.into_iter().take(max_results).map(|(_, result)| result).collect()
+}
+
+/// What to search for, shared by every index searched.
+#[derive(Clone)]
+struct SearchParams<'a> {
+    query: &'a str,
+    /// Set unless the mode is lexical
+    query_embedding: Option<&'a [f32]>,
+    mode: SearchMode,
+    max_results: u32,
+    filters: &'a SearchFilters,
+    shaping: &'a ResultShaping,
+}
+
+/// Rank, shape and convert the results of one index. Takes the connection by
+/// value: a borrowed one held across the rerank call would make the future `!Send`.
+async fn search_index(
+    conn: rusqlite::Connection,
+    params: &SearchParams<'_>,
+    passes_filters: &(dyn Fn(&db::ChunkRow) -> bool + Sync),
+) -> Result<Vec<SearchResult>, AppError> {
+    let SearchParams { query, query_embedding, mode, max_results, filters, shaping } = params.clone();
+
+    // 1. Lexical ranking
+    let lexical: Vec<(f32, db::ChunkRow)> = if mode == SearchMode::Semantic {
+        Vec::new()
+    } else {
+        match build_fts_query(query) {
+            Some(fts_query) => {
+                let rows = db::lexical_search(&conn, &fts_query, max_results.saturating_mul(20).max(100), filters)?;
+                let filtered: Vec<(db::ChunkRow, f64)> =
+                    rows.into_iter().filter(|(c, _)| passes_filters(c)).collect();
+                // bm25() is negative, best first — normalise relative to the best match
+                let best = filtered.first().map(|(_, s)| *s).unwrap_or(0.0);
+                filtered
+                    .into_iter()
+                    .map(|(c, s)| {
+                        let score = if best < 0.0 { (s / best) as f32 } else { 1.0 };
+                        (score, c)
+                    })
+                    .collect()
             }
+            None => Vec::new(),
         }
+    };
 
-        // Filter: book scope
-        if let Some(book_filter) = filter_book_id {
-            if chunk.book_id.as_deref() != Some(book_filter) {
-                continue;
+    // 2. Semantic ranking
+    let semantic: Vec<(f32, db::ChunkRow)> = match query_embedding {
+        Some(query_embedding) if mode != SearchMode::Lexical => {
+            let candidates = max_results.saturating_mul(20).max(100) as usize;
+
+            match semantic_ranking(&conn, query_embedding, candidates, filters, passes_filters) {
+                Ok(ranked) => ranked,
+                Err(e) => {
+                    eprintln!("Warning: vector index lookup failed, scanning all chunks: {}", e);
+                    brute_force_ranking(&conn, query_embedding, filters, passes_filters)?
+                }
             }
         }
+        _ => Vec::new(),
+    };
 
-        // Compute similarity
-        let chunk_embedding = embeddings::bytes_to_embedding(&chunk.embedding);
-        let score = cosine_similarity(&query_embedding, &chunk_embedding);
-        scored.push((score, chunk));
-    }
+    // 3. Merge
+    let ranked = match mode {
+        SearchMode::Lexical => lexical,
+        SearchMode::Semantic => semantic,
+        SearchMode::Hybrid => reciprocal_rank_fusion(vec![lexical, semantic]),
+    };
 
-    // 4. Sort by similarity descending
-    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    // 4. Take a candidate pool — wider when it will be reranked or diversified
+    let pool_size = if shaping.is_identity() {
+        max_results as usize
+    } else {
+        (max_results as usize).saturating_mul(4).max(20)
+    };
+    let pool: Vec<(f32, db::ChunkRow)> = ranked.into_iter().take(pool_size).collect();
+    let ids: Vec<i64> = pool.iter().map(|(_, c)| c.id).collect();
+    let mut texts = db::get_chunk_texts(&conn, &ids)?;
+    let mut candidates: Vec<Candidate> = pool
+        .into_iter()
+        .map(|(score, chunk)| {
+            let content = texts.remove(&chunk.id).unwrap_or_else(|| chunk.content_preview.clone());
+            Candidate { score, chunk, content }
+        })
+        .collect();
+
+    // 5. Shape
+    if let Some(ref reranker) = shaping.reranker {
+        if let Err(e) = rerank::apply_reranker(reranker.as_ref(), query, &mut candidates).await {
+            eprintln!("Warning: reranking failed, keeping original order: {}", e);
+        }
+    }
+    let embeddings_by_id = if shaping.mmr_lambda < 1.0 {
+        let ids: Vec<i64> = candidates.iter().map(|c| c.chunk.id).collect();
+        db::get_chunk_embeddings(&conn, &ids)?
+    } else {
+        HashMap::new()
+    };
+    let mut selected = rerank::select_diverse(
+        candidates,
+        &embeddings_by_id,
+        shaping.mmr_lambda,
+        shaping.max_per_file,
+        max_results as usize,
+    );
+    if shaping.merge_adjacent {
+        selected = rerank::merge_adjacent(selected);
+    }
 
-    // 5. Take top-K
-    let results: Vec<SearchResult> = scored
+    // 6. Convert
+    let results: Vec<SearchResult> = selected
         .into_iter()
-        .take(max_results as usize)
-        .map(|(score, chunk)| SearchResult {
+        .map(|Candidate { score, chunk, content }| SearchResult {
             file_path: chunk.file_path.replace('\\', "/"),
-            section_heading: chunk.section_heading.clone(),
+            section_heading: chunk.section_heading,
             similarity_score: score,
-            content_preview: chunk.content_preview.clone(),
+            content_preview: chunk.content_preview,
+            content,
+            chunk_index: chunk.chunk_index,
             token_count: chunk.token_count as u64,
-            entity_type: chunk.entity_type.clone(),
-            entity_name: chunk.entity_name.clone(),
-            book_id: chunk.book_id.clone(),
+            entity_type: chunk.entity_type,
+            entity_name: chunk.entity_name,
+            book_id: chunk.book_id,
+            project: None,
         })
         .collect();
 
     Ok(results)
 }
 
+/// Nearest chunks to the query via the sqlite-vec index, best first, scored by
+/// cosine similarity. Book and entity filters run inside the KNN lookup; the other
+/// filters and file exclusions can't, so the lookup widens (at most `MAX_WIDENINGS`
+/// times) until `wanted` chunks survive them or the index runs out. A quantized index is searched with oversampling and
+/// the candidates are rescored against the full-precision vectors.
+pub fn semantic_ranking(
+    conn: &rusqlite::Connection,
+    query_embedding: &[f32],
+    wanted: usize,
+    filters: &SearchFilters,
+    passes_filters: &dyn Fn(&db::ChunkRow) -> bool,
+) -> Result<Vec<(f32, db::ChunkRow)>, AppError> {
+    db::ensure_vector_index(conn)?;
+    let quantization = match db::get_vector_index_layout(conn)? {
+        Some((dimensions, quantization)) if dimensions == query_embedding.len() => quantization,
+        // Nothing embedded yet, or vectors from another model that can't be compared
+        _ => return Ok(Vec::new()),
+    };
+
+    let query_bytes = embeddings::embedding_to_bytes(query_embedding);
+    let candidates = wanted.saturating_mul(quantization.oversampling());
+    let mut k = candidates.min(VEC_MAX_K);
+    let mut widenings = 0;
+    let ranked = loop {
+        let (rows, neighbours) = db::vector_search(conn, &query_bytes, k as u32, filters)?;
+        let exhausted = (neighbours < k && !rows.is_empty()) || k == VEC_MAX_K;
+        let ranked: Vec<(f32, db::ChunkRow)> = rows
+            .into_iter()
+            .filter(|(c, _)| passes_filters(c))
+            .map(|(c, distance)| ((1.0 - distance) as f32, c))
+            .collect();
+        if ranked.len() >= candidates || exhausted || widenings == MAX_WIDENINGS {
+            break ranked;
+        }
+        widenings += 1;
+        k = (k * 4).min(VEC_MAX_K);
+    };
+
+    if quantization == Quantization::None {
+        return Ok(ranked);
+    }
+    rescore(conn, query_embedding, ranked, wanted)
+}
+
+/// Re-rank candidates from a quantized lookup by exact cosine similarity and keep the best `wanted`.
+fn rescore(
+    conn: &rusqlite::Connection,
+    query_embedding: &[f32],
+    candidates: Vec<(f32, db::ChunkRow)>,
+    wanted: usize,
+) -> Result<Vec<(f32, db::ChunkRow)>, AppError> {
+    let ids: Vec<i64> = candidates.iter().map(|(_, c)| c.id).collect();
+    let full_precision = db::get_chunk_embeddings(conn, &ids)?;
+    let mut rescored: Vec<(f32, db::ChunkRow)> = candidates
+        .into_iter()
+        .filter_map(|(_, c)| {
+            let bytes = full_precision.get(&c.id)?;
+            Some((cosine_similarity(query_embedding, &embeddings::bytes_to_embedding(bytes)), c))
+        })
+        .collect();
+    rescored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    rescored.truncate(wanted);
+    Ok(rescored)
+}
+
+/// Score every stored embedding against the query, best first.
+/// Fallback for when the vector index can't be used.
+fn brute_force_ranking(
+    conn: &rusqlite::Connection,
+    query_embedding: &[f32],
+    filters: &SearchFilters,
+    passes_filters: &dyn Fn(&db::ChunkRow) -> bool,
+) -> Result<Vec<(f32, db::ChunkRow)>, AppError> {
+    let mut scored: Vec<(f32, db::ChunkRow)> = db::get_all_chunks_for_search(conn, filters)?
+        .into_iter()
+        // Chunks indexed without an embedding are only reachable lexically, and
+        // vectors of another size come from another model and can't be compared
+        .filter(|c| c.embedding.len() == query_embedding.len() * 4 && passes_filters(c))
+        .map(|c| {
+            let chunk_embedding = embeddings::bytes_to_embedding(&c.embedding);
+            (cosine_similarity(query_embedding, &chunk_embedding), c)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(scored)
+}
+
+/// Merge several best-first rankings with reciprocal-rank fusion.
+/// Each chunk scores Σ 1/(k + rank) over the rankings it appears in; the result is
+/// scaled so a chunk ranked first everywhere scores 1.0.
+fn reciprocal_rank_fusion(rankings: Vec<Vec<(f32, db::ChunkRow)>>) -> Vec<(f32, db::ChunkRow)> {
+    let max_score = rankings.len() as f32 / (RRF_K + 1.0);
+    let mut fused: HashMap<i64, (f32, db::ChunkRow)> = HashMap::new();
+
+    for ranking in rankings {
+        for (rank, (_, chunk)) in ranking.into_iter().enumerate() {
+            let contribution = 1.0 / (RRF_K + rank as f32 + 1.0);
+            fused
+                .entry(chunk.id)
+                .and_modify(|(score, _)| *score += contribution)
+                .or_insert((contribution, chunk));
+        }
+    }
+
+    let mut merged: Vec<(f32, db::ChunkRow)> = fused
+        .into_values()
+        .map(|(score, chunk)| (score / max_score, chunk))
+        .collect();
+    merged.sort_by(|a, b| {
+        b.0.partial_cmp(&a.0)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.1.id.cmp(&b.1.id))
+    });
+    merged
+}
+
+/// Turn free text into an FTS5 query: every word becomes a quoted term, OR-ed together
+/// so BM25 rewards chunks matching more of them. Returns None if there are no words.
+fn build_fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"", t))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" OR "))
+    }
+}
+
 /// Check if a file path is excluded by context settings.
-fn is_excluded_by_settings(rel_path: &str, settings: &HashMap<String, String>) -> bool {
+pub fn is_excluded_by_settings(rel_path: &str, settings: &HashMap<String, String>) -> bool {
     settings.get(rel_path).map(|v| v == "exclude").unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::vector::chunker;
+
+    #[test]
+    fn test_build_fts_query() {
+        assert_eq!(build_fts_query("Okafor's ring").as_deref(), Some("\"Okafor\" OR \"s\" OR \"ring\""));
+        assert_eq!(build_fts_query("  -- ").as_deref(), None);
+    }
+
+    #[test]
+    fn test_lexical_search_finds_exact_names() {
+        let dir = std::env::temp_dir().join(format!("saipling-fts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = db::open_index(&dir).unwrap();
+        db::init_schema(&conn).unwrap();
+
+        db::upsert_indexed_file(&conn, "characters/okafor/profile.md", "h1", "character", 1).unwrap();
+        let id = db::insert_chunk(&conn, "characters/okafor/profile.md", 0, None, "c1", "Okafor", 5, &[]).unwrap();
+        db::insert_chunk_text(&conn, id, None, "Captain Okafor guards the Vhaelri gate.").unwrap();
+        db::upsert_indexed_file(&conn, "world/city/entry.md", "h2", "world", 1).unwrap();
+        let id = db::insert_chunk(&conn, "world/city/entry.md", 0, None, "c2", "City", 5, &[]).unwrap();
+        db::insert_chunk_text(&conn, id, None, "The city gate is closed at night.").unwrap();
+
+        let hits = db::lexical_search(&conn, &build_fts_query("vhaelri").unwrap(), 10, &SearchFilters::default()).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.file_path, "characters/okafor/profile.md");
+
+        // Deleting a file removes its chunks from the full-text index too
+        db::delete_file_data(&conn, "characters/okafor/profile.md").unwrap();
+        let hits = db::lexical_search(&conn, &build_fts_query("gate").unwrap(), 10, &SearchFilters::default()).unwrap();
+        assert_eq!(hits.len(), 1);
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_rich_filters_push_down() {
+        let dir = std::env::temp_dir().join(format!("saipling-filters-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = db::open_index(&dir).unwrap();
+        db::init_schema(&conn).unwrap();
+
+        let scenes = [
+            ("books/book-01/chapters/ch-02/scene-01/draft.md", "Sarah Chen", "drafted", "2025-01-10"),
+            ("books/book-01/chapters/ch-04/scene-01/draft.md", "Sarah Chen", "revised", "2025-03-01"),
+            ("books/book-01/chapters/ch-09/scene-01/draft.md", "Okafor", "drafted", "2025-03-05"),
+            ("books/book-02/chapters/ch-04/scene-01/draft.md", "Sarah Chen", "drafted", "2025-03-05"),
+        ];
+        for (path, pov, status, modified) in scenes {
+            let content = format!(
+                "---\ntype: scene-draft\npov_character: \"{}\"\nstatus: {}\n---\n\n## Scene\n\nShe looks into the broken mirror at {}.",
+                pov, status, path
+            );
+            let chunk = chunker::chunk_file(&content, path).remove(1);
+            db::upsert_indexed_file(&conn, path, "h", &chunk.metadata.file_type, 1).unwrap();
+            db::set_file_modified(&conn, path, &format!("{}T12:00:00+00:00", modified)).unwrap();
+            let id = db::insert_chunk(&conn, path, 0, None, "c", "", 5, &[]).unwrap();
+            db::insert_chunk_metadata(&conn, id, &chunk.metadata).unwrap();
+            db::insert_chunk_text(&conn, id, None, &chunk.content).unwrap();
+        }
+
+        // Chapter numbers come from reading order, not ids: ch-09 was moved up to third
+        for (book_id, reading_order) in [("book-01", ["ch-01", "ch-02", "ch-09", "ch-04"]), ("book-02", ["ch-01", "ch-02", "ch-03", "ch-04"])] {
+            let mut book = crate::book_repo::Book::new(book_id, book_id, 1);
+            for (i, chapter_id) in reading_order.iter().enumerate() {
+                book.chapters.push(crate::book_repo::Chapter::new(chapter_id, chapter_id, i as u32 + 1));
+            }
+            std::fs::create_dir_all(dir.join("books").join(book_id)).unwrap();
+            crate::book_repo::save_book(&dir, &book).unwrap();
+        }
+
+        let search = |query: &str| -> Vec<String> {
+            let parsed = super::super::query::parse_query(query).unwrap();
+            let fts = build_fts_query(&parsed.text).unwrap();
+            let filters = resolve_chapters(&dir, &parsed.filters);
+            let mut paths: Vec<String> = db::lexical_search(&conn, &fts, 10, &filters)
+                .unwrap()
+                .into_iter()
+                .map(|(c, _)| c.file_path)
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        assert_eq!(search("mirror").len(), 4);
+        assert_eq!(search("mirror book:book-01 ch:3..9").len(), 2);
+        assert_eq!(
+            search("mirror book:book-01 ch:3..9 pov:\"Sarah Chen\""),
+            vec!["books/book-01/chapters/ch-04/scene-01/draft.md"]
+        );
+        assert_eq!(search("mirror status:drafted after:2025-03-02").len(), 2);
+        assert_eq!(search("mirror type:scene_draft entity:scene-draft ch:..2").len(), 1);
+        assert_eq!(search("\"broken mirror\" ch:4").len(), 2);
+        assert_eq!(search("mirror ch:3"), vec!["books/book-01/chapters/ch-09/scene-01/draft.md"]);
+        assert!(search("\"mirror broken\"").is_empty());
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_search_linked_projects() {
+        let root = std::env::temp_dir().join(format!("saipling-linked-{}", uuid::Uuid::new_v4()));
+        let seed = |name: &str, path: &str, text: &str| -> PathBuf {
+            let dir = root.join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("project.json"), "{}").unwrap();
+            let conn = db::open_index(&dir).unwrap();
+            db::init_schema(&conn).unwrap();
+            db::upsert_indexed_file(&conn, path, "h", "character", 1).unwrap();
+            let id = db::insert_chunk(&conn, path, 0, None, "c", text, 5, &[]).unwrap();
+            db::insert_chunk_text(&conn, id, None, text).unwrap();
+            dir
+        };
+        let main = seed("main", "characters/okafor/profile.md", "Captain Okafor guards the Vhaelri gate.");
+        seed("shared", "characters/vhaelri/profile.md", "The Vhaelri are river folk.");
+        std::fs::write(
+            main.join("project.json"),
+            r#"{"linked_projects": [{"name": "shared", "path": "../shared"}, {"name": "gone", "path": "../gone"}]}"#,
+        )
+        .unwrap();
+
+        let run = |projects: &[&str]| {
+            let filters = SearchFilters { projects: projects.iter().map(|p| p.to_string()).collect(), ..Default::default() };
+            let main = main.clone();
+            async move {
+                search(&main, "vhaelri", None, SearchMode::Lexical, 10, &filters, &HashMap::new(), &Default::default(), &ResultShaping::default())
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|r| (r.project, r.file_path))
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        let all = run(&[]).await;
+        assert_eq!(all.len(), 2);
+        assert!(all.contains(&(Some("shared".to_string()), "characters/vhaelri/profile.md".to_string())));
+        assert!(all.contains(&(None, "characters/okafor/profile.md".to_string())));
+        assert_eq!(run(&["this"]).await, vec![(None, "characters/okafor/profile.md".to_string())]);
+        assert_eq!(run(&["shared"]).await.len(), 1);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_merge_indexes_interleaves_by_rank() {
+        let result = |file: &str, score: f32, project: Option<&str>| SearchResult {
+            file_path: file.to_string(),
+            section_heading: None,
+            similarity_score: score,
+            content_preview: String::new(),
+            content: String::new(),
+            chunk_index: 0,
+            token_count: 0,
+            entity_type: None,
+            entity_name: None,
+            book_id: None,
+            project: project.map(str::to_string),
+        };
+        // The linked index's weak matches score 1.0 relative to its own best
+        let this = vec![result("a.md", 1.0, None), result("b.md", 0.9, None), result("c.md", 0.8, None)];
+        let linked = vec![result("x.md", 1.0, Some("shared")), result("y.md", 1.0, Some("shared"))];
+
+        let merged = merge_indexes(vec![this, linked], 4);
+        let files: Vec<&str> = merged.iter().map(|r| r.file_path.as_str()).collect();
+        assert_eq!(files, ["a.md", "x.md", "b.md", "y.md"]);
+        assert_eq!(merged[2].similarity_score, 0.9);
+    }
+
+    #[test]
+    fn test_reciprocal_rank_fusion_prefers_agreement() {
+        let row = |id: i64| db::ChunkRow {
+            id,
+            file_path: format!("f{}.md", id),
+            chunk_index: 0,
+            section_heading: None,
+            content_preview: String::new(),
+            token_count: 0,
+            embedding: Vec::new(),
+            book_id: None,
+            entity_type: None,
+            entity_name: None,
+        };
+        let lexical = vec![(1.0, row(1)), (0.5, row(2))];
+        let semantic = vec![(0.9, row(2)), (0.8, row(3))];
+        let merged = reciprocal_rank_fusion(vec![lexical, semantic]);
+        assert_eq!(merged[0].1.id, 2);
+        assert!(merged[0].0 <= 1.0);
+    }
+
+    /// Deterministic pseudo-random unit vectors (xorshift) for index tests.
+    fn random_vectors(count: usize, dims: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed.max(1);
+        (0..count)
+            .map(|_| {
+                let v: Vec<f32> = (0..dims)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 7;
+                        state ^= state << 17;
+                        (state % 2000) as f32 / 1000.0 - 1.0
+                    })
+                    .collect();
+                let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+                v.into_iter().map(|x| x / norm).collect()
+            })
+            .collect()
+    }
+
+    /// Index of `count` single-chunk files spread over two books and three entity types,
+    /// stored with `quantization`, keeping full-precision copies if `keep`.
+    fn seeded_index(count: usize, dims: usize, quantization: Quantization, keep: bool) -> (PathBuf, rusqlite::Connection) {
+        let dir = std::env::temp_dir().join(format!("saipling-vec-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let conn = db::open_index(&dir).unwrap();
+        db::init_schema(&conn).unwrap();
+        db::set_quantization(&conn, quantization).unwrap();
+        db::set_keep_full_precision(&conn, keep).unwrap();
+
+        let tx = conn.unchecked_transaction().unwrap();
+        let entity_types = ["character", "location", "scene"];
+        for (i, vector) in random_vectors(count, dims, 42).iter().enumerate() {
+            let file = format!("f{}.md", i);
+            let book = if i % 2 == 0 { "book-1" } else { "book-2" };
+            let entity = entity_types[i % 3];
+            let bytes = embeddings::embedding_to_bytes(vector);
+            db::upsert_indexed_file(&tx, &file, "h", entity, 1).unwrap();
+            let id = db::insert_chunk(&tx, &file, 0, None, "c", "", 1, &bytes).unwrap();
+            let metadata = chunker::ChunkMetadata {
+                file_type: entity.to_string(),
+                book_id: Some(book.to_string()),
+                chapter_id: None,
+                entity_type: Some(entity.to_string()),
+                entity_name: None,
+                pov_character: None,
+                status: None,
+            };
+            db::insert_chunk_metadata(&tx, id, &metadata).unwrap();
+            db::insert_chunk_vector(&tx, id, &bytes, Some(book), Some(entity)).unwrap();
+        }
+        tx.commit().unwrap();
+        (dir, conn)
+    }
+
+    #[test]
+    fn test_vector_index_matches_brute_force_with_filters() {
+        let (dir, conn) = seeded_index(300, 16, Quantization::None, false);
+        let query = random_vectors(1, 16, 7).remove(0);
+        let filters = SearchFilters {
+            book_id: Some("book-1".to_string()),
+            entity_types: vec!["character".to_string(), "scene".to_string()],
+            ..Default::default()
+        };
+        let all = |_: &db::ChunkRow| true;
+
+        let ann = semantic_ranking(&conn, &query, 10, &filters, &all).unwrap();
+        let exact = brute_force_ranking(&conn, &query, &filters, &all).unwrap();
+        assert!(exact.iter().all(|(_, c)| c.book_id.as_deref() == Some("book-1") && c.entity_type.as_deref() != Some("location")));
+        assert_eq!(ann.len(), 10);
+        let ann_ids: Vec<i64> = ann.iter().map(|(_, c)| c.id).collect();
+        let exact_ids: Vec<i64> = exact.iter().take(10).map(|(_, c)| c.id).collect();
+        assert_eq!(ann_ids, exact_ids);
+        assert!((ann[0].0 - exact[0].0).abs() < 1e-4);
+
+        // Deindexing a file removes its vector too
+        let top_file = ann[0].1.file_path.clone();
+        db::delete_file_data(&conn, &top_file).unwrap();
+        let ann = semantic_ranking(&conn, &query, 10, &filters, &all).unwrap();
+        assert!(ann.iter().all(|(_, c)| c.file_path != top_file));
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_quantized_index_migrates_and_rescores() {
+        let (dir, conn) = seeded_index(500, 256, Quantization::Int8, true);
+        let query = random_vectors(1, 256, 11).remove(0);
+        let all = |_: &db::ChunkRow| true;
+        let exact: Vec<i64> = brute_force_ranking(&conn, &query, &SearchFilters::default(), &all).unwrap().iter().take(10).map(|(_, c)| c.id).collect();
+
+        for (quantization, min_overlap) in [(Quantization::Int8, 9), (Quantization::Binary, 7)] {
+            db::set_quantization(&conn, quantization).unwrap();
+            let ranked = semantic_ranking(&conn, &query, 10, &SearchFilters::default(), &all).unwrap();
+            assert_eq!(db::get_vector_index_layout(&conn).unwrap(), Some((256, quantization)));
+            assert_eq!(ranked.len(), 10);
+            // Rescoring restores exact similarities for whatever the quantized lookup found
+            assert_eq!(ranked[0].1.id, exact[0], "{:?}", quantization);
+            let overlap = ranked.iter().filter(|(_, c)| exact.contains(&c.id)).count();
+            assert!(overlap >= min_overlap, "{:?} recall@10 {}", quantization, overlap);
+        }
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_vectors_stored_once() {
+        let (dir, conn) = seeded_index(200, 64, Quantization::None, false);
+        let copies = |conn: &rusqlite::Connection| -> u32 {
+            conn.query_row("SELECT COUNT(*) FROM chunks WHERE length(embedding) > 0", [], |row| row.get(0)).unwrap()
+        };
+        let query = random_vectors(1, 64, 5).remove(0);
+        let all = |_: &db::ChunkRow| true;
+        let exact: Vec<i64> = brute_force_ranking(&conn, &query, &SearchFilters::default(), &all).unwrap().iter().take(10).map(|(_, c)| c.id).collect();
+        assert_eq!(exact.len(), 10, "float32 vectors are read back from the index");
+        assert_eq!(copies(&conn), 0);
+
+        // A quantized index keeps no float32 copy unless asked to
+        db::set_quantization(&conn, Quantization::Int8).unwrap();
+        let ranked = semantic_ranking(&conn, &query, 10, &SearchFilters::default(), &all).unwrap();
+        assert_eq!(copies(&conn), 0);
+        let overlap = ranked.iter().filter(|(_, c)| exact.contains(&c.id)).count();
+        assert!(overlap >= 9, "recall@10 {}", overlap);
+
+        // Vectors read back from int8 quantize to the same bytes
+        let before: Vec<u8> = conn.query_row("SELECT embedding FROM vec_chunks WHERE chunk_id = ?1", [exact[0]], |row| row.get(0)).unwrap();
+        db::set_quantization(&conn, Quantization::None).unwrap();
+        db::ensure_vector_index(&conn).unwrap();
+        db::set_quantization(&conn, Quantization::Int8).unwrap();
+        db::ensure_vector_index(&conn).unwrap();
+        let after: Vec<u8> = conn.query_row("SELECT embedding FROM vec_chunks WHERE chunk_id = ?1", [exact[0]], |row| row.get(0)).unwrap();
+        assert_eq!(before, after);
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// Timing comparison of the sqlite-vec lookup against the brute-force scan.
+    /// Run with: cargo test --release bench_vector_index -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bench_vector_index_vs_brute_force() {
+        const CHUNKS: usize = 20_000;
+        const DIMS: usize = 1024;
+        const QUERIES: usize = 20;
+
+        let (dir, conn) = seeded_index(CHUNKS, DIMS, Quantization::None, false);
+        let queries = random_vectors(QUERIES, DIMS, 99);
+        let no_filter = |_: &db::ChunkRow| true;
+
+        let start = std::time::Instant::now();
+        let exact: Vec<Vec<i64>> = queries
+            .iter()
+            .map(|q| brute_force_ranking(&conn, q, &SearchFilters::default(), &no_filter).unwrap().iter().take(10).map(|(_, c)| c.id).collect())
+            .collect();
+        let brute_force = start.elapsed();
+
+        let start = std::time::Instant::now();
+        let ann: Vec<Vec<i64>> = queries
+            .iter()
+            .map(|q| semantic_ranking(&conn, q, 10, &SearchFilters::default(), &no_filter).unwrap().iter().map(|(_, c)| c.id).collect())
+            .collect();
+        let indexed = start.elapsed();
+
+        let hits: usize = ann
+            .iter()
+            .zip(&exact)
+            .map(|(a, e)| a.iter().filter(|id| e.contains(id)).count())
+            .sum();
+        println!(
+            "{} chunks x {} dims, {} queries: brute force {:?}/query, sqlite-vec {:?}/query, recall@10 {:.3}",
+            CHUNKS,
+            DIMS,
+            QUERIES,
+            brute_force / QUERIES as u32,
+            indexed / QUERIES as u32,
+            hits as f64 / (QUERIES * 10) as f64
+        );
+
+        drop(conn);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -29,7 +29,6 @@ pub enum AppError {
     #[error("Chapter not found: {0}")]
     ChapterNotFound(String),
 
-    #[allow(dead_code)]
     #[error("Scene not found: {0}")]
     SceneNotFound(String),
 
@@ -60,6 +59,9 @@ pub enum AppError {
     #[error("Index error: {0}")]
     IndexError(String),
 
+    #[error("{} changed on disk since it was read", .0.path)]
+    Conflict(Box<crate::file_io::WriteConflict>),
+
     #[error("SQLite error: {0}")]
     Sqlite(#[from] rusqlite::Error),
 
@@ -72,6 +74,14 @@ impl Serialize for AppError {
     where
         S: serde::Serializer,
     {
+        // Conflicts carry the merge preview so the frontend can offer a resolution
+        if let AppError::Conflict(conflict) = self {
+            use serde::ser::SerializeStruct;
+            let mut s = serializer.serialize_struct("AppError", 2)?;
+            s.serialize_field("message", &self.to_string())?;
+            s.serialize_field("conflict", conflict)?;
+            return s.end();
+        }
         serializer.serialize_str(&self.to_string())
     }
 }
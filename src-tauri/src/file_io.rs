@@ -0,0 +1,308 @@
+// Conflict-safe file writes
+// Versions are content hashes: a write that names the version it was based on fails
+// with `AppError::Conflict` if the file changed since, instead of losing the change.
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use crate::error::AppError;
+
+/// A write refused because the file changed on disk after it was read.
+#[derive(Debug, Clone, Serialize)]
+pub struct WriteConflict {
+    pub path: String,
+    pub expected_version: String,
+    /// None when the file has since been deleted
+    pub current_version: Option<String>,
+    /// The rejected content merged with what is on disk now
+    pub merge: MergePreview,
+}
+
+/// Line-based three-way merge of the rejected write ("yours") with the file on
+/// disk, using the version both started from as the base.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MergePreview {
+    /// Merged text; conflicting sections carry `<<<<<<<` / `=======` / `>>>>>>>` markers
+    pub merged: String,
+    pub conflicts: u32,
+    /// False when the base version is no longer known (e.g. after a restart); every
+    /// difference is then shown as a conflict
+    pub base_available: bool,
+}
+
+// ─── Versions ───
+
+/// Version of a file's content, as returned by `read_file` and expected by writes.
+pub fn content_version(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())[..16].to_string()
+}
+
+/// Contents recently read or written, by version, so a conflict can be merged
+/// against the version the writer started from.
+static RECENT_VERSIONS: Lazy<Mutex<VecDeque<(String, String)>>> =
+    Lazy::new(|| Mutex::new(VecDeque::new()));
+const RECENT_VERSIONS_MAX: usize = 64;
+
+/// Record content handed to or received from the frontend; returns its version.
+pub fn remember_version(content: &str) -> String {
+    let version = content_version(content);
+    let mut recent = RECENT_VERSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    if !recent.iter().any(|(v, _)| *v == version) {
+        if recent.len() >= RECENT_VERSIONS_MAX {
+            recent.pop_front();
+        }
+        recent.push_back((version.clone(), content.to_string()));
+    }
+    version
+}
+
+fn recalled_version(version: &str) -> Option<String> {
+    let recent = RECENT_VERSIONS.lock().unwrap_or_else(|e| e.into_inner());
+    recent.iter().find(|(v, _)| v == version).map(|(_, c)| c.clone())
+}
+
+// ─── Writes ───
+
+/// Replace a file's content in one step: write a temporary file next to it, then
+/// rename it over the original, so readers never see a half-written file.
+pub fn atomic_write(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), AppError> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| AppError::InvalidPath(path.to_string_lossy().to_string()))?;
+    let tmp = path.with_file_name(format!(".{}.{}{}", name.to_string_lossy(), uuid::Uuid::new_v4().simple(), TEMP_SUFFIX));
+
+    let written = (|| -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(contents.as_ref())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    })();
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+    crate::watcher::note_own_write(path);
+    Ok(())
+}
+
+const TEMP_SUFFIX: &str = ".saipling-tmp";
+
+/// Whether a path is one of `atomic_write`'s temporary files.
+pub fn is_temp_file(path: &Path) -> bool {
+    path.file_name()
+        .map(|n| n.to_string_lossy().ends_with(TEMP_SUFFIX))
+        .unwrap_or(false)
+}
+
+/// Write `content` atomically and return its new version. With `expected_version`,
+/// fail with `AppError::Conflict` if the file on disk is no longer that version.
+pub fn write_versioned(path: &Path, content: &str, expected_version: Option<&str>) -> Result<String, AppError> {
+    if let Some(expected) = expected_version {
+        check_version(path, content, expected)?;
+    }
+    atomic_write(path, content)?;
+    Ok(remember_version(content))
+}
+
+/// Fail with `AppError::Conflict` unless the file on disk is still at `expected`.
+/// `content` is the write being attempted, merged into the conflict's preview.
+pub fn check_version(path: &Path, content: &str, expected: &str) -> Result<(), AppError> {
+    let current = match std::fs::read_to_string(path) {
+        Ok(c) => Some(c),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let current_version = current.as_deref().map(content_version);
+    if current_version.as_deref() == Some(expected) {
+        return Ok(());
+    }
+    let base = recalled_version(expected);
+    Err(AppError::Conflict(Box::new(WriteConflict {
+        path: path.to_string_lossy().to_string(),
+        expected_version: expected.to_string(),
+        current_version,
+        merge: merge3(base.as_deref(), content, current.as_deref().unwrap_or("")),
+    })))
+}
+
+// ─── Three-way merge ───
+
+/// Largest LCS table computed; beyond it the differing middle is one hunk.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Merge `ours` and `theirs`, both edited from `base`, line by line. Sections only
+/// one side changed take that side; sections both changed differently conflict.
+/// Without a base, every difference conflicts.
+pub fn merge3(base: Option<&str>, ours: &str, theirs: &str) -> MergePreview {
+    let ours_lines: Vec<&str> = ours.split_inclusive('\n').collect();
+    let theirs_lines: Vec<&str> = theirs.split_inclusive('\n').collect();
+    let mut merged = String::new();
+    let mut conflicts = 0;
+
+    let Some(base) = base else {
+        // Two-way: lines common to both sides are kept, everything else conflicts
+        let mut last = (0, 0);
+        let matches = lcs_matches(&ours_lines, &theirs_lines);
+        for (o, t) in matches.into_iter().chain(std::iter::once((ours_lines.len(), theirs_lines.len()))) {
+            let (o_part, t_part) = (&ours_lines[last.0..o], &theirs_lines[last.1..t]);
+            if !o_part.is_empty() || !t_part.is_empty() {
+                push_conflict(&mut merged, o_part, t_part);
+                conflicts += 1;
+            }
+            if o < ours_lines.len() {
+                merged.push_str(ours_lines[o]);
+            }
+            last = (o + 1, t + 1);
+        }
+        return MergePreview { merged, conflicts, base_available: false };
+    };
+
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let in_ours = match_map(&base_lines, &ours_lines);
+    let in_theirs = match_map(&base_lines, &theirs_lines);
+    // Base lines both sides kept are the points where the three texts line up
+    let sync = (0..base_lines.len())
+        .filter_map(|b| Some((b, in_ours[b]?, in_theirs[b]?)))
+        .chain(std::iter::once((base_lines.len(), ours_lines.len(), theirs_lines.len())));
+
+    let (mut b0, mut o0, mut t0) = (0, 0, 0);
+    for (b, o, t) in sync {
+        let (b_part, o_part, t_part) = (&base_lines[b0..b], &ours_lines[o0..o], &theirs_lines[t0..t]);
+        if o_part == b_part || o_part == t_part {
+            merged.extend(t_part.iter().copied());
+        } else if t_part == b_part {
+            merged.extend(o_part.iter().copied());
+        } else {
+            push_conflict(&mut merged, o_part, t_part);
+            conflicts += 1;
+        }
+        if b < base_lines.len() {
+            merged.push_str(base_lines[b]);
+        }
+        (b0, o0, t0) = (b + 1, o + 1, t + 1);
+    }
+    MergePreview { merged, conflicts, base_available: true }
+}
+
+fn push_conflict(merged: &mut String, ours: &[&str], theirs: &[&str]) {
+    // Markers always start a line, even after a last line without a newline
+    let push_lines = |merged: &mut String, lines: &[&str]| {
+        for line in lines {
+            merged.push_str(line);
+        }
+        if !merged.is_empty() && !merged.ends_with('\n') {
+            merged.push('\n');
+        }
+    };
+    push_lines(merged, &[]);
+    merged.push_str("<<<<<<< your version\n");
+    push_lines(merged, ours);
+    merged.push_str("=======\n");
+    push_lines(merged, theirs);
+    merged.push_str(">>>>>>> version on disk\n");
+}
+
+/// For each line of `a`, the line of `b` it is matched with, if any.
+fn match_map(a: &[&str], b: &[&str]) -> Vec<Option<usize>> {
+    let mut map = vec![None; a.len()];
+    for (i, j) in lcs_matches(a, b) {
+        map[i] = Some(j);
+    }
+    map
+}
+
+/// Index pairs of a longest common subsequence of lines, in order.
+fn lcs_matches(a: &[&str], b: &[&str]) -> Vec<(usize, usize)> {
+    // Common prefix and suffix need no table
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..].iter().rev().zip(b[prefix..].iter().rev()).take_while(|(x, y)| x == y).count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut matches: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    if !a_mid.is_empty() && !b_mid.is_empty() && a_mid.len() * b_mid.len() <= MAX_LCS_CELLS {
+        let (n, m) = (a_mid.len(), b_mid.len());
+        // lengths[i][j] = LCS length of a_mid[i..] and b_mid[j..]
+        let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lengths[i * (m + 1) + j] = if a_mid[i] == b_mid[j] {
+                    lengths[(i + 1) * (m + 1) + j + 1] + 1
+                } else {
+                    lengths[(i + 1) * (m + 1) + j].max(lengths[i * (m + 1) + j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n && j < m {
+            if a_mid[i] == b_mid[j] {
+                matches.push((prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if lengths[(i + 1) * (m + 1) + j] >= lengths[i * (m + 1) + j + 1] {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+    }
+    matches.extend((0..suffix).map(|s| (a.len() - suffix + s, b.len() - suffix + s)));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge3() {
+        let base = "one\ntwo\nthree\nfour\n";
+        // Non-overlapping edits merge cleanly
+        let merged = merge3(Some(base), "ONE\ntwo\nthree\nfour\n", "one\ntwo\nthree\nFOUR\nfive\n");
+        assert_eq!(merged.merged, "ONE\ntwo\nthree\nFOUR\nfive\n");
+        assert_eq!(merged.conflicts, 0);
+
+        // Both sides changing the same line conflict
+        let merged = merge3(Some(base), "one\n2\nthree\nfour\n", "one\nTWO\nthree\nfour\n");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.merged,
+            "one\n<<<<<<< your version\n2\n=======\nTWO\n>>>>>>> version on disk\nthree\nfour\n"
+        );
+
+        // Without a base, each difference is a conflict
+        let merged = merge3(None, "a\nb\nc", "a\nB\nc");
+        assert!(!merged.base_available);
+        assert_eq!(merged.conflicts, 1);
+        assert!(merged.merged.starts_with("a\n<<<<<<< your version\nb\n=======\nB\n>>>>>>>"));
+        assert!(merged.merged.ends_with("version on disk\nc"));
+    }
+
+    #[test]
+    fn test_write_versioned_detects_conflicts() {
+        let dir = std::env::temp_dir().join(format!("saipling-write-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("draft.md");
+
+        let v1 = write_versioned(&path, "opening\n\nmiddle\n\nending\n", None).unwrap();
+        let v2 = write_versioned(&path, "opening\n\nmiddle\n\nnew ending\n", Some(&v1)).unwrap();
+        assert_ne!(v1, v2);
+
+        // Changed on disk by someone else since v2 was read
+        std::fs::write(&path, "new opening\n\nmiddle\n\nnew ending\n").unwrap();
+        let err = write_versioned(&path, "opening\n\nbetter middle\n\nnew ending\n", Some(&v2)).unwrap_err();
+        let AppError::Conflict(conflict) = err else { panic!("expected a conflict") };
+        assert_eq!(conflict.merge.merged, "new opening\n\nbetter middle\n\nnew ending\n");
+        assert_eq!(conflict.merge.conflicts, 0);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new opening\n\nmiddle\n\nnew ending\n");
+
+        // No temporary files left behind
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,90 @@
+// Frontmatter — the YAML block at the top of project markdown files
+// Commands, the manuscript migration and the consistency check read it and patch
+// single fields through here.
+
+use std::path::Path;
+use crate::error::AppError;
+
+/// Split markdown into its frontmatter, as JSON, and the body after it. Without
+/// readable frontmatter the value is null and the body is the whole file.
+pub fn parse_frontmatter(content: &str) -> (serde_json::Value, String) {
+    if let Some((yaml, body)) = bounds(content).map(|(yaml, _, body)| (&content[yaml], &content[body..])) {
+        if let Ok(yaml_val) = serde_yaml::from_str::<serde_yaml::Value>(yaml) {
+            let json_val = serde_json::to_value(&yaml_val).unwrap_or(serde_json::Value::Null);
+            return (json_val, body.to_string());
+        }
+    }
+    (serde_json::Value::Null, content.to_string())
+}
+
+/// Split markdown into its frontmatter block, through the closing `---` line, and
+/// the body after it, without parsing the YAML. Without frontmatter the block is empty.
+pub fn split_frontmatter(content: &str) -> (&str, &str) {
+    match bounds(content) {
+        Some((_, head_end, body)) => (&content[..head_end], &content[body..]),
+        None => ("", content),
+    }
+}
+
+/// A top-level string field of a markdown file's frontmatter.
+pub fn frontmatter_str(path: &Path, key: &str) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let (frontmatter, _) = parse_frontmatter(&content);
+    frontmatter.get(key)?.as_str().map(str::to_string)
+}
+
+/// Byte ranges of a frontmatter block: the YAML, the end of the closing `---` line
+/// and the start of the body, with the blank lines before it skipped.
+fn bounds(content: &str) -> Option<(std::ops::Range<usize>, usize, usize)> {
+    let start = if content.starts_with("---\n") { 4 } else if content.starts_with("---\r\n") { 5 } else { return None };
+    let end = start + content[start..].find("\n---")?;
+    let close = end + 4;
+    let head_end = content[close..].find('\n').map(|i| close + i + 1).unwrap_or(content.len());
+    let body = content.len() - content[head_end..].trim_start_matches(['\n', '\r']).len();
+    Some((start..end, head_end, body))
+}
+
+/// Replace the values of top-level frontmatter keys that are already present.
+/// Missing files and files without frontmatter are left alone.
+pub fn set_frontmatter_fields(path: &Path, fields: &[(&str, String)]) -> Result<(), AppError> {
+    let Ok(content) = std::fs::read_to_string(path) else { return Ok(()) };
+    let Some(rest) = content.strip_prefix("---\n") else { return Ok(()) };
+    let Some(end) = rest.find("\n---") else { return Ok(()) };
+
+    let mut updated = String::from("---\n");
+    for line in rest[..end].split('\n') {
+        let field = fields.iter().find(|(key, _)| {
+            line.strip_prefix(key).is_some_and(|after| after.starts_with(':'))
+        });
+        match field {
+            Some((key, value)) => updated.push_str(&format!("{}: {}", key, value)),
+            None => updated.push_str(line),
+        }
+        updated.push('\n');
+    }
+    updated.pop();
+    updated.push_str(&rest[end..]);
+    if updated != content {
+        crate::file_io::atomic_write(path, updated)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_parse_agree_on_the_block() {
+        for content in ["---\ntitle: Harbour\n---\n\nThe boat came in.\n", "---\r\ntitle: Harbour\r\n---\r\n\r\nThe boat came in.\n"] {
+            let (head, body) = split_frontmatter(content);
+            assert!(head.starts_with("---") && head.trim_end().ends_with("---"), "{:?}", head);
+            assert_eq!(body, "The boat came in.\n");
+            let (frontmatter, parsed_body) = parse_frontmatter(content);
+            assert_eq!(frontmatter["title"], "Harbour");
+            assert_eq!(parsed_body, body);
+        }
+        assert_eq!(split_frontmatter("No frontmatter.\n"), ("", "No frontmatter.\n"));
+        assert_eq!(parse_frontmatter("---\nunclosed\n").1, "---\nunclosed\n");
+    }
+}
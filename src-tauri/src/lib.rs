@@ -3,10 +3,17 @@ mod commands;
 mod context;
 mod agent;
 mod watcher;
+mod project_path;
+mod file_io;
+mod book_repo;
+mod manuscript;
+mod frontmatter;
 mod data;
+#[cfg(test)]
+mod test_support;
 
 use commands::{
-    project, book, filesystem, draft, attachment, chapter, matter, agent as agent_cmd, config, models, export,
+    project, book, filesystem, draft, attachment, chapter, consistency, progress, writing, matter, agent as agent_cmd, config, models, export,
     vector_search as vs_cmd, templates,
 };
 use data::genres;
@@ -18,6 +25,10 @@ pub fn run() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .setup(|_app| {
+            agent_cmd::report_skill_issues();
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             // Project management
             project::create_project,
@@ -26,6 +37,13 @@ pub fn run() {
             project::get_project_metadata,
             project::update_project_metadata,
             project::delete_project,
+            consistency::check_project,
+            consistency::repair_project,
+            progress::recompute_phase_progress,
+            writing::get_writing_stats,
+            writing::get_word_count_history,
+            writing::list_writing_sessions,
+            writing::set_daily_word_goal,
             // Book management
             book::create_book,
             book::get_book_metadata,
@@ -60,6 +78,14 @@ pub fn run() {
             chapter::reorder_chapters,
             chapter::reorder_scenes,
             chapter::move_scene,
+            chapter::delete_chapter,
+            chapter::delete_scene,
+            chapter::renumber_book,
+            chapter::split_scene,
+            chapter::merge_scenes,
+            chapter::split_chapter,
+            chapter::merge_chapters,
+            chapter::move_chapter_to_book,
             // Front & Back matter
             matter::create_front_matter,
             matter::create_back_matter,
@@ -75,6 +101,7 @@ pub fn run() {
             agent_cmd::estimate_context_tokens,
             agent_cmd::list_available_skills,
             agent_cmd::get_skill_settings,
+            agent_cmd::validate_skills,
             // Configuration
             config::get_config,
             config::update_config,
@@ -91,10 +118,20 @@ pub fn run() {
             genres::get_genres,
             // File watcher
             project::start_file_watcher,
+            project::switch_file_watcher,
+            project::stop_file_watcher,
             // Vector Search
             vs_cmd::vector_search,
+            vs_cmd::find_related,
             vs_cmd::get_index_status,
             vs_cmd::reindex_project,
+            vs_cmd::estimate_reindex,
+            vs_cmd::cancel_reindex,
+            vs_cmd::verify_index,
+            vs_cmd::list_entities,
+            vs_cmd::entity_appearances,
+            vs_cmd::entity_first_appearance,
+            vs_cmd::entity_cooccurrences,
             vs_cmd::clear_index,
             // Templates
             templates::load_template,
@@ -0,0 +1,362 @@
+// Manuscript layout — where a book's chapters and scenes live on disk
+// Commands resolve manuscript paths through here and the indexer classifies them
+// with `locate`, so both agree on one layout:
+//
+//   books/{book}/chapters/ch-NN/_chapter.md              chapter notes
+//   books/{book}/chapters/ch-NN/scene-NN/outline.md      scene outline
+//   books/{book}/chapters/ch-NN/scene-NN/draft.md        scene prose
+//   books/{book}/chapters/ch-NN/scene-NN/.drafts/        draft snapshots
+//   books/{book}/chapters/ch-NN/scene-NN/attachments/
+//
+// Projects from before it kept prose in `books/{book}/phase-5-bloom/ch-NN/scene-NN.md`;
+// `migrate_legacy_layout` moves those files into place.
+
+use std::path::{Path, PathBuf};
+use crate::book_repo::{self, Book, Chapter, Scene};
+use crate::error::AppError;
+use crate::frontmatter::{frontmatter_str, parse_frontmatter, set_frontmatter_fields};
+use crate::project_path::ProjectPath;
+
+pub const CHAPTERS_DIR: &str = "chapters";
+pub const CHAPTER_FILE: &str = "_chapter.md";
+pub const OUTLINE_FILE: &str = "outline.md";
+pub const DRAFT_FILE: &str = "draft.md";
+pub const SNAPSHOTS_DIR: &str = ".drafts";
+
+/// The pre-chapters/ home of scene drafts, only read by the migration.
+const LEGACY_DRAFTS_DIR: &str = "phase-5-bloom";
+
+pub fn book_dir(project_dir: &Path, book_id: &str) -> PathBuf {
+    project_dir.join("books").join(book_id)
+}
+
+pub fn chapters_dir(project_dir: &Path, book_id: &str) -> PathBuf {
+    book_dir(project_dir, book_id).join(CHAPTERS_DIR)
+}
+
+pub fn chapter_dir(project_dir: &Path, book_id: &str, chapter_id: &str) -> PathBuf {
+    chapters_dir(project_dir, book_id).join(chapter_id)
+}
+
+pub fn scene_dir(project_dir: &Path, book_id: &str, chapter_id: &str, scene_id: &str) -> PathBuf {
+    chapter_dir(project_dir, book_id, chapter_id).join(scene_id)
+}
+
+/// The project a file inside `books/` belongs to: the folder above `books/` that
+/// holds a project.json.
+pub fn project_of(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .filter(|dir| dir.file_name().is_some_and(|name| name == "books"))
+        .filter_map(Path::parent)
+        .find(|project_dir| project_dir.join("project.json").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Which manuscript file a path is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManuscriptFile {
+    ChapterNotes,
+    Outline,
+    Draft,
+    /// Anything else in a chapter or scene folder, e.g. attachments and snapshots
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManuscriptLocation {
+    pub book_id: String,
+    pub chapter_id: String,
+    pub scene_id: Option<String>,
+    pub file: ManuscriptFile,
+}
+
+/// Where a project-relative path (forward slashes) sits in the manuscript, if it does.
+pub fn locate(rel_path: &str) -> Option<ManuscriptLocation> {
+    let parts: Vec<&str> = rel_path.split('/').collect();
+    let ["books", book_id, CHAPTERS_DIR, chapter_id, rest @ ..] = parts.as_slice() else {
+        return None;
+    };
+    if !chapter_id.starts_with("ch-") || rest.is_empty() {
+        return None;
+    }
+    let (scene_id, file) = match rest {
+        [CHAPTER_FILE] => (None, ManuscriptFile::ChapterNotes),
+        [scene, OUTLINE_FILE] if scene.starts_with("scene-") => (Some(*scene), ManuscriptFile::Outline),
+        [scene, DRAFT_FILE] if scene.starts_with("scene-") => (Some(*scene), ManuscriptFile::Draft),
+        [scene, _, ..] if scene.starts_with("scene-") => (Some(*scene), ManuscriptFile::Other),
+        _ => (None, ManuscriptFile::Other),
+    };
+    Some(ManuscriptLocation {
+        book_id: book_id.to_string(),
+        chapter_id: chapter_id.to_string(),
+        scene_id: scene_id.map(str::to_string),
+        file,
+    })
+}
+
+/// Point the frontmatter of a scene's outline and draft at its chapter and scene ids.
+pub fn set_scene_frontmatter_ids(scene_dir: &Path, chapter_id: &str, scene_id: &str) -> Result<(), AppError> {
+    for file in [OUTLINE_FILE, DRAFT_FILE] {
+        set_frontmatter_fields(&scene_dir.join(file), &[
+            ("chapter", chapter_id.to_string()),
+            ("scene", scene_id.to_string()),
+        ])?;
+    }
+    Ok(())
+}
+
+/// Words in a scene draft's body, without its frontmatter; 0 if there is no draft.
+pub fn draft_word_count(draft_path: &Path) -> Result<u64, AppError> {
+    if !draft_path.exists() {
+        return Ok(0);
+    }
+    let content = std::fs::read_to_string(draft_path)?;
+    let (_, body) = parse_frontmatter(&content);
+    Ok(word_count(&body))
+}
+
+/// Words in a piece of prose, as every word count in the app counts them.
+pub fn word_count(text: &str) -> u64 {
+    text.split_whitespace().count() as u64
+}
+
+// ─── Migration ───
+
+/// Move a book's drafts from `phase-5-bloom/ch-NN/scene-NN.md` into scene folders and
+/// list them in book.json. A chapter or scene whose id is already used by a folder in
+/// `chapters/` gets a new id; a book.json entry with no folder takes the legacy files.
+/// Search index entries and context settings move with the files. book.json is saved
+/// after each chapter; if a chapter fails, its files are moved back and the chapters
+/// before it stay migrated. Returns the (from, to) project-relative paths moved.
+pub fn migrate_legacy_layout(project_dir: &Path, book_id: &str) -> Result<Vec<(String, String)>, AppError> {
+    let legacy_dir = book_dir(project_dir, book_id).join(LEGACY_DRAFTS_DIR);
+    let legacy_chapters = entries(&legacy_dir, |name, is_dir| is_dir && name.starts_with("ch-"));
+    if legacy_chapters.is_empty() {
+        // New books used to get an empty phase-5-bloom/; fails harmlessly if it has files
+        let _ = std::fs::remove_dir(&legacy_dir);
+        return Ok(Vec::new());
+    }
+
+    let chapters_dir = chapters_dir(project_dir, book_id);
+    let mut moved = Vec::new();
+    for legacy_id in legacy_chapters {
+        let from_dir = legacy_dir.join(&legacy_id);
+        let mut chapter_moves = Vec::new();
+        let migrated = book_repo::update_book(project_dir, book_id, |book| {
+            migrate_chapter(project_dir, book, &from_dir, &legacy_id, &chapters_dir, &mut chapter_moves)
+        });
+        if let Err(e) = migrated {
+            undo_moves(project_dir, &chapter_moves);
+            return Err(e);
+        }
+        let _ = std::fs::remove_dir(&from_dir);
+        moved.extend(chapter_moves);
+    }
+    let _ = std::fs::remove_dir(&legacy_dir);
+    Ok(moved)
+}
+
+/// Move one legacy chapter folder's notes and drafts into `chapters/` and list them in `book`.
+fn migrate_chapter(
+    project_dir: &Path,
+    book: &mut Book,
+    from_dir: &Path,
+    legacy_id: &str,
+    chapters_dir: &Path,
+    moved: &mut Vec<(String, String)>,
+) -> Result<(), AppError> {
+    let listed = book.chapters.iter().position(|c| c.id == legacy_id);
+    let (mut chapter, index) = match listed {
+        Some(i) if !chapters_dir.join(legacy_id).exists() => (book.chapters.remove(i), i),
+        _ => {
+            let id = if listed.is_some() || chapters_dir.join(legacy_id).exists() {
+                book.allocate_chapter_id(chapters_dir)
+            } else {
+                legacy_id.to_string()
+            };
+            let title = frontmatter_str(&from_dir.join(CHAPTER_FILE), "title").unwrap_or_else(|| id.clone());
+            (Chapter::new(&id, &title, book.next_chapter_sort_order()), book.chapters.len())
+        }
+    };
+    let ch_dir = chapters_dir.join(&chapter.id);
+    std::fs::create_dir_all(&ch_dir)?;
+    if from_dir.join(CHAPTER_FILE).exists() && !ch_dir.join(CHAPTER_FILE).exists() {
+        // Ids go in before the move so the re-keyed index picks them up
+        set_frontmatter_fields(&from_dir.join(CHAPTER_FILE), &[("chapter", chapter.id.clone())])?;
+        move_file(project_dir, &from_dir.join(CHAPTER_FILE), &ch_dir.join(CHAPTER_FILE), moved)?;
+    }
+
+    for file_name in entries(from_dir, |name, is_dir| !is_dir && name.starts_with("scene-") && name.ends_with(".md")) {
+        let legacy_scene = file_name.trim_end_matches(".md").to_string();
+        let listed = chapter.scenes.iter().position(|s| s.id == legacy_scene);
+        let sc_id = match listed {
+            Some(_) if !ch_dir.join(&legacy_scene).exists() => legacy_scene,
+            None if !ch_dir.join(&legacy_scene).exists() => legacy_scene,
+            _ => chapter.allocate_scene_id(&ch_dir),
+        };
+        let sc_dir = ch_dir.join(&sc_id);
+        std::fs::create_dir_all(&sc_dir)?;
+        set_frontmatter_fields(&from_dir.join(&file_name), &[
+            ("chapter", chapter.id.clone()),
+            ("scene", sc_id.clone()),
+        ])?;
+        move_file(project_dir, &from_dir.join(&file_name), &sc_dir.join(DRAFT_FILE), moved)?;
+
+        let word_count = draft_word_count(&sc_dir.join(DRAFT_FILE))?;
+        match chapter.scenes.iter_mut().find(|s| s.id == sc_id) {
+            Some(scene) => scene.word_count = word_count,
+            None => {
+                let draft = sc_dir.join(DRAFT_FILE);
+                let title = frontmatter_str(&draft, "title").unwrap_or_else(|| sc_id.clone());
+                let scene_type = frontmatter_str(&draft, "scene_type").map(|t| t.to_lowercase()).unwrap_or_else(|| "action".into());
+                let sort_order = chapter.scenes.iter().map(|s| s.sort_order).max().unwrap_or(0) + 1;
+                let mut scene = Scene::new(&sc_id, &title, sort_order, &scene_type);
+                scene.word_count = word_count;
+                chapter.scenes.push(scene);
+            }
+        }
+    }
+    book.chapters.insert(index, chapter);
+    Ok(())
+}
+
+/// Rename a file within the book, taking its index entries and context settings along.
+fn move_file(project_dir: &Path, from: &Path, to: &Path, moved: &mut Vec<(String, String)>) -> Result<(), AppError> {
+    std::fs::rename(from, to)?;
+    crate::watcher::note_own_write(from);
+    crate::watcher::note_own_write(to);
+    let (Some(from_rel), Some(to_rel)) =
+        (ProjectPath::from_absolute(project_dir, from), ProjectPath::from_absolute(project_dir, to))
+    else {
+        return Ok(());
+    };
+    moved.push((from_rel.to_string(), to_rel.to_string()));
+    follow_file(project_dir, &from_rel, &to_rel)
+}
+
+/// Point a moved file's context settings and index entries at its new path.
+fn follow_file(project_dir: &Path, from_rel: &ProjectPath, to_rel: &ProjectPath) -> Result<(), AppError> {
+    crate::context::assembler::move_context_settings(project_dir, from_rel, to_rel)?;
+    if let Err(e) = crate::context::vector::indexer::move_indexed_dir(
+        &project_dir.to_path_buf(), from_rel.as_str(), to_rel.as_str(),
+    ) {
+        eprintln!("Warning: failed to move index entries for {}: {}", from_rel, e);
+    }
+    Ok(())
+}
+
+/// Move files back to where `move_file` found them, latest first, removing the
+/// folders made for them if they are left empty.
+fn undo_moves(project_dir: &Path, moved: &[(String, String)]) {
+    for (from, to) in moved.iter().rev() {
+        let (Ok(from_rel), Ok(to_rel)) = (ProjectPath::new(from), ProjectPath::new(to)) else { continue };
+        let (from_path, to_path) = (from_rel.to_absolute(project_dir), to_rel.to_absolute(project_dir));
+        if let Err(e) = std::fs::rename(&to_path, &from_path) {
+            eprintln!("Warning: failed to move {} back to {}: {}", to, from, e);
+            continue;
+        }
+        crate::watcher::note_own_write(&to_path);
+        crate::watcher::note_own_write(&from_path);
+        if let Err(e) = follow_file(project_dir, &to_rel, &from_rel) {
+            eprintln!("Warning: failed to move context settings back for {}: {}", from, e);
+        }
+        for dir in to_path.ancestors().skip(1).take(2) {
+            let _ = std::fs::remove_dir(dir);
+        }
+    }
+}
+
+/// Names of the entries in `dir` that `keep` accepts, sorted.
+fn entries(dir: &Path, keep: impl Fn(&str, bool) -> bool) -> Vec<String> {
+    let Ok(read) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut names: Vec<String> = read
+        .filter_map(|e| e.ok())
+        .filter(|e| keep(&e.file_name().to_string_lossy(), e.path().is_dir()))
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{TestProject, BOOK};
+
+    /// Legacy ch-01 collides with the existing chapter, ch-02 then with the renumbered one.
+    fn legacy_project() -> TestProject {
+        let project = TestProject::with_scene("manuscript");
+        let legacy = project.book_dir().join(LEGACY_DRAFTS_DIR);
+        std::fs::create_dir_all(legacy.join("ch-01")).unwrap();
+        std::fs::create_dir_all(legacy.join("ch-02")).unwrap();
+        std::fs::write(legacy.join("ch-01").join(CHAPTER_FILE), "---\ntitle: \"Storm\"\nchapter: ch-01\n---\n\nNotes.\n").unwrap();
+        std::fs::write(
+            legacy.join("ch-01").join("scene-01.md"),
+            "---\ntitle: \"Squall\"\nchapter: ch-01\nscene: scene-01\nscene_type: REACTION\n---\n\nThe sky broke open.\n",
+        ).unwrap();
+        std::fs::write(legacy.join("ch-01").join("scene-02.md"), "Rain.\n").unwrap();
+        std::fs::write(legacy.join("ch-02").join("scene-01.md"), "Calm again.\n").unwrap();
+        project
+    }
+
+    #[test]
+    fn test_legacy_drafts_move_into_scene_folders() {
+        let project = legacy_project();
+        let moved = migrate_legacy_layout(&project.dir, BOOK).unwrap();
+        assert_eq!(moved.len(), 4);
+        assert!(moved.contains(&(
+            "books/book-01/phase-5-bloom/ch-02/scene-01.md".to_string(),
+            "books/book-01/chapters/ch-03/scene-01/draft.md".to_string(),
+        )));
+        assert!(!project.book_dir().join(LEGACY_DRAFTS_DIR).exists());
+
+        let book = project.book();
+        let ids: Vec<&str> = book.chapters.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["ch-01", "ch-02", "ch-03"]);
+        let storm = &book.chapters[1];
+        assert_eq!(storm.title, "Storm");
+        assert_eq!(storm.scenes.len(), 2);
+        assert_eq!(storm.scenes[0].title, "Squall");
+        assert_eq!(storm.scenes[0].scene_type, "reaction");
+        assert_eq!(storm.scenes[0].word_count, 4);
+    }
+
+    #[test]
+    fn test_migrated_files_take_their_new_ids() {
+        let project = legacy_project();
+        migrate_legacy_layout(&project.dir, BOOK).unwrap();
+        let draft = std::fs::read_to_string(project.scene_dir("ch-02", "scene-01").join(DRAFT_FILE)).unwrap();
+        assert!(draft.contains("chapter: ch-02\nscene: scene-01"));
+        let notes = std::fs::read_to_string(chapter_dir(&project.dir, BOOK, "ch-02").join(CHAPTER_FILE)).unwrap();
+        assert!(notes.contains("chapter: ch-02"));
+    }
+
+    #[test]
+    fn test_migrated_book_has_nothing_left_to_move() {
+        let project = legacy_project();
+        migrate_legacy_layout(&project.dir, BOOK).unwrap();
+        assert!(migrate_legacy_layout(&project.dir, BOOK).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failed_legacy_chapter_moves_back() {
+        let project = TestProject::new("manuscript");
+        let legacy = project.book_dir().join(LEGACY_DRAFTS_DIR);
+        std::fs::create_dir_all(legacy.join("ch-01")).unwrap();
+        std::fs::create_dir_all(legacy.join("ch-02")).unwrap();
+        std::fs::write(legacy.join("ch-01").join("scene-01.md"), "One.\n").unwrap();
+        std::fs::write(legacy.join("ch-02").join("scene-01.md"), "Two.\n").unwrap();
+        // Not UTF-8, so counting its words fails once it has moved
+        std::fs::write(legacy.join("ch-02").join("scene-02.md"), [0xff, 0xfe, 0xfd]).unwrap();
+
+        assert!(migrate_legacy_layout(&project.dir, BOOK).is_err());
+        // ch-01 was saved before ch-02 failed; ch-02's files are back where they were
+        let ids: Vec<String> = project.book().chapters.iter().map(|c| c.id.clone()).collect();
+        assert_eq!(ids, vec!["ch-01"]);
+        assert!(project.scene_dir("ch-01", "scene-01").join(DRAFT_FILE).exists());
+        assert!(legacy.join("ch-02").join("scene-01.md").exists());
+        assert!(legacy.join("ch-02").join("scene-02.md").exists());
+        assert!(!chapter_dir(&project.dir, BOOK, "ch-02").exists());
+    }
+}
@@ -0,0 +1,147 @@
+// Project-relative paths — one representation on every platform
+// Stored with forward slashes (as in the index, context settings and skill patterns)
+// and converted to native paths only when touching the file system.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::error::AppError;
+
+/// A path relative to a project directory, e.g. `characters/sarah-chen/profile.md`.
+/// Always uses forward slashes and never escapes the project.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ProjectPath(String);
+
+impl ProjectPath {
+    /// Parse a relative path written with either separator. Empty and `.` segments
+    /// are dropped; absolute paths and `..` segments are rejected.
+    pub fn new(rel: &str) -> Result<Self, AppError> {
+        let unified = rel.replace('\\', "/");
+        if unified.starts_with('/') || has_drive_prefix(&unified) {
+            return Err(AppError::InvalidPath(format!("Not a project-relative path: {}", rel)));
+        }
+        let mut segments = Vec::new();
+        for segment in unified.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => return Err(AppError::InvalidPath(format!("Path leaves the project: {}", rel))),
+                s => segments.push(s),
+            }
+        }
+        if segments.is_empty() {
+            return Err(AppError::InvalidPath(format!("Empty project path: {:?}", rel)));
+        }
+        Ok(ProjectPath(segments.join("/")))
+    }
+
+    /// The project-relative path of `abs`, or None if it isn't inside `project_dir`.
+    /// Accepts paths joined with either separator, as the frontend builds them.
+    pub fn from_absolute(project_dir: &Path, abs: &Path) -> Option<Self> {
+        if let Ok(rel) = abs.strip_prefix(project_dir) {
+            return Self::new(&rel.to_string_lossy()).ok();
+        }
+        let abs = with_forward_slashes(abs);
+        let strip = |dir: &Path| -> Option<Self> {
+            let rest = abs.strip_prefix(with_forward_slashes(dir).as_str())?.strip_prefix('/')?;
+            Self::new(rest).ok()
+        };
+        strip(project_dir).or_else(|| {
+            // e.g. /var → /private/var on macOS, where watcher paths are canonical
+            let canonical = project_dir.canonicalize().ok()?;
+            strip(&canonical)
+        })
+    }
+
+    /// The native absolute path inside `project_dir`.
+    pub fn to_absolute(&self, project_dir: &Path) -> PathBuf {
+        let mut path = project_dir.to_path_buf();
+        path.extend(self.0.split('/'));
+        path
+    }
+
+    /// Read the file at this path inside `project_dir`.
+    pub fn read_to_string(&self, project_dir: &Path) -> Result<String, AppError> {
+        Ok(std::fs::read_to_string(self.to_absolute(project_dir))?)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether this path is `dir` itself or lies under it.
+    pub fn is_within(&self, dir: &ProjectPath) -> bool {
+        self.0 == dir.0 || (self.0.starts_with(&dir.0) && self.0[dir.0.len()..].starts_with('/'))
+    }
+}
+
+impl fmt::Display for ProjectPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for ProjectPath {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for ProjectPath {
+    type Error = AppError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::new(&value)
+    }
+}
+
+impl From<ProjectPath> for String {
+    fn from(path: ProjectPath) -> Self {
+        path.0
+    }
+}
+
+fn with_forward_slashes(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+/// `C:` at the start of a Windows path.
+fn has_drive_prefix(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_path_normalises() {
+        let path = ProjectPath::new("books\\book-01/./ch-01//scene-01/draft.md").unwrap();
+        assert_eq!(path.as_str(), "books/book-01/ch-01/scene-01/draft.md");
+        assert!(ProjectPath::new("../other/project.json").is_err());
+        assert!(ProjectPath::new("/etc/passwd").is_err());
+        assert!(ProjectPath::new("C:\\Users").is_err());
+        assert!(ProjectPath::new("./").is_err());
+
+        let dir = ProjectPath::new("books/book-01").unwrap();
+        assert!(path.is_within(&dir));
+        assert!(!ProjectPath::new("books/book-011/x.md").unwrap().is_within(&dir));
+    }
+
+    #[test]
+    fn test_project_path_round_trip() {
+        let project = std::env::temp_dir().join(format!("saipling-paths-{}", uuid::Uuid::new_v4()));
+        let path = ProjectPath::new("characters/sarah-chen/profile.md").unwrap();
+
+        let abs = path.to_absolute(&project);
+        assert_eq!(abs, project.join("characters").join("sarah-chen").join("profile.md"));
+        assert_eq!(ProjectPath::from_absolute(&project, &abs), Some(path.clone()));
+
+        // Joined with backslashes, as the frontend does
+        let joined = format!("{}\\characters\\sarah-chen\\profile.md", project.display());
+        assert_eq!(ProjectPath::from_absolute(&project, Path::new(&joined)), Some(path));
+        assert_eq!(ProjectPath::from_absolute(&project, &std::env::temp_dir().join("elsewhere.md")), None);
+        assert_eq!(ProjectPath::from_absolute(&project, &project), None);
+    }
+}
@@ -0,0 +1,79 @@
+// Test scaffolding — a throwaway project on disk, shared by the command tests
+
+use std::path::PathBuf;
+use serde_json::json;
+use crate::book_repo::{self, Book};
+use crate::commands::chapter::{create_chapter, create_scene};
+use crate::manuscript;
+
+pub const BOOK: &str = "book-01";
+
+/// A project in the temp dir holding book-01 "Tides". Removed when dropped.
+pub struct TestProject {
+    pub dir: PathBuf,
+}
+
+impl TestProject {
+    /// project.json and an empty book-01, its `chapters/` folder included.
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("saipling-{}-{}", name, uuid::Uuid::new_v4()));
+        let project = json!({
+            "version": "1.0.0", "name": "Tides", "created": "2026-01-01T00:00:00Z", "modified": "2026-01-01T00:00:00Z",
+            "books": [],
+        });
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("project.json"), project.to_string()).unwrap();
+        let project = TestProject { dir };
+        project.add_book(BOOK, "Tides");
+        project
+    }
+
+    /// As `new`, with chapter "Harbour" (ch-01) holding scene "Arrival" (scene-01).
+    pub fn with_scene(name: &str) -> Self {
+        let project = Self::new(name);
+        create_chapter(project.dir.clone(), BOOK.into(), "Harbour".into()).unwrap();
+        create_scene(project.dir.clone(), BOOK.into(), "ch-01".into(), "Arrival".into(), "action".into()).unwrap();
+        project
+    }
+
+    /// Save an empty book and list it in project.json.
+    pub fn add_book(&self, id: &str, title: &str) {
+        let sort_order = self.list_book(id, title);
+        std::fs::create_dir_all(manuscript::chapters_dir(&self.dir, id)).unwrap();
+        book_repo::save_book(&self.dir, &Book::new(id, title, sort_order)).unwrap();
+    }
+
+    /// List a book in project.json without creating it, returning its sort order.
+    pub fn list_book(&self, id: &str, title: &str) -> u32 {
+        let path = self.dir.join("project.json");
+        let mut project: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        let books = project["books"].as_array_mut().unwrap();
+        let sort_order = books.len() as u32 + 1;
+        books.push(json!({ "id": id, "title": title, "sort_order": sort_order }));
+        std::fs::write(&path, project.to_string()).unwrap();
+        sort_order
+    }
+
+    pub fn book_dir(&self) -> PathBuf {
+        manuscript::book_dir(&self.dir, BOOK)
+    }
+
+    pub fn scene_dir(&self, chapter_id: &str, scene_id: &str) -> PathBuf {
+        manuscript::scene_dir(&self.dir, BOOK, chapter_id, scene_id)
+    }
+
+    /// The draft of ch-01/scene-01, as created by `with_scene`.
+    pub fn draft(&self) -> PathBuf {
+        self.scene_dir("ch-01", "scene-01").join(manuscript::DRAFT_FILE)
+    }
+
+    pub fn book(&self) -> Book {
+        book_repo::load_book(&self.dir, BOOK).unwrap()
+    }
+}
+
+impl Drop for TestProject {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
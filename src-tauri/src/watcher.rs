@@ -1,83 +1,466 @@
-// File System Watcher — monitors project directory for external changes
+// File System Watcher — monitors project directories for external changes
 // Emits events to the frontend via Tauri events
 
-use std::path::PathBuf;
-use std::time::{Duration, Instant};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use once_cell::sync::Lazy;
+use notify::{Event, EventKind};
+use notify::event::{ModifyKind, RenameMode};
 use tauri::Emitter;
+use crate::context::vector::embeddings;
 use crate::context::vector::indexer::{PENDING_FILES, DELETED_FILES};
+use crate::error::AppError;
+use crate::project_path::ProjectPath;
 
-pub fn start_watcher(app: tauri::AppHandle, project_dir: PathBuf) -> Result<(), crate::error::AppError> {
-    use notify::{Watcher, RecursiveMode, Event, EventKind};
+/// Events are collected until none arrives for this long...
+const DEBOUNCE: Duration = Duration::from_millis(300);
+/// ...or the first event of the batch is this old.
+const MAX_BATCH_WAIT: Duration = Duration::from_secs(2);
 
-    let app_handle = app.clone();
+// ─── Registry ───
 
+/// A running watcher and the tasks serving it. Dropping it stops them all.
+struct ProjectWatcher {
+    _watcher: notify::RecommendedWatcher,
+    pipeline: tauri::async_runtime::JoinHandle<()>,
+    indexer: tauri::async_runtime::JoinHandle<()>,
+}
+
+impl Drop for ProjectWatcher {
+    fn drop(&mut self) {
+        self.pipeline.abort();
+        self.indexer.abort();
+    }
+}
+
+/// Watched projects, keyed by canonical project directory.
+static WATCHERS: Lazy<Mutex<HashMap<PathBuf, (PathBuf, ProjectWatcher)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn registry_key(project_dir: &Path) -> PathBuf {
+    project_dir.canonicalize().unwrap_or_else(|_| project_dir.to_path_buf())
+}
+
+/// Start watching a project, alongside any already watched. Does nothing if the
+/// project is already watched.
+pub fn start_watcher(app: tauri::AppHandle, project_dir: PathBuf) -> Result<(), AppError> {
+    let key = registry_key(&project_dir);
+    let mut watchers = WATCHERS.lock().unwrap_or_else(|e| e.into_inner());
+    if watchers.contains_key(&key) {
+        return Ok(());
+    }
+    let watcher = spawn_watcher(app, project_dir.clone())?;
+    watchers.insert(key, (project_dir, watcher));
+    Ok(())
+}
+
+/// Stop watching a project and drop its queued indexing work.
+/// Returns false if it wasn't watched.
+pub fn stop_watcher(project_dir: &Path) -> bool {
+    let removed = WATCHERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&registry_key(project_dir));
+    match removed {
+        Some((dir, watcher)) => {
+            drop(watcher);
+            clear_queues(&dir);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Stop every watcher.
+pub fn stop_all_watchers() {
+    let watched: Vec<PathBuf> = watched_projects();
+    for dir in watched {
+        stop_watcher(&dir);
+    }
+}
+
+/// Watch only this project: stop the watchers of every other project, then start this one.
+pub fn switch_watcher(app: tauri::AppHandle, project_dir: PathBuf) -> Result<(), AppError> {
+    let key = registry_key(&project_dir);
+    for dir in watched_projects() {
+        if registry_key(&dir) != key {
+            stop_watcher(&dir);
+        }
+    }
+    start_watcher(app, project_dir)
+}
+
+/// Directories of the projects being watched.
+pub fn watched_projects() -> Vec<PathBuf> {
+    WATCHERS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .map(|(dir, _)| dir.clone())
+        .collect()
+}
+
+fn spawn_watcher(app: tauri::AppHandle, project_dir: PathBuf) -> Result<ProjectWatcher, AppError> {
+    use notify::{Watcher, RecursiveMode};
+
+    // The notify callback runs on its own thread; hand events to the async pipeline
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Event>();
     let mut watcher = notify::recommended_watcher(move |res: Result<Event, notify::Error>| {
-        if let Ok(event) = res {
-            let paths: Vec<String> = event.paths.iter()
-                .map(|p| p.to_string_lossy().to_string())
-                .collect();
+        match res {
+            Ok(event) => {
+                let _ = tx.send(event);
+            }
+            Err(e) => eprintln!("Warning: file watcher error: {}", e),
+        }
+    }).map_err(|e| AppError::General(format!("Watcher error: {}", e)))?;
 
-            if paths.is_empty() {
-                return;
+    watcher.watch(&project_dir, RecursiveMode::Recursive)
+        .map_err(|e| AppError::General(format!("Watch error: {}", e)))?;
+
+    let pipeline = tauri::async_runtime::spawn(event_pipeline(app.clone(), project_dir.clone(), rx));
+    // Always start the background indexer task — it checks config on every tick
+    let indexer = tauri::async_runtime::spawn(background_indexer_loop(app, project_dir));
+
+    Ok(ProjectWatcher { _watcher: watcher, pipeline, indexer })
+}
+
+/// Remove a project's files from the indexing queues.
+fn clear_queues(project_dir: &Path) {
+    if let Ok(mut pending) = PENDING_FILES.lock() {
+        pending.retain(|path, _| !Path::new(path).starts_with(project_dir));
+    }
+    if let Ok(mut deleted) = DELETED_FILES.lock() {
+        deleted.retain(|path| !Path::new(path).starts_with(project_dir));
+    }
+}
+
+// ─── Event pipeline ───
+
+/// A file system change after coalescing a batch of raw events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsChange {
+    Created(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
+    Renamed { from: PathBuf, to: PathBuf },
+}
+
+/// Collect raw events into debounced batches, coalesce each batch and dispatch the
+/// result. Ends when the watcher is dropped and the channel closes.
+async fn event_pipeline(
+    app: tauri::AppHandle,
+    project_dir: PathBuf,
+    mut rx: tokio::sync::mpsc::UnboundedReceiver<Event>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut batch = vec![first];
+        let deadline = Instant::now() + MAX_BATCH_WAIT;
+        loop {
+            let wait = DEBOUNCE.min(deadline.saturating_duration_since(Instant::now()));
+            match tokio::time::timeout(wait, rx.recv()).await {
+                Ok(Some(event)) => batch.push(event),
+                Ok(None) | Err(_) => break,
             }
+        }
+        for change in coalesce(&batch) {
+            dispatch(&app, &project_dir, &change);
+        }
+    }
+}
 
-            let path = &paths[0];
+/// What a batch has done to one path so far.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathState {
+    Created,
+    Modified,
+    Removed,
+    /// Now holds what was at this path before the batch
+    RenamedFrom(PathBuf),
+}
 
-            // Queue indexable files for vector search
-            let is_md = path.ends_with(".md");
-            let is_hidden = path.contains("/.saipling/") || path.contains("\\.saipling\\");
+/// Reduce a batch of raw events to one change per path, in order of first
+/// appearance. Every path of every event counts. Rename halves are paired by
+/// tracker (`From` + `To`, or `Both` with both paths); an unpaired half becomes a
+/// removal or creation depending on whether the path still exists.
+pub fn coalesce(events: &[Event]) -> Vec<FsChange> {
+    // Trackers with a destination in this batch; their `From` is half of a rename
+    let paired: HashSet<usize> = events
+        .iter()
+        .filter(|e| matches!(e.kind, EventKind::Modify(ModifyKind::Name(RenameMode::To | RenameMode::Both))))
+        .filter_map(|e| e.tracker())
+        .collect();
+    let mut rename_from: HashMap<usize, PathBuf> = HashMap::new();
+    // Trackers already applied, so inotify's `To` + `Both` pair counts once
+    let mut renamed: HashSet<usize> = HashSet::new();
+    let mut states: Vec<(PathBuf, PathState)> = Vec::new();
 
-            match event.kind {
-                EventKind::Modify(_) => {
-                    let _ = app_handle.emit("fs:file_changed", serde_json::json!({
-                        "path": path,
-                        "change_type": "modified"
-                    }));
-                    if is_md && !is_hidden {
-                        if let Ok(mut pending) = PENDING_FILES.lock() {
-                            pending.insert(path.clone(), Instant::now());
-                        }
-                    }
+    for event in events {
+        let tracker = event.tracker();
+        match event.kind {
+            EventKind::Access(_) => {}
+            EventKind::Create(_) => {
+                for path in &event.paths {
+                    apply(&mut states, path, PathState::Created);
                 }
-                EventKind::Create(_) => {
-                    let _ = app_handle.emit("fs:file_created", serde_json::json!({
-                        "path": path
-                    }));
-                    if is_md && !is_hidden {
-                        if let Ok(mut pending) = PENDING_FILES.lock() {
-                            pending.insert(path.clone(), Instant::now());
-                        }
-                    }
+            }
+            EventKind::Remove(_) => {
+                for path in &event.paths {
+                    apply(&mut states, path, PathState::Removed);
                 }
-                EventKind::Remove(_) => {
-                    let _ = app_handle.emit("fs:file_deleted", serde_json::json!({
-                        "path": path
-                    }));
-                    if is_md && !is_hidden {
-                        // Remove from pending (if queued) and add to deleted
-                        if let Ok(mut pending) = PENDING_FILES.lock() {
-                            pending.remove(path);
-                        }
-                        if let Ok(mut deleted) = DELETED_FILES.lock() {
-                            deleted.push(path.clone());
-                        }
-                    }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                if tracker.is_none_or(|t| renamed.insert(t)) {
+                    rename(&mut states, &event.paths[0], &event.paths[1]);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From))
+                if tracker.is_some_and(|t| paired.contains(&t)) && event.paths.len() == 1 =>
+            {
+                rename_from.insert(tracker.unwrap_or_default(), event.paths[0].clone());
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To))
+                if tracker.is_some_and(|t| rename_from.contains_key(&t)) && event.paths.len() == 1 =>
+            {
+                let t = tracker.unwrap_or_default();
+                if let Some(from) = rename_from.remove(&t).filter(|_| renamed.insert(t)) {
+                    rename(&mut states, &from, &event.paths[0]);
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(_)) => {
+                for path in &event.paths {
+                    let state = if path.exists() { PathState::Created } else { PathState::Removed };
+                    apply(&mut states, path, state);
+                }
+            }
+            EventKind::Modify(_) => {
+                for path in &event.paths {
+                    apply(&mut states, path, PathState::Modified);
+                }
+            }
+            _ => {
+                for path in &event.paths {
+                    let state = if path.exists() { PathState::Modified } else { PathState::Removed };
+                    apply(&mut states, path, state);
                 }
-                _ => {}
             }
         }
-    }).map_err(|e| crate::error::AppError::General(format!("Watcher error: {}", e)))?;
+    }
 
-    watcher.watch(&project_dir, RecursiveMode::Recursive)
-        .map_err(|e| crate::error::AppError::General(format!("Watch error: {}", e)))?;
+    states
+        .into_iter()
+        .map(|(path, state)| match state {
+            PathState::Created => FsChange::Created(path),
+            PathState::Modified => FsChange::Modified(path),
+            PathState::Removed => FsChange::Removed(path),
+            PathState::RenamedFrom(from) => FsChange::Renamed { from, to: path },
+        })
+        .collect()
+}
 
-    // Keep watcher alive by leaking it (it lives for the app's lifetime)
-    std::mem::forget(watcher);
+fn apply(states: &mut Vec<(PathBuf, PathState)>, path: &Path, next: PathState) {
+    let Some(i) = states.iter().position(|(p, _)| p == path) else {
+        states.push((path.to_path_buf(), next));
+        return;
+    };
+    let merged = match (&states[i].1, next) {
+        (PathState::Created, PathState::Modified) => Some(PathState::Created),
+        // Created and gone again within the batch: nothing to report
+        (PathState::Created, PathState::Removed) => None,
+        (PathState::Removed, PathState::Created | PathState::Modified) => Some(PathState::Modified),
+        (PathState::RenamedFrom(from), PathState::Modified) => Some(PathState::RenamedFrom(from.clone())),
+        (PathState::RenamedFrom(from), PathState::Removed) => {
+            // The original file was moved here and then deleted
+            let from = from.clone();
+            states.remove(i);
+            if !states.iter().any(|(p, _)| *p == from) {
+                states.push((from, PathState::Removed));
+            }
+            return;
+        }
+        (_, next) => Some(next),
+    };
+    match merged {
+        Some(state) => states[i].1 = state,
+        None => {
+            states.remove(i);
+        }
+    }
+}
 
-    // Always start the background indexer task — it checks config on every tick
-    tauri::async_runtime::spawn(background_indexer_loop(app, project_dir));
+fn rename(states: &mut Vec<(PathBuf, PathState)>, from: &Path, to: &Path) {
+    let before = states.iter().position(|(p, _)| p == from).map(|i| states.remove(i).1);
+    let origin = match before {
+        // Created in this batch: the destination is simply new
+        Some(PathState::Created) => None,
+        Some(PathState::RenamedFrom(origin)) => Some(origin),
+        _ => Some(from.to_path_buf()),
+    };
+    let state = match origin {
+        Some(origin) if origin == to => PathState::Modified,
+        Some(origin) => PathState::RenamedFrom(origin),
+        None => PathState::Created,
+    };
+    states.retain(|(p, _)| p != to);
+    states.push((to.to_path_buf(), state));
+}
 
-    Ok(())
+/// Report a change to the frontend, unless the app made it itself, and queue
+/// indexing work for it.
+fn dispatch(app: &tauri::AppHandle, project_dir: &Path, change: &FsChange) {
+    let in_app_data = |path: &Path| path.components().any(|c| c.as_os_str() == ".saipling");
+    let project = project_dir.to_string_lossy();
+    match change {
+        // An atomic write's temporary file renamed over its target is a modification
+        FsChange::Renamed { from, to } if crate::file_io::is_temp_file(from) => {
+            dispatch(app, project_dir, &FsChange::Modified(to.clone()));
+        }
+        FsChange::Created(path) | FsChange::Modified(path) | FsChange::Removed(path)
+            if crate::file_io::is_temp_file(path) => {}
+        FsChange::Created(path) | FsChange::Modified(path) | FsChange::Removed(path) if in_app_data(path) => {}
+        FsChange::Renamed { from, to } if in_app_data(from) || in_app_data(to) => {}
+        FsChange::Created(path) => {
+            if !is_own_change(path) {
+                let _ = app.emit("fs:file_created", serde_json::json!({
+                    "path": path.to_string_lossy(),
+                    "project_dir": project,
+                }));
+                crate::commands::writing::record_draft_change(path, None);
+            }
+            queue_created(project_dir, path);
+        }
+        FsChange::Modified(path) => {
+            // Directory metadata changes aren't interesting
+            if path.is_dir() {
+                return;
+            }
+            if !is_own_change(path) {
+                let _ = app.emit("fs:file_changed", serde_json::json!({
+                    "path": path.to_string_lossy(),
+                    "change_type": "modified",
+                    "project_dir": project,
+                }));
+                // Words written in another editor count towards the writing history
+                crate::commands::writing::record_draft_change(path, None);
+            }
+            queue_created(project_dir, path);
+        }
+        FsChange::Removed(path) => {
+            if !is_own_change(path) {
+                let _ = app.emit("fs:file_deleted", serde_json::json!({
+                    "path": path.to_string_lossy(),
+                    "project_dir": project,
+                }));
+            }
+            queue_removed(project_dir, path);
+        }
+        FsChange::Renamed { from, to } => {
+            if !(is_own_change(from) && is_own_change(to)) {
+                let _ = app.emit("fs:file_renamed", serde_json::json!({
+                    "from": from.to_string_lossy(),
+                    "to": to.to_string_lossy(),
+                    "project_dir": project,
+                }));
+                crate::commands::writing::record_draft_change(to, None);
+            }
+            queue_removed(project_dir, from);
+            queue_created(project_dir, to);
+        }
+    }
+}
+
+/// Whether the indexer picks up this project-relative path (matches `collect_indexable_files`).
+fn is_indexable(rel_path: &str) -> bool {
+    rel_path.ends_with(".md")
+        && !rel_path
+            .split('/')
+            .any(|part| part.starts_with('.') || part == "exports" || part == "node_modules")
+}
+
+/// Queue a new or changed file — or every file of a new directory — for indexing.
+pub fn queue_created(project_dir: &Path, path: &Path) {
+    let Some(rel_path) = ProjectPath::from_absolute(project_dir, path) else {
+        return;
+    };
+    let files: Vec<ProjectPath> = if path.is_dir() {
+        crate::context::vector::indexer::collect_indexable_files(&project_dir.to_path_buf())
+            .iter()
+            .filter_map(|f| ProjectPath::new(f).ok())
+            .filter(|f| f.is_within(&rel_path))
+            .collect()
+    } else if is_indexable(rel_path.as_str()) {
+        vec![rel_path]
+    } else {
+        return;
+    };
+    if let Ok(mut pending) = PENDING_FILES.lock() {
+        for rel in files {
+            pending.insert(rel.to_absolute(project_dir).to_string_lossy().to_string(), Instant::now());
+        }
+    }
+}
+
+/// Queue a removed file or directory for removal from the index.
+pub fn queue_removed(project_dir: &Path, path: &Path) {
+    let abs_path = path.to_string_lossy().to_string();
+    let Some(rel_path) = ProjectPath::from_absolute(project_dir, path) else {
+        return;
+    };
+    // A removed directory can't be told from a removed extensionless file; both are queued
+    if rel_path.as_str().ends_with(".md") && !is_indexable(rel_path.as_str()) {
+        return;
+    }
+    if let Ok(mut pending) = PENDING_FILES.lock() {
+        pending.retain(|p, _| !Path::new(p).starts_with(path));
+    }
+    if let Ok(mut deleted) = DELETED_FILES.lock() {
+        deleted.push(abs_path);
+    }
+}
+
+// ─── Own writes ───
+
+/// How long a recorded write suppresses the watcher's report of it.
+const OWN_WRITE_TTL: Duration = Duration::from_secs(10);
+
+struct OwnWrite {
+    at: Instant,
+    /// Modification time right after the write; None when the app removed the path
+    modified: Option<SystemTime>,
+}
+
+static OWN_WRITES: Lazy<Mutex<HashMap<PathBuf, OwnWrite>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Record that the app has just written, moved or deleted `path`, so the watcher
+/// doesn't report it as an external change. Call after the write. A later
+/// external edit changes the modification time and is reported as usual.
+pub fn note_own_write(path: &Path) {
+    let mut writes = OWN_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    writes.retain(|_, w| w.at.elapsed() < OWN_WRITE_TTL);
+    writes.insert(path.to_path_buf(), OwnWrite { at: Instant::now(), modified: modified_time(path) });
+}
+
+/// Whether the current state of `path` is the app's own doing: it matches a recent
+/// write, or the path is gone and it or a parent directory was removed by the app.
+fn is_own_change(path: &Path) -> bool {
+    let writes = OWN_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+    let current = modified_time(path);
+    path.ancestors().enumerate().any(|(depth, p)| match writes.get(p) {
+        Some(w) if w.at.elapsed() < OWN_WRITE_TTL => {
+            if depth == 0 {
+                w.modified == current
+            } else {
+                w.modified.is_none() && current.is_none()
+            }
+        }
+        _ => false,
+    })
 }
 
 /// Spawns a background tokio task that processes the pending/deleted file queues
@@ -86,7 +469,13 @@ pub fn start_watcher(app: tauri::AppHandle, project_dir: PathBuf) -> Result<(),
 async fn background_indexer_loop(app: tauri::AppHandle, project_dir: PathBuf) {
     const TICK_INTERVAL: Duration = Duration::from_secs(10);
     const QUIET_PERIOD: Duration = Duration::from_secs(120); // 2 minutes
+    const REEMBED_BATCH: u32 = 256;
     let mut startup_scan_done = false;
+    let mut reembed_announced = false;
+    // Whether the last tick left a re-embed unfinished, and the embedding settings
+    // it ran with; an idle tick with neither changed does nothing
+    let mut reembed_pending = true;
+    let mut last_settings = None;
 
     loop {
         tokio::time::sleep(TICK_INTERVAL).await;
@@ -96,10 +485,7 @@ async fn background_indexer_loop(app: tauri::AppHandle, project_dir: PathBuf) {
             Ok(c) => c,
             Err(_) => continue,
         };
-        if !config.vector_search.enabled
-            || !config.vector_search.auto_index
-            || config.vector_search.embedding_api_key_encrypted.is_empty()
-        {
+        if !config.vector_search.enabled || !config.vector_search.auto_index {
             continue;
         }
 
@@ -112,9 +498,11 @@ async fn background_indexer_loop(app: tauri::AppHandle, project_dir: PathBuf) {
                     let all_files = crate::context::vector::indexer::collect_indexable_files(&project_dir);
                     if let Ok(mut pending) = PENDING_FILES.lock() {
                         for rel_path in all_files {
-                            if !indexed.contains(&rel_path) {
-                                let abs_path = project_dir.join(rel_path.replace('/', "\\"))
-                                    .to_string_lossy().to_string();
+                            if indexed.contains(&rel_path) {
+                                continue;
+                            }
+                            if let Ok(rel) = ProjectPath::new(&rel_path) {
+                                let abs_path = rel.to_absolute(&project_dir).to_string_lossy().to_string();
                                 pending.insert(abs_path, Instant::now() - QUIET_PERIOD);
                             }
                         }
@@ -123,19 +511,30 @@ async fn background_indexer_loop(app: tauri::AppHandle, project_dir: PathBuf) {
             }
         }
 
-        // Process deleted files immediately
+        // Process deleted files immediately; other projects' entries stay queued for their own loop
         let deleted: Vec<String> = {
             match DELETED_FILES.lock() {
-                Ok(mut d) => d.drain(..).collect(),
+                Ok(mut d) => {
+                    let (mine, others) = d.drain(..).partition(|p| Path::new(p).starts_with(&project_dir));
+                    *d = others;
+                    mine
+                }
                 Err(_) => Vec::new(),
             }
         };
         for abs_path in &deleted {
-            let rel_path = match abs_to_rel(abs_path, &project_dir) {
-                Some(r) => r,
+            let rel_path = match ProjectPath::from_absolute(&project_dir, Path::new(abs_path)) {
+                Some(r) => r.to_string(),
                 None => continue,
             };
-            let _ = crate::context::vector::indexer::deindex_file(&project_dir, &rel_path);
+            let result = if rel_path.ends_with(".md") {
+                crate::context::vector::indexer::deindex_file(&project_dir, &rel_path)
+            } else {
+                crate::context::vector::indexer::deindex_dir(&project_dir, &rel_path)
+            };
+            if let Err(e) = result {
+                eprintln!("Background indexer: failed to remove {}: {}", rel_path, e);
+            }
         }
 
         // Check pending files — only process those past the quiet period
@@ -146,7 +545,9 @@ async fn background_indexer_loop(app: tauri::AppHandle, project_dir: PathBuf) {
                     let mut ready_paths = Vec::new();
                     let mut to_remove = Vec::new();
                     for (path, last_modified) in pending.iter() {
-                        if now.duration_since(*last_modified) >= QUIET_PERIOD {
+                        if now.duration_since(*last_modified) >= QUIET_PERIOD
+                            && Path::new(path).starts_with(&project_dir)
+                        {
                             ready_paths.push(path.clone());
                             to_remove.push(path.clone());
                         }
@@ -160,26 +561,52 @@ async fn background_indexer_loop(app: tauri::AppHandle, project_dir: PathBuf) {
             }
         };
 
-        if ready.is_empty() {
+        let vs = &config.vector_search;
+        let settings = (vs.provider.clone(), vs.embedding_model.clone(), vs.embedding_dimensions, vs.embedding_quantization.clone());
+        if ready.is_empty() && !reembed_pending && last_settings.as_ref() == Some(&settings) {
             continue;
         }
+        last_settings = Some(settings);
 
-        // Decode the Voyage API key
-        let api_key = match decode_voyage_key(&config.vector_search.embedding_api_key_encrypted) {
-            Ok(k) => k,
-            Err(_) => continue,
+        let embedding = match config.vector_search.embedding_config() {
+            Ok(e) => e,
+            Err(e) => {
+                eprintln!("Background indexer: {}", e);
+                continue;
+            }
+        };
+
+        // A running re-index writes the same rows; leave edits queued until it finishes
+        let Some(_guard) = crate::context::vector::indexer::IndexingGuard::acquire() else {
+            if let Ok(mut pending) = PENDING_FILES.lock() {
+                for abs_path in ready {
+                    pending.entry(abs_path).or_insert(now - QUIET_PERIOD);
+                }
+            }
+            reembed_pending = true;
+            continue;
+        };
+
+        // Migrate the vector index if the quantization setting changed
+        if let Err(e) = crate::context::vector::indexer::sync_vector_index(&project_dir, &embedding) {
+            eprintln!("Background indexer: {}", e);
+        }
+
+        // Without a usable embedding provider, files are indexed for lexical search only
+        let client = match embeddings::client_from_config(&embedding) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("Background indexer: {}", e);
+                continue;
+            }
         };
-        let client = crate::context::vector::embeddings::VoyageClient::new(
-            api_key,
-            config.vector_search.embedding_model.clone(),
-        );
 
         for abs_path in &ready {
-            let rel_path = match abs_to_rel(abs_path, &project_dir) {
-                Some(r) => r,
+            let rel_path = match ProjectPath::from_absolute(&project_dir, Path::new(abs_path)) {
+                Some(r) => r.to_string(),
                 None => continue,
             };
-            match crate::context::vector::indexer::index_file(&project_dir, &rel_path, &client).await {
+            match crate::context::vector::indexer::index_file(&project_dir, &rel_path, client.as_deref()).await {
                 Ok(_) => {
                     let _ = app.emit("vector:file_indexed", serde_json::json!({
                         "file": rel_path,
@@ -190,40 +617,111 @@ async fn background_indexer_loop(app: tauri::AppHandle, project_dir: PathBuf) {
                 }
             }
         }
+
+        // If the embedding model changed, re-embed a batch per tick; searches keep
+        // using the old vectors until every chunk has a new one
+        reembed_pending = false;
+        if let Some(client) = client.as_deref() {
+            match crate::context::vector::indexer::reembed_step(&project_dir, client, REEMBED_BATCH).await {
+                Ok(Some(progress)) => {
+                    reembed_pending = !progress.complete;
+                    if !reembed_announced {
+                        reembed_announced = true;
+                        let _ = app.emit("vector:embedding_mismatch", serde_json::json!({
+                            "provider": client.provider(),
+                            "model": client.model(),
+                        }));
+                    }
+                    let _ = app.emit("vector:reembed_progress", &progress);
+                    if progress.complete {
+                        reembed_announced = false;
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("Background indexer: re-embed failed: {}", e);
+                }
+            }
+        }
     }
 }
 
-/// Convert an absolute path to a project-relative forward-slash path.
-fn abs_to_rel(abs_path: &str, project_dir: &PathBuf) -> Option<String> {
-    let abs = PathBuf::from(abs_path);
-    abs.strip_prefix(project_dir)
-        .ok()
-        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RemoveKind};
 
-/// Decode the base64-encoded Voyage API key.
-fn decode_voyage_key(encoded: &str) -> Result<String, ()> {
-    let input = encoded.trim().as_bytes();
-    if input.is_empty() || input.len() % 4 != 0 {
-        return Err(());
-    }
-    let mut output = Vec::with_capacity(input.len() * 3 / 4);
-    for chunk in input.chunks(4) {
-        let vals: Vec<u8> = chunk.iter().map(|&c| match c {
-            b'A'..=b'Z' => c - b'A',
-            b'a'..=b'z' => c - b'a' + 26,
-            b'0'..=b'9' => c - b'0' + 52,
-            b'+' => 62,
-            b'/' => 63,
-            _ => 0,
-        }).collect();
-        output.push((vals[0] << 2) | (vals[1] >> 4));
-        if chunk[2] != b'=' {
-            output.push((vals[1] << 4) | (vals[2] >> 2));
+    fn event(kind: EventKind, paths: &[&str], tracker: Option<usize>) -> Event {
+        let mut event = Event::new(kind);
+        for path in paths {
+            event = event.add_path(PathBuf::from(path));
         }
-        if chunk[3] != b'=' {
-            output.push((vals[2] << 6) | vals[3]);
+        match tracker {
+            Some(t) => event.set_tracker(t),
+            None => event,
         }
     }
-    String::from_utf8(output).map_err(|_| ())
+
+    #[test]
+    fn test_coalesce_events() {
+        let created = EventKind::Create(CreateKind::File);
+        let modified = EventKind::Modify(ModifyKind::Data(DataChange::Content));
+        let removed = EventKind::Remove(RemoveKind::File);
+        let name = |mode| EventKind::Modify(ModifyKind::Name(mode));
+
+        // inotify reports a rename as From, To and Both with the same tracker
+        let changes = coalesce(&[
+            event(modified, &["/p/a.md"], None),
+            event(name(RenameMode::From), &["/p/a.md"], Some(7)),
+            event(name(RenameMode::To), &["/p/b.md"], Some(7)),
+            event(name(RenameMode::Both), &["/p/a.md", "/p/b.md"], Some(7)),
+            event(modified, &["/p/b.md"], None),
+        ]);
+        assert_eq!(changes, vec![FsChange::Renamed { from: "/p/a.md".into(), to: "/p/b.md".into() }]);
+
+        // Every path counts; a file created and removed within the batch disappears
+        let changes = coalesce(&[
+            event(created, &["/p/new.md", "/p/other.md"], None),
+            event(modified, &["/p/new.md"], None),
+            event(removed, &["/p/other.md"], None),
+        ]);
+        assert_eq!(changes, vec![FsChange::Created("/p/new.md".into())]);
+
+        // A new file renamed is just created; renamed back is just modified
+        let changes = coalesce(&[
+            event(created, &["/p/tmp.md"], None),
+            event(name(RenameMode::Both), &["/p/tmp.md", "/p/c.md"], Some(1)),
+            event(name(RenameMode::Both), &["/p/d.md", "/p/e.md"], Some(2)),
+            event(name(RenameMode::Both), &["/p/e.md", "/p/d.md"], Some(3)),
+        ]);
+        assert_eq!(changes, vec![FsChange::Created("/p/c.md".into()), FsChange::Modified("/p/d.md".into())]);
+
+        // An unpaired half of a rename falls back on whether the path exists
+        let changes = coalesce(&[event(name(RenameMode::From), &["/p/missing.md"], Some(9))]);
+        assert_eq!(changes, vec![FsChange::Removed("/p/missing.md".into())]);
+    }
+
+    #[test]
+    fn test_own_writes_are_recognised() {
+        let dir = std::env::temp_dir().join(format!("saipling-watch-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("ch-01")).unwrap();
+        let file = dir.join("scene.md");
+
+        std::fs::write(&file, "draft").unwrap();
+        note_own_write(&file);
+        assert!(is_own_change(&file));
+
+        // An external edit afterwards changes the modification time
+        let later = SystemTime::now() + Duration::from_secs(5);
+        std::fs::File::options().write(true).open(&file).unwrap().set_modified(later).unwrap();
+        assert!(!is_own_change(&file));
+
+        // Removing a directory covers the files that were in it
+        std::fs::write(dir.join("ch-01/scene.md"), "x").unwrap();
+        std::fs::remove_dir_all(dir.join("ch-01")).unwrap();
+        note_own_write(&dir.join("ch-01"));
+        assert!(is_own_change(&dir.join("ch-01/scene.md")));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
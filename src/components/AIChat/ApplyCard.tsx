@@ -5,6 +5,7 @@ import { useProjectStore } from '../../stores/projectStore';
 import { writeFile } from '../../utils/tauri';
 import { parseFrontmatter } from '../../utils/markdown';
 import { ApplyEditModal } from './ApplyEditModal';
+import { joinPath } from '../../utils/path';
 
 interface ApplyCardProps {
   block: ApplyBlock;
@@ -25,7 +26,7 @@ export function ApplyCard({ block }: ApplyCardProps) {
   const handleApply = async () => {
     if (!projectDir) return;
     try {
-      const fullPath = `${projectDir}\\${block.target.replace(/\//g, '\\\\')}`;
+      const fullPath = joinPath(projectDir, block.target);
       const { frontmatter, body } = parseFrontmatter(block.content);
       await writeFile(fullPath, frontmatter, body);
       useProjectStore.getState().bumpRefresh();
@@ -3,6 +3,7 @@ import { X, Check, FileText } from 'lucide-react';
 import { useProjectStore } from '../../stores/projectStore';
 import { writeFile } from '../../utils/tauri';
 import { parseFrontmatter } from '../../utils/markdown';
+import { joinPath } from '../../utils/path';
 
 interface ApplyEditModalProps {
   target: string;
@@ -22,7 +23,7 @@ export function ApplyEditModal({ target, content, onClose, onApplied }: ApplyEdi
     setSaving(true);
     setError('');
     try {
-      const fullPath = `${projectDir}\\${target.replace(/\//g, '\\\\')}`;
+      const fullPath = joinPath(projectDir, target);
       const { frontmatter, body } = parseFrontmatter(editedContent);
       await writeFile(fullPath, frontmatter, body);
       useProjectStore.getState().bumpRefresh();
@@ -128,7 +128,8 @@ export function ChatPanel({ width }: ChatPanelProps) {
         const preview = r.content_preview.length > 150
           ? r.content_preview.substring(0, 150) + '...'
           : r.content_preview;
-        response += `**${i + 1}.** \`${r.file_path}${section}\` (${score}% match)\n`;
+        const path = r.project ? `@${r.project}/${r.file_path}` : r.file_path;
+        response += `**${i + 1}.** \`${path}${section}\` (${score}% match)\n`;
         response += `> ${preview.replace(/\n/g, ' ')}\n\n`;
       });
 
@@ -157,7 +158,7 @@ export function ChatPanel({ width }: ChatPanelProps) {
       if (query.trim()) {
         await handleSearch(query.trim());
       } else {
-        appendToLastAssistant('Usage: `/search <your query>`\n\nExample: `/search Marcus\'s relationship with his father`');
+        appendToLastAssistant('Usage: `/search <your query>`\n\nExample: `/search Marcus\'s relationship with his father`\n\nFilters: `entity:` `type:` `name:` `book:` `ch:3..7` `pov:` `status:` `after:2025-06-01` `"exact phrase"`');
       }
       return;
     }
@@ -8,6 +8,13 @@ import {
   getBookMetadata,
   createChapter,
   createScene,
+  deleteChapter,
+  deleteScene,
+  renumberBook,
+  mergeScenes,
+  splitChapter,
+  mergeChapters,
+  moveChapterToBook,
   listFrontMatter,
   listBackMatter,
   createFrontMatter,
@@ -19,6 +26,7 @@ import {
 import type { BookMetadata, MatterEntry, PhaseProgress } from '../../types/project';
 import { PHASES } from '../../types/sapling';
 import { ExportDialog } from './ExportDialog';
+import { joinPath } from '../../utils/path';
 
 function getPhaseProgressPct(phaseProgress: Record<string, PhaseProgress>, phaseId: string): number {
   const p = phaseProgress[phaseId];
@@ -116,7 +124,7 @@ export function BookView() {
     if (!projectDir || !bookId) return;
     setOverviewChecked(false);
     setBookOverviewPath(null);
-    const overviewDir = `${projectDir}\\books\\${bookId}\\overview`;
+    const overviewDir = joinPath(projectDir, 'books', bookId, 'overview');
     listDirectory(overviewDir)
       .then((entries) => {
         const overview = entries.find((e) => e.name === 'overview.md');
@@ -134,7 +142,7 @@ export function BookView() {
   const handleBookBrainstorm = () => {
     if (!projectDir || !bookId) return;
     setActiveSkill('brainstorm');
-    setActiveFile(bookOverviewPath ?? `${projectDir}\\books\\${bookId}\\overview\\brainstorm.md`);
+    setActiveFile(bookOverviewPath ?? joinPath(projectDir, 'books', bookId, 'overview', 'brainstorm.md'));
   };
 
   const bumpRefresh = useProjectStore((s) => s.bumpRefresh);
@@ -160,12 +168,114 @@ export function BookView() {
     } catch { /* ignore */ }
   };
 
+  const handleDeleteChapter = async (chapterId: string) => {
+    if (!projectDir || !bookId) return;
+    try {
+      await deleteChapter(projectDir, bookId, chapterId);
+      if (useProjectStore.getState().activeChapterId === chapterId) {
+        useProjectStore.getState().setActiveChapter(null);
+        useProjectStore.getState().setActiveScene(null);
+      }
+      loadBook();
+      bumpRefresh();
+    } catch (e) {
+      console.error('Failed to delete chapter:', e);
+    }
+  };
+
+  const handleDeleteScene = async (chapterId: string, sceneId: string) => {
+    if (!projectDir || !bookId) return;
+    try {
+      await deleteScene(projectDir, bookId, chapterId, sceneId);
+      loadBook();
+      bumpRefresh();
+    } catch (e) {
+      console.error('Failed to delete scene:', e);
+    }
+  };
+
+  const handleMergeScenes = async (chapterId: string, sceneId: string, nextSceneId: string) => {
+    if (!projectDir || !bookId) return;
+    try {
+      await mergeScenes(projectDir, bookId, chapterId, sceneId, nextSceneId);
+      if (useProjectStore.getState().activeSceneId === nextSceneId) {
+        setActiveFile(null);
+        useProjectStore.getState().setActiveScene(null);
+      }
+      loadBook();
+      bumpRefresh();
+    } catch (e) {
+      console.error('Failed to merge scenes:', e);
+    }
+  };
+
+  const handleSplitChapter = async (chapterId: string, atScene: string) => {
+    if (!projectDir || !bookId) return;
+    try {
+      await splitChapter(projectDir, bookId, chapterId, atScene);
+      // Scene folders moved to the new chapter, so open paths are stale
+      setActiveFile(null);
+      loadBook();
+      bumpRefresh();
+    } catch (e) {
+      console.error('Failed to split chapter:', e);
+    }
+  };
+
+  const handleMergeChapters = async (chapterId: string, nextChapterId: string) => {
+    if (!projectDir || !bookId) return;
+    try {
+      await mergeChapters(projectDir, bookId, chapterId, nextChapterId);
+      if (useProjectStore.getState().activeChapterId === nextChapterId) {
+        setActiveFile(null);
+        useProjectStore.getState().setActiveChapter(null);
+        useProjectStore.getState().setActiveScene(null);
+      }
+      loadBook();
+      bumpRefresh();
+    } catch (e) {
+      console.error('Failed to merge chapters:', e);
+    }
+  };
+
+  const handleMoveChapterToBook = async (chapterId: string, toBookId: string) => {
+    if (!projectDir || !bookId) return;
+    try {
+      const target = await getBookMetadata(projectDir, toBookId);
+      await moveChapterToBook(projectDir, bookId, chapterId, toBookId, target.chapters.length);
+      if (useProjectStore.getState().activeChapterId === chapterId) {
+        setActiveFile(null);
+        useProjectStore.getState().setActiveChapter(null);
+        useProjectStore.getState().setActiveScene(null);
+      }
+      loadBook();
+      bumpRefresh();
+    } catch (e) {
+      console.error('Failed to move chapter to another book:', e);
+    }
+  };
+
+  const handleRenumber = async () => {
+    if (!projectDir || !bookId) return;
+    try {
+      await renumberBook(projectDir, bookId);
+      // Folder names changed, so open paths and selections are stale
+      setActiveFile(null);
+      useProjectStore.getState().setActiveChapter(null);
+      useProjectStore.getState().setActiveScene(null);
+      loadBook();
+      bumpRefresh();
+    } catch (e) {
+      console.error('Failed to renumber chapters:', e);
+    }
+  };
+
   const handleSceneSelect = (chapterId: string, sceneId: string) => {
     useProjectStore.getState().setActiveChapter(chapterId);
     useProjectStore.getState().setActiveScene(sceneId);
     // Build path to the scene draft file
     if (projectDir && bookId) {
-      const path = `${projectDir}\\books\\${bookId}\\chapters\\${chapterId}\\${sceneId}\\draft.md`;
+      const path = joinPath(projectDir, 'books', bookId, 'chapters', chapterId, sceneId, 'draft.md');
       setActiveFile(path);
     }
   };
@@ -469,6 +579,14 @@ export function BookView() {
             onSceneSelect={handleSceneSelect}
             onCreateChapter={handleCreateChapter}
             onCreateScene={handleCreateScene}
+            onDeleteChapter={handleDeleteChapter}
+            onDeleteScene={handleDeleteScene}
+            onRenumber={handleRenumber}
+            onMergeScenes={handleMergeScenes}
+            onSplitChapter={handleSplitChapter}
+            onMergeChapters={handleMergeChapters}
+            otherBooks={(project?.books ?? []).filter((b) => b.id !== bookId)}
+            onMoveChapterToBook={handleMoveChapterToBook}
           />
         </div>
 
@@ -1,6 +1,6 @@
 import { useState } from 'react';
-import { ChevronRight, ChevronDown, FileText, Plus, GripVertical } from 'lucide-react';
-import type { ChapterMeta, SceneMeta } from '../../types/project';
+import { ChevronRight, ChevronDown, FileText, Plus, GripVertical, Trash2, ListOrdered, Scissors, Merge, BookCopy } from 'lucide-react';
+import type { BookRef, ChapterMeta, SceneMeta } from '../../types/project';
 
 interface ChapterListProps {
   chapters: ChapterMeta[];
@@ -9,6 +9,14 @@ interface ChapterListProps {
   onSceneSelect: (chapterId: string, sceneId: string) => void;
   onCreateChapter: () => void;
   onCreateScene: (chapterId: string) => void;
+  onDeleteChapter: (chapterId: string) => void;
+  onDeleteScene: (chapterId: string, sceneId: string) => void;
+  onRenumber: () => void;
+  onMergeScenes: (chapterId: string, sceneId: string, nextSceneId: string) => void;
+  onSplitChapter: (chapterId: string, atScene: string) => void;
+  onMergeChapters: (chapterId: string, nextChapterId: string) => void;
+  otherBooks: BookRef[];
+  onMoveChapterToBook: (chapterId: string, toBookId: string) => void;
 }
 
 function statusColor(status: SceneMeta['status']): string {
@@ -36,10 +44,61 @@ export function ChapterList({
   onSceneSelect,
   onCreateChapter,
   onCreateScene,
+  onDeleteChapter,
+  onDeleteScene,
+  onRenumber,
+  onMergeScenes,
+  onSplitChapter,
+  onMergeChapters,
+  otherBooks,
+  onMoveChapterToBook,
 }: ChapterListProps) {
   const [expandedChapters, setExpandedChapters] = useState<Set<string>>(
     new Set(chapters.map((c) => c.id))
   );
+  // Deleting takes a second click on the same button
+  const [pendingDelete, setPendingDelete] = useState<string | null>(null);
+  // Chapter whose "move to book" list is open
+  const [movingChapter, setMovingChapter] = useState<string | null>(null);
+
+  const confirmDelete = (key: string, action: () => void) => (e: React.MouseEvent) => {
+    e.stopPropagation();
+    if (pendingDelete === key) {
+      setPendingDelete(null);
+      action();
+    } else {
+      setPendingDelete(key);
+    }
+  };
+
+  const deleteButton = (key: string, label: string, action: () => void) => (
+    <span
+      role="button"
+      onClick={confirmDelete(key, action)}
+      onMouseLeave={() => pendingDelete === key && setPendingDelete(null)}
+      title={pendingDelete === key ? `Click again to move this ${label} to the trash` : `Delete ${label}`}
+      className="flex items-center gap-1 text-xs hover-icon"
+      style={{ marginLeft: '6px', cursor: 'pointer', color: pendingDelete === key ? 'var(--color-error)' : 'var(--text-tertiary)' }}
+    >
+      <Trash2 size={12} />
+      {pendingDelete === key && 'Delete?'}
+    </span>
+  );
+
+  const actionButton = (icon: React.ReactNode, title: string, action: () => void) => (
+    <span
+      role="button"
+      onClick={(e) => { e.stopPropagation(); action(); }}
+      title={title}
+      className="flex items-center text-xs hover-icon"
+      style={{ marginLeft: '6px', cursor: 'pointer', color: 'var(--text-tertiary)' }}
+    >
+      {icon}
+    </span>
+  );
+
+  // Display numbers follow the reading order, whatever the folder ids are
+  const sortedChapters = [...chapters].sort((a, b) => a.sort_order - b.sort_order);
 
   const toggleChapter = (id: string) => {
     setExpandedChapters((prev) => {
@@ -56,14 +115,27 @@ export function ChapterList({
         <h3 className="text-xs font-semibold uppercase tracking-wider" style={{ color: 'var(--text-tertiary)' }}>
           Chapters
         </h3>
-        <button
-          onClick={onCreateChapter}
-          className="flex items-center gap-1 text-xs hover-action"
-          style={{ color: 'var(--accent)', background: 'none', border: 'none', cursor: 'pointer' }}
-        >
-          <Plus size={12} />
-          Add
-        </button>
+        <div className="flex items-center gap-3">
+          {chapters.length > 0 && (
+            <button
+              onClick={onRenumber}
+              title="Rename chapter and scene folders to match the reading order"
+              className="flex items-center gap-1 text-xs hover-action"
+              style={{ color: 'var(--text-tertiary)', background: 'none', border: 'none', cursor: 'pointer' }}
+            >
+              <ListOrdered size={12} />
+              Renumber
+            </button>
+          )}
+          <button
+            onClick={onCreateChapter}
+            className="flex items-center gap-1 text-xs hover-action"
+            style={{ color: 'var(--accent)', background: 'none', border: 'none', cursor: 'pointer' }}
+          >
+            <Plus size={12} />
+            Add
+          </button>
+        </div>
       </div>
 
       {chapters.length === 0 ? (
@@ -72,9 +144,11 @@ export function ChapterList({
         </p>
       ) : (
         <div style={{ display: 'flex', flexDirection: 'column', gap: '2px' }}>
-          {chapters.map((chapter) => {
+          {sortedChapters.map((chapter, chapterIndex) => {
             const isExpanded = expandedChapters.has(chapter.id);
             const totalWords = chapter.scenes.reduce((sum, s) => sum + s.word_count, 0);
+            const nextChapter = sortedChapters[chapterIndex + 1];
+            const sortedScenes = [...chapter.scenes].sort((a, b) => a.sort_order - b.sort_order);
 
             return (
               <div key={chapter.id}>
@@ -89,17 +163,39 @@ export function ChapterList({
                   <span style={{ marginRight: '6px', color: 'var(--text-tertiary)' }}>
                     {isExpanded ? <ChevronDown size={14} /> : <ChevronRight size={14} />}
                   </span>
+                  <span className="text-xs" style={{ color: 'var(--text-tertiary)', marginRight: '6px' }}>
+                    {chapterIndex + 1}.
+                  </span>
                   <span className="text-sm font-medium flex-1 truncate" style={{ color: 'var(--text-primary)' }}>
                     {chapter.title}
                   </span>
                   <span className="text-xs" style={{ color: 'var(--text-tertiary)', marginLeft: '8px' }}>
                     {chapter.scenes.length} scene{chapter.scenes.length !== 1 ? 's' : ''} · {totalWords.toLocaleString()}w
                   </span>
+                  {otherBooks.length > 0 && actionButton(<BookCopy size={12} />, 'Move to another book', () => setMovingChapter(movingChapter === chapter.id ? null : chapter.id))}
+                  {nextChapter && actionButton(<Merge size={12} />, `Merge with "${nextChapter.title}"`, () => onMergeChapters(chapter.id, nextChapter.id))}
+                  {deleteButton(chapter.id, 'chapter', () => onDeleteChapter(chapter.id))}
                 </button>
 
+                {movingChapter === chapter.id && (
+                  <div className="flex items-center gap-2 text-xs" style={{ padding: '4px 10px 6px 38px', color: 'var(--text-tertiary)' }}>
+                    Move to:
+                    {otherBooks.map((b) => (
+                      <button
+                        key={b.id}
+                        onClick={() => { setMovingChapter(null); onMoveChapterToBook(chapter.id, b.id); }}
+                        className="hover-action"
+                        style={{ color: 'var(--accent)', background: 'none', border: 'none', cursor: 'pointer' }}
+                      >
+                        {b.title}
+                      </button>
+                    ))}
+                  </div>
+                )}
+
                 {isExpanded && (
                   <div style={{ paddingLeft: '28px' }}>
-                    {chapter.scenes.map((scene) => (
+                    {sortedScenes.map((scene, sceneIndex) => (
                       <button
                         key={scene.id}
                         onClick={() => onSceneSelect(chapter.id, scene.id)}
@@ -109,6 +205,9 @@ export function ChapterList({
                         onMouseLeave={(e) => (e.currentTarget.style.backgroundColor = 'transparent')}
                       >
                         <FileText size={12} style={{ color: 'var(--text-tertiary)', marginRight: '8px', flexShrink: 0 }} />
+                        <span className="text-xs" style={{ color: 'var(--text-tertiary)', marginRight: '6px' }}>
+                          {chapterIndex + 1}.{sceneIndex + 1}
+                        </span>
                         <span className="text-xs flex-1 truncate" style={{ color: 'var(--text-primary)' }}>
                           {scene.title}
                         </span>
@@ -119,6 +218,9 @@ export function ChapterList({
                         >
                           {scene.word_count > 0 ? `${scene.word_count.toLocaleString()}w` : statusLabel(scene.status)}
                         </span>
+                        {sceneIndex > 0 && actionButton(<Scissors size={12} />, 'Start a new chapter at this scene', () => onSplitChapter(chapter.id, scene.id))}
+                        {sceneIndex < sortedScenes.length - 1 && actionButton(<Merge size={12} />, `Merge with "${sortedScenes[sceneIndex + 1].title}"`, () => onMergeScenes(chapter.id, scene.id, sortedScenes[sceneIndex + 1].id))}
+                        {deleteButton(`${chapter.id}/${scene.id}`, 'scene', () => onDeleteScene(chapter.id, scene.id))}
                       </button>
                     ))}
                     <button
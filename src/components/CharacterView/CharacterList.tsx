@@ -3,6 +3,7 @@ import { Users, FileText, Plus, Link2, X, Check } from 'lucide-react';
 import { useProjectStore } from '../../stores/projectStore';
 import { listDirectory, createDirectory, writeFile, loadTemplate, readFile } from '../../utils/tauri';
 import type { FileEntry } from '../../types/project';
+import { joinPath } from '../../utils/path';
 
 export function CharacterList() {
   const projectDir = useProjectStore((s) => s.projectDir);
@@ -16,7 +17,7 @@ export function CharacterList() {
   const [newCharName, setNewCharName] = useState('');
   const inputRef = useRef<HTMLInputElement>(null);
 
-  const charsDir = projectDir ? `${projectDir}\\characters` : null;
+  const charsDir = projectDir ? joinPath(projectDir, 'characters') : null;
 
   const loadCharacters = useCallback(async () => {
     if (!charsDir) return;
@@ -47,7 +48,7 @@ export function CharacterList() {
       const result: Record<string, boolean> = {};
       for (const char of characters) {
         try {
-          await readFile(`${char.path}\\profile.md`);
+          await readFile(joinPath(char.path, 'profile.md'));
           result[char.name] = true;
         } catch {
           result[char.name] = false;
@@ -68,8 +69,8 @@ export function CharacterList() {
     if (!newCharName.trim() || !charsDir) return;
     const name = newCharName.trim();
     const slug = name.toLowerCase().replace(/\s+/g, '-');
-    const charDir = `${charsDir}\\${slug}`;
-    const brainstormPath = `${charDir}\\brainstorm.md`;
+    const charDir = joinPath(charsDir, slug);
+    const brainstormPath = joinPath(charDir, 'brainstorm.md');
     setShowNewModal(false);
     setNewCharName('');
     try {
@@ -169,7 +170,7 @@ export function CharacterList() {
                   return (
                     <button
                       key={char.path}
-                      onClick={() => setActiveFile(`${char.path}\\brainstorm.md`)}
+                      onClick={() => setActiveFile(joinPath(char.path, 'brainstorm.md'))}
                       className="flex items-start gap-3 text-left rounded-xl transition-all"
                       style={{
                         padding: '14px',
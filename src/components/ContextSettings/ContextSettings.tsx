@@ -8,6 +8,7 @@ import { useProjectStore } from '../../stores/projectStore';
 import { listDirectory, deleteEntry, readFile, writeFile, revealInExplorer, vectorSearch } from '../../utils/tauri';
 import type { FileEntry } from '../../types/project';
 import type { SearchResult } from '../../types/vectorSearch';
+import { joinPath } from '../../utils/path';
 
 type ContextMode = 'auto' | 'exclude' | 'force';
 
@@ -30,7 +31,7 @@ export function ContextSettings() {
   const [searchResults, setSearchResults] = useState<SearchResult[]>([]);
   const [isSearching, setIsSearching] = useState(false);
 
-  const settingsPath = projectDir ? `${projectDir}\\.context_settings.json` : null;
+  const settingsPath = projectDir ? joinPath(projectDir, '.context_settings.json') : null;
 
   const loadContextSettings = useCallback(async () => {
     if (!settingsPath) return;
@@ -121,7 +122,7 @@ export function ContextSettings() {
   };
 
   const handleForceInclude = async (filePath: string) => {
-    const absPath = projectDir ? `${projectDir}\\${filePath.replace(/\//g, '\\')}` : filePath;
+    const absPath = projectDir ? joinPath(projectDir, filePath) : filePath;
     await handleContextChange(absPath, 'force');
   };
 
@@ -257,29 +258,33 @@ export function ContextSettings() {
               >
                 <div className="flex items-center justify-between" style={{ marginBottom: '4px' }}>
                   <span className="text-xs font-medium truncate" style={{ color: 'var(--text-primary)', flex: 1 }}>
-                    {r.file_path}{section}
+                    {r.project ? `@${r.project}/` : ''}{r.file_path}{section}
                   </span>
                   <div className="flex items-center gap-2 shrink-0" style={{ marginLeft: '8px' }}>
                     <span className="text-xs" style={{ color: 'var(--text-tertiary)' }}>{score}%</span>
-                    <button
-                      onClick={() => {
-                        const absPath = projectDir ? `${projectDir}\\${r.file_path.replace(/\//g, '\\')}` : r.file_path;
-                        setActiveFile(absPath);
-                      }}
-                      title="Open in editor"
-                      className="flex items-center justify-center hover-icon"
-                      style={{ color: 'var(--accent)', background: 'none', border: 'none', cursor: 'pointer', padding: '2px' }}
-                    >
-                      <Pencil size={12} />
-                    </button>
-                    <button
-                      onClick={() => handleForceInclude(r.file_path)}
-                      title="Force include in context"
-                      className="flex items-center justify-center hover-icon"
-                      style={{ color: 'var(--color-success)', background: 'none', border: 'none', cursor: 'pointer', padding: '2px' }}
-                    >
-                      <Plus size={12} />
-                    </button>
+                    {!r.project && (
+                      <>
+                        <button
+                          onClick={() => {
+                            const absPath = projectDir ? joinPath(projectDir, r.file_path) : r.file_path;
+                            setActiveFile(absPath);
+                          }}
+                          title="Open in editor"
+                          className="flex items-center justify-center hover-icon"
+                          style={{ color: 'var(--accent)', background: 'none', border: 'none', cursor: 'pointer', padding: '2px' }}
+                        >
+                          <Pencil size={12} />
+                        </button>
+                        <button
+                          onClick={() => handleForceInclude(r.file_path)}
+                          title="Force include in context"
+                          className="flex items-center justify-center hover-icon"
+                          style={{ color: 'var(--color-success)', background: 'none', border: 'none', cursor: 'pointer', padding: '2px' }}
+                        >
+                          <Plus size={12} />
+                        </button>
+                      </>
+                    )}
                   </div>
                 </div>
                 <p className="text-xs" style={{ color: 'var(--text-tertiary)', lineHeight: '1.4' }}>
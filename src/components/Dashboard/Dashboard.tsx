@@ -15,6 +15,7 @@ import { ContinueWorkingModal } from './ContinueWorkingModal';
 import type { BookMetadata } from '../../types/project';
 import { PHASES } from '../../types/sapling';
 import type { Phase } from '../../types/sapling';
+import { joinPath } from '../../utils/path';
 
 interface BookCardData {
   sceneWords: number;
@@ -114,7 +115,7 @@ export function Dashboard() {
       setActiveBookHasOverview(false);
       return;
     }
-    const bookOverviewDir = `${projectDir}\\books\\${activeBookId}\\overview`;
+    const bookOverviewDir = joinPath(projectDir, 'books', activeBookId, 'overview');
     listDirectory(bookOverviewDir)
       .then((entries) => {
         setActiveBookHasOverview(entries.some((e) => e.name === 'overview.md'));
@@ -129,7 +130,7 @@ export function Dashboard() {
 
   useEffect(() => {
     if (!projectDir) return;
-    const overviewDir = `${projectDir}\\overview`;
+    const overviewDir = joinPath(projectDir, 'overview');
     listDirectory(overviewDir)
       .then((entries) => {
         const overview = entries.find((e) => e.name === 'overview.md');
@@ -170,7 +171,7 @@ export function Dashboard() {
           getBookTotalDocWords(projectDir, book.id),
           getBookMetadata(projectDir, book.id),
         ]);
-        const bookOverviewDir = `${projectDir}\\books\\${book.id}\\overview`;
+        const bookOverviewDir = joinPath(projectDir, 'books', book.id, 'overview');
         let hasOverview = false;
         try {
           const entries = await listDirectory(bookOverviewDir);
@@ -182,7 +183,7 @@ export function Dashboard() {
         const seedStatus = (meta.phase_progress as Record<string, { status: string }>)['seed'];
         if (seedStatus?.status === 'complete') {
           try {
-            const loglineContent = await readFile(`${projectDir}\\books\\${book.id}\\phase-1-seed\\logline.md`);
+            const loglineContent = await readFile(joinPath(projectDir, 'books', book.id, 'phase-1-seed', 'logline.md'));
             logline = loglineContent.body.trim();
           } catch { /* no logline yet */ }
         }
@@ -208,7 +209,7 @@ export function Dashboard() {
   const handleBrainstorm = () => {
     if (!projectDir) return;
     setActiveSkill('brainstorm');
-    setActiveFile(overviewPath ?? `${projectDir}\\overview\\brainstorm.md`);
+    setActiveFile(overviewPath ?? joinPath(projectDir, 'overview', 'brainstorm.md'));
   };
 
   const handleCreateBook = async () => {
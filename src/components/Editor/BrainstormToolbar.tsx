@@ -8,6 +8,7 @@ import { trackCost } from '../../utils/projectCost';
 import { calculateCost } from '../../utils/modelPricing';
 import type { FileEntry } from '../../types/project';
 import type { AgentPlan } from '../../types/ai';
+import { joinPath } from '../../utils/path';
 
 interface BrainstormToolbarProps {
   currentFilePath: string;
@@ -55,7 +56,7 @@ export function BrainstormToolbar({ currentFilePath }: BrainstormToolbarProps) {
   const isBookLevel = !!bookOverviewMatch;
   const bookId = bookOverviewMatch?.[1] ?? null;
   const overviewDir = projectDir
-    ? (isBookLevel && bookId ? `${projectDir}\\books\\${bookId}\\overview` : `${projectDir}\\overview`)
+    ? (isBookLevel && bookId ? joinPath(projectDir, 'books', bookId, 'overview') : joinPath(projectDir, 'overview'))
     : null;
   const overviewLabel = isBookLevel ? 'Book Overview' : 'Project Overview';
   const [showWelcome, setShowWelcome] = useState(true);
@@ -159,7 +160,7 @@ export function BrainstormToolbar({ currentFilePath }: BrainstormToolbarProps) {
 
     // Determine versioned filename — never overwrite existing overviews
     const nextVersion = getNextOverviewVersion(files);
-    const overviewPath = `${overviewDir}\\${nextVersion}`;
+    const overviewPath = joinPath(overviewDir, nextVersion);
     try {
       const now = new Date().toISOString().slice(0, 10);
       const genFrontmatter: Record<string, unknown> = {
@@ -241,7 +242,7 @@ export function BrainstormToolbar({ currentFilePath }: BrainstormToolbarProps) {
     setCreatingBlank(true);
     try {
       const nextVersion = getNextOverviewVersion(files);
-      const blankPath = `${overviewDir}\\${nextVersion}`;
+      const blankPath = joinPath(overviewDir, nextVersion);
       const now = new Date().toISOString().slice(0, 10);
       const blankFrontmatter: Record<string, unknown> = {
         type: 'overview',
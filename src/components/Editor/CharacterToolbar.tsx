@@ -8,6 +8,7 @@ import { trackCost } from '../../utils/projectCost';
 import { calculateCost } from '../../utils/modelPricing';
 import { extractDraftBody } from '../../utils/applyParser';
 import type { AgentPlan } from '../../types/ai';
+import { joinPath } from '../../utils/path';
 
 interface CharacterToolbarProps {
   currentFilePath: string;
@@ -39,11 +40,11 @@ export function CharacterToolbar({ currentFilePath }: CharacterToolbarProps) {
   const charName = charSlug?.replace(/-/g, ' ').replace(/\b\w/g, (c) => c.toUpperCase()) ?? 'Character';
 
   const charDir = projectDir && charSlug
-    ? `${projectDir}\\characters\\${charSlug}`
+    ? joinPath(projectDir, 'characters', charSlug)
     : null;
 
-  const brainstormPath = charDir ? `${charDir}\\brainstorm.md` : null;
-  const profilePath = charDir ? `${charDir}\\profile.md` : null;
+  const brainstormPath = charDir ? joinPath(charDir, 'brainstorm.md') : null;
+  const profilePath = charDir ? joinPath(charDir, 'profile.md') : null;
   const currentFileName = currentFilePath.split(/[\\/]/).pop() || '';
 
   const checkProfile = useCallback(async () => {
@@ -1,12 +1,12 @@
 import { useState, useEffect, useCallback, useRef } from 'react';
-import { useEditor, EditorContent } from '@tiptap/react';
+import { useEditor, EditorContent, getHTMLFromFragment } from '@tiptap/react';
 import { TextSelection } from '@tiptap/pm/state';
 import StarterKit from '@tiptap/starter-kit';
 import Placeholder from '@tiptap/extension-placeholder';
 import CharacterCount from '@tiptap/extension-character-count';
 import Highlight from '@tiptap/extension-highlight';
 import Typography from '@tiptap/extension-typography';
-import { X, Save, Scan } from 'lucide-react';
+import { X, Save, Scan, AlertTriangle, Scissors } from 'lucide-react';
 import { EditorToolbar } from './EditorToolbar';
 import { FrontmatterPanel } from './FrontmatterPanel';
 import { InlineAIToolbar } from './InlineAIToolbar';
@@ -18,7 +18,8 @@ import { CharacterToolbar } from './CharacterToolbar';
 import { WorldEntryToolbar } from './WorldEntryToolbar';
 import { useEditorStore } from '../../stores/editorStore';
 import { useProjectStore } from '../../stores/projectStore';
-import { readFile, writeFile } from '../../utils/tauri';
+import { readFile, writeFile, asWriteConflict, splitScene } from '../../utils/tauri';
+import type { WriteConflict } from '../../types/project';
 import { markdownToHtml, htmlToMarkdown } from '../../utils/markdown';
 
 interface ProseEditorProps {
@@ -29,6 +30,7 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
   const [frontmatter, setFrontmatter] = useState<Record<string, unknown>>({});
   const [loading, setLoading] = useState(true);
   const [error, setError] = useState<string | null>(null);
+  const [conflict, setConflict] = useState<WriteConflict | null>(null);
   const markSaving = useEditorStore((s) => s.markSaving);
   const markSaved = useEditorStore((s) => s.markSaved);
   const setBody = useEditorStore((s) => s.setBody);
@@ -41,6 +43,8 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
   const filePathRef = useRef(filePath);
   const autoSaveTimerRef = useRef<ReturnType<typeof setInterval> | null>(null);
   const savedAtRefreshRef = useRef(-1);
+  // Version of the file on disk that the editor content is based on
+  const versionRef = useRef<string | undefined>(undefined);
 
   const isBrainstormFile = /[\\/]overview[\\/]/.test(filePath);
   const isSeedPhaseFile = /[\\/]phase-1-seed[\\/]/.test(filePath);
@@ -48,6 +52,7 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
   const isSproutPhaseFile = /[\\/]phase-3-sprout[\\/]/.test(filePath);
   const isCharacterFile = /[\\/]characters[\\/][^\\/]+[\\/]/.test(filePath);
   const isWorldEntryFile = /[\\/]world[\\/][^\\/]+[\\/][^\\/]+[\\/]/.test(filePath);
+  const sceneDraftMatch = filePath.match(/[\\/]books[\\/]([^\\/]+)[\\/]chapters[\\/]([^\\/]+)[\\/]([^\\/]+)[\\/]draft\.md$/);
 
   const editor = useEditor({
     extensions: [
@@ -151,8 +156,10 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
   const loadFile = useCallback(async () => {
     setLoading(true);
     setError(null);
+    setConflict(null);
     try {
       const content = await readFile(filePath);
+      versionRef.current = content.version;
       setFrontmatter(content.frontmatter || {});
       frontmatterRef.current = content.frontmatter || {};
       const html = markdownToHtml(content.body);
@@ -166,7 +173,7 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
       const errMsg = String(readErr).toLowerCase();
       if (errMsg.includes('not found') || errMsg.includes('no such file') || errMsg.includes('does not exist') || errMsg.includes('(os error 2)') || errMsg.includes('(os error 3)')) {
         try {
-          await writeFile(filePath, {}, '');
+          versionRef.current = await writeFile(filePath, {}, '');
           setFrontmatter({});
           frontmatterRef.current = {};
           initialContentRef.current = '';
@@ -199,7 +206,8 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
   }, [filePath, editor, refreshCounter]);
 
   // Save function — guarded against writing empty content over a non-empty file
-  const saveFile = useCallback(async () => {
+  // `expectedVersion` overrides the loaded version, e.g. to overwrite after a conflict
+  const saveFile = useCallback(async (expectedVersion?: string | null) => {
     if (!editor || editor.isDestroyed) return;
     const html = editor.getHTML();
     const markdown = htmlToMarkdown(html);
@@ -214,21 +222,53 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
 
     markSaving();
     try {
-      await writeFile(filePathRef.current, frontmatterRef.current, markdown);
+      const expected = expectedVersion === undefined ? versionRef.current : expectedVersion ?? undefined;
+      versionRef.current = await writeFile(filePathRef.current, frontmatterRef.current, markdown, expected);
+      setConflict(null);
       markSaved();
       useProjectStore.getState().bumpRefresh();
       savedAtRefreshRef.current = useProjectStore.getState().refreshCounter;
     } catch (e) {
+      const writeConflict = asWriteConflict(e);
+      if (writeConflict) {
+        // Keep the edits (and the unsaved marker) until the user picks a side
+        setConflict(writeConflict);
+        useEditorStore.setState({ isSaving: false });
+        return;
+      }
       console.error('Save failed:', e);
       markSaved();
     }
   }, [editor, markSaving, markSaved]);
 
-  // Auto-save every 30 seconds
+  // Split the scene before the paragraph holding the cursor; the rest becomes a new scene
+  const splitAtCursor = useCallback(async () => {
+    const projectDir = useProjectStore.getState().projectDir;
+    if (!editor || !sceneDraftMatch || !projectDir) return;
+    const { $from } = editor.state.selection;
+    const before = editor.state.doc.cut(0, $from.depth > 0 ? $from.before(1) : 0);
+    // The backend counts characters of the saved markdown body
+    const atOffset = [...htmlToMarkdown(getHTMLFromFragment(before.content, editor.schema))].length;
+    if (atOffset === 0) return;
+    await saveFile();
+    if (useEditorStore.getState().isDirty) return;
+    try {
+      const [, bookId, chapterId, sceneId] = sceneDraftMatch;
+      await splitScene(projectDir, bookId, chapterId, sceneId, atOffset);
+      useProjectStore.getState().bumpRefresh();
+    } catch (e) {
+      console.error('Failed to split scene:', e);
+    }
+  // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [editor, filePath, saveFile]);
+
+  // Auto-save every 30 seconds, paused while a conflict is unresolved
+  const conflictRef = useRef(conflict);
+  conflictRef.current = conflict;
   useEffect(() => {
     autoSaveTimerRef.current = setInterval(() => {
       const isDirty = useEditorStore.getState().isDirty;
-      if (isDirty && editor) {
+      if (isDirty && editor && !conflictRef.current) {
         saveFile();
       }
     }, 30000);
@@ -296,7 +336,7 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
           </div>
           <div className="flex items-center gap-1">
             <button
-              onClick={saveFile}
+              onClick={() => saveFile()}
               disabled={!isDirty}
               title="Save (Ctrl+S)"
               className="flex items-center justify-center hover-icon"
@@ -304,6 +344,16 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
             >
               <Save size={14} />
             </button>
+            {sceneDraftMatch && (
+              <button
+                onClick={splitAtCursor}
+                title="Split scene: text from the cursor's paragraph on becomes a new scene"
+                className="flex items-center justify-center hover-icon"
+                style={{ width: '26px', height: '26px', borderRadius: '4px', background: 'none', border: 'none', cursor: 'pointer', color: 'var(--text-tertiary)' }}
+              >
+                <Scissors size={14} />
+              </button>
+            )}
             <button
               onClick={() => window.dispatchEvent(new KeyboardEvent('keydown', { key: 'F', ctrlKey: true, shiftKey: true, bubbles: true }))}
               title="Focus Mode (Ctrl+Shift+F)"
@@ -333,6 +383,34 @@ export function ProseEditor({ filePath }: ProseEditorProps) {
       {!focusMode && <FrontmatterPanel frontmatter={frontmatter} />}
       {!focusMode && <EditorToolbar editor={editor} />}
 
+      {conflict && (
+        <div
+          className="flex items-center gap-2 shrink-0 text-xs"
+          style={{ padding: '6px 16px', borderBottom: '1px solid var(--border-secondary)', backgroundColor: 'var(--bg-elevated)', color: 'var(--color-error)' }}
+        >
+          <AlertTriangle size={14} />
+          <span className="flex-1">
+            {conflict.current_version === null
+              ? 'This file was deleted on disk since you opened it.'
+              : `This file changed on disk since you opened it${conflict.merge.conflicts > 0 ? ` (${conflict.merge.conflicts} conflicting section${conflict.merge.conflicts === 1 ? '' : 's'})` : ''}.`}
+          </span>
+          <button
+            onClick={() => saveFile(conflict.current_version)}
+            className="hover-icon"
+            style={{ padding: '2px 8px', borderRadius: '4px', background: 'none', border: '1px solid var(--border-secondary)', cursor: 'pointer', color: 'var(--text-primary)' }}
+          >
+            Overwrite
+          </button>
+          <button
+            onClick={() => loadFile()}
+            className="hover-icon"
+            style={{ padding: '2px 8px', borderRadius: '4px', background: 'none', border: '1px solid var(--border-secondary)', cursor: 'pointer', color: 'var(--text-primary)' }}
+          >
+            Reload
+          </button>
+        </div>
+      )}
+
       <div className="flex-1 overflow-y-auto relative">
         <EditorContent editor={editor} style={{ height: '100%' }} />
         {!focusMode && <InlineAIToolbar editor={editor} />}
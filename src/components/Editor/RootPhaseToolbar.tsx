@@ -10,6 +10,7 @@ import { BEATS } from '../../types/sapling';
 import { ANCHOR_BEATS } from '../PhaseWorkflow/beatHelpers';
 import { extractDraftBody } from '../../utils/applyParser';
 import type { AgentPlan } from '../../types/ai';
+import { joinPath } from '../../utils/path';
 
 interface RootPhaseToolbarProps {
   currentFilePath: string;
@@ -49,11 +50,11 @@ export function RootPhaseToolbar({ currentFilePath }: RootPhaseToolbarProps) {
   const isAnchor = beatNum ? ANCHOR_BEATS.has(beatNum) : false;
 
   const beatDir = projectDir && bookId && beatDirName
-    ? `${projectDir}\\books\\${bookId}\\phase-2-root\\${beatDirName}`
+    ? joinPath(projectDir, 'books', bookId, 'phase-2-root', beatDirName)
     : null;
 
-  const brainstormPath = beatDir ? `${beatDir}\\brainstorm.md` : null;
-  const draftPath = beatDir ? `${beatDir}\\draft.md` : null;
+  const brainstormPath = beatDir ? joinPath(beatDir, 'brainstorm.md') : null;
+  const draftPath = beatDir ? joinPath(beatDir, 'draft.md') : null;
   const currentFileName = currentFilePath.split(/[\\/]/).pop() || '';
 
   const scanBeatFiles = useCallback(async () => {
@@ -71,12 +72,12 @@ export function RootPhaseToolbar({ currentFilePath }: RootPhaseToolbarProps) {
           return vA - vB;
         });
       for (const name of brainstormFiles) {
-        files.push({ name, path: `${beatDir}\\${name}` });
+        files.push({ name, path: joinPath(beatDir, name) });
       }
       // Add draft.md if it exists
       const hasDraftFile = entries.some(e => e.name === 'draft.md');
       if (hasDraftFile) {
-        files.push({ name: 'draft.md', path: `${beatDir}\\draft.md` });
+        files.push({ name: 'draft.md', path: joinPath(beatDir, 'draft.md') });
         setHasDraft(true);
       } else {
         setHasDraft(false);
@@ -9,6 +9,7 @@ import { calculateCost } from '../../utils/modelPricing';
 import { SEED_ELEMENTS } from '../PhaseWorkflow/seedElements';
 import { extractDraftBody } from '../../utils/applyParser';
 import type { AgentPlan } from '../../types/ai';
+import { joinPath } from '../../utils/path';
 
 interface SeedPhaseToolbarProps {
   currentFilePath: string;
@@ -48,11 +49,11 @@ export function SeedPhaseToolbar({ currentFilePath }: SeedPhaseToolbarProps) {
   const elementLabel = element?.label ?? elementSlug ?? 'Element';
 
   const elementDir = projectDir && bookId && elementSlug
-    ? `${projectDir}\\books\\${bookId}\\phase-1-seed\\${elementSlug}`
+    ? joinPath(projectDir, 'books', bookId, 'phase-1-seed', elementSlug)
     : null;
 
-  const brainstormPath = elementDir ? `${elementDir}\\brainstorm.md` : null;
-  const draftPath = elementDir ? `${elementDir}\\draft.md` : null;
+  const brainstormPath = elementDir ? joinPath(elementDir, 'brainstorm.md') : null;
+  const draftPath = elementDir ? joinPath(elementDir, 'draft.md') : null;
   const currentFileName = currentFilePath.split(/[\\/]/).pop() || '';
 
   // Check if draft exists
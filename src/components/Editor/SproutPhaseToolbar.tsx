@@ -9,6 +9,7 @@ import { calculateCost } from '../../utils/modelPricing';
 import { JOURNEY_STAGES } from '../PhaseWorkflow/sproutHelpers';
 import { extractDraftBody } from '../../utils/applyParser';
 import type { AgentPlan } from '../../types/ai';
+import { joinPath } from '../../utils/path';
 
 interface SproutPhaseToolbarProps {
   currentFilePath: string;
@@ -48,11 +49,11 @@ export function SproutPhaseToolbar({ currentFilePath }: SproutPhaseToolbarProps)
   const charName = charSlug?.replace(/-/g, ' ').replace(/\b\w/g, (c) => c.toUpperCase()) ?? 'Character';
 
   const stageDirectory = projectDir && bookId && charSlug && stageDirName
-    ? `${projectDir}\\books\\${bookId}\\phase-3-sprout\\${charSlug}\\${stageDirName}`
+    ? joinPath(projectDir, 'books', bookId, 'phase-3-sprout', charSlug, stageDirName)
     : null;
 
-  const brainstormPath = stageDirectory ? `${stageDirectory}\\brainstorm.md` : null;
-  const draftPath = stageDirectory ? `${stageDirectory}\\draft.md` : null;
+  const brainstormPath = stageDirectory ? joinPath(stageDirectory, 'brainstorm.md') : null;
+  const draftPath = stageDirectory ? joinPath(stageDirectory, 'draft.md') : null;
   const currentFileName = currentFilePath.split(/[\\/]/).pop() || '';
 
   const checkDraft = useCallback(async () => {
@@ -8,6 +8,7 @@ import { trackCost } from '../../utils/projectCost';
 import { calculateCost } from '../../utils/modelPricing';
 import { extractDraftBody } from '../../utils/applyParser';
 import type { AgentPlan } from '../../types/ai';
+import { joinPath } from '../../utils/path';
 
 interface WorldEntryToolbarProps {
   currentFilePath: string;
@@ -41,11 +42,11 @@ export function WorldEntryToolbar({ currentFilePath }: WorldEntryToolbarProps) {
   const entryName = entrySlug?.replace(/-/g, ' ').replace(/\b\w/g, (c) => c.toUpperCase()) ?? 'Entry';
 
   const entryDir = projectDir && category && entrySlug
-    ? `${projectDir}\\world\\${category}\\${entrySlug}`
+    ? joinPath(projectDir, 'world', category, entrySlug)
     : null;
 
-  const brainstormPath = entryDir ? `${entryDir}\\brainstorm.md` : null;
-  const entryPath = entryDir ? `${entryDir}\\entry.md` : null;
+  const brainstormPath = entryDir ? joinPath(entryDir, 'brainstorm.md') : null;
+  const entryPath = entryDir ? joinPath(entryDir, 'entry.md') : null;
   const currentFileName = currentFilePath.split(/[\\/]/).pop() || '';
 
   const checkEntry = useCallback(async () => {
@@ -3,6 +3,7 @@ import { StickyNote, FileText, Plus, FolderOpen, X, ChevronDown, ChevronRight, B
 import { useProjectStore } from '../../stores/projectStore';
 import { listDirectory } from '../../utils/tauri';
 import type { FileEntry } from '../../types/project';
+import { joinPath } from '../../utils/path';
 
 interface NoteGroup {
   label: string;
@@ -33,7 +34,7 @@ export function NotesBrowser() {
     const groups: NoteGroup[] = [];
 
     // Project-level notes
-    const projNotesDir = `${projectDir}\\notes`;
+    const projNotesDir = joinPath(projectDir, 'notes');
     try {
       const entries = await listDirectory(projNotesDir);
       groups.push({
@@ -48,7 +49,7 @@ export function NotesBrowser() {
 
     // Book-level notes
     for (const book of books) {
-      const bookNotesDir = `${projectDir}\\books\\${book.id}\\notes`;
+      const bookNotesDir = joinPath(projectDir, 'books', book.id, 'notes');
       try {
         const entries = await listDirectory(bookNotesDir);
         groups.push({
@@ -81,9 +82,9 @@ export function NotesBrowser() {
     if (!newNoteTitle.trim() || !projectDir) return;
     const slug = newNoteTitle.trim().toLowerCase().replace(/\s+/g, '-');
     const targetDir = newNoteScope === 'project'
-      ? `${projectDir}\\notes`
-      : `${projectDir}\\books\\${newNoteScope}\\notes`;
-    const path = `${targetDir}\\${slug}.md`;
+      ? joinPath(projectDir, 'notes')
+      : joinPath(projectDir, 'books', newNoteScope, 'notes');
+    const path = joinPath(targetDir, `${slug}.md`);
     setShowNewNoteModal(false);
     setNewNoteTitle('');
     setActiveFile(path);
@@ -5,6 +5,7 @@ import { useProjectStore } from '../../stores/projectStore';
 import { getBookMetadata } from '../../utils/tauri';
 import { openHelpWindow } from '../../utils/helpWindow';
 import type { BookMetadata } from '../../types/project';
+import { joinPath } from '../../utils/path';
 
 export function BloomPhaseView() {
   const projectDir = useProjectStore((s) => s.projectDir);
@@ -99,7 +100,7 @@ export function BloomPhaseView() {
                         key={scene.id}
                         onClick={() => {
                           if (projectDir && activeBookId) {
-                            const path = `${projectDir}\\books\\${activeBookId}\\chapters\\${chapter.id}\\${scene.id}\\draft.md`;
+                            const path = joinPath(projectDir, 'books', activeBookId, 'chapters', chapter.id, scene.id, 'draft.md');
                             setActiveFile(path);
                           }
                         }}
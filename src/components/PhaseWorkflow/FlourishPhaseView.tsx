@@ -5,6 +5,7 @@ import { useProjectStore } from '../../stores/projectStore';
 import { getBookMetadata } from '../../utils/tauri';
 import { openHelpWindow } from '../../utils/helpWindow';
 import type { BookMetadata } from '../../types/project';
+import { joinPath } from '../../utils/path';
 
 export function FlourishPhaseView() {
   const projectDir = useProjectStore((s) => s.projectDir);
@@ -96,7 +97,7 @@ export function FlourishPhaseView() {
                         onClick={() => {
                           setSelectedScene(isSelected ? null : sceneKey);
                           if (projectDir && activeBookId) {
-                            const path = `${projectDir}\\books\\${activeBookId}\\chapters\\${chapter.id}\\${scene.id}\\outline.md`;
+                            const path = joinPath(projectDir, 'books', activeBookId, 'chapters', chapter.id, scene.id, 'outline.md');
                             setActiveFile(path);
                           }
                         }}
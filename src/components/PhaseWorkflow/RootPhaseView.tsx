@@ -11,6 +11,7 @@ import { BEATS } from '../../types/sapling';
 import type { AgentPlan, Message } from '../../types/ai';
 import { PhaseIcon } from './PhaseIcon';
 import { beatDir, beatTemplate, ANCHOR_BEATS } from './beatHelpers';
+import { joinPath } from '../../utils/path';
 
 const ACT_COLORS: Record<string, string> = {
   I: 'var(--color-info)',
@@ -163,7 +164,7 @@ export function RootPhaseView() {
   const [loading, setLoading] = useState(true);
 
   const rootDir = projectDir && activeBookId
-    ? `${projectDir}\\books\\${activeBookId}\\phase-2-root`
+    ? joinPath(projectDir, 'books', activeBookId, 'phase-2-root')
     : null;
 
   // ─── Suggest All Beats ───
@@ -241,9 +242,9 @@ export function RootPhaseView() {
 
       let written = 0;
       for (const beat of beatsWithContent) {
-        const dir = `${rootDir}\\${beatDir(beat.num, beat.name)}`;
+        const dir = joinPath(rootDir, beatDir(beat.num, beat.name));
         const filename = await getNextBrainstormFilename(dir);
-        const filePath = `${dir}\\${filename}`;
+        const filePath = joinPath(dir, filename);
         const now = new Date().toISOString().slice(0, 10);
         const frontmatter: Record<string, unknown> = {
           type: 'brainstorm',
@@ -296,9 +297,9 @@ export function RootPhaseView() {
     setLoading(true);
     const result: Record<number, BeatStatus> = {};
     for (const beat of BEATS) {
-      const dir = `${rootDir}\\${beatDir(beat.num, beat.name)}`;
-      const brainstormPath = `${dir}\\brainstorm.md`;
-      const draftPath = `${dir}\\draft.md`;
+      const dir = joinPath(rootDir, beatDir(beat.num, beat.name));
+      const brainstormPath = joinPath(dir, 'brainstorm.md');
+      const draftPath = joinPath(dir, 'draft.md');
       let hasBrainstorm = false;
       let hasDraft = false;
       let brainstormPreview = '';
@@ -324,8 +325,8 @@ export function RootPhaseView() {
 
   const handleBeatClick = async (beat: typeof BEATS[number]) => {
     if (!rootDir || !projectDir || !activeBookId) return;
-    const dir = `${rootDir}\\${beatDir(beat.num, beat.name)}`;
-    const brainstormPath = `${dir}\\brainstorm.md`;
+    const dir = joinPath(rootDir, beatDir(beat.num, beat.name));
+    const brainstormPath = joinPath(dir, 'brainstorm.md');
     const status = statuses[beat.num];
     if (!status?.hasBrainstorm) {
       try {
@@ -1,12 +1,13 @@
 import { useState, useEffect, useCallback, useMemo } from 'react';
 import { Check, Circle, Loader2, Info, FileText, Sparkles, ExternalLink } from 'lucide-react';
 import { useProjectStore } from '../../stores/projectStore';
-import { readFile, writeFile, loadTemplate, getBookMetadata, updateBookMetadata } from '../../utils/tauri';
+import { readFile, writeFile, loadTemplate, getBookMetadata, recomputePhaseProgress } from '../../utils/tauri';
 import { openHelpWindow } from '../../utils/helpWindow';
 import { useGenerate } from '../../hooks/useGenerate';
 import { extractDraftBody } from '../../utils/applyParser';
 import { PhaseIcon } from './PhaseIcon';
 import { SEED_ELEMENTS } from './seedElements';
+import { joinPath } from '../../utils/path';
 
 interface ElementStatus {
   hasBrainstorm: boolean;
@@ -24,7 +25,7 @@ export function SeedPhaseView() {
   const [loglineText, setLoglineText] = useState('');
 
   const seedDir = projectDir && activeBookId
-    ? `${projectDir}\\books\\${activeBookId}\\phase-1-seed`
+    ? joinPath(projectDir, 'books', activeBookId, 'phase-1-seed')
     : null;
 
   const loadStatuses = useCallback(async () => {
@@ -32,9 +33,9 @@ export function SeedPhaseView() {
     setLoading(true);
     const result: Record<string, ElementStatus> = {};
     for (const el of SEED_ELEMENTS) {
-      const elDir = `${seedDir}\\${el.slug}`;
-      const brainstormPath = `${elDir}\\brainstorm.md`;
-      const draftPath = `${elDir}\\draft.md`;
+      const elDir = joinPath(seedDir, el.slug);
+      const brainstormPath = joinPath(elDir, 'brainstorm.md');
+      const draftPath = joinPath(elDir, 'draft.md');
       let hasBrainstorm = false;
       let hasDraft = false;
       try {
@@ -50,8 +51,8 @@ export function SeedPhaseView() {
     setStatuses(result);
 
     // Check for phase deliverables
-    const loglinePath = `${seedDir}\\logline.md`;
-    const foundationPath = `${seedDir}\\story-foundation.md`;
+    const loglinePath = joinPath(seedDir, 'logline.md');
+    const foundationPath = joinPath(seedDir, 'story-foundation.md');
     let foundFoundation = false;
     try {
       const loglineContent = await readFile(loglinePath);
@@ -74,9 +75,9 @@ export function SeedPhaseView() {
 
   const handleElementClick = async (el: typeof SEED_ELEMENTS[0]) => {
     if (!seedDir || !projectDir || !activeBookId) return;
-    const elDir = `${seedDir}\\${el.slug}`;
-    const brainstormPath = `${elDir}\\brainstorm.md`;
-    const draftPath = `${elDir}\\draft.md`;
+    const elDir = joinPath(seedDir, el.slug);
+    const brainstormPath = joinPath(elDir, 'brainstorm.md');
+    const draftPath = joinPath(elDir, 'draft.md');
     const status = statuses[el.key];
 
     // If a draft exists, open it
@@ -112,23 +113,15 @@ export function SeedPhaseView() {
   const allDrafted = filledCount === 6;
   const phaseComplete = hasStoryFoundation;
 
-  // When story foundation is created, mark seed phase complete + root as in_progress
+  // When story foundation is created, pick up the seed phase's new status
   useEffect(() => {
     if (!hasStoryFoundation || !projectDir || !activeBookId) return;
     (async () => {
       try {
+        await recomputePhaseProgress(projectDir, activeBookId);
         const meta = await getBookMetadata(projectDir, activeBookId);
-        const pp = { ...meta.phase_progress };
-        const seedProgress = pp['seed'] || { status: 'not_started' };
-        if (seedProgress.status === 'complete') return; // already done
-        pp['seed'] = { ...seedProgress, status: 'complete', completed_at: new Date().toISOString() };
-        if (!pp['root'] || pp['root'].status === 'not_started') {
-          pp['root'] = { status: 'in_progress' };
-        }
-        const updated = { ...meta, phase_progress: pp };
-        await updateBookMetadata(projectDir, activeBookId, updated);
         // Refresh activeBookMeta in the store so phase bar + dashboard update
-        useProjectStore.getState().setActiveBook(activeBookId, updated);
+        useProjectStore.getState().setActiveBook(activeBookId, meta);
       } catch (e) {
         console.error('Failed to update phase progress:', e);
       }
@@ -161,7 +154,7 @@ export function SeedPhaseView() {
 
   const handleGenerateFoundation = async () => {
     if (!seedDir || !projectDir || !activeBookId) return;
-    const foundationPath = `${seedDir}\\story-foundation.md`;
+    const foundationPath = joinPath(seedDir, 'story-foundation.md');
     const now = new Date().toISOString().slice(0, 10);
     await startGenerate({
       skill: 'seed_developer',
@@ -184,7 +177,7 @@ export function SeedPhaseView() {
         const loglineMatch = body.match(/##\s*Logline\s*\n+([\s\S]*?)(?=\n##|$)/);
         if (loglineMatch && seedDir && activeBookId) {
           const loglineBody = loglineMatch[1].trim().replace(/\n---\s*$/, '').trim();
-          const loglinePath = `${seedDir}\\logline.md`;
+          const loglinePath = joinPath(seedDir, 'logline.md');
           writeFile(loglinePath, {
             type: 'logline',
             scope: activeBookId,
@@ -329,7 +322,7 @@ export function SeedPhaseView() {
                       {loglineText}
                     </p>
                     <button
-                      onClick={() => seedDir && setActiveFile(`${seedDir}\\logline.md`)}
+                      onClick={() => seedDir && setActiveFile(joinPath(seedDir, 'logline.md'))}
                       className="shrink-0 flex items-center justify-center rounded hover-icon"
                       style={{ background: 'none', border: 'none', cursor: 'pointer', color: 'var(--text-tertiary)', padding: '2px' }}
                       title="Open logline"
@@ -340,7 +333,7 @@ export function SeedPhaseView() {
                 </div>
               )}
               <button
-                onClick={() => seedDir && setActiveFile(`${seedDir}\\story-foundation.md`)}
+                onClick={() => seedDir && setActiveFile(joinPath(seedDir, 'story-foundation.md'))}
                 className="flex items-center gap-1.5 text-xs font-medium rounded-md hover-btn-primary"
                 style={{ backgroundColor: 'var(--accent)', color: 'var(--text-inverse)', border: 'none', padding: '6px 14px', cursor: 'pointer' }}
               >
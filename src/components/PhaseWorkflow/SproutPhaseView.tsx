@@ -6,6 +6,7 @@ import { openHelpWindow } from '../../utils/helpWindow';
 import { listDirectory, readFile, writeFile, loadTemplate } from '../../utils/tauri';
 import { JOURNEY_STAGES, stageDir } from './sproutHelpers';
 import type { FileEntry } from '../../types/project';
+import { joinPath } from '../../utils/path';
 
 interface CharacterInfo {
   name: string;
@@ -29,10 +30,10 @@ export function SproutPhaseView() {
   const [loading, setLoading] = useState(true);
 
   const sproutDir = projectDir && activeBookId
-    ? `${projectDir}\\books\\${activeBookId}\\phase-3-sprout`
+    ? joinPath(projectDir, 'books', activeBookId, 'phase-3-sprout')
     : null;
 
-  const charsDir = projectDir ? `${projectDir}\\characters` : null;
+  const charsDir = projectDir ? joinPath(projectDir, 'characters') : null;
 
   const loadCharacters = useCallback(async () => {
     if (!charsDir) return;
@@ -57,11 +58,11 @@ export function SproutPhaseView() {
     if (!sproutDir) return;
     const result: Record<number, StageStatus> = {};
     for (const stage of JOURNEY_STAGES) {
-      const dir = `${sproutDir}\\${charSlug}\\${stageDir(stage.num, stage.slug)}`;
+      const dir = joinPath(sproutDir, charSlug, stageDir(stage.num, stage.slug));
       let hasBrainstorm = false;
       let hasDraft = false;
-      try { await readFile(`${dir}\\brainstorm.md`); hasBrainstorm = true; } catch { /* */ }
-      try { await readFile(`${dir}\\draft.md`); hasDraft = true; } catch { /* */ }
+      try { await readFile(joinPath(dir, 'brainstorm.md')); hasBrainstorm = true; } catch { /* */ }
+      try { await readFile(joinPath(dir, 'draft.md')); hasDraft = true; } catch { /* */ }
       result[stage.num] = { hasBrainstorm, hasDraft };
     }
     setStageStatuses(prev => ({ ...prev, [charSlug]: result }));
@@ -77,8 +78,8 @@ export function SproutPhaseView() {
 
   const handleStageClick = async (char: CharacterInfo, stage: typeof JOURNEY_STAGES[number]) => {
     if (!sproutDir || !projectDir || !activeBookId) return;
-    const dir = `${sproutDir}\\${char.slug}\\${stageDir(stage.num, stage.slug)}`;
-    const brainstormPath = `${dir}\\brainstorm.md`;
+    const dir = joinPath(sproutDir, char.slug, stageDir(stage.num, stage.slug));
+    const brainstormPath = joinPath(dir, 'brainstorm.md');
     const statuses = stageStatuses[char.slug];
     if (!statuses?.[stage.num]?.hasBrainstorm) {
       try {
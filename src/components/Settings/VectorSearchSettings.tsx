@@ -1,9 +1,9 @@
 import { useState, useEffect, useCallback } from 'react';
 import { Search, RefreshCw, Trash2, Loader2, ExternalLink, Info } from 'lucide-react';
 import { openHelpWindow } from '../../utils/helpWindow';
-import { getConfig, updateConfig, getIndexStatus, reindexProject, clearIndex, type AppConfig } from '../../utils/tauri';
+import { getConfig, updateConfig, getIndexStatus, reindexProject, estimateReindex, cancelReindex, clearIndex, type AppConfig } from '../../utils/tauri';
 import { useProjectStore } from '../../stores/projectStore';
-import type { IndexStatus } from '../../types/vectorSearch';
+import type { IndexStatus, ReindexEstimate } from '../../types/vectorSearch';
 
 export function VectorSearchSettings() {
   const projectDir = useProjectStore((s) => s.projectDir);
@@ -12,6 +12,7 @@ export function VectorSearchSettings() {
   const [showKeyInput, setShowKeyInput] = useState(false);
   const [indexStatus, setIndexStatus] = useState<IndexStatus | null>(null);
   const [isReindexing, setIsReindexing] = useState(false);
+  const [estimate, setEstimate] = useState<ReindexEstimate | null>(null);
   const [showEnableConfirm, setShowEnableConfirm] = useState(false);
 
   const loadConfig = useCallback(async () => {
@@ -27,6 +28,7 @@ export function VectorSearchSettings() {
       const status = await getIndexStatus(projectDir);
       setIndexStatus(status);
       setIsReindexing(status.is_indexing);
+      if (!status.is_indexing) setEstimate(await estimateReindex(projectDir));
     } catch { /* index may not exist yet */ }
   }, [projectDir]);
 
@@ -91,6 +93,14 @@ export function VectorSearchSettings() {
     }
   };
 
+  const handleCancelReindex = async () => {
+    try {
+      await cancelReindex();
+    } catch (e) {
+      console.error('Cancel re-index failed:', e);
+    }
+  };
+
   const handleClearIndex = async () => {
     if (!projectDir) return;
     try {
@@ -321,22 +331,28 @@ export function VectorSearchSettings() {
                 {isReindexing ? 'Indexing...' : 'Re-index Project'}
               </button>
               <button
-                onClick={handleClearIndex}
-                disabled={isReindexing}
+                onClick={isReindexing ? handleCancelReindex : handleClearIndex}
                 className="flex items-center gap-2 rounded-lg text-sm"
                 style={{
                   backgroundColor: 'var(--bg-tertiary)',
                   border: '1px solid var(--border-primary)',
                   color: 'var(--text-tertiary)',
                   padding: '8px 14px',
-                  cursor: isReindexing ? 'not-allowed' : 'pointer',
+                  cursor: 'pointer',
                 }}
               >
                 <Trash2 size={14} />
-                Clear Index
+                {isReindexing ? 'Cancel' : 'Clear Index'}
               </button>
             </div>
           )}
+          {projectDir && !isReindexing && estimate && estimate.files_changed + estimate.reembed_chunks > 0 && (
+            <p className="text-xs" style={{ color: 'var(--text-tertiary)', marginTop: '8px' }}>
+              Next re-index: {estimate.files_changed} changed file{estimate.files_changed === 1 ? '' : 's'},{' '}
+              {estimate.chunks_to_embed + estimate.reembed_chunks} chunks to embed
+              ({estimate.tokens_to_embed.toLocaleString()} tokens, ~${estimate.estimated_cost_usd.toFixed(4)})
+            </p>
+          )}
 
           {/* Advanced */}
           <div style={{ marginTop: '16px' }}>
@@ -7,6 +7,7 @@ import { loadProjectCost } from '../../utils/projectCost';
 import { loadProjectChat } from '../../utils/projectChat';
 import { open } from '@tauri-apps/plugin-dialog';
 import type { RecentProject } from '../../types/project';
+import { joinPath } from '../../utils/path';
 
 export function Welcome() {
   const setProject = useProjectStore((s) => s.setProject);
@@ -45,7 +46,7 @@ export function Welcome() {
     if (!newName.trim()) return;
     try {
       const config = await getConfig();
-      const dir = `${config.projects_root}\\${newName.trim().toLowerCase().replace(/\s+/g, '-')}`;
+      const dir = joinPath(config.projects_root, newName.trim().toLowerCase().replace(/\s+/g, '-'));
       const meta = await createProject(newName.trim(), newDescription.trim() || null, dir);
       setProject(meta, dir);
       setTotalProjectCost(0);
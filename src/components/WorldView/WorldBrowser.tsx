@@ -3,6 +3,7 @@ import { Globe, FolderOpen, FileText, ChevronRight, ChevronDown, Folder, Plus, X
 import { useProjectStore } from '../../stores/projectStore';
 import { listDirectory, createDirectory, writeFile, loadTemplate, readFile } from '../../utils/tauri';
 import type { FileEntry } from '../../types/project';
+import { joinPath } from '../../utils/path';
 
 interface WorldFolder {
   slug: string;
@@ -76,7 +77,7 @@ export function WorldBrowser() {
   const [newEntryFolder, setNewEntryFolder] = useState<{ path: string; name: string; slug: string } | null>(null);
   const entryInputRef = useRef<HTMLInputElement>(null);
 
-  const worldDir = projectDir ? `${projectDir}\\world` : null;
+  const worldDir = projectDir ? joinPath(projectDir, 'world') : null;
 
   const loadWorld = useCallback(async () => {
     if (!worldDir) return;
@@ -119,7 +120,7 @@ export function WorldBrowser() {
       for (const folder of folders) {
         for (const entry of folder.entries) {
           try {
-            await readFile(`${entry.path}\\entry.md`);
+            await readFile(joinPath(entry.path, 'entry.md'));
             result[entry.path] = true;
           } catch {
             result[entry.path] = false;
@@ -153,7 +154,7 @@ export function WorldBrowser() {
   const handleCreateSection = async (slug: string) => {
     if (!worldDir) return;
     try {
-      await createDirectory(`${worldDir}\\${slug}`);
+      await createDirectory(joinPath(worldDir, slug));
       setShowNewSection(false);
       setCustomName('');
       loadWorld();
@@ -171,8 +172,8 @@ export function WorldBrowser() {
     if (!newEntryName.trim() || !newEntryFolder) return;
     const name = newEntryName.trim();
     const slug = name.toLowerCase().replace(/\s+/g, '-');
-    const entryDir = `${newEntryFolder.path}\\${slug}`;
-    const brainstormPath = `${entryDir}\\brainstorm.md`;
+    const entryDir = joinPath(newEntryFolder.path, slug);
+    const brainstormPath = joinPath(entryDir, 'brainstorm.md');
     setShowNewEntryModal(false);
     setNewEntryName('');
     try {
@@ -385,7 +386,7 @@ export function WorldBrowser() {
                         return (
                           <button
                             key={entry.path}
-                            onClick={() => setActiveFile(`${entry.path}\\brainstorm.md`)}
+                            onClick={() => setActiveFile(joinPath(entry.path, 'brainstorm.md'))}
                             className="flex items-center gap-2 w-full text-left text-xs rounded-md transition-all"
                             style={{
                               color: 'var(--text-primary)',
@@ -343,6 +343,23 @@ const voyageApiKeySection: HelpSection = {
         'Voyage AI charges per token for embeddings. The cost is extremely low — typically less than $0.01 to index an entire novel-length project.\n\nThe default model (voyage-4) costs $0.06 per million tokens. A typical 80,000-word novel is roughly 100,000 tokens, so indexing the entire project costs around $0.006 (less than one cent).\n\nRe-indexing is incremental — only changed files are re-embedded, so ongoing costs are negligible. SAiPLING tracks your total embedding cost in the Settings → Vector Search panel.',
       table: [
         { label: 'voyage-4', desc: '$0.06 per million tokens — recommended default, excellent quality' },
+        { label: 'local-ngram', desc: 'Free — runs offline on your CPU with no API key, lower quality than Voyage' },
+      ],
+    },
+    {
+      id: 'voyage-api-key-search-syntax',
+      title: 'Search Filters',
+      content:
+        'The /search command in the chat panel accepts filters alongside your search text. Quote words to require an exact phrase. For example: /search entity:character book:book-01 ch:3..7 "the broken mirror"',
+      table: [
+        { label: 'entity:', desc: 'Entity type, e.g. entity:character or entity:world' },
+        { label: 'type:', desc: 'File type, e.g. type:scene_draft or type:notes' },
+        { label: 'name:', desc: 'A specific character or world entry, e.g. name:sarah-chen' },
+        { label: 'book:', desc: 'Only search one book, e.g. book:book-01' },
+        { label: 'ch:', desc: 'A chapter or chapter range: ch:4, ch:3..7, ch:..5' },
+        { label: 'pov:', desc: 'Scenes told from a POV character, e.g. pov:"Sarah Chen"' },
+        { label: 'status:', desc: 'Scene status, e.g. status:drafted,revised' },
+        { label: 'after:', desc: 'Files modified on or after a date, e.g. after:2025-06-01' },
       ],
     },
     {
@@ -1,29 +1,27 @@
-import { useEffect, useRef } from 'react';
+import { useEffect } from 'react';
 import { listen } from '@tauri-apps/api/event';
-import { startFileWatcher, getBookMetadata } from '../utils/tauri';
+import { switchFileWatcher, stopFileWatcher, getBookMetadata } from '../utils/tauri';
 import { useProjectStore } from '../stores/projectStore';
 
 /**
- * Starts the file watcher when a project is open and listens for
- * fs:file_changed, fs:file_created, fs:file_deleted events.
+ * Watches the open project — switching the watcher when another project is
+ * opened and stopping it when the project is closed — and listens for
+ * fs:file_changed, fs:file_created, fs:file_deleted, fs:file_renamed events.
+ * The app's own saves are not reported.
  * Triggers a book metadata reload when relevant files change.
  */
 export function useFileWatcher() {
   const projectDir = useProjectStore((s) => s.projectDir);
-  const watcherStarted = useRef(false);
 
   useEffect(() => {
-    if (!projectDir || watcherStarted.current) return;
+    if (!projectDir) {
+      stopFileWatcher().catch(() => {});
+      return;
+    }
 
-    startFileWatcher(projectDir).then(() => {
-      watcherStarted.current = true;
-    }).catch((e) => {
+    switchFileWatcher(projectDir).catch((e) => {
       console.warn('File watcher failed to start:', e);
     });
-
-    return () => {
-      watcherStarted.current = false;
-    };
   }, [projectDir]);
 
   // Listen for file change events
@@ -56,6 +54,11 @@ export function useFileWatcher() {
         // Could refresh file tree if needed
       });
       unlisteners.push(u3);
+
+      const u4 = await listen<{ from: string; to: string }>('fs:file_renamed', () => {
+        // Could refresh file tree if needed
+      });
+      unlisteners.push(u4);
     };
 
     setup();
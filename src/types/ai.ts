@@ -50,6 +50,13 @@ export interface SkillMeta {
   temperature: number;
 }
 
+export interface SkillIssue {
+  file: string;
+  line: number | null;
+  severity: 'error' | 'warning';
+  message: string;
+}
+
 export interface ApplyBlock {
   target: string;
   action: 'create' | 'replace' | 'append' | 'update_frontmatter';
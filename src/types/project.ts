@@ -7,9 +7,17 @@ export interface ProjectMetadata {
   books: BookRef[];
   world_sections: string[];
   active_book_id?: string | null;
+  /** Other projects searched as read-only context, referenced as `@name/...` */
+  linked_projects?: LinkedProject[];
   directory: string;
 }
 
+export interface LinkedProject {
+  name: string;
+  /** Project directory, absolute or relative to this project */
+  path: string;
+}
+
 export interface PhaseProgress {
   status: 'not_started' | 'in_progress' | 'complete';
   completed_at?: string;
@@ -51,6 +59,9 @@ export interface BookMetadata {
 export interface BookSettings {
   perspective: string;
   tense: string;
+  writing_style_notes?: string;
+  /** Words to write per day; absent or 0 means no goal */
+  daily_word_goal?: number;
 }
 
 export interface ChapterMeta {
@@ -73,6 +84,22 @@ export interface FileContent {
   frontmatter: Record<string, unknown>;
   body: string;
   path: string;
+  /** Pass back to writeFile as expectedVersion to detect conflicting edits */
+  version: string;
+}
+
+export interface MergePreview {
+  merged: string;
+  conflicts: number;
+  base_available: boolean;
+}
+
+/** Rejected by a write whose expectedVersion no longer matches the file on disk */
+export interface WriteConflict {
+  path: string;
+  expected_version: string;
+  current_version: string | null;
+  merge: MergePreview;
 }
 
 export interface FileEntry {
@@ -101,6 +128,35 @@ export interface SceneWordCount {
   word_count: number;
 }
 
+/** One day of word-count history; dates are local YYYY-MM-DD */
+export interface DailyWordCount {
+  date: string;
+  words: number;
+  added: number;
+  deleted: number;
+}
+
+export interface WritingSession {
+  started: string;
+  ended: string;
+  words_added: number;
+  words_deleted: number;
+}
+
+export interface WritingStats {
+  total_words: number;
+  target_word_count: number;
+  daily_goal: number;
+  added_today: number;
+  deleted_today: number;
+  goal_met_today: boolean;
+  current_streak: number;
+  longest_streak: number;
+  average_daily_words: number;
+  remaining_words: number;
+  projected_completion: string | null;
+}
+
 export interface DraftSnapshot {
   name: string;
   path: string;
@@ -113,3 +169,37 @@ export interface MatterEntry {
   path: string;
   exists: boolean;
 }
+
+export type ConsistencySeverity = 'error' | 'warning' | 'info';
+
+export type ConsistencyIssueKind =
+  | 'book_folder_missing'
+  | 'untracked_book'
+  | 'unreadable_book'
+  | 'chapter_folder_missing'
+  | 'untracked_chapter'
+  | 'duplicate_chapter_id'
+  | 'duplicate_chapter_sort_order'
+  | 'scene_folder_missing'
+  | 'untracked_scene'
+  | 'duplicate_scene_id'
+  | 'duplicate_scene_sort_order'
+  | 'word_count_mismatch'
+  | 'frontmatter_mismatch';
+
+export interface ConsistencyIssue {
+  severity: ConsistencySeverity;
+  kind: ConsistencyIssueKind;
+  book_id: string;
+  chapter_id: string | null;
+  scene_id: string | null;
+  message: string;
+  /** What repair_project does about it; null if it needs the user */
+  repair: string | null;
+}
+
+/** Result of check_project / repair_project */
+export interface ConsistencyReport {
+  issues: ConsistencyIssue[];
+  repaired: boolean;
+}
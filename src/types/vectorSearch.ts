@@ -1,21 +1,92 @@
+export type EmbeddingProvider = 'voyage' | 'openai' | 'local';
+
+export type EmbeddingQuantization = 'none' | 'int8' | 'binary';
+
 export interface VectorSearchConfig {
   enabled: boolean;
+  provider: EmbeddingProvider;
   embedding_model: string;
   embedding_api_key_encrypted: string;
+  embedding_base_url: string;
+  embedding_dimensions: number;
+  embedding_quantization: EmbeddingQuantization;
+  /** With int8 or binary storage, also keep each float32 vector to rescore with */
+  embedding_keep_full_precision: boolean;
+  /** Embedding requests in flight at once during a full re-index */
+  embedding_concurrency: number;
+  /** Embedding requests started per minute during a full re-index; 0 = no limit */
+  embedding_requests_per_minute: number;
   auto_index: boolean;
   max_results_default: number;
   max_search_tokens_default: number;
 }
 
+export type SearchMode = 'lexical' | 'semantic' | 'hybrid';
+
 export interface SearchResult {
   file_path: string;
   section_heading: string | null;
   similarity_score: number;
   content_preview: string;
+  /** Full chunk text, or adjacent chunks of the same file merged into one excerpt */
+  content: string;
+  chunk_index: number;
   token_count: number;
   entity_type: string | null;
   entity_name: string | null;
   book_id: string | null;
+  /** Linked project the result came from; absent for the current project */
+  project?: string | null;
+}
+
+export type RelatedMode = 'related' | 'similar_passages';
+
+export interface RelatedGroup {
+  entity_type: string;
+  results: SearchResult[];
+}
+
+export interface SimilarPassage {
+  source_chunk_index: number;
+  source_heading: string | null;
+  source_preview: string;
+  passage: SearchResult;
+}
+
+export interface RelatedContent {
+  groups: RelatedGroup[];
+  similar_passages: SimilarPassage[];
+}
+
+export type EntityKind = 'character' | 'location' | 'item';
+
+/** A character, location or item whose mentions are tracked in the manuscript */
+export interface KnownEntity {
+  entity_type: EntityKind;
+  slug: string;
+  name: string;
+  aliases: string[];
+  source_path: string;
+}
+
+export type ManuscriptFileType = 'scene_draft' | 'scene_outline';
+
+export interface EntityAppearance {
+  file_path: string;
+  file_type: ManuscriptFileType;
+  book_id: string | null;
+  chapter_id: string | null;
+  scene_id: string | null;
+  mention_count: number;
+  /** Character offset of the first mention in the file */
+  first_offset: number;
+}
+
+export interface EntityCooccurrence {
+  entity_type: EntityKind;
+  slug: string;
+  name: string;
+  shared_files: number;
 }
 
 export interface IndexStatus {
@@ -25,6 +96,35 @@ export interface IndexStatus {
   total_cost_usd: number;
   is_indexing: boolean;
   index_progress: IndexProgress | null;
+  embedding: EmbeddingSignature | null;
+  reembed_progress: ReembedProgress | null;
+  quantization: EmbeddingQuantization;
+  storage_bytes: number;
+}
+
+/** Dry run of a re-index: what would be embedded and roughly what it costs */
+export interface ReindexEstimate {
+  files_total: number;
+  files_changed: number;
+  chunks_total: number;
+  chunks_to_embed: number;
+  tokens_to_embed: number;
+  reembed_chunks: number;
+  estimated_cost_usd: number;
+  provider: string | null;
+  model: string | null;
+}
+
+/** Result of verify_index: problems found, and whether they were repaired */
+export interface IndexIntegrityReport {
+  schema_version: number;
+  orphaned_rows: number;
+  missing_files: string[];
+  changed_files: string[];
+  wrong_size_embeddings: number;
+  missing_vectors: number;
+  repaired: boolean;
+  chunks_reembedded: number;
 }
 
 export interface IndexProgress {
@@ -33,6 +133,18 @@ export interface IndexProgress {
   current_file: string;
 }
 
+export interface EmbeddingSignature {
+  provider: string;
+  model: string;
+  dimensions: number;
+}
+
+export interface ReembedProgress {
+  chunks_done: number;
+  chunks_total: number;
+  complete: boolean;
+}
+
 export interface SearchResultInfo {
   file_path: string;
   section: string | null;
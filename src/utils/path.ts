@@ -0,0 +1,10 @@
+/**
+ * Joins path segments onto `base` using the separator `base` already uses:
+ * backslash for a Windows path, slash otherwise. Segments may contain either
+ * separator, so project-relative paths (always `/`) can be passed as they are.
+ */
+export function joinPath(base: string, ...segments: string[]): string {
+  const sep = base.includes('\\') ? '\\' : '/';
+  const parts = segments.flatMap((s) => s.split(/[\\/]/)).filter(Boolean);
+  return [base.replace(/[\\/]+$/, ''), ...parts].join(sep);
+}
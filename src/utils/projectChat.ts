@@ -1,6 +1,7 @@
 import { readFile, writeFile } from './tauri';
 import { useAIStore } from '../stores/aiStore';
 import type { Message } from '../types/ai';
+import { joinPath } from './path';
 
 const CHAT_FILE = '.ai_chat.json';
 
@@ -13,7 +14,7 @@ interface ChatData {
 
 export async function loadProjectChat(projectDir: string): Promise<void> {
   try {
-    const { body } = await readFile(`${projectDir}\\${CHAT_FILE}`);
+    const { body } = await readFile(joinPath(projectDir, CHAT_FILE));
     const data: ChatData = JSON.parse(body);
     const store = useAIStore.getState();
     const msgs = data.messages || [];
@@ -31,7 +32,7 @@ export async function saveProjectChat(projectDir: string): Promise<void> {
   const { messages, activeSkill, conversationId, sessionCost } = useAIStore.getState();
   const data: ChatData = { messages, activeSkill, conversationId, sessionCost };
   try {
-    await writeFile(`${projectDir}\\${CHAT_FILE}`, {}, JSON.stringify(data, null, 2));
+    await writeFile(joinPath(projectDir, CHAT_FILE), {}, JSON.stringify(data, null, 2));
   } catch (e) {
     console.error('Failed to save project chat:', e);
   }
@@ -1,5 +1,6 @@
 import { readFile, writeFile } from './tauri';
 import { useProjectStore } from '../stores/projectStore';
+import { joinPath } from './path';
 
 const COST_FILE = '.ai_cost.json';
 
@@ -9,7 +10,7 @@ interface CostData {
 
 export async function loadProjectCost(projectDir: string): Promise<number> {
   try {
-    const { body } = await readFile(`${projectDir}\\${COST_FILE}`);
+    const { body } = await readFile(joinPath(projectDir, COST_FILE));
     const data: CostData = JSON.parse(body);
     return data.total || 0;
   } catch {
@@ -19,7 +20,7 @@ export async function loadProjectCost(projectDir: string): Promise<number> {
 
 export async function saveProjectCost(projectDir: string, total: number): Promise<void> {
   try {
-    await writeFile(`${projectDir}\\${COST_FILE}`, {}, JSON.stringify({ total }, null, 2));
+    await writeFile(joinPath(projectDir, COST_FILE), {}, JSON.stringify({ total }, null, 2));
   } catch (e) {
     console.error('Failed to save project cost:', e);
   }
@@ -1,10 +1,14 @@
 import { invoke } from '@tauri-apps/api/core';
 import type {
   ProjectMetadata, RecentProject, BookMetadata, FileContent, FileEntry,
-  WordCountSummary, DraftSnapshot, MatterEntry,
+  WordCountSummary, DraftSnapshot, MatterEntry, WriteConflict, ChapterMeta, SceneMeta, ConsistencyReport,
+  PhaseProgress, DailyWordCount, WritingSession, WritingStats,
 } from '../types/project';
 import type { AgentPlan, ContextScope, Message, TokenEstimate, ModelsConfig, SkillSettingsEntry, SkillOverride } from '../types/ai';
-import type { SearchResult, IndexStatus } from '../types/vectorSearch';
+import type {
+  SearchResult, IndexStatus, SearchMode, RelatedMode, RelatedContent, ReindexEstimate, IndexIntegrityReport,
+  KnownEntity, EntityAppearance, EntityCooccurrence, ManuscriptFileType,
+} from '../types/vectorSearch';
 
 // ─── Project Management ───
 export const createProject = (name: string, description: string | null, directory: string) =>
@@ -25,6 +29,14 @@ export const updateProjectMetadata = (projectDir: string, metadata: ProjectMetad
 export const deleteProject = (directory: string) =>
   invoke<void>('delete_project', { directory });
 
+/** Reports where project.json and book.json files disagree with the folders on disk */
+export const checkProject = (projectDir: string) =>
+  invoke<ConsistencyReport>('check_project', { projectDir });
+
+/** Fixes what checkProject finds; with dryRun only lists the repairs */
+export const repairProject = (projectDir: string, dryRun = false) =>
+  invoke<ConsistencyReport>('repair_project', { projectDir, dryRun });
+
 // ─── Book Management ───
 export const createBook = (
   projectDir: string,
@@ -50,12 +62,24 @@ export const deleteBook = (projectDir: string, bookId: string) =>
 export const reorderBooks = (projectDir: string, bookIds: string[]) =>
   invoke<void>('reorder_books', { projectDir, bookIds });
 
+/** Re-derives phase_progress from the phase files and saves it; saves already do this */
+export const recomputePhaseProgress = (projectDir: string, bookId: string) =>
+  invoke<Record<string, PhaseProgress>>('recompute_phase_progress', { projectDir, bookId });
+
 // ─── File System ───
 export const readFile = (path: string) =>
   invoke<FileContent>('read_file', { path });
 
-export const writeFile = (path: string, frontmatter: Record<string, unknown>, body: string) =>
-  invoke<void>('write_file', { path, frontmatter, body });
+/** Returns the new version. With expectedVersion, rejects with a WriteConflict error
+ *  (see asWriteConflict) if the file changed since that version was read. */
+export const writeFile = (path: string, frontmatter: Record<string, unknown>, body: string, expectedVersion?: string) =>
+  invoke<string>('write_file', { path, frontmatter, body, expectedVersion: expectedVersion ?? null });
+
+/** The conflict details if a write was rejected because the file changed on disk */
+export const asWriteConflict = (error: unknown): WriteConflict | null =>
+  typeof error === 'object' && error !== null && 'conflict' in error
+    ? (error as { conflict: WriteConflict }).conflict
+    : null;
 
 export const createFromTemplate = (path: string, template: string, variables: Record<string, string>) =>
   invoke<void>('create_from_template', { path, template, variables });
@@ -90,9 +114,24 @@ export const getBookTotalDocWords = (projectDir: string, bookId: string) =>
 export const getProjectTotalDocWords = (projectDir: string) =>
   invoke<number>('get_project_total_doc_words', { projectDir });
 
+// ─── Writing History ───
+export const getWritingStats = (projectDir: string, bookId: string) =>
+  invoke<WritingStats>('get_writing_stats', { projectDir, bookId });
+
+/** Daily totals for the book, or for one scene given as "ch-01/scene-02" */
+export const getWordCountHistory = (projectDir: string, bookId: string, scene?: string) =>
+  invoke<DailyWordCount[]>('get_word_count_history', { projectDir, bookId, scene: scene ?? null });
+
+/** Newest first */
+export const listWritingSessions = (projectDir: string, bookId: string, limit?: number) =>
+  invoke<WritingSession[]>('list_writing_sessions', { projectDir, bookId, limit: limit ?? null });
+
+export const setDailyWordGoal = (projectDir: string, bookId: string, goal: number) =>
+  invoke<void>('set_daily_word_goal', { projectDir, bookId, goal });
+
 // ─── Draft Management ───
-export const saveDraft = (path: string, content: string) =>
-  invoke<void>('save_draft', { path, content });
+export const saveDraft = (path: string, content: string, expectedVersion?: string) =>
+  invoke<string>('save_draft', { path, content, expectedVersion: expectedVersion ?? null });
 
 export const listDrafts = (sceneDir: string) =>
   invoke<DraftSnapshot[]>('list_drafts', { sceneDir });
@@ -102,10 +141,10 @@ export const restoreDraft = (sceneDir: string, snapshotName: string) =>
 
 // ─── Chapter & Scene Management ───
 export const createChapter = (projectDir: string, bookId: string, title: string) =>
-  invoke<{ id: string; title: string; sort_order: number; scenes: unknown[] }>('create_chapter', { projectDir, bookId, title });
+  invoke<ChapterMeta>('create_chapter', { projectDir, bookId, title });
 
 export const createScene = (projectDir: string, bookId: string, chapterId: string, title: string, sceneType: string) =>
-  invoke<{ id: string; title: string; sort_order: number }>('create_scene', { projectDir, bookId, chapterId, title, sceneType });
+  invoke<SceneMeta>('create_scene', { projectDir, bookId, chapterId, title, sceneType });
 
 export const reorderChapters = (projectDir: string, bookId: string, chapterIds: string[]) =>
   invoke<void>('reorder_chapters', { projectDir, bookId, chapterIds });
@@ -113,8 +152,42 @@ export const reorderChapters = (projectDir: string, bookId: string, chapterIds:
 export const reorderScenes = (projectDir: string, bookId: string, chapterId: string, sceneIds: string[]) =>
   invoke<void>('reorder_scenes', { projectDir, bookId, chapterId, sceneIds });
 
-export const moveScene = (projectDir: string, bookId: string, sceneId: string, fromChapter: string, toChapter: string, position: number) =>
-  invoke<void>('move_scene', { projectDir, bookId, sceneId, fromChapter, toChapter, position });
+/** Returns the moved scene, whose id changes if the target chapter already uses it */
+/** Moves a scene to `toChapter`, in another book when `toBookId` is given */
+export const moveScene = (projectDir: string, bookId: string, sceneId: string, fromChapter: string, toChapter: string, position: number, toBookId?: string) =>
+  invoke<SceneMeta>('move_scene', { projectDir, bookId, sceneId, fromChapter, toChapter, position, toBookId });
+
+/** Moves a chapter and its scenes to another book at `position` in its reading order */
+export const moveChapterToBook = (projectDir: string, bookId: string, chapterId: string, toBookId: string, position: number) =>
+  invoke<ChapterMeta>('move_chapter_to_book', { projectDir, bookId, chapterId, toBookId, position });
+
+/** Moves the chapter folder to the trash */
+export const deleteChapter = (projectDir: string, bookId: string, chapterId: string) =>
+  invoke<void>('delete_chapter', { projectDir, bookId, chapterId });
+
+/** Moves the scene folder to the trash */
+export const deleteScene = (projectDir: string, bookId: string, chapterId: string, sceneId: string) =>
+  invoke<void>('delete_scene', { projectDir, bookId, chapterId, sceneId });
+
+/** Renames chapter and scene folders to match the reading order */
+export const renumberBook = (projectDir: string, bookId: string) =>
+  invoke<BookMetadata>('renumber_book', { projectDir, bookId });
+
+/** Splits a scene's draft at a character offset in its body; returns the new scene */
+export const splitScene = (projectDir: string, bookId: string, chapterId: string, sceneId: string, atOffset: number, newTitle?: string) =>
+  invoke<SceneMeta>('split_scene', { projectDir, bookId, chapterId, sceneId, atOffset, newTitle });
+
+/** Appends the next scene into this one and trashes its folder */
+export const mergeScenes = (projectDir: string, bookId: string, chapterId: string, sceneId: string, nextSceneId: string) =>
+  invoke<SceneMeta>('merge_scenes', { projectDir, bookId, chapterId, sceneId, nextSceneId });
+
+/** Moves `atScene` and every later scene into a new chapter after this one */
+export const splitChapter = (projectDir: string, bookId: string, chapterId: string, atScene: string, newTitle?: string) =>
+  invoke<ChapterMeta>('split_chapter', { projectDir, bookId, chapterId, atScene, newTitle });
+
+/** Appends the next chapter's scenes and notes into this one and trashes its folder */
+export const mergeChapters = (projectDir: string, bookId: string, chapterId: string, nextChapterId: string) =>
+  invoke<ChapterMeta>('merge_chapters', { projectDir, bookId, chapterId, nextChapterId });
 
 // ─── Front & Back Matter ───
 export const createFrontMatter = (projectDir: string, bookId: string, subtype: string) =>
@@ -162,6 +235,9 @@ export const estimateContextTokens = (projectDir: string, skill: string, scope:
 export const listAvailableSkills = () =>
   invoke<import('../types/ai').SkillMeta[]>('list_available_skills');
 
+export const validateSkills = () =>
+  invoke<import('../types/ai').SkillIssue[]>('validate_skills');
+
 // ─── Configuration ───
 export interface AppConfig {
   version: string;
@@ -184,8 +260,15 @@ export interface AppConfig {
   custom_theme_colors: Record<string, string>;
   vector_search: {
     enabled: boolean;
+    provider: string;
     embedding_model: string;
     embedding_api_key_encrypted: string;
+    embedding_base_url: string;
+    embedding_dimensions: number;
+    embedding_quantization: string;
+    embedding_keep_full_precision: boolean;
+    embedding_concurrency: number;
+    embedding_requests_per_minute: number;
     auto_index: boolean;
     max_results_default: number;
     max_search_tokens_default: number;
@@ -247,6 +330,14 @@ export const getGenres = () =>
 export const startFileWatcher = (projectDir: string) =>
   invoke<void>('start_file_watcher', { projectDir });
 
+/** Watch only this project, stopping the watchers of any others */
+export const switchFileWatcher = (projectDir: string) =>
+  invoke<void>('switch_file_watcher', { projectDir });
+
+/** Stop watching a project, or every project when none is given */
+export const stopFileWatcher = (projectDir?: string) =>
+  invoke<void>('stop_file_watcher', { projectDir: projectDir ?? null });
+
 // ─── Vector Search ───
 
 export const vectorSearch = (
@@ -256,9 +347,23 @@ export const vectorSearch = (
   filterEntityTypes?: string[],
   filterBookId?: string,
   respectContextSettings?: boolean,
+  mode?: SearchMode,
 ) => invoke<SearchResult[]>('vector_search', {
   projectDir, query, maxResults, filterEntityTypes, filterBookId,
   respectContextSettings: respectContextSettings ?? true,
+  mode,
+});
+
+export const findRelated = (
+  projectDir: string,
+  filePath: string,
+  mode?: RelatedMode,
+  maxResults?: number,
+  minSimilarity?: number,
+  respectContextSettings?: boolean,
+) => invoke<RelatedContent>('find_related', {
+  projectDir, filePath, mode, maxResults, minSimilarity,
+  respectContextSettings: respectContextSettings ?? true,
 });
 
 export const getIndexStatus = (projectDir: string) =>
@@ -267,6 +372,27 @@ export const getIndexStatus = (projectDir: string) =>
 export const reindexProject = (projectDir: string) =>
   invoke<void>('reindex_project', { projectDir });
 
+export const estimateReindex = (projectDir: string) =>
+  invoke<ReindexEstimate>('estimate_reindex', { projectDir });
+
+export const cancelReindex = () =>
+  invoke<void>('cancel_reindex');
+
+export const verifyIndex = (projectDir: string, repair = false) =>
+  invoke<IndexIntegrityReport>('verify_index', { projectDir, repair });
+
+export const listEntities = (projectDir: string) =>
+  invoke<KnownEntity[]>('list_entities', { projectDir });
+
+export const entityAppearances = (projectDir: string, entity: string, fileType?: ManuscriptFileType) =>
+  invoke<EntityAppearance[]>('entity_appearances', { projectDir, entity, fileType });
+
+export const entityFirstAppearance = (projectDir: string, entity: string, fileType?: ManuscriptFileType) =>
+  invoke<EntityAppearance | null>('entity_first_appearance', { projectDir, entity, fileType });
+
+export const entityCooccurrences = (projectDir: string, entity: string, fileType?: ManuscriptFileType) =>
+  invoke<EntityCooccurrence[]>('entity_cooccurrences', { projectDir, entity, fileType });
+
 export const clearIndex = (projectDir: string) =>
   invoke<void>('clear_index', { projectDir });
 